@@ -0,0 +1,110 @@
+//! Accessibility settings for Crux apps
+//!
+//! `crux_accessibility` allows Crux apps to read the accessibility settings the user chose
+//! for their device, like reduced motion or larger text, and to subscribe to changes to them,
+//! so that the core can adapt e.g. the animations it asks the Shell to run.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+/// The accessibility settings of the device
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccessibilitySettings {
+    /// Whether the user asked for less motion in the user interface
+    pub reduce_motion: bool,
+    /// Whether the user asked for bold text
+    pub bold_text: bool,
+    /// The factor the user scaled text by, 1.0 being the default size
+    pub text_scale: f32,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self {
+            reduce_motion: false,
+            bold_text: false,
+            text_scale: 1.0,
+        }
+    }
+}
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccessibilityOperation {
+    /// Read the current settings
+    Settings,
+    /// Subscribe to changes of the settings. The Shell resolves it with the new settings
+    /// every time they change.
+    Subscribe,
+}
+
+impl Operation for AccessibilityOperation {
+    type Output = AccessibilitySettings;
+}
+
+#[derive(Capability)]
+pub struct Accessibility<Ev> {
+    context: CapabilityContext<AccessibilityOperation, Ev>,
+}
+
+impl<Ev> Clone for Accessibility<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Accessibility<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<AccessibilityOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Read the current accessibility settings, which will be passed to the app wrapped
+    /// in the event produced by the `callback`.
+    pub fn settings<F>(&self, callback: F)
+    where
+        F: FnOnce(AccessibilitySettings) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.settings_async().await));
+            }
+        });
+    }
+
+    /// Read the current accessibility settings.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn settings_async(&self) -> AccessibilitySettings {
+        self.context
+            .request_from_shell(AccessibilityOperation::Settings)
+            .await
+    }
+
+    /// Subscribe to changes of the accessibility settings. Every time they change, the new
+    /// settings will be passed to the app wrapped in the event produced by the `callback`.
+    pub fn on_change<F>(&self, callback: F)
+    where
+        F: Fn(AccessibilitySettings) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                let mut stream = context.stream_from_shell(AccessibilityOperation::Subscribe);
+
+                while let Some(settings) = stream.next().await {
+                    context.update_app(callback(settings));
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,130 @@
+mod shared {
+    use crux_accessibility::{Accessibility, AccessibilitySettings};
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Start,
+        Watch,
+
+        #[serde(skip)]
+        SettingsChanged(AccessibilitySettings),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub settings: AccessibilitySettings,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub animate: bool,
+        pub font_size: f32,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Start => caps.accessibility.settings(Event::SettingsChanged),
+                Event::Watch => caps.accessibility.on_change(Event::SettingsChanged),
+                Event::SettingsChanged(settings) => {
+                    model.settings = settings;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                animate: !model.settings.reduce_motion,
+                font_size: 16.0 * model.settings.text_scale,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub accessibility: Accessibility<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_accessibility::{AccessibilityOperation, AccessibilitySettings};
+    use crux_core::{assert_effect, testing::AppTester};
+
+    #[test]
+    pub fn test_settings() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Start, &mut model)
+            .expect_one_effect()
+            .expect_accessibility();
+
+        assert_eq!(request.operation, AccessibilityOperation::Settings);
+
+        let settings = AccessibilitySettings {
+            reduce_motion: true,
+            bold_text: false,
+            text_scale: 1.5,
+        };
+        let event = app
+            .resolve(&mut request, settings)
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::SettingsChanged(settings));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+
+        let view = app.view(&model);
+        assert!(!view.animate);
+        assert_eq!(view.font_size, 24.0);
+
+        assert!(!request.expects_response());
+    }
+
+    #[test]
+    pub fn test_on_change() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Watch, &mut model)
+            .expect_one_effect()
+            .expect_accessibility();
+
+        assert_eq!(request.operation, AccessibilityOperation::Subscribe);
+        assert!(app.view(&model).animate);
+
+        let settings = AccessibilitySettings {
+            reduce_motion: true,
+            ..Default::default()
+        };
+        let event = app
+            .resolve(&mut request, settings)
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::SettingsChanged(settings));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert!(!app.view(&model).animate);
+
+        // still subscribed
+        assert!(request.expects_response());
+    }
+}
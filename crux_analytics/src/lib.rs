@@ -0,0 +1,88 @@
+//! Product analytics for Crux apps
+//!
+//! `crux_analytics` allows Crux apps to track analytics events from the core, which the Shell
+//! forwards to the analytics SDK of its platform. Tracking is fire-and-forget, the app doesn't
+//! hear back from the Shell.
+
+use std::collections::BTreeMap;
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use serde::{Deserialize, Serialize};
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AnalyticsOperation {
+    /// Track an event called `name`, with additional properties
+    Track {
+        name: String,
+        properties: BTreeMap<String, AnalyticsValue>,
+    },
+}
+
+/// The value of an event property
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AnalyticsValue {
+    String(String),
+    Number(f64),
+    Bool(bool),
+}
+
+impl From<String> for AnalyticsValue {
+    fn from(value: String) -> Self {
+        AnalyticsValue::String(value)
+    }
+}
+
+impl From<&str> for AnalyticsValue {
+    fn from(value: &str) -> Self {
+        AnalyticsValue::String(value.to_string())
+    }
+}
+
+impl From<f64> for AnalyticsValue {
+    fn from(value: f64) -> Self {
+        AnalyticsValue::Number(value)
+    }
+}
+
+impl From<bool> for AnalyticsValue {
+    fn from(value: bool) -> Self {
+        AnalyticsValue::Bool(value)
+    }
+}
+
+impl Operation for AnalyticsOperation {
+    type Output = ();
+}
+
+#[derive(Capability)]
+pub struct Analytics<Ev> {
+    context: CapabilityContext<AnalyticsOperation, Ev>,
+}
+
+impl<Ev> Analytics<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<AnalyticsOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Track an event called `name`, with the provided `properties`.
+    ///
+    /// The properties are kept in a `BTreeMap`, so they are always sent to the Shell
+    /// in the same order.
+    pub fn track(&self, name: impl Into<String>, properties: BTreeMap<String, AnalyticsValue>) {
+        let name = name.into();
+
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                context
+                    .notify_shell(AnalyticsOperation::Track { name, properties })
+                    .await;
+            }
+        });
+    }
+}
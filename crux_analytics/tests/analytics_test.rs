@@ -0,0 +1,109 @@
+mod shared {
+    use std::collections::BTreeMap;
+
+    use crux_analytics::Analytics;
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Event {
+        Purchase { item: String, price: f64 },
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub purchases: usize,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub purchases: usize,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Purchase { item, price } => {
+                    model.purchases += 1;
+
+                    caps.analytics.track(
+                        "purchase",
+                        BTreeMap::from([
+                            ("item".to_string(), item.into()),
+                            ("price".to_string(), price.into()),
+                            ("first".to_string(), (model.purchases == 1).into()),
+                        ]),
+                    );
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                purchases: model.purchases,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub analytics: Analytics<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_analytics::{AnalyticsOperation, AnalyticsValue};
+    use crux_core::testing::AppTester;
+
+    #[test]
+    pub fn test_track() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(
+            Event::Purchase {
+                item: "coffee".to_string(),
+                price: 3.5,
+            },
+            &mut model,
+        );
+
+        let request = update
+            .take_effects(Effect::is_analytics)
+            .pop_front()
+            .expect("an analytics effect")
+            .expect_analytics();
+
+        assert_eq!(
+            request.operation,
+            AnalyticsOperation::Track {
+                name: "purchase".to_string(),
+                properties: BTreeMap::from([
+                    ("first".to_string(), AnalyticsValue::Bool(true)),
+                    (
+                        "item".to_string(),
+                        AnalyticsValue::String("coffee".to_string())
+                    ),
+                    ("price".to_string(), AnalyticsValue::Number(3.5)),
+                ]),
+            }
+        );
+
+        // tracking doesn't expect a response from the shell
+        assert!(!request.expects_response());
+    }
+}
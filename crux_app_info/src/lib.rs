@@ -0,0 +1,132 @@
+//! App version information for Crux apps
+//!
+//! `crux_app_info` allows Crux apps to ask the Shell which version of the app is installed,
+//! and whether a newer version is available in the store, e.g. to ask the user to update
+//! before carrying on.
+
+use std::fmt;
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use serde::{Deserialize, Serialize};
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AppInfoOperation {
+    /// Ask for the installed version of the app, answered with an [`AppVersion`]
+    Version,
+    /// Ask whether a newer version of the app is available in the store
+    UpdateAvailable,
+}
+
+/// The version of the app, following semantic versioning, and the build it was installed from
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    /// The build number, e.g. `CFBundleVersion` on iOS or `versionCode` on Android
+    pub build: String,
+}
+
+impl fmt::Display for AppVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{} ({})",
+            self.major, self.minor, self.patch, self.build
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AppInfoResponse {
+    Version(AppVersion),
+    UpdateAvailable(bool),
+}
+
+impl Operation for AppInfoOperation {
+    type Output = AppInfoResponse;
+}
+
+#[derive(Capability)]
+pub struct AppInfo<Ev> {
+    context: CapabilityContext<AppInfoOperation, Ev>,
+}
+
+impl<Ev> Clone for AppInfo<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> AppInfo<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<AppInfoOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Ask for the installed version of the app, which will be passed to the app wrapped
+    /// in the event produced by the `callback`.
+    pub fn version<F>(&self, callback: F)
+    where
+        F: FnOnce(AppVersion) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.version_async().await));
+            }
+        });
+    }
+
+    /// Ask for the installed version of the app.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn version_async(&self) -> AppVersion {
+        let AppInfoResponse::Version(version) = self
+            .context
+            .request_from_shell(AppInfoOperation::Version)
+            .await
+        else {
+            panic!("attempt to convert AppInfoResponse other than Version to AppVersion");
+        };
+
+        version
+    }
+
+    /// Ask whether a newer version of the app is available in the store. The answer is
+    /// passed to the app wrapped in the event produced by the `callback`.
+    pub fn update_available<F>(&self, callback: F)
+    where
+        F: FnOnce(bool) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.update_available_async().await));
+            }
+        });
+    }
+
+    /// Ask whether a newer version of the app is available in the store.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn update_available_async(&self) -> bool {
+        let AppInfoResponse::UpdateAvailable(available) = self
+            .context
+            .request_from_shell(AppInfoOperation::UpdateAvailable)
+            .await
+        else {
+            panic!("attempt to convert AppInfoResponse other than UpdateAvailable to bool");
+        };
+
+        available
+    }
+}
@@ -0,0 +1,129 @@
+mod shared {
+    use crux_app_info::{AppInfo, AppVersion};
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        GetVersion,
+        CheckForUpdate,
+
+        #[serde(skip)]
+        Version(AppVersion),
+        #[serde(skip)]
+        UpdateAvailable(bool),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub version: Option<AppVersion>,
+        pub must_update: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub version: String,
+        pub must_update: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::GetVersion => caps.app_info.version(Event::Version),
+                Event::CheckForUpdate => caps.app_info.update_available(Event::UpdateAvailable),
+                Event::Version(version) => {
+                    model.version = Some(version);
+                    caps.render.render();
+                }
+                Event::UpdateAvailable(available) => {
+                    model.must_update = available;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                version: model
+                    .version
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+                must_update: model.must_update,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub app_info: AppInfo<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_app_info::{AppInfoOperation, AppInfoResponse, AppVersion};
+    use crux_core::{assert_effect, testing::AppTester};
+
+    #[test]
+    pub fn test_version() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::GetVersion, &mut model)
+            .expect_one_effect()
+            .expect_app_info();
+
+        assert_eq!(request.operation, AppInfoOperation::Version);
+
+        let version = AppVersion {
+            major: 2,
+            minor: 4,
+            patch: 1,
+            build: "318".to_string(),
+        };
+        let event = app
+            .resolve(&mut request, AppInfoResponse::Version(version.clone()))
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Version(version));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(app.view(&model).version, "2.4.1 (318)");
+    }
+
+    #[test]
+    pub fn test_update_available() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::CheckForUpdate, &mut model)
+            .expect_one_effect()
+            .expect_app_info();
+
+        assert_eq!(request.operation, AppInfoOperation::UpdateAvailable);
+
+        let event = app
+            .resolve(&mut request, AppInfoResponse::UpdateAvailable(true))
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::UpdateAvailable(true));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert!(app.view(&model).must_update);
+    }
+}
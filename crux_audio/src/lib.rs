@@ -0,0 +1,117 @@
+//! Audio playback control for Crux apps
+//!
+//! `crux_audio` allows Crux apps to control audio playback in the Shell: play audio from
+//! a URL, pause it and seek within it. The playback commands are fire-and-forget. Apps
+//! follow the playback by subscribing to its state, which resolves every time the state
+//! or the playback position changes.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+/// The status of the playback
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlaybackStatus {
+    Playing,
+    Paused,
+    Buffering,
+    Ended,
+}
+
+/// The state of the playback, with the `position` in seconds from the start of the audio
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlaybackState {
+    pub status: PlaybackStatus,
+    pub position: f64,
+}
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AudioOperation {
+    /// Play the audio at the `url`. This isn't resolved.
+    Play { url: String },
+    /// Pause the playback. This isn't resolved.
+    Pause,
+    /// Move the playback to the `position`, in seconds from the start. This isn't resolved.
+    Seek { position: f64 },
+    /// Start delivering the playback state every time it changes
+    SubscribeState,
+}
+
+impl Operation for AudioOperation {
+    type Output = PlaybackState;
+}
+
+#[derive(Capability)]
+pub struct Audio<Ev> {
+    context: CapabilityContext<AudioOperation, Ev>,
+}
+
+impl<Ev> Clone for Audio<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Audio<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<AudioOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Ask the Shell to play the audio at the `url`
+    pub fn play(&self, url: impl Into<String>) {
+        self.notify(AudioOperation::Play { url: url.into() });
+    }
+
+    /// Ask the Shell to pause the playback
+    pub fn pause(&self) {
+        self.notify(AudioOperation::Pause);
+    }
+
+    /// Ask the Shell to move the playback to the `position`, in seconds from the start.
+    /// Negative positions are clamped to the start, and a position which isn't a number
+    /// is ignored.
+    pub fn seek(&self, position: f64) {
+        if position.is_nan() {
+            return;
+        }
+
+        self.notify(AudioOperation::Seek {
+            position: position.max(0.0),
+        });
+    }
+
+    /// Receive the playback state every time it changes. The new state is passed to the app
+    /// wrapped in the event produced by the `callback`.
+    pub fn on_state<F>(&self, callback: F)
+    where
+        F: Fn(PlaybackState) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                let mut stream = context.stream_from_shell(AudioOperation::SubscribeState);
+
+                while let Some(state) = stream.next().await {
+                    context.update_app(callback(state));
+                }
+            }
+        });
+    }
+
+    fn notify(&self, operation: AudioOperation) {
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                context.notify_shell(operation).await;
+            }
+        });
+    }
+}
@@ -0,0 +1,156 @@
+mod shared {
+    use crux_audio::{Audio, PlaybackState, PlaybackStatus};
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        PlayEpisode(String),
+        Pause,
+        Skip(f64),
+
+        #[serde(skip)]
+        Playback(PlaybackState),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub playback: Option<PlaybackState>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub playing: bool,
+        pub position: f64,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::PlayEpisode(url) => {
+                    caps.audio.play(url);
+                    caps.audio.on_state(Event::Playback);
+                }
+                Event::Pause => caps.audio.pause(),
+                Event::Skip(position) => caps.audio.seek(position),
+                Event::Playback(state) => {
+                    model.playback = Some(state);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                playing: model
+                    .playback
+                    .map_or(false, |state| state.status == PlaybackStatus::Playing),
+                position: model.playback.map_or(0.0, |state| state.position),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub audio: Audio<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_audio::{AudioOperation, PlaybackState, PlaybackStatus};
+    use crux_core::{assert_effect, testing::AppTester};
+
+    const EPISODE_URL: &str = "https://example.com/episodes/42.mp3";
+
+    #[test]
+    pub fn test_play() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::PlayEpisode(EPISODE_URL.to_string()), &mut model);
+
+        let play = update
+            .take_one_effect(|effect| matches!(effect, Effect::Audio(_)))
+            .unwrap()
+            .expect_audio();
+        assert_eq!(
+            play.operation,
+            AudioOperation::Play {
+                url: EPISODE_URL.to_string()
+            }
+        );
+        assert!(!play.expects_response());
+
+        let subscription = update.expect_one_effect().expect_audio();
+        assert_eq!(subscription.operation, AudioOperation::SubscribeState);
+    }
+
+    #[test]
+    pub fn test_state_updates() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::PlayEpisode(EPISODE_URL.to_string()), &mut model);
+        let mut request = update
+            .take_one_effect(|effect| {
+                matches!(
+                    effect,
+                    Effect::Audio(request) if request.operation == AudioOperation::SubscribeState
+                )
+            })
+            .unwrap()
+            .expect_audio();
+
+        for state in [
+            PlaybackState {
+                status: PlaybackStatus::Buffering,
+                position: 0.0,
+            },
+            PlaybackState {
+                status: PlaybackStatus::Playing,
+                position: 12.5,
+            },
+        ] {
+            let event = app.resolve(&mut request, state).unwrap().expect_one_event();
+            assert_eq!(event, Event::Playback(state));
+
+            let update = app.update(event, &mut model);
+            assert_effect!(update, Effect::Render(_));
+        }
+
+        let view = app.view(&model);
+        assert!(view.playing);
+        assert_eq!(view.position, 12.5);
+    }
+
+    #[test]
+    pub fn test_pause_and_seek() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = app
+            .update(Event::Pause, &mut model)
+            .expect_one_effect()
+            .expect_audio();
+        assert_eq!(request.operation, AudioOperation::Pause);
+
+        let request = app
+            .update(Event::Skip(-5.0), &mut model)
+            .expect_one_effect()
+            .expect_audio();
+        assert_eq!(request.operation, AudioOperation::Seek { position: 0.0 });
+
+        app.update(Event::Skip(f64::NAN), &mut model).assert_empty();
+    }
+}
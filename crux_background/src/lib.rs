@@ -0,0 +1,108 @@
+//! Background tasks for Crux apps
+//!
+//! `crux_background` allows Crux apps to register work which the OS runs periodically while
+//! the app is suspended, e.g. to sync data. The Shell maps it to the platform's scheduler, like
+//! `BGTaskScheduler` on iOS or `WorkManager` on Android. When the OS grants a task background
+//! time, the Shell resolves the app's subscription to runs with the task's id. The app is then
+//! expected to do a bounded amount of work and signal its completion, so that the Shell can
+//! tell the OS the run is over.
+
+use std::time::Duration;
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+/// A run of a background task the OS granted background time
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackgroundRun {
+    pub task_id: String,
+}
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BackgroundTasksOperation {
+    /// Register the task identified by `task_id`, to run at most every `interval_secs`
+    /// seconds. This isn't resolved.
+    Register { task_id: String, interval_secs: u64 },
+    /// Subscribe to runs of the registered tasks. The Shell resolves it every time the OS
+    /// grants a task background time.
+    Subscribe,
+    /// Signal that the run of the task identified by `task_id` is over, and whether the work
+    /// succeeded. This isn't resolved.
+    Complete { task_id: String, success: bool },
+}
+
+impl Operation for BackgroundTasksOperation {
+    type Output = BackgroundRun;
+}
+
+#[derive(Capability)]
+pub struct BackgroundTasks<Ev> {
+    context: CapabilityContext<BackgroundTasksOperation, Ev>,
+}
+
+impl<Ev> Clone for BackgroundTasks<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> BackgroundTasks<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<BackgroundTasksOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Ask the Shell to register the task identified by `task_id` with the OS, to run
+    /// at most every `interval`. The OS decides when the task actually runs.
+    pub fn register(&self, task_id: impl Into<String>, interval: Duration) {
+        self.notify(BackgroundTasksOperation::Register {
+            task_id: task_id.into(),
+            interval_secs: interval.as_secs(),
+        });
+    }
+
+    /// Subscribe to runs of the registered tasks. Every time the OS grants a task background
+    /// time, the run will be passed to the app wrapped in the event produced by the `callback`.
+    /// The app must call [`BackgroundTasks::complete`] once it's done with the run.
+    pub fn on_run<F>(&self, callback: F)
+    where
+        F: Fn(BackgroundRun) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                let mut stream = context.stream_from_shell(BackgroundTasksOperation::Subscribe);
+
+                while let Some(run) = stream.next().await {
+                    context.update_app(callback(run));
+                }
+            }
+        });
+    }
+
+    /// Signal to the Shell that the run of the task identified by `task_id` is over,
+    /// and whether the work succeeded
+    pub fn complete(&self, task_id: impl Into<String>, success: bool) {
+        self.notify(BackgroundTasksOperation::Complete {
+            task_id: task_id.into(),
+            success,
+        });
+    }
+
+    fn notify(&self, operation: BackgroundTasksOperation) {
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                context.notify_shell(operation).await;
+            }
+        });
+    }
+}
@@ -0,0 +1,144 @@
+mod shared {
+    use std::time::Duration;
+
+    use crux_background::{BackgroundRun, BackgroundTasks};
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    pub const SYNC: &str = "com.example.sync";
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Start,
+
+        #[serde(skip)]
+        Run(BackgroundRun),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub syncs: u32,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub syncs: u32,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Start => {
+                    caps.background.register(SYNC, Duration::from_secs(15 * 60));
+                    caps.background.on_run(Event::Run);
+                }
+                Event::Run(run) => {
+                    let success = run.task_id == SYNC;
+                    if success {
+                        model.syncs += 1;
+                    }
+                    caps.background.complete(run.task_id, success);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel { syncs: model.syncs }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub background: BackgroundTasks<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model, SYNC};
+    use crux_background::{BackgroundRun, BackgroundTasksOperation};
+    use crux_core::{assert_effect, testing::AppTester};
+
+    #[test]
+    pub fn test_register() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::Start, &mut model);
+        let request = update
+            .take_one_effect(|effect| {
+                matches!(
+                    effect,
+                    Effect::BackgroundTasks(request)
+                        if matches!(request.operation, BackgroundTasksOperation::Register { .. })
+                )
+            })
+            .unwrap()
+            .expect_background();
+
+        assert_eq!(
+            request.operation,
+            BackgroundTasksOperation::Register {
+                task_id: SYNC.to_string(),
+                interval_secs: 900
+            }
+        );
+        assert!(!request.expects_response());
+    }
+
+    #[test]
+    pub fn test_run_and_complete() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::Start, &mut model);
+        let mut request = update
+            .take_one_effect(|effect| {
+                matches!(
+                    effect,
+                    Effect::BackgroundTasks(request)
+                        if request.operation == BackgroundTasksOperation::Subscribe
+                )
+            })
+            .unwrap()
+            .expect_background();
+
+        for syncs in 1..=2 {
+            let run = BackgroundRun {
+                task_id: SYNC.to_string(),
+            };
+            let event = app
+                .resolve(&mut request, run.clone())
+                .unwrap()
+                .expect_one_event();
+            assert_eq!(event, Event::Run(run));
+
+            let mut update = app.update(event, &mut model);
+            assert_effect!(update, Effect::Render(_));
+
+            let complete = update
+                .take_one_effect(|effect| matches!(effect, Effect::BackgroundTasks(_)))
+                .unwrap()
+                .expect_background();
+            assert_eq!(
+                complete.operation,
+                BackgroundTasksOperation::Complete {
+                    task_id: SYNC.to_string(),
+                    success: true
+                }
+            );
+            assert!(!complete.expects_response());
+            assert_eq!(app.view(&model).syncs, syncs);
+        }
+    }
+}
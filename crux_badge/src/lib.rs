@@ -0,0 +1,77 @@
+//! The app icon badge for Crux apps
+//!
+//! `crux_badge` allows Crux apps to set the count shown on the app's icon badge, e.g. the
+//! number of unread messages, so that it reflects the app's state. Setting the count is
+//! fire-and-forget. Apps can also read the current count, which may have been changed
+//! outside of the app, e.g. by a push notification.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use serde::{Deserialize, Serialize};
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BadgeOperation {
+    /// Set the badge count, 0 clears the badge. This isn't resolved.
+    Set { count: u32 },
+    /// Read the current badge count
+    Get,
+}
+
+impl Operation for BadgeOperation {
+    type Output = u32;
+}
+
+#[derive(Capability)]
+pub struct Badge<Ev> {
+    context: CapabilityContext<BadgeOperation, Ev>,
+}
+
+impl<Ev> Clone for Badge<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Badge<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<BadgeOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Ask the Shell to set the badge count to `count`, 0 clears the badge
+    pub fn set(&self, count: u32) {
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                context.notify_shell(BadgeOperation::Set { count }).await;
+            }
+        });
+    }
+
+    /// Read the current badge count, which will be passed to the app wrapped in the event
+    /// produced by the `callback`.
+    pub fn get<F>(&self, callback: F)
+    where
+        F: FnOnce(u32) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.get_async().await));
+            }
+        });
+    }
+
+    /// Read the current badge count.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn get_async(&self) -> u32 {
+        self.context.request_from_shell(BadgeOperation::Get).await
+    }
+}
@@ -0,0 +1,101 @@
+mod shared {
+    use crux_badge::Badge;
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        MessagesReceived(u32),
+        Resume,
+
+        #[serde(skip)]
+        BadgeCount(u32),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub unread: u32,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub unread: u32,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::MessagesReceived(count) => {
+                    model.unread += count;
+                    caps.badge.set(model.unread);
+                }
+                Event::Resume => caps.badge.get(Event::BadgeCount),
+                Event::BadgeCount(count) => {
+                    model.unread = count;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                unread: model.unread,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub badge: Badge<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_badge::BadgeOperation;
+    use crux_core::{assert_effect, testing::AppTester};
+
+    #[test]
+    pub fn test_set() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model { unread: 2 };
+
+        let request = app
+            .update(Event::MessagesReceived(3), &mut model)
+            .expect_one_effect()
+            .expect_badge();
+
+        assert_eq!(request.operation, BadgeOperation::Set { count: 5 });
+        assert!(!request.expects_response());
+    }
+
+    #[test]
+    pub fn test_get() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Resume, &mut model)
+            .expect_one_effect()
+            .expect_badge();
+
+        assert_eq!(request.operation, BadgeOperation::Get);
+
+        let event = app.resolve(&mut request, 7).unwrap().expect_one_event();
+        assert_eq!(event, Event::BadgeCount(7));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(app.view(&model).unread, 7);
+    }
+}
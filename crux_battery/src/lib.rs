@@ -0,0 +1,107 @@
+//! Battery status for Crux apps
+//!
+//! `crux_battery` allows Crux apps to read the device's battery status, i.e. the battery level,
+//! whether it's charging and whether low-power mode is on, e.g. to throttle background work
+//! when the battery is low. Apps can also subscribe to changes of the status, which resolve
+//! every time it changes.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+/// The status of the device's battery
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BatteryStatus {
+    /// The charge level, from 0.0 (empty) to 1.0 (full), or `None` if the platform
+    /// doesn't report it, e.g. on a desktop without a battery
+    pub level: Option<f32>,
+    pub charging: bool,
+    pub low_power_mode: bool,
+}
+
+impl BatteryStatus {
+    /// Whether the level is known and below `threshold`, while not charging
+    pub fn is_below(&self, threshold: f32) -> bool {
+        !self.charging && self.level.map_or(false, |level| level < threshold)
+    }
+}
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BatteryOperation {
+    /// Read the current battery status
+    Status,
+    /// Subscribe to changes of the battery status. The Shell resolves it with the new status
+    /// every time it changes.
+    Subscribe,
+}
+
+impl Operation for BatteryOperation {
+    type Output = BatteryStatus;
+}
+
+#[derive(Capability)]
+pub struct Battery<Ev> {
+    context: CapabilityContext<BatteryOperation, Ev>,
+}
+
+impl<Ev> Clone for Battery<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Battery<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<BatteryOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Read the current battery status, which will be passed to the app wrapped in the event
+    /// produced by the `callback`.
+    pub fn status<F>(&self, callback: F)
+    where
+        F: FnOnce(BatteryStatus) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.status_async().await));
+            }
+        });
+    }
+
+    /// Read the current battery status.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn status_async(&self) -> BatteryStatus {
+        self.context
+            .request_from_shell(BatteryOperation::Status)
+            .await
+    }
+
+    /// Subscribe to changes of the battery status. Every new status will be passed to the
+    /// app wrapped in the event produced by the `callback`.
+    pub fn on_change<F>(&self, callback: F)
+    where
+        F: Fn(BatteryStatus) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                let mut stream = context.stream_from_shell(BatteryOperation::Subscribe);
+
+                while let Some(status) = stream.next().await {
+                    context.update_app(callback(status));
+                }
+            }
+        });
+    }
+}
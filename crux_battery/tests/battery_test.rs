@@ -0,0 +1,134 @@
+mod shared {
+    use crux_battery::{Battery, BatteryStatus};
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    pub const LOW_BATTERY: f32 = 0.2;
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        CheckBattery,
+        WatchBattery,
+
+        #[serde(skip)]
+        BatteryStatus(BatteryStatus),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub throttled: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub throttled: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::CheckBattery => caps.battery.status(Event::BatteryStatus),
+                Event::WatchBattery => caps.battery.on_change(Event::BatteryStatus),
+                Event::BatteryStatus(status) => {
+                    model.throttled = status.low_power_mode || status.is_below(LOW_BATTERY);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                throttled: model.throttled,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub battery: Battery<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_battery::{BatteryOperation, BatteryStatus};
+    use crux_core::{assert_effect, testing::AppTester};
+
+    fn status(level: Option<f32>, charging: bool) -> BatteryStatus {
+        BatteryStatus {
+            level,
+            charging,
+            low_power_mode: false,
+        }
+    }
+
+    #[test]
+    pub fn test_status() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::CheckBattery, &mut model)
+            .expect_one_effect()
+            .expect_battery();
+
+        assert_eq!(request.operation, BatteryOperation::Status);
+
+        let event = app
+            .resolve(&mut request, status(Some(0.1), false))
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::BatteryStatus(status(Some(0.1), false)));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert!(app.view(&model).throttled);
+    }
+
+    #[test]
+    pub fn test_on_change() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::WatchBattery, &mut model)
+            .expect_one_effect()
+            .expect_battery();
+
+        assert_eq!(request.operation, BatteryOperation::Subscribe);
+
+        for (status, throttled) in [
+            (status(Some(0.1), false), true),
+            (status(Some(0.1), true), false),
+            // the level is unknown, e.g. on a desktop
+            (status(None, false), false),
+            (
+                BatteryStatus {
+                    low_power_mode: true,
+                    ..status(None, false)
+                },
+                true,
+            ),
+        ] {
+            let event = app
+                .resolve(&mut request, status.clone())
+                .unwrap()
+                .expect_one_event();
+            assert_eq!(event, Event::BatteryStatus(status));
+
+            let update = app.update(event, &mut model);
+            assert_effect!(update, Effect::Render(_));
+            assert_eq!(app.view(&model).throttled, throttled);
+        }
+    }
+}
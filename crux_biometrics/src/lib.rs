@@ -0,0 +1,77 @@
+//! Biometric authentication for Crux apps
+//!
+//! `crux_biometrics` allows Crux apps to ask the Shell to authenticate the user with the
+//! biometric methods of the platform, like Face ID or a fingerprint. The Shell shows the system
+//! prompt and reports the outcome back to the app.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use serde::{Deserialize, Serialize};
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BiometricsOperation {
+    /// Prompt the user to authenticate, showing the `reason` in the system prompt
+    Authenticate { reason: String },
+}
+
+/// The outcome of a biometric authentication prompt
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BiometricsResult {
+    /// The user was authenticated
+    Success,
+    /// The user couldn't be authenticated, e.g. the face or fingerprint wasn't recognised
+    Failed,
+    /// Biometric authentication isn't available on the device, or the user hasn't enrolled
+    Unavailable,
+    /// The user dismissed the prompt
+    UserCancelled,
+}
+
+impl Operation for BiometricsOperation {
+    type Output = BiometricsResult;
+}
+
+#[derive(Capability)]
+pub struct Biometrics<Ev> {
+    context: CapabilityContext<BiometricsOperation, Ev>,
+}
+
+impl<Ev> Biometrics<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<BiometricsOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Prompt the user to authenticate, with the `reason` shown in the system prompt.
+    /// The outcome is passed to the app wrapped in the event produced by the `callback`.
+    pub fn authenticate<F>(&self, reason: impl Into<String>, callback: F)
+    where
+        F: FnOnce(BiometricsResult) -> Ev + Send + 'static,
+    {
+        let reason = reason.into();
+
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                let result = context
+                    .request_from_shell(BiometricsOperation::Authenticate { reason })
+                    .await;
+                context.update_app(callback(result));
+            }
+        });
+    }
+
+    /// Prompt the user to authenticate, with the `reason` shown in the system prompt.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn authenticate_async(&self, reason: impl Into<String>) -> BiometricsResult {
+        self.context
+            .request_from_shell(BiometricsOperation::Authenticate {
+                reason: reason.into(),
+            })
+            .await
+    }
+}
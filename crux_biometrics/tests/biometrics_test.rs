@@ -0,0 +1,120 @@
+mod shared {
+    use crux_biometrics::{Biometrics, BiometricsResult};
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        ShowBalance,
+
+        #[serde(skip)]
+        Authenticated(BiometricsResult),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub unlocked: bool,
+        pub message: Option<String>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub unlocked: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::ShowBalance => caps
+                    .biometrics
+                    .authenticate("Show your balance", Event::Authenticated),
+                Event::Authenticated(result) => {
+                    model.unlocked = result == BiometricsResult::Success;
+                    model.message = match result {
+                        BiometricsResult::Success => None,
+                        BiometricsResult::UserCancelled => Some("Cancelled".to_string()),
+                        _ => Some("Could not authenticate".to_string()),
+                    };
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                unlocked: model.unlocked,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub biometrics: Biometrics<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_biometrics::{BiometricsOperation, BiometricsResult};
+    use crux_core::{assert_effect, testing::AppTester};
+
+    #[test]
+    pub fn test_authenticate_success() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::ShowBalance, &mut model)
+            .expect_one_effect()
+            .expect_biometrics();
+
+        assert_eq!(
+            request.operation,
+            BiometricsOperation::Authenticate {
+                reason: "Show your balance".to_string()
+            }
+        );
+
+        let event = app
+            .resolve(&mut request, BiometricsResult::Success)
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Authenticated(BiometricsResult::Success));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert!(model.unlocked);
+        assert_eq!(model.message, None);
+    }
+
+    #[test]
+    pub fn test_authenticate_user_cancelled() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::ShowBalance, &mut model)
+            .expect_one_effect()
+            .expect_biometrics();
+
+        let event = app
+            .resolve(&mut request, BiometricsResult::UserCancelled)
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Authenticated(BiometricsResult::UserCancelled));
+
+        let _update = app.update(event, &mut model);
+        assert!(!model.unlocked);
+        assert_eq!(model.message, Some("Cancelled".to_string()));
+    }
+}
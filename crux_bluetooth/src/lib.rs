@@ -0,0 +1,167 @@
+//! Bluetooth Low Energy for Crux apps
+//!
+//! `crux_bluetooth` allows Crux apps to communicate with BLE peripherals using the platform's
+//! native Bluetooth stack. Scanning resolves every time a peripheral is discovered and subscribing
+//! to a characteristic resolves every time it notifies, while connecting resolves once.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+/// A peripheral discovered by a scan
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Peripheral {
+    /// The identifier the Shell uses for the peripheral
+    pub id: String,
+    /// The advertised name of the peripheral, if any
+    pub name: Option<String>,
+    /// The received signal strength, in dBm
+    pub rssi: i16,
+}
+
+/// A characteristic of a service on a connected peripheral
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Characteristic {
+    pub peripheral_id: String,
+    pub service_uuid: String,
+    pub uuid: String,
+}
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BluetoothOperation {
+    /// Scan for peripherals advertising any of the services, or all peripherals if empty
+    Scan { service_uuids: Vec<String> },
+    /// Connect to the peripheral
+    Connect { peripheral_id: String },
+    /// Subscribe to notifications from the characteristic
+    Notify { characteristic: Characteristic },
+}
+
+/// The status of a connection to a peripheral
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConnectionStatus {
+    Connected,
+    Disconnected,
+    Failed { message: String },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BluetoothResponse {
+    /// Response to a `BluetoothOperation::Scan`, sent for every peripheral discovered
+    Discovered { peripheral: Peripheral },
+    /// Response to a `BluetoothOperation::Connect`
+    Connection { status: ConnectionStatus },
+    /// Response to a `BluetoothOperation::Notify`, sent for every notification
+    Notification {
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
+    },
+}
+
+impl Operation for BluetoothOperation {
+    type Output = BluetoothResponse;
+}
+
+#[derive(Capability)]
+pub struct Bluetooth<Ev> {
+    context: CapabilityContext<BluetoothOperation, Ev>,
+}
+
+impl<Ev> Clone for Bluetooth<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Bluetooth<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<BluetoothOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Scan for peripherals advertising any of the `service_uuids`, or all peripherals
+    /// if it's empty. Every peripheral discovered is passed to the app wrapped in the
+    /// event produced by the `callback`.
+    pub fn scan<F>(&self, service_uuids: Vec<String>, callback: F)
+    where
+        F: Fn(Peripheral) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                let mut stream =
+                    context.stream_from_shell(BluetoothOperation::Scan { service_uuids });
+
+                while let Some(response) = stream.next().await {
+                    if let BluetoothResponse::Discovered { peripheral } = response {
+                        context.update_app(callback(peripheral));
+                    }
+                }
+            }
+        });
+    }
+
+    /// Connect to the peripheral with the `peripheral_id`. The status of the connection
+    /// is passed to the app wrapped in the event produced by the `callback`.
+    pub fn connect<F>(&self, peripheral_id: impl Into<String>, callback: F)
+    where
+        F: FnOnce(ConnectionStatus) -> Ev + Send + 'static,
+    {
+        let peripheral_id = peripheral_id.into();
+
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.connect_async(peripheral_id).await));
+            }
+        });
+    }
+
+    /// Connect to the peripheral with the `peripheral_id`, returning the status of the connection.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn connect_async(&self, peripheral_id: impl Into<String>) -> ConnectionStatus {
+        let peripheral_id = peripheral_id.into();
+
+        match self
+            .context
+            .request_from_shell(BluetoothOperation::Connect { peripheral_id })
+            .await
+        {
+            BluetoothResponse::Connection { status } => status,
+            response => ConnectionStatus::Failed {
+                message: format!("unexpected response to connect: {response:?}"),
+            },
+        }
+    }
+
+    /// Subscribe to notifications from the `characteristic`. The data of every notification
+    /// is passed to the app wrapped in the event produced by the `callback`.
+    pub fn on_notify<F>(&self, characteristic: Characteristic, callback: F)
+    where
+        F: Fn(Vec<u8>) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                let mut stream =
+                    context.stream_from_shell(BluetoothOperation::Notify { characteristic });
+
+                while let Some(response) = stream.next().await {
+                    if let BluetoothResponse::Notification { data } = response {
+                        context.update_app(callback(data));
+                    }
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,208 @@
+mod shared {
+    use crux_bluetooth::{Bluetooth, Characteristic, ConnectionStatus, Peripheral};
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    pub const HEART_RATE_SERVICE: &str = "180D";
+    pub const HEART_RATE_MEASUREMENT: &str = "2A37";
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Scan,
+        Connect(String),
+
+        #[serde(skip)]
+        Discovered(Peripheral),
+        #[serde(skip)]
+        Connection(String, ConnectionStatus),
+        #[serde(skip)]
+        HeartRate(Vec<u8>),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub peripherals: Vec<Peripheral>,
+        pub heart_rate: Option<u8>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub peripherals: Vec<String>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Scan => caps
+                    .bluetooth
+                    .scan(vec![HEART_RATE_SERVICE.to_string()], Event::Discovered),
+                Event::Connect(peripheral_id) => caps
+                    .bluetooth
+                    .connect(peripheral_id.clone(), move |status| {
+                        Event::Connection(peripheral_id, status)
+                    }),
+                Event::Discovered(peripheral) => {
+                    model.peripherals.push(peripheral);
+                    caps.render.render();
+                }
+                Event::Connection(peripheral_id, ConnectionStatus::Connected) => {
+                    caps.bluetooth.on_notify(
+                        Characteristic {
+                            peripheral_id,
+                            service_uuid: HEART_RATE_SERVICE.to_string(),
+                            uuid: HEART_RATE_MEASUREMENT.to_string(),
+                        },
+                        Event::HeartRate,
+                    )
+                }
+                Event::Connection(_, _) => {}
+                Event::HeartRate(data) => {
+                    model.heart_rate = data.get(1).copied();
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                peripherals: model
+                    .peripherals
+                    .iter()
+                    .map(|peripheral| peripheral.name.clone().unwrap_or_default())
+                    .collect(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub bluetooth: Bluetooth<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model, HEART_RATE_MEASUREMENT, HEART_RATE_SERVICE};
+    use crux_bluetooth::{
+        BluetoothOperation, BluetoothResponse, Characteristic, ConnectionStatus, Peripheral,
+    };
+    use crux_core::{assert_effect, testing::AppTester};
+
+    #[test]
+    pub fn test_scan_discovers_peripherals() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Scan, &mut model)
+            .expect_one_effect()
+            .expect_bluetooth();
+
+        assert_eq!(
+            request.operation,
+            BluetoothOperation::Scan {
+                service_uuids: vec![HEART_RATE_SERVICE.to_string()]
+            }
+        );
+
+        let peripherals = [
+            Peripheral {
+                id: "a".to_string(),
+                name: Some("Chest strap".to_string()),
+                rssi: -60,
+            },
+            Peripheral {
+                id: "b".to_string(),
+                name: Some("Watch".to_string()),
+                rssi: -72,
+            },
+        ];
+
+        for peripheral in peripherals.clone() {
+            let event = app
+                .resolve(
+                    &mut request,
+                    BluetoothResponse::Discovered {
+                        peripheral: peripheral.clone(),
+                    },
+                )
+                .unwrap()
+                .expect_one_event();
+            assert_eq!(event, Event::Discovered(peripheral));
+
+            let update = app.update(event, &mut model);
+            assert_effect!(update, Effect::Render(_));
+        }
+
+        assert!(request.expects_response());
+        assert_eq!(model.peripherals, peripherals);
+        assert_eq!(app.view(&model).peripherals, vec!["Chest strap", "Watch"]);
+    }
+
+    #[test]
+    pub fn test_notification_delivers_data() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Connect("a".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_bluetooth();
+
+        assert_eq!(
+            request.operation,
+            BluetoothOperation::Connect {
+                peripheral_id: "a".to_string()
+            }
+        );
+
+        let event = app
+            .resolve(
+                &mut request,
+                BluetoothResponse::Connection {
+                    status: ConnectionStatus::Connected,
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+
+        let mut request = app
+            .update(event, &mut model)
+            .expect_one_effect()
+            .expect_bluetooth();
+
+        assert_eq!(
+            request.operation,
+            BluetoothOperation::Notify {
+                characteristic: Characteristic {
+                    peripheral_id: "a".to_string(),
+                    service_uuid: HEART_RATE_SERVICE.to_string(),
+                    uuid: HEART_RATE_MEASUREMENT.to_string(),
+                }
+            }
+        );
+
+        let event = app
+            .resolve(
+                &mut request,
+                BluetoothResponse::Notification { data: vec![0, 72] },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::HeartRate(vec![0, 72]));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(model.heart_rate, Some(72));
+        assert!(request.expects_response());
+    }
+}
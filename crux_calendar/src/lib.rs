@@ -0,0 +1,224 @@
+//! Access to the system calendar for Crux apps
+//!
+//! `crux_calendar` allows Crux apps to add events to the user's calendar, query the events
+//! within a time range and delete them, using the platform's native calendar store.
+//! Times are represented with the portable [`Instant`] type from `crux_time`.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_time::Instant;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// An event in the calendar
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalendarEvent {
+    pub title: String,
+    pub start: Instant,
+    pub end: Instant,
+    pub location: Option<String>,
+}
+
+/// An event stored in the calendar, with the identifier the Shell assigned to it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredEvent {
+    pub id: String,
+    pub event: CalendarEvent,
+}
+
+/// A range of time, from `start` (inclusive) to `end` (exclusive)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub start: Instant,
+    pub end: Instant,
+}
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CalendarOperation {
+    /// Add the event to the calendar
+    Add { event: CalendarEvent },
+    /// Find the events overlapping the range
+    Query { range: TimeRange },
+    /// Delete the event with the id
+    Delete { id: String },
+}
+
+/// The result of an operation on the calendar.
+///
+/// Note: we can't use `Result` here because generics are not currently
+/// supported across the FFI boundary, when using the builtin typegen.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CalendarResult {
+    Ok { response: CalendarResponse },
+    Err { error: CalendarError },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CalendarResponse {
+    /// Response to a `CalendarOperation::Add`, with the id of the new event
+    Added { id: String },
+    /// Response to a `CalendarOperation::Query`, with the matching events
+    Events { events: Vec<StoredEvent> },
+    /// Response to a `CalendarOperation::Delete`
+    Deleted,
+}
+
+/// Error type for calendar operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Error)]
+#[serde(rename_all = "camelCase")]
+pub enum CalendarError {
+    #[error("access to the calendar was denied")]
+    PermissionDenied,
+    #[error("event {id} not found")]
+    NotFound { id: String },
+    #[error("other error: {message}")]
+    Other { message: String },
+}
+
+impl Operation for CalendarOperation {
+    type Output = CalendarResult;
+}
+
+pub struct Calendar<Ev> {
+    context: CapabilityContext<CalendarOperation, Ev>,
+}
+
+impl<Ev> crux_core::Capability<Ev> for Calendar<Ev> {
+    type Operation = CalendarOperation;
+
+    type MappedSelf<MappedEv> = Calendar<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static + Send,
+    {
+        Calendar::new(self.context.map_event(f))
+    }
+
+    #[cfg(feature = "typegen")]
+    fn register_types(generator: &mut crux_core::typegen::TypeGen) -> crux_core::typegen::Result {
+        generator.register_type::<Instant>()?;
+        generator.register_type::<CalendarEvent>()?;
+        generator.register_type::<StoredEvent>()?;
+        generator.register_type::<TimeRange>()?;
+        generator.register_type::<CalendarResponse>()?;
+        generator.register_type::<CalendarError>()?;
+        generator.register_type::<Self::Operation>()?;
+        generator.register_type::<<Self::Operation as Operation>::Output>()?;
+        Ok(())
+    }
+}
+
+impl<Ev> Clone for Calendar<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Calendar<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<CalendarOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Add the `event` to the calendar, dispatching the event from `make_event`
+    /// with the id of the new calendar event
+    pub fn add_event<F>(&self, event: CalendarEvent, make_event: F)
+    where
+        F: FnOnce(Result<String, CalendarError>) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(make_event(this.add_event_async(event).await));
+            }
+        });
+    }
+
+    /// Add the `event` to the calendar, returning the id of the new calendar event.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn add_event_async(&self, event: CalendarEvent) -> Result<String, CalendarError> {
+        match self.request(CalendarOperation::Add { event }).await? {
+            CalendarResponse::Added { id } => Ok(id),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Find the events overlapping the `range`, dispatching the event from `make_event`
+    /// with the matching calendar events
+    pub fn query<F>(&self, range: TimeRange, make_event: F)
+    where
+        F: FnOnce(Result<Vec<StoredEvent>, CalendarError>) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(make_event(this.query_async(range).await));
+            }
+        });
+    }
+
+    /// Find the events overlapping the `range`.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn query_async(&self, range: TimeRange) -> Result<Vec<StoredEvent>, CalendarError> {
+        match self.request(CalendarOperation::Query { range }).await? {
+            CalendarResponse::Events { events } => Ok(events),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Delete the calendar event with the `id`, dispatching the event from `make_event`
+    /// once it has been deleted
+    pub fn delete<F>(&self, id: impl Into<String>, make_event: F)
+    where
+        F: FnOnce(Result<(), CalendarError>) -> Ev + Send + 'static,
+    {
+        let id = id.into();
+
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(make_event(this.delete_async(id).await));
+            }
+        });
+    }
+
+    /// Delete the calendar event with the `id`.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn delete_async(&self, id: impl Into<String>) -> Result<(), CalendarError> {
+        let id = id.into();
+
+        match self.request(CalendarOperation::Delete { id }).await? {
+            CalendarResponse::Deleted => Ok(()),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    async fn request(
+        &self,
+        operation: CalendarOperation,
+    ) -> Result<CalendarResponse, CalendarError> {
+        match self.context.request_from_shell(operation).await {
+            CalendarResult::Ok { response } => Ok(response),
+            CalendarResult::Err { error } => Err(error),
+        }
+    }
+}
+
+fn unexpected(response: CalendarResponse) -> CalendarError {
+    CalendarError::Other {
+        message: format!("unexpected response: {response:?}"),
+    }
+}
@@ -0,0 +1,160 @@
+mod shared {
+    use crux_calendar::{Calendar, CalendarError, CalendarEvent, StoredEvent, TimeRange};
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Schedule(CalendarEvent),
+        Load(TimeRange),
+
+        #[serde(skip)]
+        Scheduled(Result<String, CalendarError>),
+        #[serde(skip)]
+        Loaded(Result<Vec<StoredEvent>, CalendarError>),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub scheduled: Vec<String>,
+        pub events: Vec<StoredEvent>,
+        pub error: Option<CalendarError>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub titles: Vec<String>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Schedule(event) => caps.calendar.add_event(event, Event::Scheduled),
+                Event::Load(range) => caps.calendar.query(range, Event::Loaded),
+                Event::Scheduled(Ok(id)) => {
+                    model.scheduled.push(id);
+                    caps.render.render();
+                }
+                Event::Loaded(Ok(events)) => {
+                    model.events = events;
+                    caps.render.render();
+                }
+                Event::Scheduled(Err(error)) | Event::Loaded(Err(error)) => {
+                    model.error = Some(error);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                titles: model
+                    .events
+                    .iter()
+                    .map(|stored| stored.event.title.clone())
+                    .collect(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub calendar: Calendar<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_calendar::{
+        CalendarError, CalendarEvent, CalendarOperation, CalendarResponse, CalendarResult,
+        TimeRange,
+    };
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_time::Instant;
+
+    fn standup() -> CalendarEvent {
+        CalendarEvent {
+            title: "Standup".to_string(),
+            start: Instant::new(1_700_000_000, 0).unwrap(),
+            end: Instant::new(1_700_000_900, 0).unwrap(),
+            location: Some("Room 1".to_string()),
+        }
+    }
+
+    #[test]
+    pub fn test_add_event() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Schedule(standup()), &mut model)
+            .expect_one_effect()
+            .expect_calendar();
+
+        assert_eq!(
+            request.operation,
+            CalendarOperation::Add { event: standup() }
+        );
+
+        let event = app
+            .resolve(
+                &mut request,
+                CalendarResult::Ok {
+                    response: CalendarResponse::Added {
+                        id: "event-1".to_string(),
+                    },
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Scheduled(Ok("event-1".to_string())));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(model.scheduled, vec!["event-1"]);
+    }
+
+    #[test]
+    pub fn test_query_permission_denied() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let range = TimeRange {
+            start: Instant::new(1_700_000_000, 0).unwrap(),
+            end: Instant::new(1_700_086_400, 0).unwrap(),
+        };
+
+        let mut request = app
+            .update(Event::Load(range), &mut model)
+            .expect_one_effect()
+            .expect_calendar();
+
+        assert_eq!(request.operation, CalendarOperation::Query { range });
+
+        let event = app
+            .resolve(
+                &mut request,
+                CalendarResult::Err {
+                    error: CalendarError::PermissionDenied,
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Loaded(Err(CalendarError::PermissionDenied)));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(model.error, Some(CalendarError::PermissionDenied));
+        assert!(app.view(&model).titles.is_empty());
+    }
+}
@@ -0,0 +1,194 @@
+//! Camera access for Crux apps
+//!
+//! `crux_camera` allows Crux apps to take photos with the device's camera and to scan
+//! barcodes and QR codes. Both are one-shot operations: the Shell presents the camera UI and
+//! resolves the request with the photo or the decoded code. If the user cancels, or hasn't
+//! granted permission to use the camera, the app receives a [`CameraError`] instead.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The kinds of codes which can be scanned
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BarcodeKind {
+    Qr,
+    Ean13,
+    Code128,
+    DataMatrix,
+}
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CameraOperation {
+    /// Take a photo
+    TakePhoto,
+    /// Scan a code of the given `kind`
+    Scan { kind: BarcodeKind },
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CameraResponse {
+    /// The photo taken, encoded as JPEG
+    Photo {
+        #[serde(with = "serde_bytes")]
+        bytes: Vec<u8>,
+    },
+    /// The value of the scanned code
+    Code { value: String },
+    /// The user dismissed the camera
+    UserCancelled,
+    /// The user hasn't granted permission to use the camera
+    PermissionDenied,
+}
+
+impl std::fmt::Debug for CameraResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CameraResponse::Photo { bytes } => f
+                .debug_struct("Photo")
+                .field("bytes", &format_args!("<{} bytes>", bytes.len()))
+                .finish(),
+            CameraResponse::Code { value } => f.debug_struct("Code").field("value", value).finish(),
+            CameraResponse::UserCancelled => f.write_str("UserCancelled"),
+            CameraResponse::PermissionDenied => f.write_str("PermissionDenied"),
+        }
+    }
+}
+
+impl Operation for CameraOperation {
+    type Output = CameraResponse;
+}
+
+pub type PhotoResult = Result<Vec<u8>, CameraError>;
+pub type ScanResult = Result<String, CameraError>;
+
+/// Error type for camera operations
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Error)]
+#[serde(rename_all = "camelCase")]
+pub enum CameraError {
+    #[error("cancelled by the user")]
+    UserCancelled,
+    #[error("permission to use the camera denied")]
+    PermissionDenied,
+    #[error("unexpected response from the shell")]
+    UnexpectedResponse,
+}
+
+/// The Camera capability API
+///
+/// This capability allows the app to take photos and to scan codes with the device's camera.
+pub struct Camera<Ev> {
+    context: CapabilityContext<CameraOperation, Ev>,
+}
+
+impl<Ev> crux_core::Capability<Ev> for Camera<Ev> {
+    type Operation = CameraOperation;
+    type MappedSelf<MappedEv> = Camera<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static + Send,
+    {
+        Camera::new(self.context.map_event(f))
+    }
+
+    #[cfg(feature = "typegen")]
+    fn register_types(generator: &mut crux_core::typegen::TypeGen) -> crux_core::typegen::Result {
+        generator.register_type::<BarcodeKind>()?;
+        generator.register_type::<CameraError>()?;
+        generator.register_type::<Self::Operation>()?;
+        generator.register_type::<<Self::Operation as Operation>::Output>()?;
+        Ok(())
+    }
+}
+
+impl<Ev> Clone for Camera<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Camera<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<CameraOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Take a photo. The photo, or a [`CameraError`] if none was taken, is passed to the app
+    /// wrapped in the event produced by the `callback`.
+    pub fn take_photo<F>(&self, callback: F)
+    where
+        F: FnOnce(PhotoResult) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.take_photo_async().await));
+            }
+        });
+    }
+
+    /// Take a photo, returning its bytes.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn take_photo_async(&self) -> PhotoResult {
+        match self
+            .context
+            .request_from_shell(CameraOperation::TakePhoto)
+            .await
+        {
+            CameraResponse::Photo { bytes } => Ok(bytes),
+            response => Err(error(response)),
+        }
+    }
+
+    /// Scan a code of the given `kind`. The decoded value, or a [`CameraError`] if no code
+    /// was scanned, is passed to the app wrapped in the event produced by the `callback`.
+    pub fn scan<F>(&self, kind: BarcodeKind, callback: F)
+    where
+        F: FnOnce(ScanResult) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.scan_async(kind).await));
+            }
+        });
+    }
+
+    /// Scan a code of the given `kind`, returning its decoded value.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn scan_async(&self, kind: BarcodeKind) -> ScanResult {
+        match self
+            .context
+            .request_from_shell(CameraOperation::Scan { kind })
+            .await
+        {
+            CameraResponse::Code { value } => Ok(value),
+            response => Err(error(response)),
+        }
+    }
+}
+
+fn error(response: CameraResponse) -> CameraError {
+    match response {
+        CameraResponse::UserCancelled => CameraError::UserCancelled,
+        CameraResponse::PermissionDenied => CameraError::PermissionDenied,
+        CameraResponse::Photo { .. } | CameraResponse::Code { .. } => {
+            CameraError::UnexpectedResponse
+        }
+    }
+}
@@ -0,0 +1,181 @@
+mod shared {
+    use crux_camera::{BarcodeKind, Camera, CameraError, PhotoResult, ScanResult};
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        CaptureReceipt,
+        ScanLoyaltyCard,
+
+        #[serde(skip)]
+        ReceiptCaptured(PhotoResult),
+        #[serde(skip)]
+        CardScanned(ScanResult),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub receipts: Vec<Vec<u8>>,
+        pub loyalty_card: Option<String>,
+        pub error: Option<CameraError>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub receipts: usize,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::CaptureReceipt => caps.camera.take_photo(Event::ReceiptCaptured),
+                Event::ScanLoyaltyCard => caps.camera.scan(BarcodeKind::Ean13, Event::CardScanned),
+                Event::ReceiptCaptured(Ok(bytes)) => {
+                    model.receipts.push(bytes);
+                    caps.render.render();
+                }
+                Event::CardScanned(Ok(value)) => {
+                    model.loyalty_card = Some(value);
+                    caps.render.render();
+                }
+                Event::ReceiptCaptured(Err(error)) | Event::CardScanned(Err(error)) => {
+                    model.error = Some(error);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                receipts: model.receipts.len(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub camera: Camera<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_camera::{BarcodeKind, CameraError, CameraOperation, CameraResponse};
+    use crux_core::{assert_effect, testing::AppTester};
+
+    #[test]
+    pub fn test_take_photo() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::CaptureReceipt, &mut model)
+            .expect_one_effect()
+            .expect_camera();
+
+        assert_eq!(request.operation, CameraOperation::TakePhoto);
+
+        let photo = vec![0xff, 0xd8, 0xff, 0xe0];
+        let event = app
+            .resolve(
+                &mut request,
+                CameraResponse::Photo {
+                    bytes: photo.clone(),
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::ReceiptCaptured(Ok(photo.clone())));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(model.receipts, vec![photo]);
+        assert_eq!(app.view(&model).receipts, 1);
+    }
+
+    #[test]
+    pub fn test_take_photo_cancelled() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::CaptureReceipt, &mut model)
+            .expect_one_effect()
+            .expect_camera();
+
+        let event = app
+            .resolve(&mut request, CameraResponse::UserCancelled)
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(
+            event,
+            Event::ReceiptCaptured(Err(CameraError::UserCancelled))
+        );
+
+        let _update = app.update(event, &mut model);
+        assert!(model.receipts.is_empty());
+        assert_eq!(model.error, Some(CameraError::UserCancelled));
+    }
+
+    #[test]
+    pub fn test_scan() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::ScanLoyaltyCard, &mut model)
+            .expect_one_effect()
+            .expect_camera();
+
+        assert_eq!(
+            request.operation,
+            CameraOperation::Scan {
+                kind: BarcodeKind::Ean13
+            }
+        );
+
+        let event = app
+            .resolve(
+                &mut request,
+                CameraResponse::Code {
+                    value: "4006381333931".to_string(),
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+
+        let _update = app.update(event, &mut model);
+        assert_eq!(model.loyalty_card, Some("4006381333931".to_string()));
+    }
+
+    #[test]
+    pub fn test_scan_permission_denied() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::ScanLoyaltyCard, &mut model)
+            .expect_one_effect()
+            .expect_camera();
+
+        let event = app
+            .resolve(&mut request, CameraResponse::PermissionDenied)
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(
+            event,
+            Event::CardScanned(Err(CameraError::PermissionDenied))
+        );
+    }
+}
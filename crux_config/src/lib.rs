@@ -0,0 +1,118 @@
+//! Configuration supplied by the Shell for Crux apps
+//!
+//! `crux_config` allows Crux apps to read configuration values supplied by the Shell, e.g.
+//! the base URL of an API, feature flags or the build variant. The values are strings keyed
+//! by name, and the app can either ask for a single value, or for all of them at once.
+
+use std::collections::BTreeMap;
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use serde::{Deserialize, Serialize};
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConfigOperation {
+    /// Read the value of the `key`
+    Get { key: String },
+    /// Read all the values
+    GetAll,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConfigResponse {
+    /// Response to a `ConfigOperation::Get`, `None` if the key isn't set
+    Value(Option<String>),
+    /// Response to a `ConfigOperation::GetAll`
+    All(BTreeMap<String, String>),
+}
+
+impl Operation for ConfigOperation {
+    type Output = ConfigResponse;
+}
+
+#[derive(Capability)]
+pub struct Config<Ev> {
+    context: CapabilityContext<ConfigOperation, Ev>,
+}
+
+impl<Ev> Clone for Config<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Config<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<ConfigOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Read the value of the `key`, which will be passed to the app (`None` if the key isn't set)
+    /// wrapped in the event produced by the `callback`.
+    pub fn get<F>(&self, key: impl Into<String>, callback: F)
+    where
+        F: FnOnce(Option<String>) -> Ev + Send + 'static,
+    {
+        let key = key.into();
+
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.get_async(key).await));
+            }
+        });
+    }
+
+    /// Read the value of the `key`, `None` if the key isn't set.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn get_async(&self, key: impl Into<String>) -> Option<String> {
+        let key = key.into();
+
+        match self
+            .context
+            .request_from_shell(ConfigOperation::Get { key: key.clone() })
+            .await
+        {
+            ConfigResponse::Value(value) => value,
+            // the shell answered with all the values, pick the one asked for
+            ConfigResponse::All(mut values) => values.remove(&key),
+        }
+    }
+
+    /// Read all the values, which will be passed to the app wrapped in the event produced
+    /// by the `callback`.
+    pub fn get_all<F>(&self, callback: F)
+    where
+        F: FnOnce(BTreeMap<String, String>) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.get_all_async().await));
+            }
+        });
+    }
+
+    /// Read all the values.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn get_all_async(&self) -> BTreeMap<String, String> {
+        match self
+            .context
+            .request_from_shell(ConfigOperation::GetAll)
+            .await
+        {
+            ConfigResponse::All(values) => values,
+            // a single value doesn't say which key it belongs to
+            ConfigResponse::Value(_) => BTreeMap::new(),
+        }
+    }
+}
@@ -0,0 +1,157 @@
+mod shared {
+    use std::collections::BTreeMap;
+
+    use crux_config::Config;
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Configure,
+        LoadFlags,
+
+        #[serde(skip)]
+        SetApiUrl(Option<String>),
+        #[serde(skip)]
+        SetFlags(BTreeMap<String, String>),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub api_url: String,
+        pub flags: BTreeMap<String, String>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub api_url: String,
+    }
+
+    pub const DEFAULT_API_URL: &str = "https://api.example.com";
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Configure => caps.config.get("api_url", Event::SetApiUrl),
+                Event::LoadFlags => caps.config.get_all(Event::SetFlags),
+                Event::SetApiUrl(url) => {
+                    model.api_url = url.unwrap_or_else(|| DEFAULT_API_URL.to_string());
+                    caps.render.render();
+                }
+                Event::SetFlags(flags) => {
+                    model.flags = flags;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                api_url: model.api_url.clone(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub config: Config<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::shared::{App, Effect, Event, Model, DEFAULT_API_URL};
+    use crux_config::{ConfigOperation, ConfigResponse};
+    use crux_core::{assert_effect, testing::AppTester};
+
+    #[test]
+    pub fn test_get() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Configure, &mut model)
+            .expect_one_effect()
+            .expect_config();
+
+        assert_eq!(
+            request.operation,
+            ConfigOperation::Get {
+                key: "api_url".to_string()
+            }
+        );
+
+        let event = app
+            .resolve(
+                &mut request,
+                ConfigResponse::Value(Some("https://staging.example.com".to_string())),
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(
+            event,
+            Event::SetApiUrl(Some("https://staging.example.com".to_string()))
+        );
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(app.view(&model).api_url, "https://staging.example.com");
+    }
+
+    #[test]
+    pub fn test_get_missing_key() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Configure, &mut model)
+            .expect_one_effect()
+            .expect_config();
+
+        let event = app
+            .resolve(&mut request, ConfigResponse::Value(None))
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::SetApiUrl(None));
+
+        let _update = app.update(event, &mut model);
+        assert_eq!(app.view(&model).api_url, DEFAULT_API_URL);
+    }
+
+    #[test]
+    pub fn test_get_all() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::LoadFlags, &mut model)
+            .expect_one_effect()
+            .expect_config();
+
+        assert_eq!(request.operation, ConfigOperation::GetAll);
+
+        let flags = BTreeMap::from([
+            ("build_variant".to_string(), "debug".to_string()),
+            ("new_onboarding".to_string(), "true".to_string()),
+        ]);
+        let event = app
+            .resolve(&mut request, ConfigResponse::All(flags.clone()))
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::SetFlags(flags.clone()));
+
+        let _update = app.update(event, &mut model);
+        assert_eq!(model.flags, flags);
+    }
+}
@@ -0,0 +1,130 @@
+//! Network connectivity status for Crux apps
+//!
+//! `crux_connectivity` allows Crux apps to ask the Shell whether the device is online, and over
+//! which kind of connection. The status can be read once with [`Connectivity::status`], or watched
+//! with [`Connectivity::watch`], in which case the Shell resolves the request every time the status
+//! changes.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectivityOperation {
+    /// Get the current connectivity status
+    Status,
+    /// Get the connectivity status every time it changes
+    Watch,
+}
+
+/// The kind of network connection the device is using
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionKind {
+    Wifi,
+    Cellular,
+    None,
+    Unknown,
+}
+
+/// The connectivity status of the device
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectivityStatus {
+    pub online: bool,
+    pub connection: ConnectionKind,
+}
+
+impl Operation for ConnectivityOperation {
+    type Output = ConnectivityStatus;
+}
+
+/// The Connectivity capability API
+///
+/// This capability provides access to the network connectivity status of the device.
+pub struct Connectivity<Ev> {
+    context: CapabilityContext<ConnectivityOperation, Ev>,
+}
+
+impl<Ev> crux_core::Capability<Ev> for Connectivity<Ev> {
+    type Operation = ConnectivityOperation;
+    type MappedSelf<MappedEv> = Connectivity<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static + Send,
+    {
+        Connectivity::new(self.context.map_event(f))
+    }
+
+    #[cfg(feature = "typegen")]
+    fn register_types(generator: &mut crux_core::typegen::TypeGen) -> crux_core::typegen::Result {
+        generator.register_type::<ConnectionKind>()?;
+        generator.register_type::<Self::Operation>()?;
+        generator.register_type::<<Self::Operation as Operation>::Output>()?;
+        Ok(())
+    }
+}
+
+impl<Ev> Clone for Connectivity<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Connectivity<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<ConnectivityOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Request the current connectivity status, which will be passed to the app
+    /// wrapped in the event produced by the `callback`.
+    pub fn status<F>(&self, callback: F)
+    where
+        F: FnOnce(ConnectivityStatus) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.status_async().await));
+            }
+        });
+    }
+
+    /// Request the current connectivity status.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn status_async(&self) -> ConnectivityStatus {
+        self.context
+            .request_from_shell(ConnectivityOperation::Status)
+            .await
+    }
+
+    /// Watch the connectivity status. Every time it changes, the new status is passed
+    /// to the app wrapped in the event produced by the `callback`.
+    pub fn watch<F>(&self, callback: F)
+    where
+        F: Fn(ConnectivityStatus) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                let mut stream = context.stream_from_shell(ConnectivityOperation::Watch);
+
+                while let Some(status) = stream.next().await {
+                    context.update_app(callback(status));
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,141 @@
+mod shared {
+    use crux_connectivity::{Connectivity, ConnectivityStatus};
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Start,
+        CheckStatus,
+        Save(String),
+
+        #[serde(skip)]
+        ConnectivityChanged(ConnectivityStatus),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub online: bool,
+        pub queued: Vec<String>,
+        pub saved: Vec<String>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub online: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Start => caps.connectivity.watch(Event::ConnectivityChanged),
+                Event::CheckStatus => caps.connectivity.status(Event::ConnectivityChanged),
+                Event::Save(note) if model.online => model.saved.push(note),
+                Event::Save(note) => model.queued.push(note),
+                Event::ConnectivityChanged(status) => {
+                    model.online = status.online;
+                    if model.online {
+                        let queued = std::mem::take(&mut model.queued);
+                        model.saved.extend(queued);
+                    }
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                online: model.online,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub connectivity: Connectivity<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_connectivity::{ConnectionKind, ConnectivityOperation, ConnectivityStatus};
+    use crux_core::{assert_effect, testing::AppTester};
+
+    #[test]
+    pub fn test_offline_to_online() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Start, &mut model)
+            .expect_one_effect()
+            .expect_connectivity();
+        assert_eq!(request.operation, ConnectivityOperation::Watch);
+
+        let offline = ConnectivityStatus {
+            online: false,
+            connection: ConnectionKind::None,
+        };
+        let event = app
+            .resolve(&mut request, offline)
+            .expect("to resolve")
+            .expect_one_event();
+        assert_eq!(event, Event::ConnectivityChanged(offline));
+        let _update = app.update(event, &mut model);
+
+        app.update(Event::Save("note".to_string()), &mut model)
+            .assert_empty();
+        assert_eq!(model.queued, vec!["note".to_string()]);
+
+        let online = ConnectivityStatus {
+            online: true,
+            connection: ConnectionKind::Wifi,
+        };
+        let event = app
+            .resolve(&mut request, online)
+            .expect("to resolve")
+            .expect_one_event();
+        assert_eq!(event, Event::ConnectivityChanged(online));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+
+        assert!(model.queued.is_empty());
+        assert_eq!(model.saved, vec!["note".to_string()]);
+    }
+
+    #[test]
+    pub fn test_status() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::CheckStatus, &mut model)
+            .expect_one_effect()
+            .expect_connectivity();
+        assert_eq!(request.operation, ConnectivityOperation::Status);
+
+        let status = ConnectivityStatus {
+            online: true,
+            connection: ConnectionKind::Cellular,
+        };
+        let event = app
+            .resolve(&mut request, status)
+            .expect("to resolve")
+            .expect_one_event();
+        assert_eq!(event, Event::ConnectivityChanged(status));
+
+        // the status is only read once
+        assert!(!request.expects_response());
+    }
+}
@@ -0,0 +1,168 @@
+//! Read access to the device contacts for Crux apps
+//!
+//! `crux_contacts` allows Crux apps to read the contacts stored on the device, either all
+//! of them or a single one by its identifier, using the platform's native contacts store.
+//! When the user hasn't granted access to the contacts, the request resolves with
+//! [`ContactsError::PermissionDenied`].
+
+use crux_core::capability::{CapabilityContext, Operation};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A contact stored on the device
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contact {
+    pub id: String,
+    pub name: String,
+    pub phone_numbers: Vec<String>,
+    pub emails: Vec<String>,
+}
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContactsOperation {
+    /// Read all the contacts
+    Fetch,
+    /// Read the contact with the id
+    FetchOne { id: String },
+}
+
+/// The result of reading contacts.
+///
+/// Note: we can't use `Result` here because generics are not currently
+/// supported across the FFI boundary, when using the builtin typegen.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContactsResult {
+    Ok { contacts: Vec<Contact> },
+    Err { error: ContactsError },
+}
+
+/// Error type for contacts operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Error)]
+#[serde(rename_all = "camelCase")]
+pub enum ContactsError {
+    #[error("access to the contacts was denied")]
+    PermissionDenied,
+    #[error("contact {id} not found")]
+    NotFound { id: String },
+    #[error("other error: {message}")]
+    Other { message: String },
+}
+
+impl From<ContactsResult> for Result<Vec<Contact>, ContactsError> {
+    fn from(result: ContactsResult) -> Self {
+        match result {
+            ContactsResult::Ok { contacts } => Ok(contacts),
+            ContactsResult::Err { error } => Err(error),
+        }
+    }
+}
+
+impl Operation for ContactsOperation {
+    type Output = ContactsResult;
+}
+
+pub struct Contacts<Ev> {
+    context: CapabilityContext<ContactsOperation, Ev>,
+}
+
+impl<Ev> crux_core::Capability<Ev> for Contacts<Ev> {
+    type Operation = ContactsOperation;
+
+    type MappedSelf<MappedEv> = Contacts<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static + Send,
+    {
+        Contacts::new(self.context.map_event(f))
+    }
+
+    #[cfg(feature = "typegen")]
+    fn register_types(generator: &mut crux_core::typegen::TypeGen) -> crux_core::typegen::Result {
+        generator.register_type::<Contact>()?;
+        generator.register_type::<ContactsError>()?;
+        generator.register_type::<Self::Operation>()?;
+        generator.register_type::<<Self::Operation as Operation>::Output>()?;
+        Ok(())
+    }
+}
+
+impl<Ev> Clone for Contacts<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Contacts<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<ContactsOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Read all the contacts on the device, dispatching the event from `make_event`
+    /// with the contacts, or the error if access to them was denied
+    pub fn fetch<F>(&self, make_event: F)
+    where
+        F: FnOnce(Result<Vec<Contact>, ContactsError>) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(make_event(this.fetch_async().await));
+            }
+        });
+    }
+
+    /// Read all the contacts on the device.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn fetch_async(&self) -> Result<Vec<Contact>, ContactsError> {
+        self.context
+            .request_from_shell(ContactsOperation::Fetch)
+            .await
+            .into()
+    }
+
+    /// Read the contact with the `id`, dispatching the event from `make_event`
+    /// with the contact, or the error if it wasn't found or access was denied
+    pub fn fetch_one<F>(&self, id: impl Into<String>, make_event: F)
+    where
+        F: FnOnce(Result<Contact, ContactsError>) -> Ev + Send + 'static,
+    {
+        let id = id.into();
+
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(make_event(this.fetch_one_async(id).await));
+            }
+        });
+    }
+
+    /// Read the contact with the `id`.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn fetch_one_async(&self, id: impl Into<String>) -> Result<Contact, ContactsError> {
+        let id = id.into();
+
+        let contacts: Result<Vec<Contact>, ContactsError> = self
+            .context
+            .request_from_shell(ContactsOperation::FetchOne { id: id.clone() })
+            .await
+            .into();
+
+        contacts?
+            .into_iter()
+            .next()
+            .ok_or(ContactsError::NotFound { id })
+    }
+}
@@ -0,0 +1,168 @@
+mod shared {
+    use crux_contacts::{Contact, Contacts, ContactsError};
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        LoadFriends,
+        LoadFriend(String),
+
+        #[serde(skip)]
+        Friends(Result<Vec<Contact>, ContactsError>),
+        #[serde(skip)]
+        Friend(Result<Contact, ContactsError>),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub friends: Vec<Contact>,
+        pub error: Option<ContactsError>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub names: Vec<String>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::LoadFriends => caps.contacts.fetch(Event::Friends),
+                Event::LoadFriend(id) => caps.contacts.fetch_one(id, Event::Friend),
+                Event::Friends(Ok(contacts)) => {
+                    model.friends = contacts;
+                    caps.render.render();
+                }
+                Event::Friend(Ok(contact)) => {
+                    model.friends = vec![contact];
+                    caps.render.render();
+                }
+                Event::Friends(Err(error)) | Event::Friend(Err(error)) => {
+                    model.error = Some(error);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                names: model.friends.iter().map(|c| c.name.clone()).collect(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub contacts: Contacts<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_contacts::{Contact, ContactsError, ContactsOperation, ContactsResult};
+    use crux_core::{assert_effect, testing::AppTester};
+
+    fn contact(id: &str, name: &str) -> Contact {
+        Contact {
+            id: id.to_string(),
+            name: name.to_string(),
+            phone_numbers: vec!["+44 20 7946 0000".to_string()],
+            emails: vec![format!("{id}@example.com")],
+        }
+    }
+
+    #[test]
+    pub fn test_fetch() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::LoadFriends, &mut model)
+            .expect_one_effect()
+            .expect_contacts();
+
+        assert_eq!(request.operation, ContactsOperation::Fetch);
+
+        let contacts = vec![contact("ada", "Ada"), contact("grace", "Grace")];
+        let event = app
+            .resolve(
+                &mut request,
+                ContactsResult::Ok {
+                    contacts: contacts.clone(),
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Friends(Ok(contacts)));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(app.view(&model).names, vec!["Ada", "Grace"]);
+    }
+
+    #[test]
+    pub fn test_fetch_denied() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::LoadFriends, &mut model)
+            .expect_one_effect()
+            .expect_contacts();
+
+        let event = app
+            .resolve(
+                &mut request,
+                ContactsResult::Err {
+                    error: ContactsError::PermissionDenied,
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Friends(Err(ContactsError::PermissionDenied)));
+
+        let _ = app.update(event, &mut model);
+        assert_eq!(model.error, Some(ContactsError::PermissionDenied));
+        assert!(model.friends.is_empty());
+    }
+
+    #[test]
+    pub fn test_fetch_one() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::LoadFriend("ada".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_contacts();
+
+        assert_eq!(
+            request.operation,
+            ContactsOperation::FetchOne {
+                id: "ada".to_string()
+            }
+        );
+
+        let event = app
+            .resolve(
+                &mut request,
+                ContactsResult::Ok {
+                    contacts: vec![contact("ada", "Ada")],
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Friend(Ok(contact("ada", "Ada"))));
+    }
+}
@@ -1,20 +1,37 @@
+mod protocol;
 mod registry;
 mod request_serde;
 
+use std::sync::Mutex;
+
 use bincode::{DefaultOptions, Options};
 use erased_serde::Serialize as _;
-use serde::{Deserialize, Serialize};
+use serde::{de::value::SeqDeserializer, ser::SerializeStruct, Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::Effect;
-use crate::{App, Core};
+use crate::{App, Core, ResolveError, ShellError};
+pub use protocol::{ProtocolError, ProtocolVersion};
 use registry::{EffectId, ResolveRegistry};
 // ResolveByte is public to be accessible from crux_macros
+use request_serde::EmptyResponse;
 #[doc(hidden)]
 pub use request_serde::ResolveSerialized;
 
 /// Request for a side-effect passed from the Core to the Shell. The `EffectId` links
 /// the `Request` with the corresponding call to [`Core::resolve`] to pass the data back
 /// to the [`App::update`] function (wrapped in the event provided to the capability originating the effect).
+///
+/// The `Request` can be serialized and persisted by the Shell, for example to complete a background
+/// download while the app is suspended. The capability task waiting for its output lives in memory
+/// and doesn't survive a restart though, so the `id` is only valid for the core which issued it.
+/// After a relaunch, the app has to issue the effect again (typically from its startup event), and
+/// the Shell resolves the new request carrying the same `effect` using the new `id`.
+///
+/// Only the `id` and the `effect` are sent to shells which didn't negotiate a protocol version
+/// with [`Bridge::handshake`]. The other fields are sent once the shell negotiated a version
+/// which has them, so shells using the types generated by the `typegen` feature should do the
+/// handshake with [`ProtocolVersion::CURRENT`] on startup.
 // used in docs/internals/bridge.md
 // ANCHOR: request
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,9 +41,117 @@ where
 {
     pub id: EffectId,
     pub effect: Eff,
+    /// The version of the effect's operation, see
+    /// [`Operation::VERSION`](crate::capability::Operation::VERSION). A shell built against
+    /// an older version of the operation reports the request with [`Bridge::handle_unsupported`].
+    /// Only sent to shells which negotiated protocol version 2.0 or later with
+    /// [`Bridge::handshake`], requests without it are for the first version.
+    #[serde(default = "first_version")]
+    pub version: u32,
+    /// The label the capability attached to the request, if any, to correlate it in logs
+    /// and traces, see [`Request::tag`](crate::Request::tag). Only sent to shells which
+    /// negotiated protocol version 1.1 or later with [`Bridge::handshake`].
+    pub tag: Option<String>,
+    /// The id of the request the shell should wait for to be resolved before carrying out
+    /// this one, if it depends on another request, see
+    /// [`Request::depends_on`](crate::Request::depends_on). Only sent to shells which
+    /// negotiated protocol version 1.2 or later with [`Bridge::handshake`].
+    pub depends_on: Option<EffectId>,
 }
 // ANCHOR_END: request
 
+fn first_version() -> u32 {
+    1
+}
+
+/// A [`Request`] as it's sent to the shell, with only the fields the protocol version
+/// negotiated with the shell has, so that shells which didn't negotiate a version keep
+/// receiving requests in the original format.
+struct WireRequest<Eff>
+where
+    Eff: Serialize,
+{
+    request: Request<Eff>,
+    protocol: Option<ProtocolVersion>,
+}
+
+impl<Eff> Serialize for WireRequest<Eff>
+where
+    Eff: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let supports = |since| self.protocol.map_or(false, |protocol| protocol >= since);
+        let tag = supports(ProtocolVersion::REQUEST_TAG);
+        let depends_on = supports(ProtocolVersion::REQUEST_DEPENDENCY);
+        let version = supports(ProtocolVersion::REQUEST_VERSION);
+
+        let len = 2 + usize::from(version) + usize::from(tag) + usize::from(depends_on);
+        let mut request = serializer.serialize_struct("Request", len)?;
+        request.serialize_field("id", &self.request.id)?;
+        request.serialize_field("effect", &self.request.effect)?;
+        if version {
+            request.serialize_field("version", &self.request.version)?;
+        } else {
+            request.skip_field("version")?;
+        }
+        if tag {
+            request.serialize_field("tag", &self.request.tag)?;
+        } else {
+            request.skip_field("tag")?;
+        }
+        if depends_on {
+            request.serialize_field("depends_on", &self.request.depends_on)?;
+        } else {
+            request.skip_field("depends_on")?;
+        }
+        request.end()
+    }
+}
+
+/// A message from the shell which couldn't be deserialized, e.g. because the shell was built
+/// against different types. The `id` is the request the message responded to, if any.
+/// Messages from a shell which failed the [`Bridge::handshake`] have no `id`, none of them
+/// can be deserialized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    pub id: Option<u32>,
+    pub message: String,
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.id {
+            Some(id) => write!(
+                f,
+                "Response to request {id} could not be deserialized: {}",
+                self.message
+            ),
+            None => write!(f, "Message could not be deserialized: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+type DecodeErrorHandler<Ev> = Box<dyn Fn(DecodeError) -> Ev + Send + Sync>;
+
+/// A request the shell reported it doesn't support, e.g. because the shell was built
+/// against an older version of the core, which didn't have the effect yet, or had an older
+/// version of it.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Request {id} for version {version} of the {effect} effect is not supported by the shell")]
+pub struct UnsupportedEffect {
+    pub id: u32,
+    /// The name of the effect, e.g. `"Http"`
+    pub effect: String,
+    pub version: u32,
+}
+
+type UnsupportedEffectHandler<Ev> = Box<dyn Fn(UnsupportedEffect) -> Ev + Send + Sync>;
+
 /// Bridge is a core wrapper presenting the same interface as the [`Core`] but in a
 /// serialized form, using bincode as the serialization format.
 pub struct Bridge<Eff, A>
@@ -49,6 +174,53 @@ where
         }
     }
 
+    /// Speak the `version` of the protocol, instead of [`ProtocolVersion::CURRENT`],
+    /// e.g. to reject shells generated for an older version of the app's types.
+    pub fn with_protocol_version(self, version: ProtocolVersion) -> Self {
+        Self {
+            inner: self.inner.with_protocol_version(version),
+        }
+    }
+
+    /// Pass a response which can't be deserialized to the app, as the event produced by the
+    /// `handler`, see [`BridgeWithSerializer::with_decode_error_handler`].
+    pub fn with_decode_error_handler<F>(self, handler: F) -> Self
+    where
+        F: Fn(DecodeError) -> A::Event + Send + Sync + 'static,
+    {
+        Self {
+            inner: self.inner.with_decode_error_handler(handler),
+        }
+    }
+
+    /// Pass a request the shell doesn't support to the app, as the event produced by the
+    /// `handler`, see [`BridgeWithSerializer::with_unsupported_effect_handler`].
+    pub fn with_unsupported_effect_handler<F>(self, handler: F) -> Self
+    where
+        F: Fn(UnsupportedEffect) -> A::Event + Send + Sync + 'static,
+    {
+        Self {
+            inner: self.inner.with_unsupported_effect_handler(handler),
+        }
+    }
+
+    /// Negotiate the protocol version with the shell, on startup.
+    ///
+    /// The `shell_version` is the serialized [`ProtocolVersion`] the shell speaks. Returns the
+    /// serialized version both sides will speak, or an error if the versions are incompatible.
+    /// Once the handshake failed, the messages from the shell are dropped and passed to the
+    /// app as a [`DecodeError`], see [`Bridge::with_decode_error_handler`].
+    pub fn handshake(&self, shell_version: &[u8]) -> Result<Vec<u8>, ProtocolError> {
+        let options = Self::bincode_options();
+
+        let mut deser = bincode::Deserializer::from_slice(shell_version, options);
+        let version = self.inner.handshake(&mut deser)?;
+
+        Ok(options
+            .serialize(&version)
+            .expect("Protocol version should serialize"))
+    }
+
     /// Receive an event from the shell.
     ///
     /// The `event` is serialized and will be deserialized by the core before it's passed
@@ -72,6 +244,10 @@ where
     /// Receive a response to a capability request from the shell.
     ///
     /// The `output` is serialized capability output. It will be deserialized by the core.
+    /// For operations with [`RAW_OUTPUT`](crate::capability::Operation::RAW_OUTPUT), the `output`
+    /// is the raw bytes of the output instead. An empty `output` only resolves operations with
+    /// a unit output. An `output` which can't be deserialized doesn't resolve the request, see
+    /// [`Bridge::with_decode_error_handler`].
     /// The `id` MUST match the `id` of the effect that triggered it, else the core will panic.
     // used in docs/internals/bridge.md
     // ANCHOR: handle_response_sig
@@ -80,14 +256,94 @@ where
     where
         A::Event: for<'a> Deserialize<'a>,
     {
+        self.respond(id, output, false)
+    }
+
+    /// Receive the last response to a capability request from the shell.
+    ///
+    /// This is the same as [`Bridge::handle_response`], except that a request expecting a
+    /// stream of responses (e.g. a subscription) is closed afterwards, ending the stream for
+    /// the capability, see [`Core::resolve_final`](crate::Core::resolve_final).
+    pub fn handle_response_final(&self, id: u32, output: &[u8]) -> Vec<u8>
+    where
+        A::Event: for<'a> Deserialize<'a>,
+    {
+        self.respond(id, output, true)
+    }
+
+    fn respond(&self, id: u32, output: &[u8], close: bool) -> Vec<u8>
+    where
+        A::Event: for<'a> Deserialize<'a>,
+    {
+        let options = Self::bincode_options();
+
+        let mut return_buffer = vec![];
+        let mut ser = bincode::Serializer::new(&mut return_buffer, options);
+
+        if self.inner.registry.has_raw_output(EffectId(id)) {
+            let deser = SeqDeserializer::<_, serde::de::value::Error>::new(output.iter().copied());
+
+            self.inner.respond(id, deser, &mut ser, close);
+        } else if output.is_empty() {
+            // an operation with a unit output can be completed without a response body
+            self.inner.respond(id, EmptyResponse, &mut ser, close);
+        } else {
+            let mut deser = bincode::Deserializer::from_slice(output, options);
+
+            self.inner.respond(id, &mut deser, &mut ser, close);
+        }
+
+        return_buffer
+    }
+
+    /// Receive a response to a capability request from the shell, serialized in a format
+    /// different from the operation's current output type.
+    ///
+    /// The `migrate` function is given the `output` as sent by the shell and returns the bytes
+    /// of the current output type, which are then handled the same way as in [`Bridge::handle_response`].
+    /// This allows a core to accept responses from a shell built against an older version of
+    /// the operation's output, e.g. while the shells are being updated.
+    pub fn handle_response_with_migrator<F>(&self, id: u32, output: &[u8], migrate: F) -> Vec<u8>
+    where
+        A::Event: for<'a> Deserialize<'a>,
+        F: FnOnce(Vec<u8>) -> Vec<u8>,
+    {
+        self.handle_response(id, &migrate(output.to_vec()))
+    }
+
+    /// Receive a failure to carry out a capability request from the shell.
+    ///
+    /// The `error` is a serialized [`ShellError`]. Only requests sent by the capability as
+    /// fallible can be failed, otherwise the core will panic. An `error` which can't be
+    /// deserialized doesn't fail the request, see [`Bridge::with_decode_error_handler`].
+    /// The `id` MUST match the `id` of the effect that triggered it, else the core will panic.
+    pub fn handle_failure(&self, id: u32, error: &[u8]) -> Vec<u8> {
         let options = Self::bincode_options();
 
-        let mut deser = bincode::Deserializer::from_slice(output, options);
+        let mut deser = bincode::Deserializer::from_slice(error, options);
 
         let mut return_buffer = vec![];
         let mut ser = bincode::Serializer::new(&mut return_buffer, options);
 
-        self.inner.handle_response(id, &mut deser, &mut ser);
+        self.inner.handle_failure(id, &mut deser, &mut ser);
+
+        return_buffer
+    }
+
+    /// Receive a report from the shell that it doesn't support the request with the `id`,
+    /// e.g. because its effect is newer than the shell, or its
+    /// [`version`](Request::version) is higher than the shell knows.
+    ///
+    /// The request is dropped, see [`BridgeWithSerializer::with_unsupported_effect_handler`]
+    /// for how the app finds out.
+    /// The `id` MUST match the `id` of the effect that triggered it, else the core will panic.
+    pub fn handle_unsupported(&self, id: u32) -> Vec<u8> {
+        let options = Self::bincode_options();
+
+        let mut return_buffer = vec![];
+        let mut ser = bincode::Serializer::new(&mut return_buffer, options);
+
+        self.inner.handle_unsupported(id, &mut ser);
 
         return_buffer
     }
@@ -104,6 +360,22 @@ where
         return_buffer
     }
 
+    /// Get the changes to the app's view model since the last call to `view_diff`
+    /// (serialized), see [`Core::view_diff`].
+    pub fn view_diff(&self) -> Vec<u8>
+    where
+        A::ViewModel: Serialize,
+    {
+        let options = Self::bincode_options();
+
+        let mut return_buffer = vec![];
+
+        self.inner
+            .view_diff(&mut bincode::Serializer::new(&mut return_buffer, options));
+
+        return_buffer
+    }
+
     fn bincode_options() -> impl bincode::Options + Copy {
         DefaultOptions::new()
             .with_fixint_encoding()
@@ -129,6 +401,10 @@ where
 {
     core: Core<Eff, A>,
     registry: ResolveRegistry,
+    version: ProtocolVersion,
+    negotiated: Mutex<Option<Result<ProtocolVersion, ProtocolError>>>,
+    decode_error: Option<DecodeErrorHandler<A::Event>>,
+    unsupported_effect: Option<UnsupportedEffectHandler<A::Event>>,
 }
 // ANCHOR_END: bridge_with_serializer
 
@@ -141,6 +417,96 @@ where
         Self {
             core,
             registry: Default::default(),
+            version: ProtocolVersion::CURRENT,
+            negotiated: Mutex::new(None),
+            decode_error: None,
+            unsupported_effect: None,
+        }
+    }
+
+    /// Speak the `version` of the protocol, instead of [`ProtocolVersion::CURRENT`].
+    pub fn with_protocol_version(mut self, version: ProtocolVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Pass a response which can't be deserialized into the output of its request to the
+    /// app, as the event produced by the `handler`, instead of dropping it.
+    ///
+    /// A fallible request is failed with the [`DecodeError`] message instead, so that the
+    /// capability which sent it can report it. Otherwise, the capability waiting for the
+    /// output never continues, as if the shell never responded, and a request expecting
+    /// many responses stays open for the next one.
+    ///
+    /// Once the [`handshake`](Self::handshake) failed, every message from the shell is dropped
+    /// and passed to the `handler` as a [`DecodeError`] without an `id`.
+    pub fn with_decode_error_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(DecodeError) -> A::Event + Send + Sync + 'static,
+    {
+        self.decode_error = Some(Box::new(handler));
+        self
+    }
+
+    /// Pass a request the shell doesn't support to the app, as the event produced by the
+    /// `handler`, instead of dropping it silently.
+    ///
+    /// A fallible request is failed with the [`UnsupportedEffect`] message instead, so that
+    /// the capability which sent it can report it. Otherwise, the capability waiting for the
+    /// output never continues, as if the shell never responded.
+    pub fn with_unsupported_effect_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(UnsupportedEffect) -> A::Event + Send + Sync + 'static,
+    {
+        self.unsupported_effect = Some(Box::new(handler));
+        self
+    }
+
+    /// Negotiate the protocol version with a shell speaking the `shell_version`, see
+    /// [`Bridge::handshake`].
+    pub fn handshake<'de, D>(&self, shell_version: D) -> Result<ProtocolVersion, ProtocolError>
+    where
+        D: ::serde::de::Deserializer<'de>,
+    {
+        let shell_version = ProtocolVersion::deserialize(shell_version)
+            .map_err(|e| ProtocolError::InvalidVersion(e.to_string()));
+
+        self.negotiate(shell_version)
+    }
+
+    /// The protocol version negotiated with the shell, if the handshake succeeded
+    pub fn protocol_version(&self) -> Option<ProtocolVersion> {
+        match *self.negotiated.lock().expect("Protocol lock poisoned") {
+            Some(Ok(version)) => Some(version),
+            _ => None,
+        }
+    }
+
+    fn negotiate(
+        &self,
+        shell_version: Result<ProtocolVersion, ProtocolError>,
+    ) -> Result<ProtocolVersion, ProtocolError> {
+        let negotiated = shell_version.and_then(|shell| self.version.negotiate(shell));
+        *self.negotiated.lock().expect("Protocol lock poisoned") = Some(negotiated.clone());
+
+        negotiated
+    }
+
+    // Messages from a shell which failed the handshake would only deserialize into garbage
+    fn check_protocol(&self) -> Result<(), DecodeError> {
+        match &*self.negotiated.lock().expect("Protocol lock poisoned") {
+            Some(Err(error)) => Err(DecodeError {
+                id: None,
+                message: format!("Bridge protocol negotiation failed: {error}"),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn decode_failed(&self, error: DecodeError) -> Vec<Eff> {
+        match &self.decode_error {
+            Some(handler) => self.core.process_event(handler(error)),
+            None => self.core.process(),
         }
     }
 
@@ -159,6 +525,7 @@ where
             None,
             &mut erased_de,
             &mut <dyn erased_serde::Serializer>::erase(requests_out),
+            false,
         );
     }
 
@@ -167,6 +534,26 @@ where
     /// The `output` is serialized capability output. It will be deserialized by the core.
     /// The `id` MUST match the `id` of the effect that triggered it, else the core will panic.
     pub fn handle_response<'de, D, S>(&self, id: u32, response: D, requests_out: S)
+    where
+        for<'a> A::Event: Deserialize<'a>,
+        D: ::serde::de::Deserializer<'de>,
+        S: ::serde::ser::Serializer,
+    {
+        self.respond(id, response, requests_out, false);
+    }
+
+    /// Receive the last response to a capability request from the shell, see
+    /// [`Bridge::handle_response_final`].
+    pub fn handle_response_final<'de, D, S>(&self, id: u32, response: D, requests_out: S)
+    where
+        for<'a> A::Event: Deserialize<'a>,
+        D: ::serde::de::Deserializer<'de>,
+        S: ::serde::ser::Serializer,
+    {
+        self.respond(id, response, requests_out, true);
+    }
+
+    fn respond<'de, D, S>(&self, id: u32, response: D, requests_out: S, close: bool)
     where
         for<'a> A::Event: Deserialize<'a>,
         D: ::serde::de::Deserializer<'de>,
@@ -177,6 +564,72 @@ where
             Some(EffectId(id)),
             &mut erased_response,
             &mut <dyn erased_serde::Serializer>::erase(requests_out),
+            close,
+        );
+    }
+
+    /// Receive a failure to carry out a capability request from the shell.
+    ///
+    /// The `error` is a serialized [`ShellError`]. Only requests sent by the capability as
+    /// fallible can be failed, otherwise the core will panic. An `error` which can't be
+    /// deserialized doesn't fail the request, see [`BridgeWithSerializer::with_decode_error_handler`].
+    /// The `id` MUST match the `id` of the effect that triggered it, else the core will panic.
+    pub fn handle_failure<'de, D, S>(&self, id: u32, error: D, requests_out: S)
+    where
+        D: ::serde::de::Deserializer<'de>,
+        S: ::serde::ser::Serializer,
+    {
+        let effects = match self.check_protocol() {
+            Ok(()) => match ShellError::deserialize(error) {
+                Ok(error) => {
+                    self.registry
+                        .fail(EffectId(id), error)
+                        .expect("Failure could not be handled. The request can't fail.");
+
+                    self.core.process()
+                }
+                Err(error) => self.decode_failed(DecodeError {
+                    id: Some(id),
+                    message: error.to_string(),
+                }),
+            },
+            Err(error) => self.decode_failed(error),
+        };
+
+        self.serialize_requests(
+            effects,
+            &mut <dyn erased_serde::Serializer>::erase(requests_out),
+        );
+    }
+
+    /// Receive a report from the shell that it doesn't support the request with the `id`,
+    /// see [`Bridge::handle_unsupported`].
+    pub fn handle_unsupported<S>(&self, id: u32, requests_out: S)
+    where
+        S: ::serde::ser::Serializer,
+    {
+        if let Err(error) = self.check_protocol() {
+            let effects = self.decode_failed(error);
+            self.serialize_requests(
+                effects,
+                &mut <dyn erased_serde::Serializer>::erase(requests_out),
+            );
+            return;
+        }
+
+        let (unsupported, mut resolve) = self.registry.reject(EffectId(id));
+
+        let effects = match resolve.fail(ShellError::new(unsupported.to_string())) {
+            Ok(()) => self.core.process(),
+            Err(_) => match &self.unsupported_effect {
+                Some(handler) => self.core.process_event(handler(unsupported)),
+                None => self.core.process(),
+            },
+        };
+
+        self.serialize_requests(
+            effects,
+            &mut <dyn erased_serde::Serializer>::erase(requests_out),
         );
     }
 
@@ -185,9 +638,16 @@ where
         id: Option<EffectId>,
         data: &mut dyn erased_serde::Deserializer,
         requests_out: &mut dyn erased_serde::Serializer,
+        close: bool,
     ) where
         A::Event: for<'a> Deserialize<'a>,
     {
+        if let Err(error) = self.check_protocol() {
+            let effects = self.decode_failed(error);
+            self.serialize_requests(effects, requests_out);
+            return;
+        }
+
         let effects = match id {
             None => {
                 let shell_event =
@@ -196,17 +656,42 @@ where
                 self.core.process_event(shell_event)
             }
             Some(id) => {
-                self.registry.resume(id, data).expect(
-                    "Response could not be handled. The request did not expect a response.",
-                );
+                let resumed = if close {
+                    self.registry.resume_final(id, data)
+                } else {
+                    self.registry.resume(id, data)
+                };
 
-                self.core.process()
+                match resumed {
+                    Ok(()) => self.core.process(),
+                    Err(ResolveError::Deserialization(message)) => {
+                        self.decode_failed(DecodeError {
+                            id: Some(id.0),
+                            message,
+                        })
+                    }
+                    Err(_) => panic!(
+                        "Response could not be handled. The request did not expect a response."
+                    ),
+                }
             }
         };
 
+        self.serialize_requests(effects, requests_out);
+    }
+
+    fn serialize_requests(
+        &self,
+        effects: Vec<Eff>,
+        requests_out: &mut dyn erased_serde::Serializer,
+    ) {
+        let protocol = self.protocol_version();
         let requests: Vec<_> = effects
             .into_iter()
-            .map(|eff| self.registry.register(eff))
+            .map(|eff| WireRequest {
+                request: self.registry.register(eff),
+                protocol,
+            })
             .collect();
 
         requests
@@ -224,4 +709,17 @@ where
             .erased_serialize(&mut <dyn erased_serde::Serializer>::erase(ser))
             .expect("View should serialize")
     }
+
+    /// Get the changes to the app's view model since the last call to `view_diff`
+    /// (serialized), see [`Core::view_diff`].
+    pub fn view_diff<S>(&self, ser: S)
+    where
+        A::ViewModel: Serialize,
+        S: ::serde::ser::Serializer,
+    {
+        self.core
+            .view_diff()
+            .serialize(ser)
+            .expect("View diff should serialize");
+    }
 }
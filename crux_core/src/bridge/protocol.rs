@@ -0,0 +1,71 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The version of the serialization protocol spoken over the bridge. The shell and the core
+/// can talk to each other as long as their major versions match, and use the lower of their
+/// minor versions. A new minor version may only add to the protocol, anything else is a new
+/// major version.
+///
+/// The shell sends its version to [`Bridge::handshake`](crate::bridge::Bridge::handshake) on
+/// startup, so that a mismatch produces a clear error rather than garbage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ProtocolVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl ProtocolVersion {
+    /// The version of the protocol spoken by this version of `crux_core`
+    pub const CURRENT: Self = Self::new(2, 0);
+
+    /// The first version sending the [`tag`](crate::bridge::Request::tag) of requests
+    pub(crate) const REQUEST_TAG: Self = Self::new(1, 1);
+
+    /// The first version sending the [`depends_on`](crate::bridge::Request::depends_on)
+    /// of requests
+    pub(crate) const REQUEST_DEPENDENCY: Self = Self::new(1, 2);
+
+    /// The first version sending the [`version`](crate::bridge::Request::version) of the
+    /// operation of requests. Shells need to check it to report unsupported requests, so
+    /// it's a new major version.
+    pub(crate) const REQUEST_VERSION: Self = Self::new(2, 0);
+
+    pub const fn new(major: u16, minor: u16) -> Self {
+        Self { major, minor }
+    }
+
+    /// The version to use to talk to a `shell` speaking its version of the protocol
+    pub fn negotiate(self, shell: ProtocolVersion) -> Result<ProtocolVersion, ProtocolError> {
+        if self.major != shell.major {
+            return Err(ProtocolError::Incompatible { shell, core: self });
+        }
+
+        Ok(self.min(shell))
+    }
+}
+
+impl Default for ProtocolVersion {
+    fn default() -> Self {
+        Self::CURRENT
+    }
+}
+
+impl fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// An error negotiating the protocol version in [`Bridge::handshake`](crate::bridge::Bridge::handshake)
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProtocolError {
+    #[error("incompatible bridge protocol versions: the shell speaks {shell} but the core speaks {core}, update both to the same major version")]
+    Incompatible {
+        shell: ProtocolVersion,
+        core: ProtocolVersion,
+    },
+    #[error("the shell's bridge protocol version could not be deserialized: {0}")]
+    InvalidVersion(String),
+}
@@ -1,22 +1,53 @@
-use std::sync::Mutex;
+use std::{collections::HashMap, sync::Mutex};
 
 use serde::{Deserialize, Serialize};
 use slab::Slab;
 
-use super::Request;
+use super::{Request, UnsupportedEffect};
 use crate::bridge::request_serde::ResolveSerialized;
 use crate::core::ResolveError;
-use crate::Effect;
+use crate::{Effect, ShellError};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct EffectId(pub u32);
 
-pub struct ResolveRegistry(Mutex<Slab<ResolveSerialized>>);
+pub struct ResolveRegistry(Mutex<Entries>);
+
+struct Entries {
+    slab: Slab<Entry>,
+    // the most recent outstanding request with each tag, for later requests to depend on
+    tagged: HashMap<&'static str, usize>,
+}
+
+struct Entry {
+    resolve: ResolveSerialized,
+    // whether the output is passed by the shell as raw bytes
+    raw_output: bool,
+    tag: Option<&'static str>,
+    // the name and version of the effect, to report it when the shell doesn't support it
+    name: &'static str,
+    version: u32,
+}
 
 impl Default for ResolveRegistry {
     fn default() -> Self {
-        Self(Mutex::new(Slab::with_capacity(1024)))
+        Self(Mutex::new(Entries {
+            slab: Slab::with_capacity(1024),
+            tagged: HashMap::new(),
+        }))
+    }
+}
+
+impl Entries {
+    fn remove(&mut self, id: usize) {
+        let entry = self.slab.remove(id);
+
+        if let Some(tag) = entry.tag {
+            if self.tagged.get(tag) == Some(&id) {
+                self.tagged.remove(tag);
+            }
+        }
     }
 }
 
@@ -32,17 +63,35 @@ impl ResolveRegistry {
     where
         Eff: Effect,
     {
+        let raw_output = effect.has_raw_output();
+        let tag = effect.tag();
+        let depends_on = effect.depends_on();
+        let name = effect.name();
+        let version = effect.version();
         let (effect, resolve) = effect.serialize();
 
-        let id = self
-            .0
-            .lock()
-            .expect("Registry Mutex poisoned.")
-            .insert(resolve);
+        let mut entries = self.0.lock().expect("Registry Mutex poisoned.");
+
+        let depends_on = depends_on
+            .and_then(|tag| entries.tagged.get(tag))
+            .map(|&id| effect_id(id));
+        let id = entries.slab.insert(Entry {
+            resolve,
+            raw_output,
+            tag,
+            name,
+            version,
+        });
+        if let Some(tag) = tag {
+            entries.tagged.insert(tag, id);
+        }
 
         Request {
-            id: EffectId(id.try_into().expect("EffectId overflow")),
+            id: effect_id(id),
             effect,
+            version,
+            tag: tag.map(String::from),
+            depends_on,
         }
     }
     // ANCHOR_END: register
@@ -54,21 +103,94 @@ impl ResolveRegistry {
         id: EffectId,
         body: &mut dyn erased_serde::Deserializer,
     ) -> Result<(), ResolveError> {
+        self.resume_with(id, |resolve| resolve.resolve(body))
+    }
+
+    /// Resume a previously registered effect for the last time, closing it even if it
+    /// expects more responses, see [`Core::resolve_final`](crate::Core::resolve_final).
+    pub fn resume_final(
+        &self,
+        id: EffectId,
+        body: &mut dyn erased_serde::Deserializer,
+    ) -> Result<(), ResolveError> {
+        self.resume_with(id, |resolve| resolve.resolve_final(body))
+    }
+
+    fn resume_with<F>(&self, id: EffectId, resume: F) -> Result<(), ResolveError>
+    where
+        F: FnOnce(&mut ResolveSerialized) -> Result<(), ResolveError>,
+    {
         let mut registry_lock = self.0.lock().expect("Registry Mutex poisoned");
 
-        let entry = registry_lock.get_mut(id.0 as usize);
+        let entry = registry_lock.slab.get_mut(id.0 as usize);
 
         let Some(entry) = entry else {
             // FIXME return an Err instead of panicking here.
             panic!("Request with {id:?} not found.");
         };
 
-        let resolved = entry.resolve(body);
+        let resolved = resume(&mut entry.resolve);
 
-        if let ResolveSerialized::Never = entry {
+        if let ResolveSerialized::Never = entry.resolve {
             registry_lock.remove(id.0 as usize);
         }
 
         resolved
     }
+
+    /// Resume a previously registered effect with a failure reported by the shell.
+    /// This fails if the effect isn't expected to be resumed again, or can't fail.
+    pub fn fail(&self, id: EffectId, error: ShellError) -> Result<(), ResolveError> {
+        let mut registry_lock = self.0.lock().expect("Registry Mutex poisoned");
+
+        let Some(entry) = registry_lock.slab.get_mut(id.0 as usize) else {
+            // FIXME return an Err instead of panicking here.
+            panic!("Request with {id:?} not found.");
+        };
+
+        let failed = entry.resolve.fail(error);
+
+        if let ResolveSerialized::Never = entry.resolve {
+            registry_lock.remove(id.0 as usize);
+        }
+
+        failed
+    }
+
+    /// Remove a previously registered effect which the shell reported it doesn't support,
+    /// returning a description of it, and the resolve callback, for the caller to fail it.
+    pub fn reject(&self, id: EffectId) -> (UnsupportedEffect, ResolveSerialized) {
+        let mut registry_lock = self.0.lock().expect("Registry Mutex poisoned");
+
+        let Some(entry) = registry_lock.slab.get_mut(id.0 as usize) else {
+            // FIXME return an Err instead of panicking here.
+            panic!("Request with {id:?} not found.");
+        };
+
+        let unsupported = UnsupportedEffect {
+            id: id.0,
+            effect: entry.name.to_string(),
+            version: entry.version,
+        };
+        let resolve = std::mem::replace(&mut entry.resolve, ResolveSerialized::Never);
+
+        registry_lock.remove(id.0 as usize);
+
+        (unsupported, resolve)
+    }
+
+    /// Whether the shell passes the output for the effect with `id` as raw bytes,
+    /// see [`Operation::RAW_OUTPUT`](crate::capability::Operation::RAW_OUTPUT).
+    pub fn has_raw_output(&self, id: EffectId) -> bool {
+        self.0
+            .lock()
+            .expect("Registry Mutex poisoned")
+            .slab
+            .get(id.0 as usize)
+            .map_or(false, |entry| entry.raw_output)
+    }
+}
+
+fn effect_id(id: usize) -> EffectId {
+    EffectId(id.try_into().expect("EffectId overflow"))
 }
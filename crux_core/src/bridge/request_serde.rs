@@ -1,14 +1,24 @@
+#[cfg(debug_assertions)]
+use std::any::type_name;
+use std::fmt::Display;
+
+use serde::{
+    de::{value::Error as ValueError, Error as _, Visitor},
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+
 use crate::{
     capability::Operation,
-    core::{Resolve, ResolveError},
-    Request,
+    core::{Fail, Resolve, ResolveError},
+    Request, ShellError,
 };
 
 // used in docs/internals/bridge.md
 // ANCHOR: resolve_serialized
-type ResolveOnceSerialized = Box<dyn FnOnce(&mut dyn erased_serde::Deserializer) + Send>;
+type ResolveOnceSerialized =
+    Box<dyn FnOnce(&mut dyn erased_serde::Deserializer) -> Result<(), ResolveError> + Send>;
 type ResolveManySerialized =
-    Box<dyn FnMut(&mut dyn erased_serde::Deserializer) -> Result<(), ()> + Send>;
+    Box<dyn FnMut(&mut dyn erased_serde::Deserializer) -> Result<(), ResolveError> + Send>;
 
 /// A deserializing version of Resolve
 ///
@@ -20,6 +30,7 @@ pub enum ResolveSerialized {
     Never,
     Once(ResolveOnceSerialized),
     Many(ResolveManySerialized),
+    Fallible(ResolveOnceSerialized, Fail),
 }
 // ANCHOR_END: resolve_serialized
 
@@ -30,13 +41,56 @@ impl ResolveSerialized {
     ) -> Result<(), ResolveError> {
         match self {
             ResolveSerialized::Never => Err(ResolveError::Never),
-            ResolveSerialized::Many(f) => f(bytes).map_err(|_| ResolveError::FinishedMany),
-            ResolveSerialized::Once(_) => {
-                // The resolve has been used, turn it into a Never
-                if let ResolveSerialized::Once(f) =
+            // a response which doesn't deserialize leaves the stream open for the next one
+            ResolveSerialized::Many(f) => f(bytes),
+            // The resolve has been used, turn it into a Never
+            ResolveSerialized::Once(_) | ResolveSerialized::Fallible(..) => {
+                match std::mem::replace(self, ResolveSerialized::Never) {
+                    ResolveSerialized::Once(f) => f(bytes),
+                    // a fallible request can tell the app the response didn't deserialize
+                    ResolveSerialized::Fallible(f, fail) => match f(bytes) {
+                        Err(ResolveError::Deserialization(message)) => {
+                            fail(ShellError::new(message));
+                            Ok(())
+                        }
+                        resolved => resolved,
+                    },
+                    ResolveSerialized::Never | ResolveSerialized::Many(_) => unreachable!(),
+                }
+            }
+        }
+    }
+
+    /// Resolve for the last time: a request expecting many responses is closed after the
+    /// response is delivered, even if it doesn't deserialize.
+    pub(crate) fn resolve_final(
+        &mut self,
+        bytes: &mut dyn erased_serde::Deserializer,
+    ) -> Result<(), ResolveError> {
+        match self {
+            ResolveSerialized::Many(f) => {
+                let resolved = f(bytes);
+
+                // dropping the callback lets the waiting task know that nothing else is coming
+                *self = ResolveSerialized::Never;
+
+                resolved
+            }
+            _ => self.resolve(bytes),
+        }
+    }
+
+    pub(crate) fn fail(&mut self, error: ShellError) -> Result<(), ResolveError> {
+        match self {
+            ResolveSerialized::Never => Err(ResolveError::Never),
+            ResolveSerialized::Once(_) | ResolveSerialized::Many(_) => {
+                Err(ResolveError::Infallible)
+            }
+            ResolveSerialized::Fallible(..) => {
+                if let ResolveSerialized::Fallible(_, fail) =
                     std::mem::replace(self, ResolveSerialized::Never)
                 {
-                    f(bytes);
+                    fail(error);
                 }
 
                 Ok(())
@@ -63,30 +117,127 @@ where
         let (operation, resolve) = (self.operation, self.resolve);
 
         let resolve = resolve.deserializing(move |deserializer| {
-            erased_serde::deserialize(deserializer).expect("Deserialization failed")
+            erased_serde::deserialize(deserializer).map_err(deserialization_error::<Op>)
         });
 
         (effect(operation), resolve)
     }
+
+    /// Resolve this effect request with an output deserialized from `output`, the shell's
+    /// response to the request's serializable counterpart.
+    ///
+    /// You should never need to call this method yourself, it will be called
+    /// by the `resolve_ffi` method generated by the Effect macro with `#[effect(convert)]`.
+    pub fn resolve_deserialized<'de, D>(&mut self, output: D) -> Result<(), ResolveError>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if let Resolve::Acknowledge(_) = self.resolve {
+            return self.resolve.acknowledge();
+        }
+
+        let output = Op::Output::deserialize(output).map_err(deserialization_error::<Op>)?;
+
+        self.resolve.resolve(output)
+    }
+}
+
+/// The output of an empty response from the shell.
+///
+/// An empty response is only a valid output for operations with a unit output, e.g. one
+/// which only signals its completion. Any other output, including an `Option`, fails to
+/// deserialize from it, so that a truncated response isn't mistaken for valid data.
+pub(crate) struct EmptyResponse;
+
+impl<'de> Deserializer<'de> for EmptyResponse {
+    type Error = ValueError;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(ValueError::custom(
+            "the response is empty, but the output isn't unit",
+        ))
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// In debug builds, the error names the operation and the output type the response didn't
+/// match, to make it easier to find which side of the bridge got the type wrong.
+#[cfg_attr(not(debug_assertions), allow(clippy::extra_unused_type_parameters))]
+fn deserialization_error<Op: Operation>(error: impl Display) -> ResolveError {
+    #[cfg(debug_assertions)]
+    let message = format!(
+        "response to {} doesn't match its output type {}: {error}",
+        type_name::<Op>(),
+        type_name::<Op::Output>()
+    );
+    #[cfg(not(debug_assertions))]
+    let message = error.to_string();
+
+    ResolveError::Deserialization(message)
 }
 
 impl<Out> Resolve<Out> {
     /// Convert this Resolve into a version which deserializes from bytes, consuming it.
     /// The `func` argument is a 'deserializer' converting from bytes into the `Out` type.
+    /// When `func` fails to deserialize the output, the error is returned without calling
+    /// the resolve callback.
     fn deserializing<F>(self, mut func: F) -> ResolveSerialized
     where
-        F: (FnMut(&mut dyn erased_serde::Deserializer) -> Out) + Send + Sync + 'static,
+        F: (FnMut(&mut dyn erased_serde::Deserializer) -> Result<Out, ResolveError>)
+            + Send
+            + Sync
+            + 'static,
         Out: 'static,
     {
         match self {
             Resolve::Never => ResolveSerialized::Never,
             Resolve::Once(resolve) => ResolveSerialized::Once(Box::new(move |deser| {
-                let out = func(deser);
-                resolve(out)
+                let out = func(deser)?;
+                resolve(out);
+                Ok(())
             })),
             Resolve::Many(resolve) => ResolveSerialized::Many(Box::new(move |deser| {
-                let out = func(deser);
-                resolve(out)
+                let out = func(deser)?;
+                resolve(out).map_err(|_| ResolveError::FinishedMany)
+            })),
+            Resolve::Fallible(resolve, fail) => ResolveSerialized::Fallible(
+                Box::new(move |deser| {
+                    let out = func(deser)?;
+                    resolve(out);
+                    Ok(())
+                }),
+                fail,
+            ),
+            // the response isn't deserialized, so that the shell can send an empty one
+            Resolve::Acknowledge(acknowledge) => ResolveSerialized::Once(Box::new(move |_| {
+                acknowledge();
+                Ok(())
             })),
         }
     }
@@ -1,6 +1,8 @@
 //! A capability which can spawn tasks which orchestrate across other capabilities. This
 //! is useful for orchestrating a number of different effects into a single transaction.
 
+use std::time::Duration;
+
 use crate::capability::{CapabilityContext, Never};
 use crate::Capability;
 use futures::Future;
@@ -60,6 +62,17 @@ impl<Ev> ComposeContext<Ev> {
     {
         self.context.update_app(event);
     }
+
+    /// Race the `future`, e.g. an async call to another capability, against a timeout of
+    /// `duration`, returning its output if it completes first, or `None` if it times out.
+    /// This forwards to [`CapabilityContext::with_timeout`].
+    pub async fn with_timeout<F>(&self, duration: Duration, future: F) -> Option<F::Output>
+    where
+        F: Future,
+        Ev: 'static,
+    {
+        self.context.with_timeout(duration, future).await
+    }
 }
 
 impl<Ev> Compose<Ev> {
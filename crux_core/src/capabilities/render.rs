@@ -32,6 +32,9 @@ pub struct RenderOperation;
 
 impl Operation for RenderOperation {
     type Output = ();
+
+    // rendering twice in a row draws the same view model
+    const COALESCE: bool = true;
 }
 
 /// Public API of the capability, called by App::update.
@@ -66,3 +69,69 @@ impl<Ev> Capability<Ev> for Render<Ev> {
         Render::new(self.context.map_event(f))
     }
 }
+
+/// Use an instance of `RenderChunks` alongside [`Render`] to notify the Shell that one chunk
+/// of a large view model (e.g. a page of a long list) should be re-drawn, so that the Shell
+/// can draw it incrementally rather than all at once.
+pub struct RenderChunks<Ev> {
+    context: CapabilityContext<RenderChunkOperation, Ev>,
+}
+
+impl<Ev> Clone for RenderChunks<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+/// The single operation `RenderChunks` implements, re-drawing the chunk with the `index`
+/// out of `total` chunks making up the view model.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct RenderChunkOperation {
+    /// The index of this chunk, starting from 0
+    pub index: u32,
+    /// The number of chunks making up the view model
+    pub total: u32,
+}
+
+// every chunk draws a different part of the view model, so they're never coalesced
+impl Operation for RenderChunkOperation {
+    type Output = ();
+}
+
+impl<Ev> RenderChunks<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<RenderChunkOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Call `render_chunk` from [`App::update`](crate::App::update) to signal to the Shell
+    /// that the chunk with the `index`, out of `total` chunks, should be re-drawn.
+    ///
+    /// The app decides what a chunk is. Renders of chunks reach the Shell in the order they
+    /// were requested in.
+    pub fn render_chunk(&self, index: u32, total: u32) {
+        let ctx = self.context.clone();
+        self.context.spawn(async move {
+            ctx.notify_shell(RenderChunkOperation { index, total })
+                .await;
+        });
+    }
+}
+
+impl<Ev> Capability<Ev> for RenderChunks<Ev> {
+    type Operation = RenderChunkOperation;
+    type MappedSelf<MappedEv> = RenderChunks<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static,
+    {
+        RenderChunks::new(self.context.map_event(f))
+    }
+}
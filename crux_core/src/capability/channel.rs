@@ -15,6 +15,21 @@ where
     (sender, receiver)
 }
 
+/// A channel holding at most `capacity` messages. As nothing can drain it while
+/// a message is being sent, sending to a full channel panics rather than blocking.
+pub(crate) fn bounded_channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>)
+where
+    T: Send + 'static,
+{
+    let (sender, receiver) = crossbeam_channel::bounded(capacity);
+    let sender = Sender {
+        inner: Arc::new(BoundedInner { sender, capacity }),
+    };
+    let receiver = Receiver { inner: receiver };
+
+    (sender, receiver)
+}
+
 pub struct Receiver<T> {
     inner: crossbeam_channel::Receiver<T>,
 }
@@ -50,7 +65,7 @@ impl<T> Receiver<T> {
         }
     }
 
-    pub fn drain(&self) -> Drain<T> {
+    pub fn drain(&self) -> Drain<'_, T> {
         Drain { receiver: self }
     }
 }
@@ -110,6 +125,25 @@ impl<T> SenderInner<T> for crossbeam_channel::Sender<T> {
     }
 }
 
+struct BoundedInner<T> {
+    sender: crossbeam_channel::Sender<T>,
+    capacity: usize,
+}
+
+impl<T> SenderInner<T> for BoundedInner<T> {
+    fn send(&self, t: T) {
+        match self.sender.try_send(t) {
+            Ok(()) => {}
+            Err(crossbeam_channel::TrySendError::Full(_)) => {
+                panic!("Channel capacity of {} exceeded", self.capacity)
+            }
+            Err(crossbeam_channel::TrySendError::Disconnected(_)) => {
+                panic!("Receiver was disconnected.")
+            }
+        }
+    }
+}
+
 pub struct MappedInner<T, F> {
     sender: Arc<dyn SenderInner<T> + Send + Sync>,
     func: F,
@@ -145,4 +179,18 @@ mod tests {
 
         assert_eq!(recv.receive(), None);
     }
+
+    #[test]
+    #[should_panic(expected = "Channel capacity of 2 exceeded")]
+    fn test_bounded_channel() {
+        let (send, recv) = bounded_channel(2);
+
+        send.send(1);
+        send.send(2);
+        assert_eq!(recv.drain().collect::<Vec<_>>(), vec![1, 2]);
+
+        send.send(3);
+        send.send(4);
+        send.send(5);
+    }
 }
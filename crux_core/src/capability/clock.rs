@@ -0,0 +1,118 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+/// A virtual clock driving the delays capabilities schedule internally with
+/// [`CapabilityContext::delay`](super::CapabilityContext::delay). The clock only moves
+/// forward when it's advanced, by the shell via the `Core`, or by a test via the `AppTester`.
+#[derive(Clone, Default)]
+pub(crate) struct Clock(Arc<Mutex<ClockInner>>);
+
+#[derive(Default)]
+struct ClockInner {
+    now: Duration,
+    timers: Vec<(Duration, Arc<Mutex<TimerState>>)>,
+}
+
+#[derive(Default)]
+struct TimerState {
+    elapsed: bool,
+    waker: Option<Waker>,
+}
+
+impl Clock {
+    pub(crate) fn sleep(&self, duration: Duration) -> Sleep {
+        let state = Arc::new(Mutex::new(TimerState {
+            elapsed: duration.is_zero(),
+            waker: None,
+        }));
+
+        if !duration.is_zero() {
+            let mut inner = self.0.lock().expect("clock lock poisoned");
+            let deadline = inner.now + duration;
+            inner.timers.push((deadline, state.clone()));
+        }
+
+        Sleep { state }
+    }
+
+    /// Move the clock forward, waking the tasks waiting for the timers which have
+    /// elapsed, in the order of their deadlines.
+    pub(crate) fn advance(&self, duration: Duration) {
+        let mut elapsed = {
+            let mut inner = self.0.lock().expect("clock lock poisoned");
+            inner.now += duration;
+            let now = inner.now;
+
+            let (elapsed, waiting) = std::mem::take(&mut inner.timers)
+                .into_iter()
+                .partition::<Vec<_>, _>(|(deadline, _)| *deadline <= now);
+            inner.timers = waiting;
+
+            elapsed
+        };
+
+        elapsed.sort_by_key(|(deadline, _)| *deadline);
+
+        for (_, state) in elapsed {
+            let mut state = state.lock().expect("timer lock poisoned");
+            state.elapsed = true;
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+pub(crate) struct Sleep {
+    state: Arc<Mutex<TimerState>>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().expect("timer lock poisoned");
+
+        if state.elapsed {
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            // the clock is advanced by the shell (or the tester), like resolving a request
+            super::executor::waiting_for_shell();
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{executor::block_on, FutureExt};
+
+    use super::*;
+
+    #[test]
+    fn sleep_elapses_once_clock_advances_past_deadline() {
+        let clock = Clock::default();
+        let mut sleep = clock.sleep(Duration::from_millis(100)).boxed();
+
+        assert!((&mut sleep).now_or_never().is_none());
+
+        clock.advance(Duration::from_millis(99));
+        assert!((&mut sleep).now_or_never().is_none());
+
+        clock.advance(Duration::from_millis(1));
+        assert!(sleep.now_or_never().is_some());
+    }
+
+    #[test]
+    fn zero_sleep_is_ready_immediately() {
+        let clock = Clock::default();
+
+        block_on(clock.sleep(Duration::ZERO));
+    }
+}
@@ -1,6 +1,10 @@
 use std::{
+    any::Any,
+    cell::Cell,
+    collections::HashSet,
+    panic::AssertUnwindSafe,
     sync::{Arc, Mutex},
-    task::{Context, Wake},
+    task::{Context, Wake, Waker},
 };
 
 use crossbeam_channel::{Receiver, Sender};
@@ -16,9 +20,38 @@ pub(crate) struct QueuingExecutor {
     ready_queue: Receiver<TaskId>,
     ready_sender: Sender<TaskId>,
     tasks: Mutex<Slab<Option<BoxFuture>>>,
+    // messages of the tasks which panicked, when catching panics
+    panics: Option<Mutex<Vec<String>>>,
+    // suspended tasks waiting for something other than the shell or the clock
+    waiting_elsewhere: Mutex<HashSet<u32>>,
+    // woken when a task is woken, for waiting on the tasks from outside the executor
+    idle_waker: Arc<Mutex<Option<Waker>>>,
+    task_order: TaskOrder,
 }
 // ANCHOR_END: executor
 
+/// The order in which the executor polls the tasks queued up to run, i.e. newly spawned
+/// tasks and tasks woken up to make progress.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TaskOrder {
+    /// Poll the tasks in the order they were queued up
+    #[default]
+    Fifo,
+    /// Poll the tasks queued up together in reverse, the most recently queued first
+    Lifo,
+}
+
+thread_local! {
+    // set while polling a task, when it suspends waiting for the shell or the clock
+    static WAITING_FOR_SHELL: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Record that the task being polled is suspended waiting for the shell (or the clock),
+/// rather than for some other async work.
+pub(crate) fn waiting_for_shell() {
+    WAITING_FOR_SHELL.with(|waiting| waiting.set(true));
+}
+
 // used in docs/internals/runtime.md
 // ANCHOR: spawner
 #[derive(Clone)]
@@ -48,6 +81,10 @@ pub(crate) fn executor_and_spawner() -> (QueuingExecutor, Spawner) {
             spawn_queue,
             ready_sender,
             tasks: Mutex::new(Slab::new()),
+            panics: None,
+            waiting_elsewhere: Mutex::default(),
+            idle_waker: Arc::default(),
+            task_order: TaskOrder::default(),
         },
         Spawner { future_sender },
     )
@@ -69,6 +106,7 @@ impl Spawner {
 struct TaskWaker {
     task_id: TaskId,
     sender: Sender<TaskId>,
+    idle_waker: Arc<Mutex<Option<Waker>>>,
 }
 
 // used in docs/internals/runtime.md
@@ -82,6 +120,10 @@ impl Wake for TaskWaker {
         // This send can fail if the executor has been dropped.
         // In which case, nothing to do
         let _ = self.sender.send(self.task_id);
+
+        if let Some(waker) = self.idle_waker.lock().expect("Waker lock poisoned").take() {
+            waker.wake();
+        }
     }
 }
 // ANCHOR_END: wake
@@ -98,7 +140,7 @@ impl QueuingExecutor {
 
         while did_some_work {
             did_some_work = false;
-            while let Ok(task) = self.spawn_queue.try_recv() {
+            for task in self.queued(&self.spawn_queue) {
                 let task_id = self
                     .tasks
                     .lock()
@@ -107,7 +149,7 @@ impl QueuingExecutor {
                 self.run_task(TaskId(task_id.try_into().expect("TaskId overflow")));
                 did_some_work = true;
             }
-            while let Ok(task_id) = self.ready_queue.try_recv() {
+            for task_id in self.queued(&self.ready_queue) {
                 match self.run_task(task_id) {
                     RunTask::Unavailable => {
                         // We were unable to run the task as it is (presumably) being run on
@@ -129,6 +171,13 @@ impl QueuingExecutor {
         }
     }
 
+    fn queued<'a, T: 'a>(&self, queue: &'a Receiver<T>) -> Box<dyn Iterator<Item = T> + 'a> {
+        match self.task_order {
+            TaskOrder::Fifo => Box::new(queue.try_iter()),
+            TaskOrder::Lifo => Box::new(queue.try_iter().collect::<Vec<_>>().into_iter().rev()),
+        }
+    }
+
     fn run_task(&self, task_id: TaskId) -> RunTask {
         let mut lock = self.tasks.lock().expect("Task slab poisoned");
         let Some(task) = lock.get_mut(*task_id as usize) else {
@@ -146,12 +195,37 @@ impl QueuingExecutor {
         let waker = Arc::new(TaskWaker {
             task_id,
             sender: self.ready_sender.clone(),
+            idle_waker: self.idle_waker.clone(),
         })
         .into();
         let context = &mut Context::from_waker(&waker);
 
         // poll the task
-        if task.as_mut().poll(context).is_pending() {
+        WAITING_FOR_SHELL.with(|waiting| waiting.set(false));
+        let poll = match &self.panics {
+            None => task.as_mut().poll(context),
+            Some(panics) => {
+                match std::panic::catch_unwind(AssertUnwindSafe(|| task.as_mut().poll(context))) {
+                    Ok(poll) => poll,
+                    Err(payload) => {
+                        // the task can't be resumed, free its slot and carry on with the others
+                        self.tasks.lock().unwrap().remove(*task_id as usize);
+                        self.set_waiting_elsewhere(task_id, false);
+                        panics
+                            .lock()
+                            .expect("Panics lock poisoned")
+                            .push(panic_message(payload.as_ref()));
+
+                        return RunTask::Completed;
+                    }
+                }
+            }
+        };
+
+        let waiting_elsewhere = poll.is_pending() && !WAITING_FOR_SHELL.with(Cell::get);
+        self.set_waiting_elsewhere(task_id, waiting_elsewhere);
+
+        if poll.is_pending() {
             // If it's still pending, put the future back in the slot
             self.tasks
                 .lock()
@@ -177,6 +251,113 @@ enum RunTask {
 
 // ANCHOR_END: run_all
 
+impl QueuingExecutor {
+    /// Run a single task which is ready to make progress, starting with newly
+    /// spawned tasks. Returns `true` if there is more work queued up after it.
+    ///
+    /// This is useful in tests which need to inspect the intermediate states
+    /// of a multi-step capability task, rather than driving it to completion
+    /// with [`run_all`](Self::run_all).
+    pub fn run_one(&self) -> bool {
+        if let Ok(task) = self.spawn_queue.try_recv() {
+            let task_id = self
+                .tasks
+                .lock()
+                .expect("Task slab poisoned")
+                .insert(Some(task));
+            self.run_task(TaskId(task_id.try_into().expect("TaskId overflow")));
+        } else if let Ok(task_id) = self.ready_queue.try_recv() {
+            if let RunTask::Unavailable = self.run_task(task_id) {
+                // the task is being run on another thread, try again later
+                self.ready_sender.send(task_id).expect("could not requeue");
+            }
+        }
+
+        self.has_pending_work()
+    }
+
+    /// Returns `true` if there are tasks waiting to be run.
+    pub fn has_pending_work(&self) -> bool {
+        !self.spawn_queue.is_empty() || !self.ready_queue.is_empty()
+    }
+
+    /// Returns `true` if there are suspended tasks waiting for async work other than
+    /// requests to the shell or the clock, which will wake them up without being resolved.
+    #[cfg(feature = "async_testing")]
+    pub fn is_waiting_elsewhere(&self) -> bool {
+        !self
+            .waiting_elsewhere
+            .lock()
+            .expect("Waiting tasks lock poisoned")
+            .is_empty()
+    }
+
+    /// Wait until there is a task ready to be run, e.g. after the async work
+    /// a suspended task was waiting for has completed.
+    #[cfg(feature = "async_testing")]
+    pub async fn wait_for_work(&self) {
+        futures::future::poll_fn(|cx| {
+            // register first, so that a task woken in the meantime isn't missed
+            *self.idle_waker.lock().expect("Waker lock poisoned") = Some(cx.waker().clone());
+
+            if self.has_pending_work() {
+                std::task::Poll::Ready(())
+            } else {
+                std::task::Poll::Pending
+            }
+        })
+        .await
+    }
+
+    fn set_waiting_elsewhere(&self, task_id: TaskId, waiting: bool) {
+        let mut tasks = self
+            .waiting_elsewhere
+            .lock()
+            .expect("Waiting tasks lock poisoned");
+
+        if waiting {
+            tasks.insert(*task_id);
+        } else {
+            tasks.remove(&*task_id);
+        }
+    }
+
+    /// Catch panics in tasks instead of propagating them. A task which panics is
+    /// dropped, its panic message is recorded and the remaining tasks carry on running.
+    /// Collect the messages with [`take_panics`](Self::take_panics).
+    pub fn catching_panics(mut self) -> Self {
+        self.panics = Some(Mutex::default());
+        self
+    }
+
+    /// Poll the queued up tasks in the given `order` when running them with
+    /// [`run_all`](Self::run_all). [`run_one`](Self::run_one) always runs the
+    /// task queued up first.
+    pub fn with_task_order(mut self, order: TaskOrder) -> Self {
+        self.task_order = order;
+        self
+    }
+
+    /// Take the messages of the tasks which panicked since the last call,
+    /// when catching panics.
+    pub fn take_panics(&self) -> Vec<String> {
+        self.panics
+            .as_ref()
+            .map(|panics| std::mem::take(&mut *panics.lock().expect("Panics lock poisoned")))
+            .unwrap_or_default()
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -212,6 +393,80 @@ mod tests {
         assert_eq!(Arc::strong_count(&counter), 1);
     }
 
+    #[test]
+    fn test_run_one() {
+        let (executor, spawner) = executor_and_spawner();
+        let (sender, receiver) = crossbeam_channel::unbounded();
+
+        for n in 0..2 {
+            let sender = sender.clone();
+            spawner.spawn(async move {
+                sender.send(n).unwrap();
+            });
+        }
+
+        assert!(executor.has_pending_work());
+
+        assert!(executor.run_one());
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![0]);
+
+        assert!(!executor.run_one());
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![1]);
+
+        // nothing left to run
+        assert!(!executor.run_one());
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_task_order() {
+        for (order, expected) in [
+            (TaskOrder::Fifo, vec![0, 1, 2]),
+            (TaskOrder::Lifo, vec![2, 1, 0]),
+        ] {
+            let (executor, spawner) = executor_and_spawner();
+            let executor = executor.with_task_order(order);
+            let (sender, receiver) = crossbeam_channel::unbounded();
+
+            for n in 0..3 {
+                let sender = sender.clone();
+                spawner.spawn(async move {
+                    sender.send(n).unwrap();
+                });
+            }
+
+            executor.run_all();
+
+            assert_eq!(receiver.try_iter().collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn test_catching_panics() {
+        let (executor, spawner) = executor_and_spawner();
+        let executor = executor.catching_panics();
+        let (sender, receiver) = crossbeam_channel::unbounded();
+
+        for n in 0..3 {
+            let sender = sender.clone();
+            spawner.spawn(async move {
+                if n == 1 {
+                    panic!("task {n} failed");
+                }
+                sender.send(n).unwrap();
+            });
+        }
+
+        executor.run_all();
+
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![0, 2]);
+        assert_eq!(executor.take_panics(), vec!["task 1 failed".to_string()]);
+        assert!(executor.take_panics().is_empty());
+
+        // the panicked task doesn't leak
+        assert_eq!(executor.tasks.lock().unwrap().len(), 0);
+    }
+
     #[test]
     fn test_multithreaded_executor() {
         // We define a future which chaotically sends notifications to wake up the task
@@ -247,12 +502,12 @@ mod tests {
                 }
                 if rand::thread_rng().gen_bool(0.1) {
                     cx.waker().wake_by_ref();
-                    return Poll::Pending;
+                    Poll::Pending
                 } else {
                     let mut ready = true;
                     let this = self.get_mut();
                     for child in &mut this.children {
-                        if let Poll::Pending = child.poll_unpin(cx) {
+                        if child.poll_unpin(cx).is_pending() {
                             ready = false;
                         }
                     }
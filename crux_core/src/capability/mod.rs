@@ -188,7 +188,9 @@
 
 pub(crate) mod channel;
 
+mod clock;
 mod executor;
+mod pending;
 mod shell_request;
 mod shell_stream;
 
@@ -196,8 +198,11 @@ use futures::Future;
 use serde::de::DeserializeOwned;
 use std::sync::Arc;
 
-pub(crate) use channel::channel;
+pub(crate) use channel::{bounded_channel, channel};
+pub(crate) use clock::Clock;
+pub use executor::TaskOrder;
 pub(crate) use executor::{executor_and_spawner, QueuingExecutor};
+pub(crate) use pending::PendingRequests;
 
 use crate::Request;
 use channel::Sender;
@@ -216,6 +221,28 @@ use channel::Sender;
 pub trait Operation: serde::Serialize + Clone + PartialEq + Send + 'static {
     /// `Output` assigns the type this request results in.
     type Output: serde::de::DeserializeOwned + Send + 'static;
+
+    /// Set to `true` for operations whose output is a blob of bytes (e.g. `Vec<u8>`)
+    /// the shell passes to the [`Bridge`](crate::bridge::Bridge) as-is, rather than
+    /// serialized with bincode. This avoids encoding data twice, when it's already serialized.
+    ///
+    /// Only the `Bridge` honours this, a [`BridgeWithSerializer`](crate::bridge::BridgeWithSerializer)
+    /// deserializes the output as usual.
+    const RAW_OUTPUT: bool = false;
+
+    /// Set to `true` for operations which only notify the shell, where several of them
+    /// requested back to back have the same effect as one, like [`RenderOperation`](crate::render::RenderOperation).
+    /// Consecutive effects carrying such an operation are coalesced into the first one
+    /// before they're handed to the shell, by a core built with
+    /// [`Core::with_coalescing`](crate::Core::with_coalescing).
+    const COALESCE: bool = false;
+
+    /// The version of the operation and its output, passed to the shell with each request
+    /// by the [`Bridge`](crate::bridge::Bridge). Bump it when they change in a way shells
+    /// built against the previous version can't handle, so that such a shell can detect the
+    /// request it doesn't support and report it with
+    /// [`Bridge::handle_unsupported`](crate::bridge::Bridge::handle_unsupported).
+    const VERSION: u32 = 1;
 }
 
 /// A type that can be used as a capability operation, but which will never be sent to the shell.
@@ -415,6 +442,10 @@ where
     shell_channel: Sender<Request<Op>>,
     app_channel: Sender<Event>,
     spawner: executor::Spawner,
+    pending: Option<PendingRequests>,
+    clock: Clock,
+    tag: Option<&'static str>,
+    depends_on: Option<&'static str>,
 }
 // ANCHOR_END: capability_context
 
@@ -423,6 +454,20 @@ pub struct ProtoContext<Eff, Event> {
     shell_channel: Sender<Eff>,
     app_channel: Sender<Event>,
     spawner: executor::Spawner,
+    pending: Option<PendingRequests>,
+    clock: Clock,
+}
+
+impl<Eff, Ev> Clone for ProtoContext<Eff, Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            shell_channel: self.shell_channel.clone(),
+            app_channel: self.app_channel.clone(),
+            spawner: self.spawner.clone(),
+            pending: self.pending.clone(),
+            clock: self.clock.clone(),
+        }
+    }
 }
 
 impl<Op, Ev> Clone for CapabilityContext<Op, Ev>
@@ -445,11 +490,70 @@ where
         shell_channel: Sender<Eff>,
         app_channel: Sender<Ev>,
         spawner: executor::Spawner,
+        clock: Clock,
     ) -> Self {
         Self {
             shell_channel,
             app_channel,
             spawner,
+            pending: None,
+            clock,
+        }
+    }
+
+    /// Keep track of the requests sent to the shell by capabilities using this context,
+    /// until they are resolved.
+    pub(crate) fn track_pending(mut self, pending: PendingRequests) -> Self {
+        self.pending = Some(pending);
+        self
+    }
+
+    /// Create a context for the capabilities of a child app, wrapping the child's effects
+    /// into the parent's effect `Eff` with `effect`, and its events into the parent's
+    /// event `Ev` with `event`. The child's effects and events end up with the parent, and
+    /// its capability tasks run on the same executor.
+    ///
+    /// This allows the parent's effect type to embed the child's as a variant:
+    ///
+    /// ```rust,ignore
+    /// pub enum Effect {
+    ///     Render(Request<RenderOperation>),
+    ///     Child(child::Effect),
+    /// }
+    ///
+    /// impl WithContext<Event, Effect> for Capabilities {
+    ///     fn new_with_context(context: ProtoContext<Effect, Event>) -> Capabilities {
+    ///         Capabilities {
+    ///             render: Render::new(context.specialize(Effect::Render)),
+    ///             child: child::Capabilities::new_with_context(
+    ///                 context.lift(Effect::Child, Event::Child),
+    ///             ),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// The shell (or a test) resolves a child's request by unwrapping the child effect, and
+    /// resolving the request it carries. To send the effect across the FFI boundary, the parent's
+    /// [`Effect::serialize`](crate::Effect::serialize) delegates to the child effect's, wrapping
+    /// its FFI counterpart.
+    pub fn lift<ChildEff, ChildEv, F, G>(
+        &self,
+        effect: F,
+        event: G,
+    ) -> ProtoContext<ChildEff, ChildEv>
+    where
+        F: Fn(ChildEff) -> Eff + Send + Sync + 'static,
+        G: Fn(ChildEv) -> Ev + Send + Sync + 'static,
+        ChildEff: 'static,
+        ChildEv: 'static,
+    {
+        ProtoContext {
+            shell_channel: self.shell_channel.map_input(effect),
+            app_channel: self.app_channel.map_input(event),
+            spawner: self.spawner.clone(),
+            pending: self.pending.clone(),
+            clock: self.clock.clone(),
         }
     }
 
@@ -469,6 +573,8 @@ where
             self.shell_channel.map_input(func),
             self.app_channel.clone(),
             self.spawner.clone(),
+            self.pending.clone(),
+            self.clock.clone(),
         )
     }
 }
@@ -482,11 +588,17 @@ where
         shell_channel: Sender<Request<Op>>,
         app_channel: Sender<Ev>,
         spawner: executor::Spawner,
+        pending: Option<PendingRequests>,
+        clock: Clock,
     ) -> Self {
         let inner = Arc::new(ContextInner {
             shell_channel,
             app_channel,
             spawner,
+            pending,
+            clock,
+            tag: None,
+            depends_on: None,
         });
 
         CapabilityContext { inner }
@@ -494,6 +606,40 @@ where
 
     /// Spawn a task to do the asynchronous work. Within the task, async code
     /// can be used to interact with the Shell and the App.
+    ///
+    /// Tasks can spawn further tasks, for example to fan out into several follow-up
+    /// requests once a response from the Shell has been resolved. The new tasks run as part
+    /// of the same resolution, so their requests are returned along with the other effects
+    /// it produced, without going through the app's `update`.
+    ///
+    /// The task may be resumed on a different thread from the one which spawned it
+    /// (for example when the shell resolves a request from a background thread),
+    /// so the future, and everything it captures - typically the callback creating
+    /// the event for the app - needs to be `Send + 'static`. Capturing a value which
+    /// isn't `Send` is rejected when the task is spawned:
+    ///
+    /// ```compile_fail,E0277
+    /// # use crux_core::capability::{CapabilityContext, Operation};
+    /// # #[derive(PartialEq, Clone, serde::Serialize)]
+    /// # struct Op;
+    /// # impl Operation for Op { type Output = (); }
+    /// use std::rc::Rc;
+    ///
+    /// fn count<Ev, F>(context: &CapabilityContext<Op, Ev>, event: F)
+    /// where
+    ///     Ev: 'static,
+    ///     F: FnOnce(usize) -> Ev + Send + 'static,
+    /// {
+    ///     let counter = Rc::new(1);
+    ///     context.spawn({
+    ///         let context = context.clone();
+    ///         async move {
+    ///             context.request_from_shell(Op).await;
+    ///             context.update_app(event(*counter));
+    ///         }
+    ///     });
+    /// }
+    /// ```
     pub fn spawn(&self, f: impl Future<Output = ()> + 'static + Send) {
         self.inner.spawner.spawn(f);
     }
@@ -505,9 +651,67 @@ where
         // it's important that it is.  It forces all capabilities to
         // spawn onto the executor which keeps the ordering of effects
         // consistent with their function calls.
-        self.inner
-            .shell_channel
-            .send(Request::resolves_never(operation));
+        let mut request = Request::resolves_never(operation);
+        request.tag = self.inner.tag;
+        request.depends_on = self.inner.depends_on;
+
+        self.inner.shell_channel.send(request);
+    }
+
+    /// Wait for `duration` to elapse, without involving the shell. This allows capabilities
+    /// to schedule internal work, e.g. to retry a failed request, from a spawned task:
+    ///
+    /// ```rust,ignore
+    /// self.context.spawn({
+    ///     let context = self.context.clone();
+    ///     async move {
+    ///         context.delay(Duration::from_secs(1)).await;
+    ///         context.update_app(callback());
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// The delays run on a virtual clock, which is advanced by the shell with
+    /// [`Core::advance_time`](crate::Core::advance_time), or by a test with
+    /// [`AppTester::advance_time`](crate::testing::AppTester::advance_time).
+    pub async fn delay(&self, duration: std::time::Duration) {
+        self.inner.clock.sleep(duration).await;
+    }
+
+    /// Race the `future`, e.g. a request to the shell, against a delay of `duration` on the
+    /// virtual clock (see [`CapabilityContext::delay`]), returning its output if it completes
+    /// first, or `None` if it times out.
+    ///
+    /// Whichever finishes first cancels the other. When the future times out, it is dropped,
+    /// and a response to a request it was waiting for is ignored.
+    ///
+    /// ```rust,ignore
+    /// self.context.spawn({
+    ///     let context = self.context.clone();
+    ///     async move {
+    ///         let request = context.request_from_shell(operation);
+    ///         match context.with_timeout(Duration::from_secs(10), request).await {
+    ///             Some(output) => context.update_app(callback(output)),
+    ///             None => context.update_app(on_timeout()),
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    pub async fn with_timeout<F>(
+        &self,
+        duration: std::time::Duration,
+        future: F,
+    ) -> Option<F::Output>
+    where
+        F: Future,
+    {
+        let delay = self.delay(duration);
+        futures::pin_mut!(future, delay);
+
+        match futures::future::select(future, delay).await {
+            futures::future::Either::Left((output, _)) => Some(output),
+            futures::future::Either::Right(_) => None,
+        }
     }
 
     /// Send an event to the app. The event will be processed on the next
@@ -518,6 +722,33 @@ where
         self.inner.app_channel.send(event);
     }
 
+    /// Send an event to the app, if there is one. This lets capabilities accept callbacks
+    /// returning an `Option` of an event, so that the app can discard the outcome of an
+    /// operation, e.g. a response which is no longer relevant:
+    ///
+    /// ```rust,ignore
+    /// pub fn fetch_optional<F>(&self, callback: F)
+    /// where
+    ///     F: FnOnce(Output) -> Option<Ev> + Send + 'static,
+    /// {
+    ///     self.context.spawn({
+    ///         let context = self.context.clone();
+    ///         async move {
+    ///             let output = context.request_from_shell(Fetch).await;
+    ///             context.update_app_optional(callback(output));
+    ///         }
+    ///     });
+    /// }
+    /// ```
+    ///
+    /// When there is no event, nothing is dispatched, and the update resolving the
+    /// operation is empty.
+    pub fn update_app_optional(&self, event: Option<Ev>) {
+        if let Some(event) = event {
+            self.update_app(event);
+        }
+    }
+
     /// Transform the CapabilityContext into one which uses the provided function to
     /// map each event dispatched with `update_app` to a different event type.
     ///
@@ -600,14 +831,83 @@ where
         F: Fn(NewEv) -> Ev + Sync + Send + 'static,
         NewEv: 'static,
     {
-        CapabilityContext::new(
-            self.inner.shell_channel.clone(),
-            self.inner.app_channel.map_input(func),
-            self.inner.spawner.clone(),
-        )
+        CapabilityContext {
+            inner: Arc::new(ContextInner {
+                shell_channel: self.inner.shell_channel.clone(),
+                app_channel: self.inner.app_channel.map_input(func),
+                spawner: self.inner.spawner.clone(),
+                pending: self.inner.pending.clone(),
+                clock: self.inner.clock.clone(),
+                tag: self.inner.tag,
+                depends_on: self.inner.depends_on,
+            }),
+        }
+    }
+
+    /// Create a copy of the CapabilityContext which attaches the `tag` to every request it
+    /// sends to the shell. The tag doesn't change the operation, it's a label carried
+    /// alongside it, to correlate the request in logs and traces. It's available as
+    /// [`Request::tag`] on the effect, and is passed to the shell as part of the
+    /// [`bridge::Request`](crate::bridge::Request).
+    ///
+    /// Capabilities derived with `#[derive(Capability)]` have a `tagged` method using it, e.g.
+    ///
+    /// ```rust,ignore
+    /// caps.profile.tagged("load_profile").fetch(Event::ProfileLoaded);
+    /// ```
+    pub fn tagged(&self, tag: &'static str) -> Self {
+        CapabilityContext {
+            inner: Arc::new(ContextInner {
+                shell_channel: self.inner.shell_channel.clone(),
+                app_channel: self.inner.app_channel.clone(),
+                spawner: self.inner.spawner.clone(),
+                pending: self.inner.pending.clone(),
+                clock: self.inner.clock.clone(),
+                tag: Some(tag),
+                depends_on: self.inner.depends_on,
+            }),
+        }
+    }
+
+    /// Create a copy of the CapabilityContext whose requests depend on the request tagged
+    /// with `tag` (see [`CapabilityContext::tagged`]), e.g. to read a key after writing it.
+    /// The dependency is available as [`Request::depends_on`] on the effect, and is passed
+    /// to the shell as the id of the request to wait for in the
+    /// [`bridge::Request`](crate::bridge::Request), so that the shell can order them.
+    ///
+    /// The dependency is on the most recent request sent with the tag, as long as the shell
+    /// hasn't resolved it yet, otherwise there's nothing to wait for.
+    ///
+    /// Capabilities derived with `#[derive(Capability)]` have a `depending_on` method using it,
+    /// e.g.
+    ///
+    /// ```rust,ignore
+    /// caps.key_value.tagged("write_token").set("token", token, Event::Written);
+    /// caps.key_value.depending_on("write_token").get("token", Event::Read);
+    /// ```
+    pub fn depending_on(&self, tag: &'static str) -> Self {
+        CapabilityContext {
+            inner: Arc::new(ContextInner {
+                shell_channel: self.inner.shell_channel.clone(),
+                app_channel: self.inner.app_channel.clone(),
+                spawner: self.inner.spawner.clone(),
+                pending: self.inner.pending.clone(),
+                clock: self.inner.clock.clone(),
+                tag: self.inner.tag,
+                depends_on: Some(tag),
+            }),
+        }
     }
 
-    pub(crate) fn send_request(&self, request: Request<Op>) {
+    pub(crate) fn send_request(&self, mut request: Request<Op>) {
+        request.tag = self.inner.tag;
+        request.depends_on = self.inner.depends_on;
+
+        let request = match &self.inner.pending {
+            Some(pending) => pending.track(request),
+            None => request,
+        };
+
         self.inner.shell_channel.send(request);
     }
 }
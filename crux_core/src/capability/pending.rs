@@ -0,0 +1,82 @@
+use std::sync::{Arc, Mutex};
+
+use slab::Slab;
+
+use super::Operation;
+use crate::{core::Resolve, Request};
+
+/// Keeps track of the requests which have been sent to the shell, expecting a single
+/// response, and haven't been resolved yet. Used by the `AppTester` to catch effects
+/// which a test forgot to resolve.
+#[derive(Clone, Default)]
+pub(crate) struct PendingRequests(Arc<Mutex<Slab<&'static str>>>);
+
+impl PendingRequests {
+    pub(crate) fn track<Op>(&self, mut request: Request<Op>) -> Request<Op>
+    where
+        Op: Operation,
+    {
+        request.resolve = match std::mem::replace(&mut request.resolve, Resolve::Never) {
+            Resolve::Once(resolve) => {
+                let key = self.insert::<Op>();
+                let pending = self.clone();
+
+                Resolve::Once(Box::new(move |output| {
+                    pending.remove(key);
+                    resolve(output);
+                }))
+            }
+            Resolve::Fallible(resolve, fail) => {
+                let key = self.insert::<Op>();
+                let (pending, pending_fail) = (self.clone(), self.clone());
+
+                Resolve::Fallible(
+                    Box::new(move |output| {
+                        pending.remove(key);
+                        resolve(output);
+                    }),
+                    Box::new(move |error| {
+                        pending_fail.remove(key);
+                        fail(error);
+                    }),
+                )
+            }
+            Resolve::Acknowledge(acknowledge) => {
+                let key = self.insert::<Op>();
+                let pending = self.clone();
+
+                Resolve::Acknowledge(Box::new(move || {
+                    pending.remove(key);
+                    acknowledge();
+                }))
+            }
+            resolve => resolve,
+        };
+
+        request
+    }
+
+    fn insert<Op>(&self) -> usize {
+        self.0
+            .lock()
+            .expect("pending requests lock poisoned")
+            .insert(std::any::type_name::<Op>())
+    }
+
+    fn remove(&self, key: usize) {
+        self.0
+            .lock()
+            .expect("pending requests lock poisoned")
+            .remove(key);
+    }
+
+    /// The operation type names of the requests which are still waiting to be resolved
+    pub(crate) fn operations(&self) -> Vec<&'static str> {
+        self.0
+            .lock()
+            .expect("pending requests lock poisoned")
+            .iter()
+            .map(|(_, operation)| *operation)
+            .collect()
+    }
+}
@@ -1,13 +1,13 @@
 //! Async support for implementing capabilities
 //!
 use std::{
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, Weak},
     task::{Poll, Waker},
 };
 
 use futures::Future;
 
-use crate::Request;
+use crate::{Request, ShellError};
 
 pub struct ShellRequest<T> {
     shared_state: Arc<Mutex<SharedState<T>>>,
@@ -53,6 +53,7 @@ impl<T> Future for ShellRequest<T> {
             None => {
                 let cloned_waker = cx.waker().clone();
                 shared_state.waker = Some(cloned_waker);
+                super::executor::waiting_for_shell();
                 Poll::Pending
             }
         }
@@ -72,6 +73,9 @@ where
     /// `request_from_shell` returns a future of the output, which can be
     /// `await`ed. You should only call this method inside an async task
     /// created with [`CapabilityContext::spawn`](crate::capability::CapabilityContext::spawn).
+    ///
+    /// The future works with the adapters from [`futures::FutureExt`], e.g. `inspect`
+    /// to look at (or log) the output on its way to the app, without changing it.
     pub fn request_from_shell(&self, operation: Op) -> ShellRequest<Op::Output> {
         let shared_state = Arc::new(Mutex::new(SharedState {
             result: None,
@@ -83,25 +87,9 @@ where
         // from shared_state -> send_request -> request -> shared_state
         let callback_shared_state = Arc::downgrade(&shared_state);
 
-        // used in docs/internals/runtime.md
-        // ANCHOR: resolve
         let request = Request::resolves_once(operation, move |result| {
-            let Some(shared_state) = callback_shared_state.upgrade() else {
-                // The ShellRequest was dropped before we were called, so just
-                // do nothing.
-                return;
-            };
-
-            let mut shared_state = shared_state.lock().unwrap();
-
-            // Attach the result to the shared state of the future
-            shared_state.result = Some(result);
-            // Signal the executor to wake the task holding this future
-            if let Some(waker) = shared_state.waker.take() {
-                waker.wake()
-            }
+            deliver(&callback_shared_state, result)
         });
-        // ANCHOR_END: resolve
 
         // Send the request on the next poll of the ShellRequest future
         let send_req_context = self.clone();
@@ -111,13 +99,98 @@ where
 
         ShellRequest { shared_state }
     }
+
+    /// Send an effect request to the shell, expecting only an acknowledgment that the
+    /// shell carried it out, e.g. to know that data was flushed to disk before continuing.
+    ///
+    /// This sits between [`notify_shell`](Self::notify_shell), which doesn't wait for the
+    /// shell at all, and [`request_from_shell`](Self::request_from_shell), which waits for
+    /// the output. The shell resolves the request with an empty response, which isn't
+    /// deserialized, so the operation can share an output type with other operations
+    /// which do return data. The returned future completes when the request is resolved.
+    pub fn acknowledge_from_shell(&self, operation: Op) -> ShellRequest<()> {
+        let shared_state = Arc::new(Mutex::new(SharedState {
+            result: None,
+            waker: None,
+            send_request: None,
+        }));
+
+        let callback_shared_state = Arc::downgrade(&shared_state);
+
+        let request =
+            Request::resolves_with_ack(operation, move || deliver(&callback_shared_state, ()));
+
+        let send_req_context = self.clone();
+        let send_request = move || send_req_context.send_request(request);
+
+        shared_state.lock().unwrap().send_request = Some(Box::new(send_request));
+
+        ShellRequest { shared_state }
+    }
+
+    /// Send an effect request to the shell, which the shell may fail to carry out.
+    ///
+    /// This is the same as [`request_from_shell`](Self::request_from_shell), except the
+    /// shell can resolve the request with a [`ShellError`] instead of the output, using
+    /// [`Core::resolve_failure`](crate::Core::resolve_failure). The returned future
+    /// completes with the output or the error, so that the capability can dispatch
+    /// a different event for each.
+    ///
+    /// Capabilities using this should register [`ShellError`] for type generation
+    /// in their `register_types`, so that shells can report failures.
+    pub fn request_from_shell_fallible(
+        &self,
+        operation: Op,
+    ) -> ShellRequest<Result<Op::Output, ShellError>> {
+        let shared_state = Arc::new(Mutex::new(SharedState {
+            result: None,
+            waker: None,
+            send_request: None,
+        }));
+
+        let resolve_shared_state = Arc::downgrade(&shared_state);
+        let fail_shared_state = resolve_shared_state.clone();
+
+        let request = Request::resolves_once_or_fails(
+            operation,
+            move |result| deliver(&resolve_shared_state, Ok(result)),
+            move |error| deliver(&fail_shared_state, Err(error)),
+        );
+
+        let send_req_context = self.clone();
+        let send_request = move || send_req_context.send_request(request);
+
+        shared_state.lock().unwrap().send_request = Some(Box::new(send_request));
+
+        ShellRequest { shared_state }
+    }
+}
+
+// used in docs/internals/runtime.md
+// ANCHOR: resolve
+fn deliver<T>(shared_state: &Weak<Mutex<SharedState<T>>>, result: T) {
+    let Some(shared_state) = shared_state.upgrade() else {
+        // The ShellRequest was dropped before we were called, so just
+        // do nothing.
+        return;
+    };
+
+    let mut shared_state = shared_state.lock().unwrap();
+
+    // Attach the result to the shared state of the future
+    shared_state.result = Some(result);
+    // Signal the executor to wake the task holding this future
+    if let Some(waker) = shared_state.waker.take() {
+        waker.wake()
+    }
 }
+// ANCHOR_END: resolve
 
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
 
-    use crate::capability::{channel, executor_and_spawner, CapabilityContext, Operation};
+    use crate::capability::{channel, executor_and_spawner, CapabilityContext, Clock, Operation};
 
     #[derive(serde::Serialize, Clone, PartialEq, Eq, Debug)]
     struct TestOperation;
@@ -131,8 +204,13 @@ mod tests {
         let (request_sender, requests) = channel();
         let (event_sender, events) = channel::<()>();
         let (executor, spawner) = executor_and_spawner();
-        let capability_context =
-            CapabilityContext::new(request_sender, event_sender.clone(), spawner.clone());
+        let capability_context = CapabilityContext::new(
+            request_sender,
+            event_sender.clone(),
+            spawner.clone(),
+            None,
+            Clock::default(),
+        );
 
         let future = capability_context.request_from_shell(TestOperation);
 
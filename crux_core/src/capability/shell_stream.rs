@@ -35,6 +35,7 @@ impl<T> Stream for ShellStream<T> {
             Ok(Some(next)) => Poll::Ready(Some(next)),
             Ok(None) => {
                 shared_state.waker = Some(cx.waker().clone());
+                super::executor::waiting_for_shell();
                 Poll::Pending
             }
             Err(_) => Poll::Ready(None),
@@ -90,7 +91,7 @@ where
 mod tests {
     use assert_matches::assert_matches;
 
-    use crate::capability::{channel, executor_and_spawner, CapabilityContext, Operation};
+    use crate::capability::{channel, executor_and_spawner, CapabilityContext, Clock, Operation};
 
     #[derive(serde::Serialize, Clone, PartialEq, Eq, Debug)]
     struct TestOperation;
@@ -107,8 +108,13 @@ mod tests {
         let (request_sender, requests) = channel();
         let (event_sender, events) = channel::<()>();
         let (executor, spawner) = executor_and_spawner();
-        let capability_context =
-            CapabilityContext::new(request_sender, event_sender.clone(), spawner.clone());
+        let capability_context = CapabilityContext::new(
+            request_sender,
+            event_sender.clone(),
+            spawner.clone(),
+            None,
+            Clock::default(),
+        );
 
         let mut stream = capability_context.stream_from_shell(TestOperation);
 
@@ -168,4 +174,55 @@ mod tests {
             .resolve(None)
             .expect_err("resolving a finished task should error");
     }
+
+    #[test]
+    fn test_shell_stream_resolve_final() {
+        let (request_sender, requests) = channel();
+        let (event_sender, events) = channel::<bool>();
+        let (executor, spawner) = executor_and_spawner();
+        let capability_context = CapabilityContext::new(
+            request_sender,
+            event_sender.clone(),
+            spawner.clone(),
+            None,
+            Clock::default(),
+        );
+
+        let mut stream = capability_context.stream_from_shell(TestOperation);
+
+        spawner.spawn(async move {
+            use futures::StreamExt;
+            while let Some(_tick) = stream.next().await {
+                event_sender.send(false);
+            }
+            // the stream has ended
+            event_sender.send(true);
+        });
+
+        executor.run_all();
+        let mut request = requests.receive().expect("we should have a request here");
+
+        // Tick twice, the request stays open
+        request.resolve(None).unwrap();
+        request.resolve(None).unwrap();
+        executor.run_all();
+
+        assert!(request.expects_response());
+        assert_matches!(events.receive(), Some(false));
+        assert_matches!(events.receive(), Some(false));
+        assert_matches!(events.receive(), None);
+
+        // Then close it with a final tick
+        request.resolve_final(None).unwrap();
+        executor.run_all();
+
+        assert!(!request.expects_response());
+        assert_matches!(events.receive(), Some(false));
+        assert_matches!(events.receive(), Some(true));
+        assert_matches!(events.receive(), None);
+
+        request
+            .resolve(None)
+            .expect_err("resolving a closed request should error");
+    }
 }
@@ -19,5 +19,63 @@ pub trait Effect: Send + 'static {
     /// You should not need to call this method directly. It is called by
     /// the [`Bridge`](crate::bridge::Bridge)
     fn serialize(self) -> (Self::Ffi, ResolveSerialized);
+
+    /// Whether the output of the operation carried by this effect is passed
+    /// by the shell as raw bytes, see [`Operation::RAW_OUTPUT`](crate::capability::Operation::RAW_OUTPUT).
+    fn has_raw_output(&self) -> bool {
+        false
+    }
+
+    /// Whether consecutive requests for this effect collapse into one,
+    /// see [`Operation::COALESCE`](crate::capability::Operation::COALESCE).
+    fn coalesces(&self) -> bool {
+        false
+    }
+
+    /// The version of the operation carried by this effect, see
+    /// [`Operation::VERSION`](crate::capability::Operation::VERSION). Passed to the shell with
+    /// the request by the [`Bridge`](crate::bridge::Bridge).
+    fn version(&self) -> u32 {
+        1
+    }
+
+    /// The priority of this effect. Effects requested together are handed to the shell
+    /// highest priority first, keeping the order they were requested in otherwise.
+    ///
+    /// Set with `#[effect(priority = N)]` on the capability field, the default is 0.
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    /// The label attached to the request carried by this effect, see
+    /// [`Request::tag`](crate::Request::tag). Passed to the shell with the request by the
+    /// [`Bridge`](crate::bridge::Bridge).
+    fn tag(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// The tag of the request the request carried by this effect depends on, see
+    /// [`Request::depends_on`](crate::Request::depends_on). Passed to the shell with the
+    /// request by the [`Bridge`](crate::bridge::Bridge), as the id of the request to wait for.
+    fn depends_on(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// The name of this effect's variant, which is the name of the capability
+    /// requesting it, e.g. `"Http"` or `"Render"`. Used to group effects in tests, see
+    /// [`Update::partition_by_capability`](crate::testing::Update::partition_by_capability).
+    ///
+    /// Defaults to the type name of the effect for implementations not derived with the
+    /// Effect macro.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 // ANCHOR_END: effect
+
+/// Collapse runs of consecutive effects which coalesce, like renders, into the first of each run.
+pub(crate) fn coalesce<Ef: Effect>(effects: &mut Vec<Ef>) {
+    effects.dedup_by(|later, earlier| {
+        later.coalesces() && earlier.coalesces() && later.name() == earlier.name()
+    });
+}
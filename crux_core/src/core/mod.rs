@@ -2,16 +2,37 @@ mod effect;
 mod request;
 mod resolve;
 
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
+
+use serde::Serialize;
 
 pub use effect::Effect;
 pub use request::Request;
 pub use resolve::ResolveError;
 
-pub(crate) use resolve::Resolve;
+pub(crate) use effect::coalesce;
+pub(crate) use resolve::{Fail, Resolve};
+
+use crate::capability::{self, channel::Receiver, Clock, Operation, ProtoContext, QueuingExecutor};
+use crate::diff::{self, Patch};
+use crate::{App, ShellError, WithContext};
+
+/// A function applied to every effect requested by the app, before it's returned
+pub(crate) type Middleware<Ef> = Box<dyn Fn(&mut Ef) + Send + Sync>;
 
-use crate::capability::{self, channel::Receiver, Operation, ProtoContext, QueuingExecutor};
-use crate::{App, WithContext};
+/// A function deciding what happens to every event, before it's passed to the app
+pub(crate) type EventMiddleware<Ev> = Box<dyn Fn(&Ev) -> EventAction<Ev> + Send + Sync>;
+
+/// What happens to an event passed to an event middleware, see [`Core::with_event_middleware`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventAction<Ev> {
+    /// Pass the event on unchanged
+    Pass,
+    /// Drop the event, the app's `update` isn't called for it
+    Drop,
+    /// Pass on this event instead
+    Replace(Ev),
+}
 
 /// The Crux core. Create an instance of this type with your effect type, and your app type as type parameters
 ///
@@ -41,6 +62,12 @@ where
     requests: Receiver<Ef>,
     capability_events: Receiver<A::Event>,
     executor: QueuingExecutor,
+    clock: Clock,
+    middleware: Vec<Middleware<Ef>>,
+    event_middleware: Vec<EventMiddleware<A::Event>>,
+    coalesce: bool,
+    // the view model as of the last call to `view_diff`
+    last_view: Mutex<serde_json::Value>,
 }
 // ANCHOR_END: core
 
@@ -62,7 +89,9 @@ where
         let (request_sender, request_receiver) = capability::channel();
         let (event_sender, event_receiver) = capability::channel();
         let (executor, spawner) = capability::executor_and_spawner();
-        let capability_context = ProtoContext::new(request_sender, event_sender, spawner);
+        let clock = Clock::default();
+        let capability_context =
+            ProtoContext::new(request_sender, event_sender, spawner, clock.clone());
 
         Self {
             model: Default::default(),
@@ -71,20 +100,80 @@ where
             capabilities: <<A as App>::Capabilities>::new_with_context(capability_context),
             requests: request_receiver,
             capability_events: event_receiver,
+            clock,
+            middleware: Vec::new(),
+            event_middleware: Vec::new(),
+            coalesce: false,
+            last_view: Mutex::new(serde_json::Value::Null),
         }
     }
 
+    /// Add an effect middleware, a function applied to every effect requested by the app
+    /// before it's returned to the shell, e.g. to add an auth header to every HTTP request.
+    /// Middleware is applied in the order it was added.
+    ///
+    /// The middleware can change the operation of an effect's request, which is then
+    /// resolved as usual, but it can't remove the effect.
+    ///
+    /// ```rust,ignore
+    /// let core: Core<Effect, App> = Core::new().with_effect_middleware(|effect| {
+    ///     if let Effect::Http(request) = effect {
+    ///         request.operation.headers.push(auth_header());
+    ///     }
+    /// });
+    /// ```
+    pub fn with_effect_middleware<F>(mut self, middleware: F) -> Self
+    where
+        F: Fn(&mut Ef) + Send + Sync + 'static,
+    {
+        self.middleware.push(Box::new(middleware));
+        self
+    }
+
+    /// Add an event middleware, a function deciding what happens to every event before it's
+    /// passed to the app's `update`, both from the shell and from capabilities, e.g. to log
+    /// events or to ignore some of them. Middleware is applied in the order it was added,
+    /// each seeing the event passed on by the previous one, until one drops it.
+    ///
+    /// ```rust,ignore
+    /// let core: Core<Effect, App> = Core::new().with_event_middleware(|event| {
+    ///     log::debug!("event: {event:?}");
+    ///     EventAction::Pass
+    /// });
+    /// ```
+    pub fn with_event_middleware<F>(mut self, middleware: F) -> Self
+    where
+        F: Fn(&A::Event) -> EventAction<A::Event> + Send + Sync + 'static,
+    {
+        self.event_middleware.push(Box::new(middleware));
+        self
+    }
+
+    /// Collapse consecutive effects which coalesce, like several renders in a row, into the
+    /// first of them before they're returned to the shell, see
+    /// [`Operation::COALESCE`](crate::capability::Operation::COALESCE).
+    ///
+    /// ```rust,ignore
+    /// let core: Core<Effect, App> = Core::new().with_coalescing();
+    /// ```
+    pub fn with_coalescing(mut self) -> Self {
+        self.coalesce = true;
+        self
+    }
+
     /// Run the app's `update` function with a given `event`, returning a vector of
     /// effect requests.
     // used in docs/internals/runtime.md
     // ANCHOR: process_event
     pub fn process_event(&self, event: A::Event) -> Vec<Ef> {
-        let mut model = self.model.write().expect("Model RwLock was poisoned.");
+        if let Some(event) = apply_event_middleware(&self.event_middleware, event) {
+            let mut model = self.model.write().expect("Model RwLock was poisoned.");
 
-        self.app.update(event, &mut model, &self.capabilities);
+            self.app.update(event, &mut model, &self.capabilities);
 
-        // drop the model here, we don't want to hold the lock for the process() call
-        drop(model);
+            // drop the model here, we don't want to hold the lock for the process() call
+            drop(model);
+        }
 
         self.process()
     }
@@ -110,12 +199,61 @@ where
     }
     // ANCHOR_END: resolve
 
+    /// Resolve an effect `request` for operation `Op` with the corresponding result,
+    /// for the last time.
+    ///
+    /// This is the same as [`Core::resolve`], except that a request expecting a stream of
+    /// results (e.g. a subscription) is closed afterwards, ending the stream for the capability.
+    pub fn resolve_final<Op>(&self, request: &mut Request<Op>, result: Op::Output) -> Vec<Ef>
+    where
+        Op: Operation,
+    {
+        let resolve_result = request.resolve_final(result);
+        debug_assert!(resolve_result.is_ok());
+
+        self.process()
+    }
+
+    /// Resolve a fallible effect `request` for operation `Op` with a failure to carry
+    /// out the operation, instead of its output.
+    ///
+    /// Only requests sent by capabilities with
+    /// [`CapabilityContext::request_from_shell_fallible`](crate::capability::CapabilityContext::request_from_shell_fallible)
+    /// can be resolved with a failure.
+    pub fn resolve_failure<Op>(&self, request: &mut Request<Op>, error: ShellError) -> Vec<Ef>
+    where
+        Op: Operation,
+    {
+        let resolve_result = request.fail(error);
+        debug_assert!(resolve_result.is_ok());
+
+        self.process()
+    }
+
+    /// Advance the clock used by capabilities to schedule internal work with
+    /// [`CapabilityContext::delay`](crate::capability::CapabilityContext::delay),
+    /// returning the effect requests resulting from the work which became due.
+    ///
+    /// The core has no notion of real time, so a shell using capabilities which rely on
+    /// delays is expected to call this periodically, with the time elapsed since the last call.
+    pub fn advance_time(&self, duration: std::time::Duration) -> Vec<Ef> {
+        self.clock.advance(duration);
+
+        self.process()
+    }
+
     // used in docs/internals/runtime.md
     // ANCHOR: process
     pub(crate) fn process(&self) -> Vec<Ef> {
         self.executor.run_all();
 
         while let Some(capability_event) = self.capability_events.receive() {
+            let Some(capability_event) =
+                apply_event_middleware(&self.event_middleware, capability_event)
+            else {
+                continue;
+            };
+
             let mut model = self.model.write().expect("Model RwLock was poisoned.");
             self.app
                 .update(capability_event, &mut model, &self.capabilities);
@@ -123,7 +261,15 @@ where
             self.executor.run_all();
         }
 
-        self.requests.drain().collect()
+        let mut effects: Vec<Ef> = self.requests.drain().collect();
+        apply_middleware(&self.middleware, &mut effects);
+        if self.coalesce {
+            effect::coalesce(&mut effects);
+        }
+        // a stable sort, so effects of the same priority stay in the order they were requested
+        effects.sort_by_key(|effect| std::cmp::Reverse(effect.priority()));
+
+        effects
     }
     // ANCHOR_END: process
 
@@ -133,6 +279,33 @@ where
 
         self.app.view(&model)
     }
+
+    /// Get the changes to the app's view model since the last call to `view_diff`, to send
+    /// the shell only the parts of the view model which changed. The first call returns
+    /// the whole view model, replacing the empty path. See [`diff`](crate::diff) for details.
+    pub fn view_diff(&self) -> Patch
+    where
+        A::ViewModel: Serialize,
+    {
+        let view = diff::to_value(&self.view());
+        let mut last_view = self
+            .last_view
+            .lock()
+            .expect("Last view Mutex was poisoned.");
+
+        let patch = diff::diff_values(&last_view, &view);
+        *last_view = view;
+
+        patch
+    }
+
+    /// Get the current state of the app's view model, or the error constructing it.
+    /// See [`App::try_view`](crate::App::try_view).
+    pub fn try_view(&self) -> Result<A::ViewModel, crate::ViewError> {
+        let model = self.model.read().expect("Model RwLock was poisoned.");
+
+        self.app.try_view(&model)
+    }
 }
 
 impl<Ef, A> Default for Core<Ef, A>
@@ -145,3 +318,27 @@ where
         Self::new()
     }
 }
+
+pub(crate) fn apply_middleware<Ef>(middleware: &[Middleware<Ef>], effects: &mut [Ef]) {
+    for effect in effects {
+        for middleware in middleware {
+            middleware(effect);
+        }
+    }
+}
+
+/// The event to pass to the app after applying the `middleware`, if it wasn't dropped
+pub(crate) fn apply_event_middleware<Ev>(
+    middleware: &[EventMiddleware<Ev>],
+    mut event: Ev,
+) -> Option<Ev> {
+    for middleware in middleware {
+        match middleware(&event) {
+            EventAction::Pass => {}
+            EventAction::Drop => return None,
+            EventAction::Replace(replacement) => event = replacement,
+        }
+    }
+
+    Some(event)
+}
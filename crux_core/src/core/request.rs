@@ -3,6 +3,7 @@ use std::fmt::{self, Debug};
 use crate::{
     capability::Operation,
     core::resolve::{Resolve, ResolveError},
+    ShellError,
 };
 
 /// Request represents an effect request from the core to the shell.
@@ -12,12 +13,34 @@ use crate::{
 ///
 /// The request can be resolved by passing it to `Core::resolve` along with the
 /// corresponding result of type `Operation::Output`.
+///
+/// Depending on the operation, a request is resolved in one of three ways:
+///
+/// * a fire-and-forget request (e.g. a render) doesn't expect a response
+///   and can't be resolved at all
+/// * a request expecting a single response is closed once it's resolved
+/// * a request expecting a stream of responses (e.g. timer ticks or websocket
+///   messages) stays open when it's resolved, so it can be resolved again.
+///   It's closed by resolving it with `Core::resolve_final`, or when the
+///   capability stops listening for responses.
+///
+/// A request may also only expect an acknowledgment that the shell carried it out,
+/// in which case the output it's resolved with is ignored, and the shell can resolve
+/// it with an empty response.
+///
+/// A request expecting a single response may also be fallible, in which case
+/// the shell can report that the operation failed instead of resolving it,
+/// using `Core::resolve_failure`.
+///
+/// Use [`Request::expects_response`] to check whether a request is still open.
 pub struct Request<Op>
 where
     Op: Operation,
 {
     pub operation: Op,
     pub(crate) resolve: Resolve<Op::Output>,
+    pub(crate) tag: Option<&'static str>,
+    pub(crate) depends_on: Option<&'static str>,
 }
 
 impl<Op> Request<Op>
@@ -28,6 +51,8 @@ where
         Self {
             operation,
             resolve: Resolve::Never,
+            tag: None,
+            depends_on: None,
         }
     }
 
@@ -38,6 +63,33 @@ where
         Self {
             operation,
             resolve: Resolve::Once(Box::new(resolve)),
+            tag: None,
+            depends_on: None,
+        }
+    }
+
+    pub(crate) fn resolves_once_or_fails<F, G>(operation: Op, resolve: F, fail: G) -> Self
+    where
+        F: FnOnce(Op::Output) + Send + 'static,
+        G: FnOnce(ShellError) + Send + 'static,
+    {
+        Self {
+            operation,
+            resolve: Resolve::Fallible(Box::new(resolve), Box::new(fail)),
+            tag: None,
+            depends_on: None,
+        }
+    }
+
+    pub(crate) fn resolves_with_ack<F>(operation: Op, acknowledge: F) -> Self
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        Self {
+            operation,
+            resolve: Resolve::Acknowledge(Box::new(acknowledge)),
+            tag: None,
+            depends_on: None,
         }
     }
 
@@ -48,12 +100,48 @@ where
         Self {
             operation,
             resolve: Resolve::Many(Box::new(resolve)),
+            tag: None,
+            depends_on: None,
         }
     }
 
+    /// Whether the core is waiting for the shell to respond to this request.
+    ///
+    /// This is `false` for fire-and-forget requests (e.g. a render) and for
+    /// requests which have already been resolved for the last time.
+    pub fn expects_response(&self) -> bool {
+        !matches!(self.resolve, Resolve::Never)
+    }
+
+    /// The label attached to this request by the capability which requested it, to
+    /// correlate it in logs and traces, see
+    /// [`CapabilityContext::tagged`](crate::capability::CapabilityContext::tagged).
+    pub fn tag(&self) -> Option<&'static str> {
+        self.tag
+    }
+
+    /// The tag of the request this request depends on, which the shell should wait for
+    /// before carrying this one out, see
+    /// [`CapabilityContext::depending_on`](crate::capability::CapabilityContext::depending_on).
+    pub fn depends_on(&self) -> Option<&'static str> {
+        self.depends_on
+    }
+
     pub(crate) fn resolve(&mut self, output: Op::Output) -> Result<(), ResolveError> {
         self.resolve.resolve(output)
     }
+
+    pub(crate) fn resolve_final(&mut self, output: Op::Output) -> Result<(), ResolveError> {
+        self.resolve.resolve_final(output)
+    }
+
+    pub(crate) fn acknowledge(&mut self) -> Result<(), ResolveError> {
+        self.resolve.acknowledge()
+    }
+
+    pub(crate) fn fail(&mut self, error: ShellError) -> Result<(), ResolveError> {
+        self.resolve.fail(error)
+    }
 }
 
 impl<Op> fmt::Debug for Request<Op>
@@ -61,6 +149,110 @@ where
     Op: Operation + Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_tuple("Request").field(&self.operation).finish()
+        f.debug_struct("Request")
+            .field("operation", &self.operation)
+            .field("resolve", &self.resolve)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Op;
+
+    impl Operation for Op {
+        type Output = ();
+    }
+
+    #[test]
+    fn resolves_never_does_not_expect_response() {
+        let request = Request::resolves_never(Op);
+
+        assert!(!request.expects_response());
+    }
+
+    #[test]
+    fn resolves_once_expects_response_until_resolved() {
+        let mut request = Request::resolves_once(Op, |()| {});
+
+        assert!(request.expects_response());
+
+        request.resolve(()).expect("to resolve");
+
+        assert!(!request.expects_response());
+    }
+
+    #[test]
+    fn resolves_many_times_expects_response_until_resolved_final() {
+        let mut request = Request::resolves_many_times(Op, |()| Ok(()));
+
+        assert!(request.expects_response());
+
+        request.resolve(()).expect("to resolve");
+        request.resolve(()).expect("to resolve");
+
+        assert!(request.expects_response());
+
+        request.resolve_final(()).expect("to resolve");
+
+        assert!(!request.expects_response());
+        assert!(request.resolve(()).is_err());
+    }
+
+    #[test]
+    fn resolves_once_or_fails_is_closed_by_a_failure() {
+        let failure = std::sync::Arc::new(std::sync::Mutex::new(None));
+        let mut request = Request::resolves_once_or_fails(Op, |()| {}, {
+            let failure = failure.clone();
+            move |error| *failure.lock().unwrap() = Some(error)
+        });
+
+        assert!(request.expects_response());
+
+        request.fail(ShellError::new("no")).expect("to fail");
+
+        assert!(!request.expects_response());
+        assert_eq!(*failure.lock().unwrap(), Some(ShellError::new("no")));
+        assert!(request.resolve(()).is_err());
+    }
+
+    #[test]
+    fn only_fallible_requests_can_fail() {
+        let mut request = Request::resolves_once(Op, |()| {});
+
+        assert!(matches!(
+            request.fail(ShellError::new("no")),
+            Err(ResolveError::Infallible)
+        ));
+        assert!(request.expects_response());
+    }
+
+    #[test]
+    fn debug_shows_operation_and_how_it_resolves() {
+        let mut request = Request::resolves_once(Op, |()| {});
+
+        assert_eq!(
+            format!("{request:?}"),
+            "Request { operation: Op, resolve: Once }"
+        );
+
+        request.resolve(()).expect("to resolve");
+
+        assert_eq!(
+            format!("{request:?}"),
+            "Request { operation: Op, resolve: Never }"
+        );
+
+        let request = Request::resolves_many_times(Op, |()| Ok(()));
+
+        assert_eq!(
+            format!("{request:?}"),
+            "Request { operation: Op, resolve: Many }"
+        );
     }
 }
@@ -1,9 +1,15 @@
+use std::fmt;
+
 use thiserror::Error;
 
+use crate::ShellError;
+
 // used in docs/internals/runtime.md
 // ANCHOR: resolve
 type ResolveOnce<Out> = Box<dyn FnOnce(Out) + Send>;
 type ResolveMany<Out> = Box<dyn Fn(Out) -> Result<(), ()> + Send>;
+pub(crate) type Acknowledge = Box<dyn FnOnce() + Send>;
+pub(crate) type Fail = Box<dyn FnOnce(ShellError) + Send>;
 
 /// Resolve is a callback used to resolve an effect request and continue
 /// one of the capability Tasks running on the executor.
@@ -11,6 +17,8 @@ pub(crate) enum Resolve<Out> {
     Never,
     Once(ResolveOnce<Out>),
     Many(ResolveMany<Out>),
+    Fallible(ResolveOnce<Out>, Fail),
+    Acknowledge(Acknowledge),
 }
 // ANCHOR_END: resolve
 
@@ -19,9 +27,13 @@ impl<Out> Resolve<Out> {
         match self {
             Resolve::Never => Err(ResolveError::Never),
             Resolve::Many(f) => f(output).map_err(|_| ResolveError::FinishedMany),
-            Resolve::Once(_) => {
+            // the output doesn't matter, only that the shell responded
+            Resolve::Acknowledge(_) => self.acknowledge(),
+            Resolve::Once(_) | Resolve::Fallible(..) => {
                 // The resolve has been used, turn it into a Never
-                if let Resolve::Once(f) = std::mem::replace(self, Resolve::Never) {
+                if let Resolve::Once(f) | Resolve::Fallible(f, _) =
+                    std::mem::replace(self, Resolve::Never)
+                {
                     f(output);
                 }
 
@@ -29,6 +41,74 @@ impl<Out> Resolve<Out> {
             }
         }
     }
+
+    /// Resolve with a failure reported by the shell. Only requests created as fallible
+    /// can be failed, and they're closed afterwards.
+    pub fn fail(&mut self, error: ShellError) -> Result<(), ResolveError> {
+        match self {
+            Resolve::Never => Err(ResolveError::Never),
+            Resolve::Once(_) | Resolve::Many(_) | Resolve::Acknowledge(_) => {
+                Err(ResolveError::Infallible)
+            }
+            Resolve::Fallible(..) => {
+                if let Resolve::Fallible(_, fail) = std::mem::replace(self, Resolve::Never) {
+                    fail(error);
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Resolve for the last time: a request expecting many responses is
+    /// closed after the output is delivered.
+    pub fn resolve_final(&mut self, output: Out) -> Result<(), ResolveError> {
+        match self {
+            Resolve::Never => Err(ResolveError::Never),
+            Resolve::Once(_) | Resolve::Fallible(..) | Resolve::Acknowledge(_) => {
+                self.resolve(output)
+            }
+            Resolve::Many(f) => {
+                let resolved = f(output).map_err(|_| ResolveError::FinishedMany);
+
+                // dropping the callback lets the waiting task know that nothing else is coming
+                *self = Resolve::Never;
+
+                resolved
+            }
+        }
+    }
+
+    /// Resolve without an output: a request expecting an acknowledgment is closed,
+    /// any other request expects its output.
+    pub fn acknowledge(&mut self) -> Result<(), ResolveError> {
+        match self {
+            Resolve::Never => Err(ResolveError::Never),
+            Resolve::Acknowledge(_) => {
+                if let Resolve::Acknowledge(f) = std::mem::replace(self, Resolve::Never) {
+                    f();
+                }
+
+                Ok(())
+            }
+            Resolve::Once(_) | Resolve::Many(_) | Resolve::Fallible(..) => {
+                Err(ResolveError::Unacknowledged)
+            }
+        }
+    }
+}
+
+// The callbacks can't be formatted, only how the request resolves
+impl<Out> fmt::Debug for Resolve<Out> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Resolve::Never => f.write_str("Never"),
+            Resolve::Once(_) => f.write_str("Once"),
+            Resolve::Many(_) => f.write_str("Many"),
+            Resolve::Fallible(..) => f.write_str("Fallible"),
+            Resolve::Acknowledge(_) => f.write_str("Acknowledge"),
+        }
+    }
 }
 
 #[derive(Error, Debug)]
@@ -37,4 +117,10 @@ pub enum ResolveError {
     Never,
     #[error("Attempted to resolve a request that has concluded.")]
     FinishedMany,
+    #[error("Attempted to fail a request that can't fail.")]
+    Infallible,
+    #[error("Attempted to acknowledge a request expecting an output.")]
+    Unacknowledged,
+    #[error("Failed to deserialize the response: {0}")]
+    Deserialization(String),
 }
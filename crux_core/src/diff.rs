@@ -0,0 +1,146 @@
+//! Structural diffing of view models, to send the shell only what changed.
+//!
+//! For large view models, serializing the whole view model on every render is wasteful
+//! over the FFI boundary. Instead, the shell can ask for a [`Patch`] with
+//! [`Core::view_diff`](crate::Core::view_diff) (or
+//! [`Bridge::view_diff`](crate::bridge::Bridge::view_diff)), listing only the parts of
+//! the view model which changed since the last diff, and apply it to its copy of the
+//! view model.
+//!
+//! Any serializable type can be diffed. The diff is computed on the JSON representation
+//! of the values: structs and maps are compared field by field, arrays of the same
+//! length element by element, and anything else is replaced as a whole.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A change to a part of a value, located by a [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901)
+/// `path`, e.g. `/items/0/title`. The empty path is the whole value.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PatchOperation {
+    /// Set the part at the `path` to the JSON encoded `value`, adding it if it's missing
+    Set { path: String, value: String },
+    /// Remove the part at the `path`
+    Remove { path: String },
+}
+
+/// The changes between two values, see [`Diffable::diff`]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Patch {
+    pub operations: Vec<PatchOperation>,
+}
+
+impl Patch {
+    /// Whether the two values were the same
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Apply the patch to the JSON representation of the previous value
+    ///
+    /// # Panics
+    ///
+    /// If the patch wasn't computed from the value it's applied to.
+    pub fn apply(&self, target: &mut Value) {
+        for operation in &self.operations {
+            match operation {
+                PatchOperation::Set { path, value } => {
+                    let value = serde_json::from_str(value).expect("Patch value is valid JSON");
+
+                    match target.pointer_mut(path) {
+                        Some(part) => *part = value,
+                        None => {
+                            let (parent, key) = split_path(path);
+                            let parent = target
+                                .pointer_mut(parent)
+                                .and_then(Value::as_object_mut)
+                                .expect("Patch should apply to the value it was computed from");
+
+                            parent.insert(key, value);
+                        }
+                    }
+                }
+                PatchOperation::Remove { path } => {
+                    let (parent, key) = split_path(path);
+                    target
+                        .pointer_mut(parent)
+                        .and_then(Value::as_object_mut)
+                        .expect("Patch should apply to the value it was computed from")
+                        .remove(&key);
+                }
+            }
+        }
+    }
+}
+
+/// Values which can be compared structurally, to describe their changes as a [`Patch`].
+/// Implemented for every serializable type.
+pub trait Diffable {
+    /// The changes from the `previous` value to this one
+    fn diff(&self, previous: &Self) -> Patch;
+}
+
+impl<T> Diffable for T
+where
+    T: Serialize,
+{
+    fn diff(&self, previous: &Self) -> Patch {
+        diff_values(&to_value(previous), &to_value(self))
+    }
+}
+
+pub(crate) fn to_value<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).expect("Value should serialize to JSON")
+}
+
+pub(crate) fn diff_values(previous: &Value, current: &Value) -> Patch {
+    let mut patch = Patch::default();
+    diff_into(&mut patch, String::new(), previous, current);
+
+    patch
+}
+
+fn diff_into(patch: &mut Patch, path: String, previous: &Value, current: &Value) {
+    match (previous, current) {
+        (Value::Object(previous), Value::Object(current)) => {
+            for (key, previous_value) in previous {
+                let path = format!("{path}/{}", escape(key));
+                match current.get(key) {
+                    Some(current_value) => diff_into(patch, path, previous_value, current_value),
+                    None => patch.operations.push(PatchOperation::Remove { path }),
+                }
+            }
+            for (key, current_value) in current {
+                if !previous.contains_key(key) {
+                    patch.operations.push(PatchOperation::Set {
+                        path: format!("{path}/{}", escape(key)),
+                        value: current_value.to_string(),
+                    });
+                }
+            }
+        }
+        (Value::Array(previous), Value::Array(current)) if previous.len() == current.len() => {
+            for (index, (previous, current)) in previous.iter().zip(current).enumerate() {
+                diff_into(patch, format!("{path}/{index}"), previous, current);
+            }
+        }
+        (previous, current) if previous != current => {
+            patch.operations.push(PatchOperation::Set {
+                path,
+                value: current.to_string(),
+            });
+        }
+        _ => {}
+    }
+}
+
+// JSON Pointer escaping, see https://www.rfc-editor.org/rfc/rfc6901#section-3
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn split_path(path: &str) -> (&str, String) {
+    let (parent, key) = path.rsplit_once('/').expect("Path should not be empty");
+
+    (parent, key.replace("~1", "/").replace("~0", "~"))
+}
@@ -151,6 +151,8 @@
 
 pub mod bridge;
 pub mod capability;
+pub mod diff;
+pub mod memo;
 pub mod testing;
 #[cfg(feature = "typegen")]
 pub mod typegen;
@@ -158,12 +160,13 @@ pub mod typegen;
 mod capabilities;
 mod core;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 pub use self::{
     capabilities::*,
     capability::{Capability, WithContext},
-    core::{Core, Effect, Request},
+    core::{Core, Effect, EventAction, Request, ResolveError},
 };
 pub use crux_macros as macros;
 
@@ -194,4 +197,48 @@ pub trait App: Default {
 
     /// View method is used by the Shell to request the current state of the user interface
     fn view(&self, model: &Self::Model) -> Self::ViewModel;
+
+    /// Fallible version of [`App::view`], for view models which can't always be constructed
+    /// from the model, so that the Shell can display an error state instead.
+    ///
+    /// The default implementation wraps [`App::view`]. When overriding it, `view` can be
+    /// implemented in terms of `try_view`.
+    fn try_view(&self, model: &Self::Model) -> Result<Self::ViewModel, ViewError> {
+        Ok(self.view(model))
+    }
+}
+
+/// An error constructing the view model in [`App::try_view`]
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[error("view failed: {message}")]
+pub struct ViewError {
+    pub message: String,
+}
+
+impl ViewError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+/// A failure to carry out an effect request, reported by the shell with
+/// [`Core::resolve_failure`] instead of resolving the request with its output.
+///
+/// Only requests sent with
+/// [`CapabilityContext::request_from_shell_fallible`](capability::CapabilityContext::request_from_shell_fallible)
+/// can be resolved with a failure.
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[error("shell failed: {message}")]
+pub struct ShellError {
+    pub message: String,
+}
+
+impl ShellError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
 }
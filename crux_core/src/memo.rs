@@ -0,0 +1,110 @@
+//! Memoized views, to avoid recomputing the view model when the model hasn't changed.
+//!
+//! The shell asks for the view model whenever it wants to render, which recomputes it from the
+//! model even if nothing changed since the last time. For apps with expensive views, wrap the
+//! app in [`Memoized`] and use it in its place, e.g. in `Core<Effect, Memoized<MyApp>>` or
+//! `AppTester<Memoized<MyApp>, Effect>`.
+
+use std::sync::Mutex;
+
+use crate::{App, ViewError};
+
+/// An [`App`] wrapping the app `A`, which returns the last view model it computed when the
+/// model is equal to the one it was computed from. It keeps a copy of that model to compare
+/// with, so the model needs to be `Clone` as well as `PartialEq`.
+///
+/// The rest of the app is passed through unchanged.
+pub struct Memoized<A>
+where
+    A: App,
+{
+    app: A,
+    last: Mutex<Option<(A::Model, A::ViewModel)>>,
+}
+
+impl<A> Memoized<A>
+where
+    A: App,
+{
+    /// Wrap the `app`, for apps with a constructor other than `Default`
+    pub fn new(app: A) -> Self {
+        Self {
+            app,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// The wrapped app
+    pub fn app(&self) -> &A {
+        &self.app
+    }
+}
+
+impl<A> Memoized<A>
+where
+    A: App,
+    A::Model: PartialEq + Clone,
+    A::ViewModel: Clone,
+{
+    fn cached(&self, model: &A::Model) -> Option<A::ViewModel> {
+        let last = self.last.lock().expect("view model lock poisoned");
+
+        match last.as_ref() {
+            Some((last_model, view_model)) if last_model == model => Some(view_model.clone()),
+            _ => None,
+        }
+    }
+
+    fn store(&self, model: &A::Model, view_model: &A::ViewModel) {
+        *self.last.lock().expect("view model lock poisoned") =
+            Some((model.clone(), view_model.clone()));
+    }
+}
+
+impl<A> Default for Memoized<A>
+where
+    A: App,
+{
+    fn default() -> Self {
+        Self::new(A::default())
+    }
+}
+
+impl<A> App for Memoized<A>
+where
+    A: App,
+    A::Model: PartialEq + Clone,
+    A::ViewModel: Clone,
+{
+    type Event = A::Event;
+    type Model = A::Model;
+    type ViewModel = A::ViewModel;
+    type Capabilities = A::Capabilities;
+
+    fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+        self.app.update(event, model, caps);
+    }
+
+    fn view(&self, model: &Self::Model) -> Self::ViewModel {
+        if let Some(view_model) = self.cached(model) {
+            return view_model;
+        }
+
+        let view_model = self.app.view(model);
+        self.store(model, &view_model);
+
+        view_model
+    }
+
+    // failures aren't memoized, the view is computed again next time
+    fn try_view(&self, model: &Self::Model) -> Result<Self::ViewModel, ViewError> {
+        if let Some(view_model) = self.cached(model) {
+            return Ok(view_model);
+        }
+
+        let view_model = self.app.try_view(model)?;
+        self.store(model, &view_model);
+
+        Ok(view_model)
+    }
+}
@@ -1,14 +1,30 @@
 //! Testing support for unit testing Crux apps.
+#[cfg(feature = "async_testing")]
+mod async_tester;
+
 use anyhow::Result;
-use std::{collections::VecDeque, sync::Arc};
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Debug,
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
 
 use crate::{
     capability::{
-        channel::Receiver, executor_and_spawner, Operation, ProtoContext, QueuingExecutor,
+        channel::Receiver, executor_and_spawner, Clock, Operation, PendingRequests, ProtoContext,
+        QueuingExecutor,
     },
-    Request, WithContext,
+    core::{apply_event_middleware, apply_middleware, coalesce, EventMiddleware, Middleware},
+    Effect, EventAction, Request, ShellError, ViewError, WithContext,
 };
 
+pub use crate::capability::TaskOrder;
+#[cfg(feature = "async_testing")]
+pub use async_tester::AsyncAppTester;
+
 /// AppTester is a simplified execution environment for Crux apps for use in
 /// tests.
 ///
@@ -27,12 +43,43 @@ where
     app: App,
     capabilities: App::Capabilities,
     context: Arc<AppContext<Ef, App::Event>>,
+    event_middleware: Vec<EventMiddleware<App::Event>>,
+    trace: Mutex<Option<Trace<Ef, App::Event>>>,
+    instrumentation: Mutex<Option<Box<dyn Instrumentation<App::Event>>>>,
+    pending: PendingRequests,
+    clock: Clock,
+    // kept to build the capabilities of child apps, see `AppTester::child`
+    capability_context: ProtoContext<Ef, App::Event>,
 }
 
+/// An entry in the trace recorded by [`AppTester`] when tracing is turned on
+/// with [`AppTester::trace`].
+///
+/// Events and effects are recorded using their `Debug` representation, so that
+/// the trace can be compared against a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TraceEntry {
+    /// An event processed by the app's `update` function
+    Event(String),
+    /// An effect requested by the app
+    Effect(String),
+}
+
+struct Trace<Ef, Ev> {
+    entries: Vec<TraceEntry>,
+    format_effect: fn(&Ef) -> String,
+    format_event: fn(&Ev) -> String,
+}
+
+type DropPredicate<Ef> = Box<dyn Fn(&Ef) -> bool + Send + Sync>;
+
 struct AppContext<Ef, Ev> {
     commands: Receiver<Ef>,
     events: Receiver<Ev>,
     executor: QueuingExecutor,
+    middleware: Vec<Middleware<Ef>>,
+    dropped: Mutex<Vec<DropPredicate<Ef>>>,
+    coalesce: Option<fn(&mut Vec<Ef>)>,
 }
 
 impl<App, Ef> AppTester<App, Ef>
@@ -42,14 +89,135 @@ where
     /// Create an `AppTester` instance for an existing app instance. This can be used if your App
     /// has a constructor other than `Default`, for example when used as a child app and expecting
     /// configuration from the parent
+    ///
+    /// Neither the app's capabilities nor the individual capabilities need to implement `Default`,
+    /// they are built by the [`WithContext`] implementation generated by `#[derive(Effect)]`.
     pub fn new(app: App) -> Self
     where
         Ef: Send + 'static,
         App::Capabilities: WithContext<App::Event, Ef>,
     {
+        Self::with_capabilities(app, App::Capabilities::new_with_context)
+    }
+
+    /// Create an `AppTester` instance for an existing app instance, with capabilities built by
+    /// the `capabilities` function from the tester's capability context. This can be used to
+    /// inject capabilities set up differently from their `WithContext` implementation, for
+    /// example when testing a child app expecting capabilities configured by its parent.
+    ///
+    /// The capabilities need to be built from the provided context (e.g. using
+    /// [`ProtoContext::specialize`]), so that the tester runs their tasks and collects their effects.
+    pub fn with_capabilities<F>(app: App, capabilities: F) -> Self
+    where
+        Ef: Send + 'static,
+        F: FnOnce(ProtoContext<Ef, App::Event>) -> App::Capabilities,
+    {
+        AppTesterBuilder::new(app).build_with_capabilities(capabilities)
+    }
+
+    /// Create an [`AppTesterBuilder`] for the default app instance, to configure the tester
+    /// before constructing it. Building without further configuration is equivalent to
+    /// [`AppTester::default`].
+    ///
+    /// ```rust,ignore
+    /// let app = AppTester::<ExampleApp, ExampleEffect>::builder()
+    ///     .channel_capacity(10)
+    ///     .catch_panics(true)
+    ///     .build();
+    /// ```
+    pub fn builder() -> AppTesterBuilder<App, Ef> {
+        AppTesterBuilder::new(App::default())
+    }
+
+    fn build<F>(config: AppTesterBuilder<App, Ef>, capabilities: F) -> Self
+    where
+        Ef: Send + 'static,
+        F: FnOnce(ProtoContext<Ef, App::Event>) -> App::Capabilities,
+    {
+        let AppTesterBuilder {
+            app,
+            channel_capacity,
+            catch_panics,
+            task_order,
+            middleware,
+            event_middleware,
+            coalesce,
+            ..
+        } = config;
+
+        let ((command_sender, commands), (event_sender, events)) = match channel_capacity {
+            Some(capacity) => (
+                crate::capability::bounded_channel(capacity),
+                crate::capability::bounded_channel(capacity),
+            ),
+            None => (crate::capability::channel(), crate::capability::channel()),
+        };
+        let (executor, spawner) = executor_and_spawner();
+        let executor = executor.with_task_order(task_order);
+        let executor = if catch_panics {
+            executor.catching_panics()
+        } else {
+            executor
+        };
+        let pending = PendingRequests::default();
+        let clock = Clock::default();
+        let capability_context =
+            ProtoContext::new(command_sender, event_sender, spawner, clock.clone())
+                .track_pending(pending.clone());
+
         Self {
             app,
-            ..Default::default()
+            capabilities: capabilities(capability_context.clone()),
+            context: Arc::new(AppContext {
+                commands,
+                events,
+                executor,
+                middleware,
+                dropped: Mutex::new(Vec::new()),
+                coalesce,
+            }),
+            event_middleware,
+            trace: Mutex::new(None),
+            instrumentation: Mutex::new(None),
+            pending,
+            clock,
+            capability_context,
+        }
+    }
+
+    /// Create a [`ChildTester`] running the `child` app embedded in this (parent) app, to test
+    /// the child in the parent's terms. The child's effects are wrapped into the parent's
+    /// effect type with `effect`, and the events dispatched by its capabilities into the
+    /// parent's event type with `event`, the same way
+    /// [`ProtoContext::lift`](crate::capability::ProtoContext::lift) does for the parent's
+    /// capabilities.
+    ///
+    /// ```rust,ignore
+    /// let parent = AppTester::<Parent, Effect>::default();
+    /// let child = parent.child(child::Child, Effect::Child, Event::Child);
+    ///
+    /// let Effect::Child(effect) = child.update(child::Event::Fetch, &mut model.child).expect_one_effect();
+    /// ```
+    pub fn child<Child, ChildEf, F, G>(
+        &self,
+        child: Child,
+        effect: F,
+        event: G,
+    ) -> ChildTester<'_, App, Ef, Child>
+    where
+        Child: crate::App,
+        Child::Capabilities: WithContext<Child::Event, ChildEf>,
+        ChildEf: 'static,
+        Ef: 'static,
+        F: Fn(ChildEf) -> Ef + Send + Sync + 'static,
+        G: Fn(Child::Event) -> App::Event + Send + Sync + 'static,
+    {
+        let context = self.capability_context.lift(effect, event);
+
+        ChildTester {
+            tester: self,
+            capabilities: Child::Capabilities::new_with_context(context),
+            child,
         }
     }
 
@@ -57,15 +225,28 @@ where
     ///
     /// You can use the resulting [`Update`] to inspect the effects which were requested
     /// and potential further events dispatched by capabilities.
+    ///
+    /// The event goes through the event middleware first, see
+    /// [`AppTesterBuilder::event_middleware`]. When it's dropped, `update` isn't called.
     pub fn update(&self, event: App::Event, model: &mut App::Model) -> Update<Ef, App::Event> {
+        let Some(event) = apply_event_middleware(&self.event_middleware, event) else {
+            return self.record_effects(self.context.updates());
+        };
+
+        self.record_event(&event);
+        let measurement = self.start_measuring(&event);
         self.app.update(event, model, &self.capabilities);
-        self.context.updates()
+        let update = self.context.updates();
+        self.finish_measuring(measurement, &update);
+
+        self.record_effects(update)
     }
 
     /// Resolve an effect `request` from previous update with an operation output.
     ///
     /// This potentially runs the app's `update` function if the effect is completed, and
-    /// produce another `Update`.
+    /// produce another `Update`. Use [`AppTester::resolve_with_status`] to also find out
+    /// whether the `request` is closed afterwards.
     pub fn resolve<Op: Operation>(
         &self,
         request: &mut Request<Op>,
@@ -73,7 +254,96 @@ where
     ) -> Result<Update<Ef, App::Event>> {
         request.resolve(value)?;
 
-        Ok(self.context.updates())
+        Ok(self.record_effects(self.context.updates()))
+    }
+
+    /// Resolve an effect `request` from previous update with an operation output, like
+    /// [`AppTester::resolve`], and report whether the `request` is closed afterwards.
+    ///
+    /// A request expecting a single output is closed by the first resolve, a request
+    /// expecting a stream of outputs stays open until it's resolved with
+    /// [`AppTester::resolve_final`].
+    pub fn resolve_with_status<Op: Operation>(
+        &self,
+        request: &mut Request<Op>,
+        value: Op::Output,
+    ) -> Result<Resolved<Ef, App::Event>> {
+        let update = self.resolve(request, value)?;
+
+        Ok(Resolved {
+            update,
+            closed: !request.expects_response(),
+        })
+    }
+
+    /// Resolve an effect `request` from previous update with an operation output, for the
+    /// last time.
+    ///
+    /// This is the same as [`AppTester::resolve`], except that a request expecting a stream
+    /// of outputs (e.g. a subscription) is closed afterwards, ending the stream. Use
+    /// [`Request::expects_response`] to check whether a request is still open.
+    pub fn resolve_final<Op: Operation>(
+        &self,
+        request: &mut Request<Op>,
+        value: Op::Output,
+    ) -> Result<Update<Ef, App::Event>> {
+        request.resolve_final(value)?;
+
+        Ok(self.record_effects(self.context.updates()))
+    }
+
+    /// Acknowledge an effect `request` from previous update, which was sent with
+    /// [`CapabilityContext::acknowledge_from_shell`](crate::capability::CapabilityContext::acknowledge_from_shell),
+    /// as if the shell carried it out.
+    ///
+    /// Fails with [`ResolveError::Unacknowledged`](crate::ResolveError::Unacknowledged) when
+    /// the request expects an output instead.
+    pub fn acknowledge<Op: Operation>(
+        &self,
+        request: &mut Request<Op>,
+    ) -> Result<Update<Ef, App::Event>> {
+        request.acknowledge()?;
+
+        Ok(self.record_effects(self.context.updates()))
+    }
+
+    /// Resolve a fallible effect `request` from previous update with a failure, as if the
+    /// shell failed to carry out the operation.
+    ///
+    /// Only requests sent with
+    /// [`CapabilityContext::request_from_shell_fallible`](crate::capability::CapabilityContext::request_from_shell_fallible)
+    /// can be resolved with a failure.
+    pub fn resolve_failure<Op: Operation>(
+        &self,
+        request: &mut Request<Op>,
+        error: ShellError,
+    ) -> Result<Update<Ef, App::Event>> {
+        request.fail(error)?;
+
+        Ok(self.record_effects(self.context.updates()))
+    }
+
+    /// Resolve an effect `request` from previous update with a `value` serialized the way
+    /// the [`Bridge`](crate::bridge::Bridge) expects the shell to send it, and decoded into the
+    /// operation's output on the way back in.
+    ///
+    /// This tests the same decode path as a real response from the shell, and is useful to
+    /// check that the operation's output type matches what the shell actually sends. Fails
+    /// with [`ResolveError::Deserialization`](crate::ResolveError::Deserialization) when
+    /// `value` doesn't decode into the output.
+    pub fn resolve_serialized<Op: Operation, T: Serialize>(
+        &self,
+        request: &mut Request<Op>,
+        value: T,
+    ) -> Result<Update<Ef, App::Event>> {
+        let options = bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes();
+        let bytes = options.serialize(&value)?;
+
+        request.resolve_deserialized(&mut bincode::Deserializer::from_slice(&bytes, options))?;
+
+        Ok(self.record_effects(self.context.updates()))
     }
 
     /// Resolve an effect `request` from previous update, then run the resulting event
@@ -91,9 +361,599 @@ where
         self.update(event, model)
     }
 
+    /// Start a [`Scenario`] by running the app's `update` function with an event and a model
+    /// state, to continue the flow with a fluent chain of resolutions and updates.
+    ///
+    /// ```rust,ignore
+    /// let effect = app
+    ///     .when(Event::Get, &mut model)
+    ///     .then_resolve(Effect::expect_time, TimeResponse::Now(now))
+    ///     .then_update()
+    ///     .expect_effect(|effect| matches!(effect, Effect::Render(_)));
+    /// ```
+    pub fn when<'a>(
+        &'a self,
+        event: App::Event,
+        model: &'a mut App::Model,
+    ) -> Scenario<'a, App, Ef> {
+        let update = self.update(event, model);
+
+        Scenario {
+            tester: self,
+            model,
+            update,
+        }
+    }
+
+    /// Run the app's `update` function with an event and a model state, without running
+    /// the capability tasks it spawned.
+    ///
+    /// Use [`AppTester::step`] to run the tasks one at a time and inspect the
+    /// intermediate effects and events they produce.
+    pub fn update_without_running(&self, event: App::Event, model: &mut App::Model) {
+        let Some(event) = apply_event_middleware(&self.event_middleware, event) else {
+            return;
+        };
+
+        self.record_event(&event);
+        self.app.update(event, model, &self.capabilities);
+    }
+
+    /// Run a recorded sequence of `events` through the app's `update` function in order,
+    /// e.g. to reproduce a bug from a user session, and return the merged [`Update`] of
+    /// all of them.
+    ///
+    /// Events dispatched by capabilities along the way are collected in the returned update,
+    /// rather than run, and effects are not resolved, so the recording should include
+    /// the events the shell responses led to.
+    pub fn replay(
+        &self,
+        events: impl IntoIterator<Item = App::Event>,
+        model: &mut App::Model,
+    ) -> Update<Ef, App::Event> {
+        events.into_iter().fold(
+            Update {
+                effects: vec![],
+                events: vec![],
+            },
+            |update, event| update.merge(self.update(event, model)),
+        )
+    }
+
+    /// Run a sequence of `events` through the app's `update` function in order, starting
+    /// from `model`, and return the resulting model along with the effects requested on
+    /// the way.
+    ///
+    /// Unlike [`AppTester::replay`], events dispatched by capabilities are run as well,
+    /// before the next event in the sequence. This makes it a convenient driver for property
+    /// based tests, which generate the starting model and the events and check the resulting
+    /// model is still valid. The tester can be reused between runs, there's no need to build
+    /// a new one for every case.
+    ///
+    /// ```rust,ignore
+    /// let (model, update) = app.run(events, model);
+    /// assert!(model.is_valid());
+    /// ```
+    pub fn run(
+        &self,
+        events: impl IntoIterator<Item = App::Event>,
+        mut model: App::Model,
+    ) -> (App::Model, Update<Ef, App::Event>) {
+        let mut effects = vec![];
+
+        for event in events {
+            // like the core, run the events dispatched by capabilities before the next event
+            let mut queue = VecDeque::from([event]);
+
+            while let Some(event) = queue.pop_front() {
+                let update = self.update(event, &mut model);
+                effects.extend(update.effects);
+                queue.extend(update.events);
+            }
+        }
+
+        let update = Update {
+            effects,
+            events: vec![],
+        };
+
+        (model, update)
+    }
+
+    /// Resolve an effect `request` from previous update with an operation output, without
+    /// running the capability task waiting for it.
+    ///
+    /// Use [`AppTester::step`] to continue the task.
+    pub fn resolve_without_running<Op: Operation>(
+        &self,
+        request: &mut Request<Op>,
+        value: Op::Output,
+    ) -> Result<()> {
+        request.resolve(value)?;
+
+        Ok(())
+    }
+
+    /// Run a single capability task which is ready to make progress, and return a
+    /// partial [`Update`] with the effects and events it produced.
+    ///
+    /// This gives fine-grained control over the order in which the tasks run, for example
+    /// to check that one capability's effect is emitted before another task continues.
+    pub fn step(&self) -> Update<Ef, App::Event> {
+        self.record_effects(self.context.step())
+    }
+
+    /// Run all capability tasks which are ready to make progress, and discard the effects
+    /// and events they produced, without building an [`Update`].
+    ///
+    /// This is useful with [`AppTester::update_without_running`] for fire-and-forget effects
+    /// (e.g. logging or analytics), in tests which only check the model or the view.
+    /// Discarded effects aren't recorded in the trace, and requests expecting a response
+    /// are dropped unresolved.
+    pub fn run_pending(&self) {
+        self.context.run_pending();
+    }
+
+    /// Returns `true` if there are capability tasks waiting to be run with [`AppTester::step`].
+    pub fn has_pending_tasks(&self) -> bool {
+        self.context.executor.has_pending_work()
+    }
+
+    /// Advance the virtual clock used by capabilities to schedule internal work with
+    /// [`CapabilityContext::delay`](crate::capability::CapabilityContext::delay), and run
+    /// the tasks whose delays have elapsed. Time doesn't pass in tests otherwise.
+    pub fn advance_time(&self, duration: std::time::Duration) -> Update<Ef, App::Event> {
+        self.clock.advance(duration);
+
+        self.record_effects(self.context.updates())
+    }
+
+    /// Simulate a shell which never handles the effects matching the `predicate`, by dropping
+    /// every matching effect requested from now on instead of returning it in an [`Update`].
+    /// The capability tasks waiting for the dropped requests never continue, so their
+    /// callbacks are discarded. Combined with [`AppTester::advance_time`], this allows testing
+    /// that the app recovers, e.g. that a timeout fires.
+    ///
+    /// Dropped requests which expect a response are still reported by
+    /// [`AppTester::assert_no_pending`], as they are never resolved.
+    ///
+    /// ```rust,ignore
+    /// app.drop_effect(|effect| matches!(effect, Effect::Http(_)));
+    /// ```
+    pub fn drop_effect<P>(&self, predicate: P)
+    where
+        P: Fn(&Ef) -> bool + Send + Sync + 'static,
+    {
+        self.context
+            .dropped
+            .lock()
+            .expect("dropped effects lock poisoned")
+            .push(Box::new(predicate));
+    }
+
     /// Run the app's `view` function with a model state
     pub fn view(&self, model: &App::Model) -> App::ViewModel {
-        self.app.view(model)
+        let instrumentation = self
+            .instrumentation
+            .lock()
+            .expect("instrumentation lock poisoned");
+        let Some(instrumentation) = instrumentation.as_ref() else {
+            return self.app.view(model);
+        };
+
+        let started = std::time::Instant::now();
+        let view = self.app.view(model);
+        instrumentation.view(started.elapsed());
+
+        view
+    }
+
+    /// Run the app's `try_view` function with a model state
+    pub fn try_view(&self, model: &App::Model) -> Result<App::ViewModel, ViewError> {
+        self.app.try_view(model)
+    }
+
+    /// Assert that every effect request expecting a response has been resolved.
+    ///
+    /// Call this at the end of a test to catch effects the test forgot to handle, for example
+    /// an HTTP request which was never answered. Fire-and-forget effects, like a render, and
+    /// streaming requests are not tracked.
+    ///
+    /// # Panics
+    ///
+    /// If there are requests still waiting to be resolved, listing their operation types.
+    pub fn assert_no_pending(&self) {
+        let pending = self.pending.operations();
+
+        assert!(
+            pending.is_empty(),
+            "{} effect request(s) were never resolved: {}",
+            pending.len(),
+            pending.join(", ")
+        );
+    }
+
+    /// Turn recording of a trace on or off. While it is on, every event processed
+    /// by the app and every effect it requests is recorded, in order, across calls to
+    /// [`AppTester::update`], [`AppTester::resolve`] and the other methods running the app.
+    ///
+    /// Turning the trace off discards any entries which haven't been taken yet.
+    pub fn trace(&self, enabled: bool)
+    where
+        Ef: Debug,
+        App::Event: Debug,
+    {
+        let mut trace = self.trace.lock().expect("trace lock poisoned");
+
+        if !enabled {
+            *trace = None;
+        } else if trace.is_none() {
+            *trace = Some(Trace {
+                entries: Vec::new(),
+                format_effect: |effect| format!("{effect:?}"),
+                format_event: |event| format!("{event:?}"),
+            });
+        }
+    }
+
+    /// Take the trace recorded since tracing was turned on with [`AppTester::trace`], or since
+    /// the last call to `take_trace`. Returns an empty trace if tracing is off.
+    pub fn take_trace(&self) -> Vec<TraceEntry> {
+        self.trace
+            .lock()
+            .expect("trace lock poisoned")
+            .as_mut()
+            .map(|trace| std::mem::take(&mut trace.entries))
+            .unwrap_or_default()
+    }
+
+    /// Install an [`Instrumentation`] to be called around each `update` and `view`, replacing
+    /// any installed before. Nothing is measured without one.
+    pub fn instrument(&self, instrumentation: impl Instrumentation<App::Event> + 'static) {
+        *self
+            .instrumentation
+            .lock()
+            .expect("instrumentation lock poisoned") = Some(Box::new(instrumentation));
+    }
+
+    fn start_measuring(&self, event: &App::Event) -> Option<(String, std::time::Instant)> {
+        self.instrumentation
+            .lock()
+            .expect("instrumentation lock poisoned")
+            .as_ref()
+            .map(|instrumentation| (instrumentation.event_name(event), std::time::Instant::now()))
+    }
+
+    fn finish_measuring(
+        &self,
+        measurement: Option<(String, std::time::Instant)>,
+        update: &Update<Ef, App::Event>,
+    ) {
+        let Some((event, started)) = measurement else {
+            return;
+        };
+        let elapsed = started.elapsed();
+
+        if let Some(instrumentation) = self
+            .instrumentation
+            .lock()
+            .expect("instrumentation lock poisoned")
+            .as_ref()
+        {
+            instrumentation.update(UpdateMeasurement {
+                event,
+                elapsed,
+                effects: update.effects.len(),
+                events: update.events.len(),
+            });
+        }
+    }
+
+    fn record_event(&self, event: &App::Event) {
+        if let Some(trace) = self.trace.lock().expect("trace lock poisoned").as_mut() {
+            let entry = TraceEntry::Event((trace.format_event)(event));
+            trace.entries.push(entry);
+        }
+    }
+
+    fn record_effects(&self, update: Update<Ef, App::Event>) -> Update<Ef, App::Event> {
+        if let Some(trace) = self.trace.lock().expect("trace lock poisoned").as_mut() {
+            for effect in &update.effects {
+                let entry = TraceEntry::Effect((trace.format_effect)(effect));
+                trace.entries.push(entry);
+            }
+        }
+
+        update
+    }
+}
+
+/// Measurements of `update` and `view` taken by [`AppTester`], installed with
+/// [`AppTester::instrument`]. All the methods do nothing by default.
+pub trait Instrumentation<Ev> {
+    /// A name for the `event`, to identify it in its [`UpdateMeasurement`]. Defaults to the
+    /// name of the event type, override it to tell the events apart, e.g. by their variant.
+    fn event_name(&self, _event: &Ev) -> String {
+        std::any::type_name::<Ev>().to_string()
+    }
+
+    /// Called after an event has been processed by the app's `update` function
+    /// and the capability tasks it spawned
+    fn update(&self, _measurement: UpdateMeasurement) {}
+
+    /// Called after the app's `view` function has run, with the time it took
+    fn view(&self, _elapsed: std::time::Duration) {}
+}
+
+/// The no-op instrumentation
+impl<Ev> Instrumentation<Ev> for () {}
+
+/// A measurement of a single event processed by [`AppTester::update`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateMeasurement {
+    /// The name of the event, see [`Instrumentation::event_name`]
+    pub event: String,
+    /// The time taken by the app's `update` function and the capability tasks it spawned
+    pub elapsed: std::time::Duration,
+    /// The number of effects requested
+    pub effects: usize,
+    /// The number of events dispatched by capabilities
+    pub events: usize,
+}
+
+/// A child app embedded in the app tested by an [`AppTester`], created with
+/// [`AppTester::child`].
+///
+/// The child runs its `update` with capabilities lifted into the parent's effect and event
+/// types, so the resulting [`Update`]s carry the parent's effects and events, as they would
+/// when the parent routes an event to the child. Requests are resolved with the parent
+/// [`AppTester`], which runs the child's capability tasks too.
+pub struct ChildTester<'a, App, Ef, Child>
+where
+    App: crate::App,
+    Child: crate::App,
+{
+    tester: &'a AppTester<App, Ef>,
+    child: Child,
+    capabilities: Child::Capabilities,
+}
+
+impl<'a, App, Ef, Child> ChildTester<'a, App, Ef, Child>
+where
+    App: crate::App,
+    Child: crate::App,
+{
+    /// Run the child app's `update` function with a child event and the child's model state,
+    /// returning the effects and events as the parent's.
+    ///
+    /// The child's event doesn't go through the parent's event middleware, and isn't
+    /// recorded in the parent's trace. Effects are.
+    pub fn update(&self, event: Child::Event, model: &mut Child::Model) -> Update<Ef, App::Event> {
+        self.child.update(event, model, &self.capabilities);
+
+        self.tester.record_effects(self.tester.context.updates())
+    }
+
+    /// Run the child app's `view` function.
+    pub fn view(&self, model: &Child::Model) -> Child::ViewModel {
+        self.child.view(model)
+    }
+}
+
+/// A multi-step test flow through an [`AppTester`], created with [`AppTester::when`].
+///
+/// The scenario carries the [`Update`] from the last step and the model, so each step
+/// continues from the previous one. It ends with one of the `expect_` assertions, or
+/// [`Scenario::into_update`] to inspect the last update directly.
+pub struct Scenario<'a, App, Ef>
+where
+    App: crate::App,
+{
+    tester: &'a AppTester<App, Ef>,
+    model: &'a mut App::Model,
+    update: Update<Ef, App::Event>,
+}
+
+impl<'a, App, Ef> Scenario<'a, App, Ef>
+where
+    App: crate::App,
+{
+    /// Resolve the one effect of the last step with `value`. The `request` function
+    /// extracts the request from the effect, e.g. `Effect::expect_http`.
+    ///
+    /// Panics if the last step didn't produce exactly one effect, or the request
+    /// couldn't be resolved.
+    pub fn then_resolve<Op, F>(self, request: F, value: Op::Output) -> Self
+    where
+        Op: Operation,
+        F: FnOnce(Ef) -> Request<Op>,
+    {
+        let Self {
+            tester,
+            model,
+            update,
+        } = self;
+        let update = update.resolve_one(tester, request, value);
+
+        Self {
+            tester,
+            model,
+            update,
+        }
+    }
+
+    /// Run the one event of the last step, e.g. dispatched by a resolved effect,
+    /// through the app's `update` function.
+    ///
+    /// Panics if the last step didn't produce exactly one event.
+    pub fn then_update(self) -> Self {
+        let Self {
+            tester,
+            model,
+            update,
+        } = self;
+        let event = update.expect_one_event();
+        let update = tester.update(event, model);
+
+        Self {
+            tester,
+            model,
+            update,
+        }
+    }
+
+    /// Assert that the last step produced an effect matching the `predicate`, and return it.
+    pub fn expect_effect<P>(self, mut predicate: P) -> Ef
+    where
+        P: FnMut(&Ef) -> bool,
+    {
+        let position = self.update.effects.iter().position(&mut predicate);
+        let count = self.update.effects.len();
+
+        match position {
+            Some(position) => self.update.into_effects().nth(position).unwrap(),
+            None => panic!("Expected a matching effect but none of {count} effect(s) matched"),
+        }
+    }
+
+    /// Assert that the last step produced exactly one effect, and return it.
+    pub fn expect_one_effect(self) -> Ef {
+        self.update.expect_one_effect()
+    }
+
+    /// Assert that the last step produced exactly one event, and return it.
+    pub fn expect_one_event(self) -> App::Event {
+        self.update.expect_one_event()
+    }
+
+    /// Assert that the last step produced no effects or events.
+    pub fn assert_empty(self) {
+        self.update.assert_empty()
+    }
+
+    /// End the scenario and return the [`Update`] of the last step.
+    pub fn into_update(self) -> Update<Ef, App::Event> {
+        self.update
+    }
+}
+
+/// Builder for an [`AppTester`] with a non-default configuration, created with
+/// [`AppTester::builder`].
+pub struct AppTesterBuilder<App, Ef>
+where
+    App: crate::App,
+{
+    app: App,
+    channel_capacity: Option<usize>,
+    catch_panics: bool,
+    task_order: TaskOrder,
+    middleware: Vec<Middleware<Ef>>,
+    event_middleware: Vec<EventMiddleware<App::Event>>,
+    coalesce: Option<fn(&mut Vec<Ef>)>,
+    effect: PhantomData<fn() -> Ef>,
+}
+
+impl<App, Ef> AppTesterBuilder<App, Ef>
+where
+    App: crate::App,
+{
+    fn new(app: App) -> Self {
+        Self {
+            app,
+            channel_capacity: None,
+            catch_panics: false,
+            task_order: TaskOrder::default(),
+            middleware: Vec::new(),
+            event_middleware: Vec::new(),
+            coalesce: None,
+            effect: PhantomData,
+        }
+    }
+
+    /// Test an existing app instance instead of the default one
+    pub fn app(self, app: App) -> Self {
+        Self { app, ..self }
+    }
+
+    /// Limit the number of effects and of events which can be outstanding at once, i.e. requested
+    /// by a single update before the tester collects them. Exceeding the capacity panics, which
+    /// catches an app flooding the shell. By default, the number is unlimited.
+    ///
+    /// A capacity of zero is raised to one, as no effect or event could be sent otherwise.
+    pub fn channel_capacity(self, capacity: usize) -> Self {
+        Self {
+            channel_capacity: Some(capacity.max(1)),
+            ..self
+        }
+    }
+
+    /// Whether to catch panics in capability tasks, so that the remaining tasks still run
+    /// before the tester reports them. By default, a panic in a task propagates straight out
+    /// of the call into the tester, as it would in the `Core`.
+    pub fn catch_panics(self, catch_panics: bool) -> Self {
+        Self {
+            catch_panics,
+            ..self
+        }
+    }
+
+    /// The order in which to poll the capability tasks queued up to run, which decides the
+    /// order of the effects and events they produce. By default, the tasks are polled in the
+    /// order they were queued up ([`TaskOrder::Fifo`]), as they are in the `Core`. Use
+    /// [`TaskOrder::Lifo`] to check that the app doesn't depend on that order.
+    pub fn task_order(self, task_order: TaskOrder) -> Self {
+        Self { task_order, ..self }
+    }
+
+    /// Collapse consecutive effects which coalesce, like several renders in a row, as
+    /// [`Core::with_coalescing`](crate::Core::with_coalescing) does.
+    pub fn coalescing(self) -> Self
+    where
+        Ef: Effect,
+    {
+        Self {
+            coalesce: Some(coalesce::<Ef>),
+            ..self
+        }
+    }
+
+    /// Apply the `middleware` to every effect requested by the app, in the order it was added,
+    /// as [`Core::with_effect_middleware`](crate::Core::with_effect_middleware) does.
+    pub fn effect_middleware<F>(mut self, middleware: F) -> Self
+    where
+        F: Fn(&mut Ef) + Send + Sync + 'static,
+    {
+        self.middleware.push(Box::new(middleware));
+        self
+    }
+
+    /// Apply the `middleware` to every event passed to [`AppTester::update`], in the order it
+    /// was added, as [`Core::with_event_middleware`](crate::Core::with_event_middleware) does.
+    pub fn event_middleware<F>(mut self, middleware: F) -> Self
+    where
+        F: Fn(&App::Event) -> EventAction<App::Event> + Send + Sync + 'static,
+    {
+        self.event_middleware.push(Box::new(middleware));
+        self
+    }
+
+    /// Build the configured [`AppTester`]
+    pub fn build(self) -> AppTester<App, Ef>
+    where
+        Ef: Send + 'static,
+        App::Capabilities: WithContext<App::Event, Ef>,
+    {
+        self.build_with_capabilities(App::Capabilities::new_with_context)
+    }
+
+    /// Build the configured [`AppTester`], with capabilities built by the `capabilities`
+    /// function, see [`AppTester::with_capabilities`].
+    pub fn build_with_capabilities<F>(self, capabilities: F) -> AppTester<App, Ef>
+    where
+        Ef: Send + 'static,
+        F: FnOnce(ProtoContext<Ef, App::Event>) -> App::Capabilities,
+    {
+        AppTester::build(self, capabilities)
     }
 }
 
@@ -104,20 +964,7 @@ where
     Ef: Send + 'static,
 {
     fn default() -> Self {
-        let (command_sender, commands) = crate::capability::channel();
-        let (event_sender, events) = crate::capability::channel();
-        let (executor, spawner) = executor_and_spawner();
-        let capability_context = ProtoContext::new(command_sender, event_sender, spawner);
-
-        Self {
-            app: App::default(),
-            capabilities: App::Capabilities::new_with_context(capability_context),
-            context: Arc::new(AppContext {
-                commands,
-                events,
-                executor,
-            }),
-        }
+        Self::new(App::default())
     }
 }
 
@@ -133,11 +980,55 @@ where
 impl<Ef, Ev> AppContext<Ef, Ev> {
     pub fn updates(self: &Arc<Self>) -> Update<Ef, Ev> {
         self.executor.run_all();
-        let effects = self.commands.drain().collect();
+        self.check_panics();
+        let effects = self.effects();
+        let events = self.events.drain().collect();
+
+        Update { effects, events }
+    }
+
+    pub fn step(self: &Arc<Self>) -> Update<Ef, Ev> {
+        self.executor.run_one();
+        self.check_panics();
+        let effects = self.effects();
         let events = self.events.drain().collect();
 
         Update { effects, events }
     }
+
+    pub fn run_pending(self: &Arc<Self>) {
+        self.executor.run_all();
+        self.check_panics();
+        self.commands.drain().for_each(drop);
+        self.events.drain().for_each(drop);
+    }
+
+    fn effects(&self) -> Vec<Ef> {
+        let mut effects: Vec<Ef> = self.commands.drain().collect();
+        apply_middleware(&self.middleware, &mut effects);
+
+        let dropped = self.dropped.lock().expect("dropped effects lock poisoned");
+        effects.retain(|effect| !dropped.iter().any(|predicate| predicate(effect)));
+
+        if let Some(coalesce) = self.coalesce {
+            coalesce(&mut effects);
+        }
+        effects
+    }
+
+    // When catching panics in capability tasks so that the other tasks still run,
+    // report them here with their messages rather than losing them
+    fn check_panics(&self) {
+        let panics = self.executor.take_panics();
+        if !panics.is_empty() {
+            let messages: Vec<_> = panics.iter().map(|m| format!("  - {m}")).collect();
+            panic!(
+                "{} capability task(s) panicked:\n{}",
+                panics.len(),
+                messages.join("\n")
+            );
+        }
+    }
 }
 
 /// Update test helper holds the result of running an app update using [`AppTester::update`]
@@ -151,6 +1042,16 @@ pub struct Update<Ef, Ev> {
     pub events: Vec<Ev>,
 }
 
+/// The result of resolving a request using [`AppTester::resolve_with_status`]
+#[derive(Debug)]
+#[must_use]
+pub struct Resolved<Ef, Ev> {
+    /// The update run by resolving the request
+    pub update: Update<Ef, Ev>,
+    /// Whether the request is closed, and won't accept any further outputs
+    pub closed: bool,
+}
+
 impl<Ef, Ev> Update<Ef, Ev> {
     pub fn into_effects(self) -> impl Iterator<Item = Ef> {
         self.effects.into_iter()
@@ -164,6 +1065,161 @@ impl<Ef, Ev> Update<Ef, Ev> {
         self.effects.iter_mut()
     }
 
+    /// Consume the [`Update`], returning both the effects and the events
+    ///
+    /// ```
+    /// # use crux_core::testing::Update;
+    /// # #[derive(Debug, PartialEq)]
+    /// # enum Effect { Render };
+    /// # #[derive(Debug, PartialEq)]
+    /// # enum Event { Tick };
+    /// let update: Update<Effect, Event> = Update {
+    ///     effects: vec![Effect::Render],
+    ///     events: vec![Event::Tick, Event::Tick],
+    /// };
+    ///
+    /// let (effects, events) = update.into_parts();
+    /// assert_eq!(effects, vec![Effect::Render]);
+    /// assert_eq!(events, vec![Event::Tick, Event::Tick]);
+    /// ```
+    pub fn into_parts(self) -> (Vec<Ef>, Vec<Ev>) {
+        (self.effects, self.events)
+    }
+
+    /// Find the first effect matching the `predicate`, without taking it
+    /// out of the [`Update`]
+    ///
+    /// ```
+    /// # use crux_core::testing::Update;
+    /// # #[derive(Debug, PartialEq)]
+    /// # enum Effect { Render, Http(String) };
+    /// # enum Event { None };
+    /// let update: Update<Effect, Event> = Update {
+    ///     effects: vec![
+    ///         Effect::Render,
+    ///         Effect::Http("/one".to_string()),
+    ///         Effect::Http("/two".to_string()),
+    ///     ],
+    ///     events: vec![],
+    /// };
+    ///
+    /// let effect = update.find_effect(|effect| matches!(effect, Effect::Http(_)));
+    /// assert_eq!(effect, Some(&Effect::Http("/one".to_string())));
+    /// assert_eq!(update.effect_count(), 3);
+    /// ```
+    pub fn find_effect<P>(&self, mut predicate: P) -> Option<&Ef>
+    where
+        P: FnMut(&Ef) -> bool,
+    {
+        self.effects.iter().find(|effect| predicate(effect))
+    }
+
+    /// Find the first effect matching the `predicate`, without taking it
+    /// out of the [`Update`], and return a mutable reference to it, e.g. to
+    /// resolve its request
+    ///
+    /// ```
+    /// # use crux_core::testing::Update;
+    /// # #[derive(Debug, PartialEq)]
+    /// # enum Effect { Render, Http(String) };
+    /// # enum Event { None };
+    /// let mut update: Update<Effect, Event> = Update {
+    ///     effects: vec![Effect::Render, Effect::Http("/one".to_string())],
+    ///     events: vec![],
+    /// };
+    ///
+    /// if let Some(Effect::Http(url)) = update.find_effect_mut(|effect| matches!(effect, Effect::Http(_))) {
+    ///     url.push_str("?page=2");
+    /// }
+    /// assert_eq!(update.effects[1], Effect::Http("/one?page=2".to_string()));
+    /// ```
+    pub fn find_effect_mut<P>(&mut self, mut predicate: P) -> Option<&mut Ef>
+    where
+        P: FnMut(&Ef) -> bool,
+    {
+        self.effects.iter_mut().find(|effect| predicate(effect))
+    }
+
+    /// The number of effects in the update
+    ///
+    /// ```
+    /// # use crux_core::testing::Update;
+    /// # enum Effect { Render };
+    /// # enum Event { None };
+    /// let update: Update<Effect, Event> = Update {
+    ///     effects: vec![Effect::Render, Effect::Render],
+    ///     events: vec![],
+    /// };
+    /// assert_eq!(update.effect_count(), 2);
+    /// ```
+    pub fn effect_count(&self) -> usize {
+        self.effects.len()
+    }
+
+    /// The number of events in the update
+    ///
+    /// ```
+    /// # use crux_core::testing::Update;
+    /// # enum Effect { Render };
+    /// # enum Event { None };
+    /// let update: Update<Effect, Event> = Update {
+    ///     effects: vec![Effect::Render],
+    ///     events: vec![Event::None],
+    /// };
+    /// assert_eq!(update.event_count(), 1);
+    /// ```
+    pub fn event_count(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns `true` if the update contains no effects or events. This is the
+    /// non-panicking version of [`Update::assert_empty`].
+    ///
+    /// ```
+    /// # use crux_core::testing::Update;
+    /// # enum Effect { Render };
+    /// # enum Event { None };
+    /// let update: Update<Effect, Event> = Update {
+    ///     effects: vec![],
+    ///     events: vec![],
+    /// };
+    /// assert!(update.is_empty());
+    ///
+    /// let update: Update<Effect, Event> = Update {
+    ///     effects: vec![],
+    ///     events: vec![Event::None],
+    /// };
+    /// assert!(!update.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty() && self.events.is_empty()
+    }
+
+    /// Append the effects and events of `other` to this update, e.g. to check the
+    /// combined result of several updates at once.
+    ///
+    /// ```
+    /// # use crux_core::testing::Update;
+    /// # enum Effect { Render };
+    /// # enum Event { None };
+    /// let first: Update<Effect, Event> = Update {
+    ///     effects: vec![Effect::Render],
+    ///     events: vec![],
+    /// };
+    /// let second = Update {
+    ///     effects: vec![Effect::Render],
+    ///     events: vec![Event::None],
+    /// };
+    /// let update = first.merge(second);
+    /// assert_eq!(update.effect_count(), 2);
+    /// assert_eq!(update.event_count(), 1);
+    /// ```
+    pub fn merge(mut self, other: Update<Ef, Ev>) -> Self {
+        self.effects.extend(other.effects);
+        self.events.extend(other.events);
+        self
+    }
+
     /// Assert that the update contains exactly one effect and zero events,
     /// and return the effect
     pub fn expect_one_effect(mut self) -> Ef {
@@ -192,9 +1248,36 @@ impl<Ef, Ev> Update<Ef, Ev> {
         }
     }
 
+    /// Assert that the update contains exactly one effect and zero events, take the request
+    /// out of it with `request` (typically one of the `expect_*` methods of the app's Effect),
+    /// resolve it with `value` using the `tester`, and return the resulting update.
+    ///
+    /// ```rust,ignore
+    /// let update = app
+    ///     .update(Event::Get, &mut model)
+    ///     .resolve_one(&app, Effect::expect_time, TimeResponse::Now(now));
+    /// ```
+    pub fn resolve_one<App, Op, F>(
+        self,
+        tester: &AppTester<App, Ef>,
+        request: F,
+        value: Op::Output,
+    ) -> Update<Ef, Ev>
+    where
+        App: crate::App<Event = Ev>,
+        Op: Operation,
+        F: FnOnce(Ef) -> Request<Op>,
+    {
+        let mut request = request(self.expect_one_effect());
+
+        tester
+            .resolve(&mut request, value)
+            .expect("failed to resolve request")
+    }
+
     /// Assert that the update contains no effects or events
     pub fn assert_empty(self) {
-        if self.effects.is_empty() && self.events.is_empty() {
+        if self.is_empty() {
             return;
         }
         panic!(
@@ -217,6 +1300,32 @@ impl<Ef, Ev> Update<Ef, Ev> {
         matching_effects
     }
 
+    /// Take the first effect matching the `predicate` out of the [`Update`]
+    /// and return it, leaving the other effects in the `Update`
+    ///
+    /// ```
+    /// # use crux_core::testing::Update;
+    /// # #[derive(Debug, PartialEq)]
+    /// # enum Effect { Render, Http(String) };
+    /// # enum Event { None };
+    /// let mut update: Update<Effect, Event> = Update {
+    ///     effects: vec![Effect::Render, Effect::Http("/one".to_string())],
+    ///     events: vec![],
+    /// };
+    ///
+    /// let effect = update.take_one_effect(|effect| matches!(effect, Effect::Http(_)));
+    /// assert_eq!(effect, Some(Effect::Http("/one".to_string())));
+    /// assert_eq!(update.effects, vec![Effect::Render]);
+    /// ```
+    pub fn take_one_effect<P>(&mut self, predicate: P) -> Option<Ef>
+    where
+        P: FnMut(&Ef) -> bool,
+    {
+        let index = self.effects.iter().position(predicate)?;
+
+        Some(self.effects.remove(index))
+    }
+
     /// Take all of the effects out of the [`Update`]
     /// and split them into those matching `predicate` and the rest
     pub fn take_effects_partitioned_by<P>(&mut self, predicate: P) -> (VecDeque<Ef>, VecDeque<Ef>)
@@ -229,6 +1338,74 @@ impl<Ef, Ev> Update<Ef, Ev> {
     }
 }
 
+impl<Ef, Ev> Update<Ef, Ev>
+where
+    Ef: Effect,
+{
+    /// Iterate over the effects in the [`Update`] alongside the names of their effect
+    /// variants (see [`Effect::name`]), e.g. to print a readable summary of an update
+    /// while debugging a test.
+    ///
+    /// ```rust,ignore
+    /// for (name, effect) in update.effects_named() {
+    ///     println!("{name}: {effect:?}");
+    /// }
+    /// ```
+    pub fn effects_named(&self) -> impl Iterator<Item = (&'static str, &Ef)> {
+        self.effects.iter().map(|effect| (effect.name(), effect))
+    }
+
+    /// Take all of the effects out of the [`Update`] and group them by the
+    /// capability which requested them, keyed by the name of the effect variant
+    /// (see [`Effect::name`]). Effects keep the order they were requested in
+    /// within each group.
+    pub fn partition_by_capability(&mut self) -> BTreeMap<&'static str, VecDeque<Ef>> {
+        let mut partitioned = BTreeMap::new();
+
+        while let Some(name) = self.effects.first().map(Effect::name) {
+            let (matching_effects, other_effects) =
+                self.take_effects_partitioned_by(|effect| effect.name() == name);
+
+            self.effects = other_effects.into_iter().collect();
+            partitioned.insert(name, matching_effects);
+        }
+
+        partitioned
+    }
+
+    /// Assert that the update contains no effects requested by the capability `name`, which
+    /// is the name of the effect variant (see [`Effect::name`]), e.g. `"KeyValue"`.
+    pub fn assert_no_effects_of(&self, name: &str) {
+        let count = self.effects.iter().filter(|e| e.name() == name).count();
+        if count > 0 {
+            panic!(
+                "Expected no {name} effects but found {count}, the update contains: {}",
+                self.effect_names()
+            );
+        }
+    }
+
+    /// Assert that the update contains at least one effect requested by the capability `name`,
+    /// which is the name of the effect variant (see [`Effect::name`]), e.g. `"KeyValue"`.
+    pub fn assert_has_effect_of(&self, name: &str) {
+        if !self.effects.iter().any(|e| e.name() == name) {
+            panic!(
+                "Expected a {name} effect, but the update contains: {}",
+                self.effect_names()
+            );
+        }
+    }
+
+    fn effect_names(&self) -> String {
+        if self.effects.is_empty() {
+            return "no effects".to_string();
+        }
+
+        let names: Vec<_> = self.effects.iter().map(Effect::name).collect();
+        format!("[{}]", names.join(", "))
+    }
+}
+
 /// Panics if the pattern doesn't match an `Effect` from the specified `Update`
 ///
 /// Like in a `match` expression, the pattern can be optionally followed by `if`
@@ -251,3 +1428,49 @@ macro_rules! assert_effect {
         assert!($expression.effects().any(|e| matches!(e, $( $pattern )|+ $( if $guard )?)));
     };
 }
+
+/// Panics unless the effects of the specified `Update` match the list of patterns
+/// exactly, one pattern per effect, in order.
+///
+/// Like in [`assert_effect`], each pattern can be optionally followed by `if`
+/// and a guard expression.
+///
+/// The panic message shows the expected patterns and the actual effects, using their
+/// `Debug` representation.
+///
+/// # Example
+///
+/// ```
+/// # use crux_core::testing::Update;
+/// # #[derive(Debug)]
+/// # enum Effect { Render(String), Http(String) };
+/// # enum Event { None };
+/// # let effects = vec![
+/// #     Effect::Render("loading".to_string()),
+/// #     Effect::Http("GET".to_string()),
+/// #     Effect::Render("loaded".to_string()),
+/// # ];
+/// # let update = Update { effects, events: vec!(Event::None) };
+/// use crux_core::assert_effects_match;
+/// assert_effects_match!(update, [Effect::Render(_), Effect::Http(_), Effect::Render(_)]);
+/// ```
+#[macro_export]
+macro_rules! assert_effects_match {
+    ($expression:expr, [$( $pattern:pat $( if $guard:expr )? ),* $(,)?] $(,)?) => {{
+        let update = &$expression;
+        let effects: Vec<_> = update.effects().collect();
+        let expected: &[&str] = &[$( stringify!($pattern $( if $guard )?) ),*];
+
+        let matching = effects.len() == expected.len() && {
+            let mut effects = effects.iter().map(|effect| &**effect);
+            true $( && matches!(effects.next(), Some($pattern) $( if $guard )?) )*
+        };
+
+        assert!(
+            matching,
+            "Effects don't match\nexpected: [{}]\n  actual: {:?}",
+            expected.join(", "),
+            effects
+        );
+    }};
+}
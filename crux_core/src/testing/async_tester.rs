@@ -0,0 +1,103 @@
+use anyhow::Result;
+
+use super::{AppTester, Update};
+use crate::{capability::Operation, Effect, Request, WithContext};
+
+/// AsyncAppTester is a version of [`AppTester`] for apps whose capabilities await
+/// async work of their own, e.g. a computation running on another thread, besides
+/// the requests to the shell.
+///
+/// Its `update` and `resolve` are `async` and only return once the capability tasks
+/// are no longer waiting for such work, with the effects and events of all of it in
+/// the [`Update`]. Tasks waiting for the shell, or for the virtual clock, don't hold
+/// them up. The tester doesn't depend on a particular async runtime, its futures can
+/// be awaited in a `#[tokio::test]` as well as with [`futures::executor::block_on`].
+///
+/// Only available with the `async_testing` feature.
+///
+/// ```rust,ignore
+/// let app = AsyncAppTester::<ExampleApp, ExampleEffect>::default();
+///
+/// let update = app.update(Event::Compute, &mut model).await;
+/// ```
+pub struct AsyncAppTester<App, Ef>
+where
+    App: crate::App,
+{
+    tester: AppTester<App, Ef>,
+}
+
+impl<App, Ef> AsyncAppTester<App, Ef>
+where
+    App: crate::App,
+    Ef: Effect,
+{
+    /// Create an `AsyncAppTester` instance for an existing app instance.
+    /// See [`AppTester::new`].
+    pub fn new(app: App) -> Self
+    where
+        Ef: Send + 'static,
+        App::Capabilities: WithContext<App::Event, Ef>,
+    {
+        Self {
+            tester: AppTester::new(app),
+        }
+    }
+
+    /// Run the app's `update` function with an event and a model state, and wait for
+    /// the capability tasks to finish their async work
+    pub async fn update(
+        &self,
+        event: App::Event,
+        model: &mut App::Model,
+    ) -> Update<Ef, App::Event> {
+        let update = self.tester.update(event, model);
+
+        self.settle(update).await
+    }
+
+    /// Resolve an effect `request` from previous update with an operation output, and wait
+    /// for the capability tasks to finish their async work
+    pub async fn resolve<Op: Operation>(
+        &self,
+        request: &mut Request<Op>,
+        value: Op::Output,
+    ) -> Result<Update<Ef, App::Event>> {
+        let update = self.tester.resolve(request, value)?;
+
+        Ok(self.settle(update).await)
+    }
+
+    /// Run the app's `view` function with a model state
+    pub fn view(&self, model: &App::Model) -> App::ViewModel {
+        self.tester.view(model)
+    }
+
+    /// The synchronous [`AppTester`] underneath, for everything else
+    pub fn tester(&self) -> &AppTester<App, Ef> {
+        &self.tester
+    }
+
+    async fn settle(&self, mut update: Update<Ef, App::Event>) -> Update<Ef, App::Event> {
+        let context = &self.tester.context;
+
+        while context.executor.is_waiting_elsewhere() {
+            context.executor.wait_for_work().await;
+
+            update = update.merge(self.tester.record_effects(context.updates()));
+        }
+
+        update
+    }
+}
+
+impl<App, Ef> Default for AsyncAppTester<App, Ef>
+where
+    App: crate::App,
+    App::Capabilities: WithContext<App::Event, Ef>,
+    Ef: Effect + Send + 'static,
+{
+    fn default() -> Self {
+        Self::new(App::default())
+    }
+}
@@ -1,4 +1,4 @@
-//! Generation of foreign language types (currently Swift, Java, TypeScript) for Crux
+//! Generation of foreign language types (currently Swift, Java, TypeScript, Dart, C#) for Crux
 //!
 //! In order to use this module, you'll need a separate crate from your shared library, possibly
 //! called `shared_types`. This is necessary because we need to reference types from your shared library
@@ -66,6 +66,10 @@
 //!    gen.java("com.example.counter.shared_types", output_root.join("java"))?;
 //!
 //!    gen.typescript("shared_types", output_root.join("typescript"))?;
+//!
+//!    gen.dart("shared_types", output_root.join("dart"))?;
+//!
+//!    gen.csharp("SharedTypes", output_root.join("csharp"))?;
 //!}
 //! ```
 //!
@@ -76,6 +80,8 @@
 //! - `generated/typescript/*`,
 //! - `generated/swift/(requests | Package).swift` -
 //! - `generated/java/Requests.java`
+//! - `generated/dart/(package)/lib/src/requests.dart`
+//! - `generated/csharp/(namespace)/Requests.cs`
 //!
 //! Then create the `typegen_extensions/{target}/{target-file}`
 //! with the desired content next to your `build.rs` file.
@@ -113,10 +119,11 @@
 //! )
 //! ```
 
-use serde::Deserialize;
-use serde_generate::{java, swift, typescript, Encoding, SourceInstaller};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_generate::{csharp, dart, java, swift, typescript, Encoding, SourceInstaller};
 use serde_reflection::{Registry, Tracer, TracerConfig};
 use std::{
+    collections::BTreeMap,
     fs::{self, File},
     io::Write,
     mem,
@@ -127,7 +134,13 @@ use thiserror::Error;
 // Expose from `serde_reflection` for `register_type_with_samples()`
 use serde_reflection::Samples;
 
-use crate::App;
+// Re-exported to describe types registered with `register_type_with_format()`
+pub use serde_reflection::{ContainerFormat, Format, Named, VariantFormat};
+
+use crate::{capability::Operation, App};
+
+mod csharp_guid;
+mod typescript_json;
 
 pub type Result = std::result::Result<(), TypeGenError>;
 
@@ -149,12 +162,62 @@ pub enum TypeGenError {
     LateRegistration,
     #[error("type generation failed: {0}")]
     Generation(String),
+    #[error("types referenced but not registered: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    MissingTypes(Vec<MissingType>),
     #[error("error writing generated types")]
     Io(#[from] std::io::Error),
     #[error("`pnpm` is needed for TypeScript type generation, but it could not be found in PATH.\nPlease install it from https://pnpm.io/installation")]
     PnpmNotFound(#[source] std::io::Error),
 }
 
+/// A type referenced by a registered type, which is not registered itself,
+/// as reported by [`TypeGen::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingType {
+    /// The name of the missing type
+    pub name: String,
+    /// The name of the registered type referencing it
+    pub referenced_by: String,
+}
+
+impl std::fmt::Display for MissingType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (referenced by {})", self.name, self.referenced_by)
+    }
+}
+
+/// The serialization helpers generated for TypeScript, see [`TsOptions`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TsSerialization {
+    /// `serialize` and `deserialize` methods using bincode, as used by the Crux bridge
+    #[default]
+    Bincode,
+    /// `toJson` and `fromJson` methods, using the JSON representation `serde_json` would use
+    Json,
+    /// Both the bincode and the JSON methods
+    Both,
+}
+
+/// Options for [`TypeGen::typescript_with_options`]
+#[derive(Debug, Clone, Default)]
+pub struct TsOptions {
+    /// Which serialization helpers to generate
+    pub serialization: TsSerialization,
+    /// Where the registered types hold a `Uuid`, which is traced as bytes, but represented
+    /// as a hyphenated string in JSON. Each location is a type name (`MyUuid`) for new type
+    /// structs, a field (`Customer.id`, or `Customer.0` for tuple structs), a new type
+    /// variant (`Event::SendUuid`) or a field of a variant (`Event::Load.id`).
+    pub uuids: Vec<String>,
+}
+
+/// Options for [`TypeGen::csharp_with_options`]
+#[derive(Debug, Clone, Default)]
+pub struct CsOptions {
+    /// Where the registered types hold a `Uuid`, which is traced as bytes, but represented
+    /// as a `System.Guid` in C#. The locations are the same as in [`TsOptions::uuids`].
+    pub uuids: Vec<String>,
+}
+
 #[derive(Debug)]
 pub enum State {
     Registering(Tracer, Samples),
@@ -165,20 +228,152 @@ pub trait Export {
     fn register_types(generator: &mut TypeGen) -> Result;
 }
 
+/// The types a capability shares with the shell, for registering the capability on its own
+/// with [`TypeGen::register_capability`], e.g. to generate the types of a third-party
+/// capability crate without an app.
+///
+/// By default, the operation and its output are registered. Capabilities with other types
+/// the shell needs, which aren't reachable from those, override `register_types`.
+pub trait RegisterTypes {
+    type Operation: Operation + DeserializeOwned;
+
+    fn register_types(generator: &mut TypeGen) -> Result {
+        generator.register_type::<Self::Operation>()?;
+        generator.register_type::<<Self::Operation as Operation>::Output>()?;
+        Ok(())
+    }
+}
+
+/// A list of types to register at once with [`TypeGen::register_types`] or [`TypeGen::from_types`],
+/// implemented for tuples of up to 12 types, e.g. `(Customer, Order)`.
+pub trait TypeList {
+    fn register(generator: &mut TypeGen) -> Result;
+}
+
+macro_rules! impl_type_list {
+    ($($ty:ident),+) => {
+        impl<$($ty),+> TypeList for ($($ty,)+)
+        where
+            $($ty: DeserializeOwned,)+
+        {
+            fn register(generator: &mut TypeGen) -> Result {
+                $(generator.register_type::<$ty>()?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_type_list!(A);
+impl_type_list!(A, B);
+impl_type_list!(A, B, C);
+impl_type_list!(A, B, C, D);
+impl_type_list!(A, B, C, D, E);
+impl_type_list!(A, B, C, D, E, F);
+impl_type_list!(A, B, C, D, E, F, G);
+impl_type_list!(A, B, C, D, E, F, G, H);
+impl_type_list!(A, B, C, D, E, F, G, H, I);
+impl_type_list!(A, B, C, D, E, F, G, H, I, J);
+impl_type_list!(A, B, C, D, E, F, G, H, I, J, K);
+impl_type_list!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// An app composed of child apps, for registering the types of the whole tree of apps
+/// with [`TypeGen::register_composed_app`].
+///
+/// The parent's effect and event types embed those of its children, so the generated
+/// code for all the apps lives in the same namespace. Types shared between the apps
+/// (like the operations of common capabilities) are only generated once, but the apps'
+/// own types need distinct names, e.g. using `#[effect(name = "ChildEffect")]` on the
+/// child's capabilities and `#[serde(rename = "ChildEvent")]` on its event.
+///
+/// Apps without children declare them as `()`.
+pub trait ComposedApp: App {
+    /// The child apps, as a tuple, e.g. `(Counter, Settings)`
+    type Children: AppList;
+}
+
+/// A list of apps to register at once with [`TypeGen::register_composed_app`],
+/// implemented for tuples of up to 12 apps, and for `()` for apps without children.
+pub trait AppList {
+    fn register(generator: &mut TypeGen) -> Result;
+}
+
+impl AppList for () {
+    fn register(_generator: &mut TypeGen) -> Result {
+        Ok(())
+    }
+}
+
+macro_rules! impl_app_list {
+    ($($app:ident),+) => {
+        impl<$($app),+> AppList for ($($app,)+)
+        where
+            $(
+                $app: ComposedApp,
+                $app::Capabilities: Export,
+                $app::Event: Deserialize<'static>,
+                $app::ViewModel: Deserialize<'static> + 'static,
+            )+
+        {
+            fn register(generator: &mut TypeGen) -> Result {
+                $(generator.register_composed_app::<$app>()?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_app_list!(A);
+impl_app_list!(A, B);
+impl_app_list!(A, B, C);
+impl_app_list!(A, B, C, D);
+impl_app_list!(A, B, C, D, E);
+impl_app_list!(A, B, C, D, E, F);
+impl_app_list!(A, B, C, D, E, F, G);
+impl_app_list!(A, B, C, D, E, F, G, H);
+impl_app_list!(A, B, C, D, E, F, G, H, I);
+impl_app_list!(A, B, C, D, E, F, G, H, I, J);
+impl_app_list!(A, B, C, D, E, F, G, H, I, J, K);
+impl_app_list!(A, B, C, D, E, F, G, H, I, J, K, L);
+
 /// The `TypeGen` struct stores the registered types so that they can be generated for foreign languages
 /// use `TypeGen::new()` to create an instance
 pub struct TypeGen {
     pub state: State,
+    // output formats of registered capability operations, keyed by operation type name
+    outputs: BTreeMap<String, Format>,
+    // formats provided by hand for types which can't be traced, keyed by type name
+    overrides: BTreeMap<String, ContainerFormat>,
+    // how many levels deep into child apps the registration currently is
+    depth: usize,
 }
 
 impl Default for TypeGen {
     fn default() -> Self {
         TypeGen {
             state: State::Registering(Tracer::new(TracerConfig::default()), Samples::new()),
+            outputs: BTreeMap::new(),
+            overrides: BTreeMap::new(),
+            depth: 0,
         }
     }
 }
 
+/// A machine-readable description of the effects an app can request,
+/// as written by [`TypeGen::manifest`]
+#[derive(Serialize, Debug)]
+struct Manifest {
+    effect: String,
+    effects: Vec<EffectManifest>,
+}
+
+#[derive(Serialize, Debug)]
+struct EffectManifest {
+    name: String,
+    operation: Format,
+    output: Option<Format>,
+}
+
 impl TypeGen {
     /// Creates an instance of the `TypeGen` struct
     pub fn new() -> Self {
@@ -207,6 +402,70 @@ impl TypeGen {
         Ok(())
     }
 
+    /// Register all the types used in the composed app `A` and, transitively, in its
+    /// child apps declared with [`ComposedApp`].
+    ///
+    /// The bridge requests carry the effect of the root app, so only the root app registers
+    /// them, the child apps register their own effect types only. Types registered by more
+    /// than one app are deduplicated, as long as they are identical.
+    pub fn register_composed_app<A: ComposedApp>(&mut self) -> Result
+    where
+        A::Capabilities: Export,
+        A::Event: Deserialize<'static>,
+        A::ViewModel: Deserialize<'static> + 'static,
+    {
+        self.register_app::<A>()?;
+
+        self.depth += 1;
+        let result = A::Children::register(self);
+        self.depth -= 1;
+
+        result
+    }
+
+    /// Register the types of the capability `C`, its operation input and output types and
+    /// any other types it declares with [`RegisterTypes`], so that they are listed in the
+    /// [manifest](TypeGen::manifest) like those of the capabilities of an app.
+    ///
+    /// The capabilities of an app are registered by [`TypeGen::register_app`].
+    pub fn register_capability<C: RegisterTypes>(&mut self) -> Result {
+        C::register_types(self)?;
+        self.register_operation::<C::Operation>()
+    }
+
+    /// Creates a `TypeGen` for a standalone list of types, e.g. the DTOs of a crate
+    /// without an [`App`], registering the types in the list and validating the result
+    /// with [`TypeGen::validate`].
+    ///
+    /// Without an app, the generated code doesn't include the helpers for the bridge
+    /// requests. Types with custom serialization still need their samples, so register
+    /// them with [`TypeGen::register_types`] after the samples instead.
+    /// e.g.
+    /// ```rust
+    /// # use crux_core::typegen::TypeGen;
+    /// # use serde::{Serialize, Deserialize};
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Customer { name: String, address: Address }
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Address { city: String }
+    ///
+    /// let gen = TypeGen::from_types::<(Customer, Address)>()?;
+    /// # Ok::<(), crux_core::typegen::TypeGenError>(())
+    /// ```
+    pub fn from_types<T: TypeList>() -> std::result::Result<Self, TypeGenError> {
+        let mut gen = Self::new();
+
+        gen.register_types::<T>()?;
+        gen.validate()?;
+
+        Ok(gen)
+    }
+
+    /// Register all the types in the list `T` (a tuple of types) with [`TypeGen::register_type`].
+    pub fn register_types<T: TypeList>(&mut self) -> Result {
+        T::register(self)
+    }
+
     /// Register sample values for types with custom serialization. This is necessary
     /// because the type registration relies on Serde to understand the structure of the types,
     /// and as part of the process runs a faux deserialization on each of them, with a best
@@ -279,6 +538,60 @@ The 2 common cases are:
         }
     }
 
+    /// Register a capability's operation type together with its output type, so that
+    /// they can be listed in the [manifest](TypeGen::manifest).
+    ///
+    /// This is called for each capability by the `Export` derive macro,
+    /// after the capability has registered its own types. Both types are traced with the
+    /// samples registered so far, so the capability needs to register samples for types
+    /// with custom serialization first, see [`TypeGen::register_samples`].
+    pub fn register_operation<Op>(&mut self) -> Result
+    where
+        Op: Operation + DeserializeOwned,
+    {
+        match &mut self.state {
+            State::Registering(tracer, samples) => {
+                let (operation, _) = tracer.trace_type::<Op>(samples).map_err(|e| {
+                    TypeGenError::TypeTracing(format!("{e}: {exp}", exp = e.explanation()))
+                })?;
+                let (output, _) = tracer.trace_type::<Op::Output>(samples).map_err(|e| {
+                    TypeGenError::TypeTracing(format!("{e}: {exp}", exp = e.explanation()))
+                })?;
+
+                if let Format::TypeName(name) = operation {
+                    self.outputs.insert(name, output);
+                }
+
+                Ok(())
+            }
+            _ => Err(TypeGenError::LateRegistration),
+        }
+    }
+
+    /// Register an app's effect type, together with the bridge [`Request`](crate::bridge::Request)
+    /// carrying it and the [`ProtocolVersion`](crate::bridge::ProtocolVersion) the shell sends in
+    /// the handshake, unless the app is a child registered by [`TypeGen::register_composed_app`].
+    ///
+    /// The generated `Request` has the fields of the current protocol version, which the core
+    /// only sends once the shell negotiated them, so the shell has to call
+    /// [`Bridge::handshake`](crate::bridge::Bridge::handshake) with
+    /// [`ProtocolVersion::CURRENT`](crate::bridge::ProtocolVersion::CURRENT) on startup.
+    ///
+    /// This is called by the `Export` derive macro, after the capabilities have been registered.
+    pub fn register_effect<Ffi>(&mut self) -> Result
+    where
+        Ffi: Serialize + DeserializeOwned,
+    {
+        self.register_type::<Ffi>()?;
+
+        if self.depth == 0 {
+            self.register_type::<crate::bridge::Request<Ffi>>()?;
+            self.register_type::<crate::bridge::ProtocolVersion>()?;
+        }
+
+        Ok(())
+    }
+
     /// Usually, the simple `register_type()` method can generate the types you need.
     /// Sometimes, though, you need to provide samples of your type. The `Uuid` type,
     /// for example, requires a sample struct to help the typegen system understand
@@ -345,6 +658,107 @@ The 2 common cases are:
         }
     }
 
+    /// Register a type with a format provided by hand, instead of tracing it. This is
+    /// an escape hatch for types which `serde_reflection` can't trace, or traces incorrectly,
+    /// for example structs using `#[serde(flatten)]`.
+    ///
+    /// The type is registered under its Rust name, replacing any traced format of the same name.
+    /// Types containing it still need to be traceable, or registered with a format as well.
+    /// e.g.
+    /// ```rust
+    /// # use crux_core::typegen::{ContainerFormat, Format, Named, TypeGen};
+    /// # use serde::{Serialize, Deserialize};
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Base { id: u32 }
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Item { #[serde(flatten)] base: Base, name: String }
+    ///
+    /// let mut gen = TypeGen::new();
+    /// gen.register_type_with_format::<Item>(ContainerFormat::Struct(vec![
+    ///     Named { name: "id".to_string(), value: Format::U32 },
+    ///     Named { name: "name".to_string(), value: Format::Str },
+    /// ]))?;
+    /// # Ok::<(), crux_core::typegen::TypeGenError>(())
+    /// ```
+    pub fn register_type_with_format<T>(&mut self, format: ContainerFormat) -> Result {
+        match &self.state {
+            State::Registering(_, _) => {
+                self.overrides
+                    .insert(rust_type_name::<T>().to_string(), format);
+                Ok(())
+            }
+            _ => Err(TypeGenError::LateRegistration),
+        }
+    }
+
+    /// Checks that every type referenced by the registered types is registered too, so that
+    /// the generated code doesn't refer to types which don't exist. Call this before generating
+    /// any code, the missing types are reported in [`TypeGenError::MissingTypes`].
+    ///
+    /// Types discovered by tracing are always complete, but formats provided with
+    /// [`TypeGen::register_type_with_format`] can reference types which were never registered.
+    /// e.g.
+    /// ```rust
+    /// # use crux_core::typegen::TypeGen;
+    /// # let mut gen = TypeGen::new();
+    /// gen.validate()?;
+    /// # Ok::<(), crux_core::typegen::TypeGenError>(())
+    /// ```
+    pub fn validate(&mut self) -> Result {
+        self.ensure_registry()?;
+
+        let registry = match &self.state {
+            State::Generating(registry) => registry,
+            _ => panic!("registry creation failed"),
+        };
+
+        let mut missing = Vec::new();
+        for (referenced_by, container) in registry {
+            let mut names = Vec::new();
+            container_type_names(container, &mut names);
+
+            for name in names {
+                let missing_type = MissingType {
+                    name,
+                    referenced_by: referenced_by.clone(),
+                };
+                if !registry.contains_key(&missing_type.name) && !missing.contains(&missing_type) {
+                    missing.push(missing_type);
+                }
+            }
+        }
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(TypeGenError::MissingTypes(missing))
+        }
+    }
+
+    /// Returns a copy of the formats of all the registered types, keyed by type name,
+    /// e.g. to inspect what will be generated, or to assert on it in tests.
+    ///
+    /// Like generating code, this finishes the registration, so any types registered
+    /// afterwards are rejected with [`TypeGenError::LateRegistration`].
+    /// e.g.
+    /// ```rust
+    /// # use crux_core::typegen::TypeGen;
+    /// # let mut gen = TypeGen::new();
+    /// let registry = gen.registry_snapshot()?;
+    /// dbg!(registry.get("Event"));
+    /// # Ok::<(), crux_core::typegen::TypeGenError>(())
+    /// ```
+    pub fn registry_snapshot(
+        &mut self,
+    ) -> std::result::Result<BTreeMap<String, ContainerFormat>, TypeGenError> {
+        self.ensure_registry()?;
+
+        match &self.state {
+            State::Generating(registry) => Ok(registry.clone()),
+            _ => panic!("registry creation failed"),
+        }
+    }
+
     /// Generates types for Swift
     /// e.g.
     /// ```rust
@@ -383,17 +797,19 @@ The 2 common cases are:
             .map_err(|e| TypeGenError::Generation(e.to_string()))?;
 
         // add bincode deserialization for Vec<Request>
-        let mut output = File::create(
-            path.join("Sources")
-                .join(module_name)
-                .join("Requests.swift"),
-        )?;
+        if self.has_requests() {
+            let mut output = File::create(
+                path.join("Sources")
+                    .join(module_name)
+                    .join("Requests.swift"),
+            )?;
 
-        let requests_path = self.extensions_path("swift/requests.swift");
+            let requests_path = self.extensions_path("swift/requests.swift");
 
-        let requests_data = fs::read_to_string(requests_path)?;
+            let requests_data = fs::read_to_string(requests_path)?;
 
-        write!(output, "{}", requests_data)?;
+            write!(output, "{}", requests_data)?;
+        }
 
         // wrap it all up in a swift package
         let mut output = File::create(path.join("Package.swift"))?;
@@ -454,19 +870,21 @@ The 2 common cases are:
             .install_module(&config, registry)
             .map_err(|e| TypeGenError::Generation(e.to_string()))?;
 
-        let requests_path = self.extensions_path("java/Requests.java");
+        if self.has_requests() {
+            let requests_path = self.extensions_path("java/Requests.java");
 
-        let requests_data = fs::read_to_string(requests_path)?;
+            let requests_data = fs::read_to_string(requests_path)?;
 
-        let requests = format!("package {package_name};\n\n{}", requests_data);
+            let requests = format!("package {package_name};\n\n{}", requests_data);
 
-        fs::write(
-            path.as_ref()
-                .to_path_buf()
-                .join(package_path)
-                .join("Requests.java"),
-            requests,
-        )?;
+            fs::write(
+                path.as_ref()
+                    .to_path_buf()
+                    .join(package_path)
+                    .join("Requests.java"),
+                requests,
+            )?;
+        }
 
         Ok(())
     }
@@ -482,6 +900,32 @@ The 2 common cases are:
     /// # Ok::<(), crux_core::typegen::TypeGenError>(())
     /// ```
     pub fn typescript(&mut self, module_name: &str, path: impl AsRef<Path>) -> Result {
+        self.typescript_with_options(module_name, path, TsOptions::default())
+    }
+
+    /// Generates types for TypeScript, with the serialization helpers selected by the `options`
+    /// e.g. for a web shell exchanging JSON with a server
+    /// ```rust
+    /// # use crux_core::typegen::{TsOptions, TsSerialization, TypeGen};
+    /// # use std::env::temp_dir;
+    /// # let mut gen = TypeGen::new();
+    /// # let output_root = temp_dir().join("crux_core_typegen_doctest");
+    /// gen.typescript_with_options(
+    ///     "shared_types",
+    ///     output_root.join("typescript"),
+    ///     TsOptions {
+    ///         serialization: TsSerialization::Both,
+    ///         ..Default::default()
+    ///     },
+    /// )?;
+    /// # Ok::<(), crux_core::typegen::TypeGenError>(())
+    /// ```
+    pub fn typescript_with_options(
+        &mut self,
+        module_name: &str,
+        path: impl AsRef<Path>,
+        options: TsOptions,
+    ) -> Result {
         self.ensure_registry()?;
 
         fs::create_dir_all(&path)?;
@@ -498,25 +942,7 @@ The 2 common cases are:
         let extensions_dir = self.extensions_path("typescript");
         copy(extensions_dir, path)?;
 
-        let registry = match &self.state {
-            State::Generating(registry) => registry,
-            _ => panic!("registry creation failed"),
-        };
-
-        let config = serde_generate::CodeGeneratorConfig::new(module_name.to_string())
-            .with_encodings(vec![Encoding::Bincode]);
-
-        let generator = serde_generate::typescript::CodeGenerator::new(&config);
-        let mut source = Vec::new();
-        generator.output(&mut source, registry)?;
-
-        // FIXME fix import paths in generated code which assume running on Deno
-        let out = String::from_utf8_lossy(&source)
-            .replace(
-                "import { BcsSerializer, BcsDeserializer } from '../bcs/mod.ts';",
-                "",
-            )
-            .replace(".ts'", "'");
+        let out = self.typescript_source(module_name, &options)?;
 
         let types_dir = output_dir.join("types");
         fs::create_dir_all(&types_dir)?;
@@ -546,6 +972,327 @@ The 2 common cases are:
         Ok(())
     }
 
+    /// Generates types for Dart (for use with Flutter)
+    ///
+    /// The output is a Dart package named `package_name`, with a class for each
+    /// struct and an abstract class with a subclass per variant for each enum.
+    /// e.g.
+    /// ```rust
+    /// # use crux_core::typegen::TypeGen;
+    /// # use std::env::temp_dir;
+    /// # let mut gen = TypeGen::new();
+    /// # let output_root = temp_dir().join("crux_core_typegen_doctest");
+    /// gen.dart("shared_types", output_root.join("dart"))?;
+    /// # Ok::<(), crux_core::typegen::TypeGenError>(())
+    /// ```
+    pub fn dart(&mut self, package_name: &str, path: impl AsRef<Path>) -> Result {
+        self.ensure_registry()?;
+
+        let path = path.as_ref().join(package_name);
+
+        // remove any existing generated shared types, this ensures that we remove no longer used types
+        fs::remove_dir_all(&path).unwrap_or(());
+        fs::create_dir_all(&path)?;
+
+        let installer = dart::Installer::new(path.clone());
+        installer
+            .install_serde_runtime()
+            .map_err(|e| TypeGenError::Generation(e.to_string()))?;
+        installer
+            .install_bincode_runtime()
+            .map_err(|e| TypeGenError::Generation(e.to_string()))?;
+
+        let registry = match &self.state {
+            State::Generating(registry) => registry,
+            _ => panic!("registry creation failed"),
+        };
+
+        let config = serde_generate::CodeGeneratorConfig::new(package_name.to_string())
+            .with_encodings(vec![Encoding::Bincode]);
+
+        installer
+            .install_module(&config, registry)
+            .map_err(|e| TypeGenError::Generation(e.to_string()))?;
+
+        // add bincode deserialization for List<Request>
+        if self.has_requests() {
+            let requests_path = self.extensions_path("dart/requests.dart");
+
+            let requests_data = fs::read_to_string(requests_path)?;
+
+            fs::write(
+                path.join("lib").join("src").join("requests.dart"),
+                requests_data.replace("shared_types", package_name),
+            )?;
+
+            let mut output = fs::OpenOptions::new()
+                .append(true)
+                .open(path.join("lib").join(format!("{package_name}.dart")))?;
+
+            writeln!(output, "\nexport 'src/requests.dart';")?;
+        }
+
+        Ok(())
+    }
+
+    /// Generates types for C# (for use with .NET shells, e.g. MAUI)
+    ///
+    /// The output is a project for the `namespace`, with a class for each struct and
+    /// an abstract class with a nested subclass per variant for each enum, alongside the
+    /// `Serde` and `Bincode` runtime projects it references. Options are represented as
+    /// `Serde.Option<T>`, and byte arrays as `Serde.ValueArray<byte>`. A `uuid::Uuid` is
+    /// a byte array too, use [`TypeGen::csharp_with_options`] to represent it as a `System.Guid`.
+    /// e.g.
+    /// ```rust
+    /// # use crux_core::typegen::TypeGen;
+    /// # use std::env::temp_dir;
+    /// # let mut gen = TypeGen::new();
+    /// # let output_root = temp_dir().join("crux_core_typegen_doctest");
+    /// gen.csharp("SharedTypes", output_root.join("csharp"))?;
+    /// # Ok::<(), crux_core::typegen::TypeGenError>(())
+    /// ```
+    pub fn csharp(&mut self, namespace: &str, path: impl AsRef<Path>) -> Result {
+        self.csharp_with_options(namespace, path, CsOptions::default())
+    }
+
+    /// Generates types for C#, with the `Uuid`s listed in the `options` represented as
+    /// `System.Guid`, (de)serialized by the `GuidExtensions` added to the `Serde` runtime.
+    /// e.g.
+    /// ```rust
+    /// # use crux_core::typegen::{CsOptions, TypeGen};
+    /// # use std::env::temp_dir;
+    /// # let mut gen = TypeGen::new();
+    /// # let output_root = temp_dir().join("crux_core_typegen_doctest");
+    /// gen.csharp_with_options(
+    ///     "SharedTypes",
+    ///     output_root.join("csharp"),
+    ///     CsOptions {
+    ///         uuids: vec!["Event::SendUuid".to_string()],
+    ///     },
+    /// )?;
+    /// # Ok::<(), crux_core::typegen::TypeGenError>(())
+    /// ```
+    pub fn csharp_with_options(
+        &mut self,
+        namespace: &str,
+        path: impl AsRef<Path>,
+        options: CsOptions,
+    ) -> Result {
+        self.ensure_registry()?;
+
+        fs::create_dir_all(&path)?;
+
+        let namespace_path = namespace.split('.').collect::<PathBuf>();
+
+        // remove any existing generated shared types, this ensures that we remove no longer used types
+        fs::remove_dir_all(path.as_ref().join(&namespace_path)).unwrap_or(());
+
+        let mut config = serde_generate::CodeGeneratorConfig::new(namespace.to_string())
+            .with_encodings(vec![Encoding::Bincode]);
+        if !options.uuids.is_empty() {
+            config = config.with_external_definitions(csharp_guid::external_definitions());
+        }
+
+        let installer = csharp::Installer::new(path.as_ref().to_path_buf());
+        installer
+            .install_serde_runtime()
+            .map_err(|e| TypeGenError::Generation(e.to_string()))?;
+        installer
+            .install_bincode_runtime()
+            .map_err(|e| TypeGenError::Generation(e.to_string()))?;
+
+        let registry = match &self.state {
+            State::Generating(registry) => registry,
+            _ => panic!("registry creation failed"),
+        };
+
+        if options.uuids.is_empty() {
+            installer
+                .install_module(&config, registry)
+                .map_err(|e| TypeGenError::Generation(e.to_string()))?;
+        } else {
+            let registry = csharp_guid::with_guids(registry, &options.uuids);
+            installer
+                .install_module(&config, &registry)
+                .map_err(|e| TypeGenError::Generation(e.to_string()))?;
+
+            fs::write(
+                path.as_ref().join("Serde").join("GuidExtensions.cs"),
+                csharp_guid::GUID_RUNTIME,
+            )?;
+
+            for entry in fs::read_dir(path.as_ref().join(&namespace_path))? {
+                let file = entry?.path();
+                if file
+                    .extension()
+                    .map_or(false, |extension| extension == "cs")
+                {
+                    let source = fs::read_to_string(&file)?;
+                    fs::write(&file, csharp_guid::fix_guid_references(&source))?;
+                }
+            }
+        }
+
+        // add bincode deserialization for List<Request>
+        if self.has_requests() {
+            let requests_path = self.extensions_path("csharp/Requests.cs");
+
+            let requests_data = fs::read_to_string(requests_path)?;
+
+            fs::write(
+                path.as_ref().join(namespace_path).join("Requests.cs"),
+                requests_data.replace("namespace SharedTypes", &format!("namespace {namespace}")),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes a JSON manifest of the app's effects to the file at `path`.
+    ///
+    /// The manifest names the effect type and lists each of its variants together with
+    /// the format of the operation it carries and the format of the output the shell
+    /// is expected to respond with (`null` if the capability didn't register one). This is
+    /// useful for generating shell-side dispatch code, or checking that a shell handles
+    /// every effect. The formats are those of `serde_reflection`, and the named types are
+    /// the same as in the other generated code.
+    ///
+    /// e.g.
+    /// ```rust
+    /// # use crux_core::typegen::TypeGen;
+    /// # use crux_core::{render::Render, macros::{Effect, Export}};
+    /// # use std::env::temp_dir;
+    /// # #[derive(Default)]
+    /// # struct App;
+    /// # #[derive(serde::Serialize, serde::Deserialize)]
+    /// # enum Event { None }
+    /// # impl crux_core::App for App {
+    /// #     type Event = Event;
+    /// #     type Model = ();
+    /// #     type ViewModel = ();
+    /// #     type Capabilities = Capabilities;
+    /// #     fn update(&self, _: Event, _: &mut (), _: &Capabilities) {}
+    /// #     fn view(&self, _: &()) {}
+    /// # }
+    /// # #[derive(Effect, Export)]
+    /// # struct Capabilities {
+    /// #     render: Render<Event>,
+    /// # }
+    /// # let mut gen = TypeGen::new();
+    /// # gen.register_app::<App>()?;
+    /// # let output_root = temp_dir().join("crux_core_typegen_doctest");
+    /// # std::fs::create_dir_all(&output_root)?;
+    /// gen.manifest(output_root.join("manifest.json"))?;
+    /// # Ok::<(), crux_core::typegen::TypeGenError>(())
+    /// ```
+    pub fn manifest(&mut self, path: impl AsRef<Path>) -> Result {
+        self.ensure_registry()?;
+
+        let registry = match &self.state {
+            State::Generating(registry) => registry,
+            _ => panic!("registry creation failed"),
+        };
+
+        // the effect type is the one carried by the bridge `Request`
+        let effect = match registry.get("Request") {
+            Some(ContainerFormat::Struct(fields)) => fields
+                .iter()
+                .find(|field| field.name == "effect")
+                .and_then(|field| match &field.value {
+                    Format::TypeName(name) => Some(name.clone()),
+                    _ => None,
+                }),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            TypeGenError::Generation(
+                "no effect type registered, make sure to call register_app first".to_string(),
+            )
+        })?;
+
+        let Some(ContainerFormat::Enum(variants)) = registry.get(&effect) else {
+            return Err(TypeGenError::Generation(format!(
+                "effect type {effect} is not an enum"
+            )));
+        };
+
+        let effects = variants
+            .values()
+            .map(|variant| {
+                let VariantFormat::NewType(operation) = &variant.value else {
+                    return Err(TypeGenError::Generation(format!(
+                        "effect variant {effect}::{} does not carry an operation",
+                        variant.name
+                    )));
+                };
+
+                let output = match operation.as_ref() {
+                    Format::TypeName(name) => self.outputs.get(name).cloned(),
+                    _ => None,
+                };
+
+                Ok(EffectManifest {
+                    name: variant.name.clone(),
+                    operation: operation.as_ref().clone(),
+                    output,
+                })
+            })
+            .collect::<std::result::Result<_, _>>()?;
+
+        let manifest = Manifest { effect, effects };
+
+        let json = serde_json::to_string_pretty(&manifest)
+            .map_err(|e| TypeGenError::Generation(e.to_string()))?;
+
+        fs::write(path, json)?;
+
+        Ok(())
+    }
+
+    fn typescript_source(
+        &self,
+        module_name: &str,
+        options: &TsOptions,
+    ) -> std::result::Result<String, TypeGenError> {
+        let registry = match &self.state {
+            State::Generating(registry) => registry,
+            _ => panic!("registry creation failed"),
+        };
+
+        let bincode = options.serialization != TsSerialization::Json;
+        let config = serde_generate::CodeGeneratorConfig::new(module_name.to_string())
+            .with_serialization(bincode)
+            .with_encodings(if bincode {
+                vec![Encoding::Bincode]
+            } else {
+                vec![]
+            });
+
+        let generator = serde_generate::typescript::CodeGenerator::new(&config);
+        let mut source = Vec::new();
+        generator.output(&mut source, registry)?;
+
+        // FIXME fix import paths in generated code which assume running on Deno
+        let out = String::from_utf8_lossy(&source)
+            .replace(
+                "import { BcsSerializer, BcsDeserializer } from '../bcs/mod.ts';",
+                "",
+            )
+            .replace(".ts'", "'");
+
+        Ok(match options.serialization {
+            TsSerialization::Bincode => out,
+            TsSerialization::Json | TsSerialization::Both => {
+                typescript_json::add_json_helpers(&out, registry, &options.uuids)
+            }
+        })
+    }
+
+    // the helpers for the bridge requests only make sense (and compile) when an app is registered
+    fn has_requests(&self) -> bool {
+        matches!(&self.state, State::Generating(registry) if registry.contains_key("Request"))
+    }
+
     fn ensure_registry(&mut self) -> Result {
         if let State::Registering(_, _) = self.state {
             // replace the current state with a dummy tracer
@@ -556,12 +1303,13 @@ The 2 common cases are:
 
             // convert tracer to registry
             if let State::Registering(tracer, _) = old_state {
+                let mut registry = tracer
+                    .registry()
+                    .map_err(|e| TypeGenError::Generation(e.explanation()))?;
+                registry.extend(mem::take(&mut self.overrides));
+
                 // replace dummy with registry
-                self.state = State::Generating(
-                    tracer
-                        .registry()
-                        .map_err(|e| TypeGenError::Generation(e.explanation()))?,
-                );
+                self.state = State::Generating(registry);
             }
         }
         Ok(())
@@ -584,6 +1332,61 @@ The 2 common cases are:
     }
 }
 
+/// The name of the type `T`, without its module path and generic arguments,
+/// which is the name serde uses for it by default
+fn rust_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Collects the names of the types referenced by the `container`
+fn container_type_names(container: &ContainerFormat, names: &mut Vec<String>) {
+    match container {
+        ContainerFormat::UnitStruct => {}
+        ContainerFormat::NewTypeStruct(format) => format_type_names(format, names),
+        ContainerFormat::TupleStruct(formats) => {
+            formats.iter().for_each(|f| format_type_names(f, names));
+        }
+        ContainerFormat::Struct(fields) => {
+            fields
+                .iter()
+                .for_each(|f| format_type_names(&f.value, names));
+        }
+        ContainerFormat::Enum(variants) => {
+            for variant in variants.values() {
+                match &variant.value {
+                    VariantFormat::Variable(_) | VariantFormat::Unit => {}
+                    VariantFormat::NewType(format) => format_type_names(format, names),
+                    VariantFormat::Tuple(formats) => {
+                        formats.iter().for_each(|f| format_type_names(f, names));
+                    }
+                    VariantFormat::Struct(fields) => {
+                        fields
+                            .iter()
+                            .for_each(|f| format_type_names(&f.value, names));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn format_type_names(format: &Format, names: &mut Vec<String>) {
+    match format {
+        Format::TypeName(name) => names.push(name.clone()),
+        Format::Option(format) | Format::Seq(format) => format_type_names(format, names),
+        Format::TupleArray { content, .. } => format_type_names(content, names),
+        Format::Map { key, value } => {
+            format_type_names(key, names);
+            format_type_names(value, names);
+        }
+        Format::Tuple(formats) => formats.iter().for_each(|f| format_type_names(f, names)),
+        _ => {}
+    }
+}
+
 fn copy(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result {
     fs::create_dir_all(to.as_ref())?;
 
@@ -605,7 +1408,11 @@ fn copy(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result {
 #[cfg(feature = "typegen")]
 #[cfg(test)]
 mod tests {
-    use crate::typegen::TypeGen;
+    use crate::capability::Operation;
+    use crate::typegen::{
+        ContainerFormat, Format, MissingType, Named, RegisterTypes, State, TsOptions,
+        TsSerialization, TypeGen, TypeGenError,
+    };
     use serde::{Deserialize, Serialize};
     use uuid::Uuid;
 
@@ -635,4 +1442,213 @@ mod tests {
         let result = gen.register_type_with_samples(sample_data);
         assert!(result.is_ok(), "typegen failed with second sample data set");
     }
+
+    #[derive(Serialize, Deserialize)]
+    struct Base {
+        id: u32,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Flattened {
+        #[serde(flatten)]
+        base: Base,
+        name: String,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Container {
+        item: Flattened,
+    }
+
+    #[test]
+    fn test_typegen_with_format_for_flattened_struct() {
+        let mut gen = TypeGen::new();
+        assert!(
+            gen.register_type::<Flattened>().is_err(),
+            "typegen unexpectedly succeeded for a flattened struct"
+        );
+
+        let format = ContainerFormat::Struct(vec![
+            Named {
+                name: "id".to_string(),
+                value: Format::U32,
+            },
+            Named {
+                name: "name".to_string(),
+                value: Format::Str,
+            },
+        ]);
+
+        let mut gen = TypeGen::new();
+        gen.register_type_with_format::<Flattened>(format.clone())
+            .unwrap();
+        gen.register_type_with_format::<Container>(ContainerFormat::Struct(vec![Named {
+            name: "item".to_string(),
+            value: Format::TypeName("Flattened".to_string()),
+        }]))
+        .unwrap();
+        gen.ensure_registry().unwrap();
+
+        let State::Generating(registry) = &gen.state else {
+            panic!("expected the registry to be generated");
+        };
+
+        assert_eq!(registry.get("Flattened"), Some(&format));
+        assert!(registry.contains_key("Container"));
+    }
+
+    #[test]
+    fn test_validate_reports_unregistered_referenced_type() {
+        let mut gen = TypeGen::new();
+        gen.register_type_with_format::<Container>(ContainerFormat::Struct(vec![Named {
+            name: "item".to_string(),
+            value: Format::Seq(Box::new(Format::TypeName("Flattened".to_string()))),
+        }]))
+        .unwrap();
+
+        let Err(TypeGenError::MissingTypes(missing)) = gen.validate() else {
+            panic!("expected the missing Flattened type to be reported");
+        };
+
+        assert_eq!(
+            missing,
+            vec![MissingType {
+                name: "Flattened".to_string(),
+                referenced_by: "Container".to_string(),
+            }]
+        );
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Order {
+        bases: Vec<Base>,
+        main: Option<Base>,
+    }
+
+    #[test]
+    fn test_validate_traced_types() {
+        let mut gen = TypeGen::new();
+        gen.register_type::<Order>().unwrap();
+
+        assert!(gen.validate().is_ok());
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+    enum BeaconOperation {
+        Scan { region: String },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+    struct BeaconResponse {
+        beacons: Vec<Beacon>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+    struct Beacon {
+        id: String,
+        proximity: Proximity,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+    enum Proximity {
+        Near,
+        Far,
+    }
+
+    impl Operation for BeaconOperation {
+        type Output = BeaconResponse;
+    }
+
+    struct Beacons;
+
+    impl RegisterTypes for Beacons {
+        type Operation = BeaconOperation;
+
+        fn register_types(generator: &mut TypeGen) -> crate::typegen::Result {
+            generator.register_type::<Proximity>()?;
+            generator.register_type::<BeaconOperation>()?;
+            generator.register_type::<BeaconResponse>()?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_register_capability() {
+        let mut gen = TypeGen::new();
+        gen.register_capability::<Beacons>().unwrap();
+
+        assert_eq!(
+            gen.outputs.get("BeaconOperation"),
+            Some(&Format::TypeName("BeaconResponse".to_string()))
+        );
+
+        gen.validate().unwrap();
+        gen.ensure_registry().unwrap();
+        let State::Generating(registry) = &gen.state else {
+            panic!("registry creation failed");
+        };
+
+        for name in ["BeaconOperation", "BeaconResponse", "Beacon", "Proximity"] {
+            assert!(registry.contains_key(name), "{name} is not registered");
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    enum Command {
+        Stop,
+        Send(MyUuid),
+        Move { x: i64, y: i64 },
+    }
+
+    fn typescript_source(serialization: TsSerialization) -> String {
+        let mut gen = TypeGen::new();
+        gen.register_type_with_samples(vec![Command::Send(MyUuid(Uuid::new_v4()))])
+            .unwrap();
+        gen.register_type::<Command>().unwrap();
+        gen.ensure_registry().unwrap();
+
+        let options = TsOptions {
+            serialization,
+            uuids: vec!["MyUuid".to_string()],
+        };
+        gen.typescript_source("shared_types", &options).unwrap()
+    }
+
+    #[test]
+    fn test_typescript_without_json_helpers() {
+        let source = typescript_source(TsSerialization::Bincode);
+
+        assert!(source.contains("static deserialize(deserializer: Deserializer): Command"));
+        assert!(!source.contains("toJson"));
+        assert!(!source.contains("fromJson"));
+    }
+
+    #[test]
+    fn test_typescript_with_json_helpers() {
+        let source = typescript_source(TsSerialization::Json);
+
+        assert!(!source.contains("serialize(serializer: Serializer)"));
+        assert!(source.contains("static fromJson(json: any): Command {"));
+        assert!(source.contains(r#"case "Move": return CommandVariantMove.loadJson(value);"#));
+        assert!(
+            source.contains(r#"return { "Move": { "x": Number(this.x), "y": Number(this.y) } };"#)
+        );
+        assert!(source
+            .contains(r#"return new CommandVariantMove(BigInt(json["x"]), BigInt(json["y"]));"#));
+        assert!(source.contains(r#"return "Stop";"#));
+        assert!(source.contains(r#"return { "Send": this.value.toJson() };"#));
+
+        // the Uuid is bytes in bincode, but a string in JSON
+        assert!(source.contains("return uuidToJson(this.value);"));
+        assert!(source.contains("return new MyUuid(uuidFromJson(json));"));
+        assert!(source.contains("function uuidFromJson(json: string): Uint8Array {"));
+    }
+
+    #[test]
+    fn test_typescript_with_both_helpers() {
+        let source = typescript_source(TsSerialization::Both);
+
+        assert!(source.contains("public serialize(serializer: Serializer): void {"));
+        assert!(source.contains("static fromJson(json: any): MyUuid {"));
+    }
 }
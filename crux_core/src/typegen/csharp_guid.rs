@@ -0,0 +1,171 @@
+//! `System.Guid` support for the generated C# types
+//!
+//! A `Uuid` is traced as a byte array, which `serde_generate` represents as
+//! `Serde.ValueArray<byte>`. The byte arrays holding a `Uuid` are replaced by references to
+//! `System.Guid` in the registry passed to `serde_generate`, and (de)serialized by the
+//! `GuidExtensions` added to the Serde runtime, in the byte order of the Rust `Uuid`.
+
+use std::collections::BTreeMap;
+
+use serde_reflection::{ContainerFormat, Format, Named, Registry, VariantFormat};
+
+/// The name of the type replacing the byte arrays holding a `Uuid`
+static GUID: &str = "Guid";
+
+pub(super) static GUID_RUNTIME: &str = r#"using System;
+
+namespace Serde
+{
+    /// <summary>
+    /// Serializes a <see cref="Guid"/> as the bytes of a Rust <c>uuid::Uuid</c>,
+    /// which are in big-endian (RFC 4122) order.
+    /// </summary>
+    public static class GuidExtensions
+    {
+        public static void Serialize(this Guid value, ISerializer serializer)
+        {
+            serializer.serialize_bytes(new ValueArray<byte>(SwapByteOrder(value.ToByteArray())));
+        }
+
+        public static Guid Deserialize(IDeserializer deserializer)
+        {
+            byte[] bytes = deserializer.deserialize_bytes().ToArray();
+            if (bytes.Length != 16)
+            {
+                throw new DeserializationException("Incorrect length value for a Uuid");
+            }
+            return new Guid(SwapByteOrder(bytes));
+        }
+
+        // Guid keeps its first three fields in little-endian order, swapping them converts
+        // between the two orders either way
+        private static byte[] SwapByteOrder(byte[] bytes)
+        {
+            Array.Reverse(bytes, 0, 4);
+            Array.Reverse(bytes, 4, 2);
+            Array.Reverse(bytes, 6, 2);
+            return bytes;
+        }
+    }
+}
+"#;
+
+/// A copy of the `registry` with the byte arrays at the `uuids` locations replaced by
+/// references to [`GUID`], see [`CsOptions::uuids`](super::CsOptions::uuids).
+pub(super) fn with_guids(registry: &Registry, uuids: &[String]) -> Registry {
+    let is_uuid = |location: String| uuids.contains(&location);
+
+    let mut registry = registry.clone();
+    for (name, container) in &mut registry {
+        match container {
+            ContainerFormat::UnitStruct => {}
+            ContainerFormat::NewTypeStruct(format) => {
+                if is_uuid(name.clone()) {
+                    replace_bytes(format);
+                }
+            }
+            ContainerFormat::TupleStruct(formats) => {
+                replace_tuple(formats, name, is_uuid);
+            }
+            ContainerFormat::Struct(fields) => replace_fields(fields, name, is_uuid),
+            ContainerFormat::Enum(variants) => {
+                for variant in variants.values_mut() {
+                    let location = format!("{name}::{}", variant.name);
+
+                    match &mut variant.value {
+                        VariantFormat::Unit => {}
+                        VariantFormat::NewType(format) => {
+                            if is_uuid(location) {
+                                replace_bytes(format);
+                            }
+                        }
+                        VariantFormat::Tuple(formats) => replace_tuple(formats, &location, is_uuid),
+                        VariantFormat::Struct(fields) => replace_fields(fields, &location, is_uuid),
+                        VariantFormat::Variable(_) => panic!("unexpected variable in the registry"),
+                    }
+                }
+            }
+        }
+    }
+
+    registry
+}
+
+fn replace_tuple(formats: &mut [Format], location: &str, is_uuid: impl Fn(String) -> bool) {
+    for (i, format) in formats.iter_mut().enumerate() {
+        if is_uuid(format!("{location}.{i}")) {
+            replace_bytes(format);
+        }
+    }
+}
+
+fn replace_fields(fields: &mut [Named<Format>], location: &str, is_uuid: impl Fn(String) -> bool) {
+    for field in fields {
+        if is_uuid(format!("{location}.{}", field.name)) {
+            replace_bytes(&mut field.value);
+        }
+    }
+}
+
+/// Replaces the byte arrays in the `format`, including those in options, sequences
+/// and map values
+fn replace_bytes(format: &mut Format) {
+    match format {
+        Format::Bytes => *format = Format::TypeName(GUID.to_string()),
+        Format::Option(format) | Format::Seq(format) => replace_bytes(format),
+        Format::TupleArray { content, .. } => replace_bytes(content),
+        Format::Map { value, .. } => replace_bytes(value),
+        _ => {}
+    }
+}
+
+/// The external definitions placing [`GUID`] in the `System` namespace
+pub(super) fn external_definitions() -> BTreeMap<String, Vec<String>> {
+    BTreeMap::from([("System".to_string(), vec![GUID.to_string()])])
+}
+
+/// Fixes the `source` of a class generated by `serde_generate` referencing `System.Guid`,
+/// which treats it like the generated types, with a static `Deserialize` method, and as
+/// nullable.
+pub(super) fn fix_guid_references(source: &str) -> String {
+    if !source.contains("System.Guid") {
+        return source.to_string();
+    }
+
+    // the `Serialize` extension method is in the Serde namespace
+    let source = source
+        .replacen(
+            "using System.Numerics;",
+            "using System.Numerics;\nusing Serde;",
+            1,
+        )
+        .replace(
+            "System.Guid.Deserialize(deserializer)",
+            "Serde.GuidExtensions.Deserialize(deserializer)",
+        );
+
+    // a Guid is a value type, which can't be null
+    let parameters: Vec<_> = source
+        .match_indices("System.Guid _")
+        .map(|(start, matched)| {
+            source[start + matched.len()..]
+                .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        })
+        .collect();
+
+    source
+        .lines()
+        .filter(|line| {
+            !parameters.iter().any(|parameter| {
+                line.trim()
+                    == format!(
+                        "if (_{parameter} == null) throw new ArgumentNullException(nameof(_{parameter}));"
+                    )
+            })
+        })
+        .map(|line| format!("{line}\n"))
+        .collect()
+}
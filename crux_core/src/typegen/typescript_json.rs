@@ -0,0 +1,345 @@
+//! JSON helpers for the generated TypeScript classes
+//!
+//! `serde_generate` only generates binary (de)serialization, so the `toJson` and `fromJson`
+//! methods are generated from the registry and added to the classes it generated. They follow
+//! the default `serde_json` representation of the Rust types: structs are objects, enums are
+//! externally tagged, and byte arrays are arrays of numbers, unless they hold a `Uuid`, which
+//! is a hyphenated string.
+
+use std::collections::BTreeMap;
+
+use serde_reflection::{ContainerFormat, Format, Named, Registry, VariantFormat};
+
+static UUID_HELPERS: &str = r#"
+function uuidToJson(bytes: Uint8Array): string {
+  const hex = Array.from(bytes, (b) => b.toString(16).padStart(2, "0")).join("");
+  return [hex.slice(0, 8), hex.slice(8, 12), hex.slice(12, 16), hex.slice(16, 20), hex.slice(20)].join("-");
+}
+
+function uuidFromJson(json: string): Uint8Array {
+  const hex = json.replace(/-/g, "");
+  return new Uint8Array(hex.match(/../g)!.map((b) => parseInt(b, 16)));
+}
+"#;
+
+/// The fields of a struct or an enum variant
+enum Fields<'a> {
+    Unit,
+    NewType(&'a Format),
+    Tuple(&'a [Format]),
+    Struct(&'a [Named<Format>]),
+}
+
+impl<'a> Fields<'a> {
+    fn of_container(container: &'a ContainerFormat) -> Self {
+        match container {
+            ContainerFormat::UnitStruct => Fields::Unit,
+            ContainerFormat::NewTypeStruct(format) => Fields::NewType(format),
+            ContainerFormat::TupleStruct(formats) => Fields::Tuple(formats),
+            ContainerFormat::Struct(fields) => Fields::Struct(fields),
+            ContainerFormat::Enum(_) => unreachable!("enums don't have fields"),
+        }
+    }
+
+    fn of_variant(variant: &'a VariantFormat) -> Self {
+        match variant {
+            VariantFormat::Unit => Fields::Unit,
+            VariantFormat::NewType(format) => Fields::NewType(format),
+            VariantFormat::Tuple(formats) => Fields::Tuple(formats),
+            VariantFormat::Struct(fields) => Fields::Struct(fields),
+            VariantFormat::Variable(_) => panic!("unexpected variable in the registry"),
+        }
+    }
+}
+
+/// Adds `toJson` and `fromJson` methods to the classes in the TypeScript `source` generated
+/// for the `registry`. The `uuids` are the locations of byte arrays holding a `Uuid`.
+pub(super) fn add_json_helpers(source: &str, registry: &Registry, uuids: &[String]) -> String {
+    let helpers = Helpers { uuids };
+
+    // class header line -> methods to add to the class
+    let mut methods = BTreeMap::new();
+    for (name, container) in registry {
+        if let ContainerFormat::Enum(variants) = container {
+            methods.insert(
+                format!("export abstract class {name} {{"),
+                helpers.enum_methods(name, variants),
+            );
+
+            for variant in variants.values() {
+                methods.insert(
+                    format!(
+                        "export class {name}Variant{} extends {name} {{",
+                        variant.name
+                    ),
+                    helpers.variant_methods(name, &variant.name, &variant.value),
+                );
+            }
+        } else {
+            methods.insert(
+                format!("export class {name} {{"),
+                helpers.struct_methods(name, container),
+            );
+        }
+    }
+
+    let mut out = String::new();
+    for line in source.lines() {
+        out.push_str(line);
+        out.push('\n');
+
+        if let Some(methods) = methods.get(line) {
+            out.push('\n');
+            out.push_str(methods);
+        }
+    }
+
+    if !uuids.is_empty() {
+        out.push_str(UUID_HELPERS);
+    }
+
+    out
+}
+
+struct Helpers<'a> {
+    uuids: &'a [String],
+}
+
+impl Helpers<'_> {
+    fn struct_methods(&self, name: &str, container: &ContainerFormat) -> String {
+        let fields = Fields::of_container(container);
+
+        format!(
+            "public toJson(): any {{\n  return {};\n}}\n\n\
+             static fromJson(json: any): {name} {{\n  return new {name}({});\n}}\n\n",
+            self.fields_to_json(&fields, name),
+            self.fields_from_json(&fields, name),
+        )
+    }
+
+    fn enum_methods(&self, name: &str, variants: &BTreeMap<u32, Named<VariantFormat>>) -> String {
+        let cases: String = variants
+            .values()
+            .map(|variant| {
+                format!(
+                    "    case \"{0}\": return {name}Variant{0}.loadJson(value);\n",
+                    variant.name
+                )
+            })
+            .collect();
+
+        format!(
+            "abstract toJson(): any;\n\n\
+             static fromJson(json: any): {name} {{\n  \
+             const [variant, value] = typeof json === \"string\" ? [json, null] : Object.entries(json)[0];\n  \
+             switch (variant) {{\n{cases}    \
+             default: throw new Error(\"Unknown variant for {name}: \" + variant);\n  \
+             }}\n}}\n\n"
+        )
+    }
+
+    fn variant_methods(&self, base: &str, name: &str, variant: &VariantFormat) -> String {
+        let fields = Fields::of_variant(variant);
+        let location = format!("{base}::{name}");
+
+        let to_json = match fields {
+            Fields::Unit => format!("\"{name}\""),
+            _ => format!(
+                "{{ \"{name}\": {} }}",
+                self.fields_to_json(&fields, &location)
+            ),
+        };
+
+        format!(
+            "public toJson(): any {{\n  return {to_json};\n}}\n\n\
+             static loadJson(json: any): {base}Variant{name} {{\n  return new {base}Variant{name}({});\n}}\n\n",
+            self.fields_from_json(&fields, &location),
+        )
+    }
+
+    fn fields_to_json(&self, fields: &Fields, location: &str) -> String {
+        match fields {
+            Fields::Unit => "null".to_string(),
+            Fields::NewType(format) => {
+                self.quote_to_json(format, "this.value", self.is_uuid(location), 0)
+            }
+            Fields::Tuple(formats) => {
+                let items = formats
+                    .iter()
+                    .enumerate()
+                    .map(|(i, format)| {
+                        let uuid = self.is_uuid(&format!("{location}.{i}"));
+                        self.quote_to_json(format, &format!("this.field{i}"), uuid, 0)
+                    })
+                    .collect::<Vec<_>>();
+
+                format!("[{}]", items.join(", "))
+            }
+            Fields::Struct([]) => "{}".to_string(),
+            Fields::Struct(fields) => {
+                let entries = fields
+                    .iter()
+                    .map(|field| {
+                        let uuid = self.is_uuid(&format!("{location}.{}", field.name));
+                        let value = self.quote_to_json(
+                            &field.value,
+                            &format!("this.{}", field.name),
+                            uuid,
+                            0,
+                        );
+
+                        format!("\"{}\": {value}", field.name)
+                    })
+                    .collect::<Vec<_>>();
+
+                format!("{{ {} }}", entries.join(", "))
+            }
+        }
+    }
+
+    fn fields_from_json(&self, fields: &Fields, location: &str) -> String {
+        match fields {
+            Fields::Unit => String::new(),
+            Fields::NewType(format) => {
+                self.quote_from_json(format, "json", self.is_uuid(location), 0)
+            }
+            Fields::Tuple(formats) => formats
+                .iter()
+                .enumerate()
+                .map(|(i, format)| {
+                    let uuid = self.is_uuid(&format!("{location}.{i}"));
+                    self.quote_from_json(format, &format!("json[{i}]"), uuid, 0)
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            Fields::Struct(fields) => fields
+                .iter()
+                .map(|field| {
+                    let uuid = self.is_uuid(&format!("{location}.{}", field.name));
+                    self.quote_from_json(
+                        &field.value,
+                        &format!("json[\"{}\"]", field.name),
+                        uuid,
+                        0,
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+
+    fn is_uuid(&self, location: &str) -> bool {
+        self.uuids.iter().any(|uuid| uuid == location)
+    }
+
+    /// A TypeScript expression converting the `value` of the `format` to its JSON representation
+    fn quote_to_json(&self, format: &Format, value: &str, uuid: bool, depth: usize) -> String {
+        let (v, k) = (format!("v{depth}"), format!("k{depth}"));
+
+        match format {
+            Format::TypeName(_) => format!("{value}.toJson()"),
+            Format::Unit => "null".to_string(),
+            Format::I64 | Format::I128 | Format::U64 | Format::U128 => format!("Number({value})"),
+            Format::Bytes if uuid => format!("uuidToJson({value})"),
+            Format::Bytes => format!("Array.from({value})"),
+            Format::Option(format) => format!(
+                "({value} === null ? null : {})",
+                self.quote_to_json(format, value, uuid, depth)
+            ),
+            Format::Seq(format) => format!(
+                "{value}.map(({v}) => {})",
+                self.quote_to_json(format, &v, uuid, depth + 1)
+            ),
+            Format::TupleArray { content, .. } => format!(
+                "{value}.map(({v}) => {})",
+                self.quote_to_json(content, &format!("{v}[0]"), uuid, depth + 1)
+            ),
+            Format::Tuple(formats) => {
+                let items = formats
+                    .iter()
+                    .enumerate()
+                    .map(|(i, format)| {
+                        self.quote_to_json(format, &format!("{value}[{i}]"), false, depth)
+                    })
+                    .collect::<Vec<_>>();
+
+                format!("[{}]", items.join(", "))
+            }
+            Format::Map { key, value: item } => {
+                // JSON object keys are strings
+                let key = match key.as_ref() {
+                    Format::Str | Format::Char => k.clone(),
+                    key => format!("String({})", self.quote_to_json(key, &k, false, depth + 1)),
+                };
+
+                format!(
+                    "Object.fromEntries(Array.from({value}, ([{k}, {v}]) => [{key}, {}]))",
+                    self.quote_to_json(item, &v, uuid, depth + 1)
+                )
+            }
+            Format::Variable(_) => panic!("unexpected variable in the registry"),
+            _ => value.to_string(),
+        }
+    }
+
+    /// A TypeScript expression converting the JSON `value` of the `format` to its TypeScript type
+    fn quote_from_json(&self, format: &Format, value: &str, uuid: bool, depth: usize) -> String {
+        let (v, k) = (format!("v{depth}"), format!("k{depth}"));
+
+        match format {
+            Format::TypeName(name) => format!("{name}.fromJson({value})"),
+            Format::Unit => "null".to_string(),
+            Format::I64 | Format::I128 | Format::U64 | Format::U128 => format!("BigInt({value})"),
+            Format::Bytes if uuid => format!("uuidFromJson({value})"),
+            Format::Bytes => format!("new Uint8Array({value})"),
+            // missing optional fields are `undefined`
+            Format::Option(format) => format!(
+                "({value} == null ? null : {})",
+                self.quote_from_json(format, value, uuid, depth)
+            ),
+            Format::Seq(format) => format!(
+                "{value}.map(({v}: any) => {})",
+                self.quote_from_json(format, &v, uuid, depth + 1)
+            ),
+            Format::TupleArray { content, .. } => format!(
+                "{value}.map(({v}: any): [any] => [{}])",
+                self.quote_from_json(content, &v, uuid, depth + 1)
+            ),
+            Format::Tuple(formats) => {
+                let items = formats
+                    .iter()
+                    .enumerate()
+                    .map(|(i, format)| {
+                        self.quote_from_json(format, &format!("{value}[{i}]"), false, depth)
+                    })
+                    .collect::<Vec<_>>();
+
+                format!(
+                    "[{}] as [{}]",
+                    items.join(", "),
+                    vec!["any"; items.len()].join(", ")
+                )
+            }
+            Format::Map { key, value: item } => {
+                // JSON object keys are strings, parse the ones which aren't
+                let key = match key.as_ref() {
+                    key @ (Format::Str
+                    | Format::Char
+                    | Format::TypeName(_)
+                    | Format::I64
+                    | Format::I128
+                    | Format::U64
+                    | Format::U128) => self.quote_from_json(key, &k, false, depth + 1),
+                    key => self.quote_from_json(key, &format!("JSON.parse({k})"), false, depth + 1),
+                };
+
+                format!(
+                    "new Map(Object.entries({value}).map(([{k}, {v}]): [any, any] => [{key}, {}]))",
+                    self.quote_from_json(item, &v, uuid, depth + 1)
+                )
+            }
+            Format::Variable(_) => panic!("unexpected variable in the registry"),
+            _ => value.to_string(),
+        }
+    }
+}
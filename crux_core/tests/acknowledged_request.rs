@@ -0,0 +1,247 @@
+//! Tests for requests which only need the shell to acknowledge them
+
+mod capability {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::Capability;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+    pub enum StoreOperation {
+        Read { key: String },
+        Flush,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+    pub enum StoreOutput {
+        Value(Option<String>),
+    }
+
+    impl Operation for StoreOperation {
+        type Output = StoreOutput;
+    }
+
+    #[derive(Capability)]
+    pub struct Store<Ev> {
+        context: CapabilityContext<StoreOperation, Ev>,
+    }
+
+    impl<Ev> Store<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<StoreOperation, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn read<F>(&self, key: &str, callback: F)
+        where
+            F: FnOnce(Option<String>) -> Ev + Send + 'static,
+        {
+            let key = key.to_string();
+
+            self.context.spawn({
+                let context = self.context.clone();
+
+                async move {
+                    let StoreOutput::Value(value) = context
+                        .request_from_shell(StoreOperation::Read { key })
+                        .await;
+                    context.update_app(callback(value));
+                }
+            });
+        }
+
+        /// Flush the store to disk, dispatching the event from `on_ack` once the shell
+        /// has done it
+        pub fn flush<F>(&self, on_ack: F)
+        where
+            F: FnOnce() -> Ev + Send + 'static,
+        {
+            self.context.spawn({
+                let context = self.context.clone();
+
+                async move {
+                    context.acknowledge_from_shell(StoreOperation::Flush).await;
+                    context.update_app(on_ack());
+                }
+            });
+        }
+    }
+}
+
+mod app {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    use crate::capability::Store;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Read,
+        Save,
+        #[serde(skip)]
+        Loaded(Option<String>),
+        #[serde(skip)]
+        Flushed,
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub value: Option<String>,
+        pub saved: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub struct ViewModel {
+        pub saved: bool,
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub store: Store<Event>,
+        pub render: Render<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Read => caps.store.read("draft", Event::Loaded),
+                Event::Save => caps.store.flush(|| Event::Flushed),
+                Event::Loaded(value) => model.value = value,
+                Event::Flushed => {
+                    model.saved = true;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Model) -> ViewModel {
+            ViewModel { saved: model.saved }
+        }
+    }
+}
+
+mod tests {
+    use crux_core::{testing::AppTester, ResolveError};
+
+    use crate::app::{App, Effect, Event, Model};
+    use crate::capability::{StoreOperation, StoreOutput};
+
+    #[test]
+    fn acknowledged_request_dispatches_event() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Save, &mut model)
+            .expect_one_effect()
+            .expect_store();
+
+        assert_eq!(request.operation, StoreOperation::Flush);
+        assert!(request.expects_response());
+
+        let event = app.acknowledge(&mut request).unwrap().expect_one_event();
+        assert_eq!(event, Event::Flushed);
+        assert!(!request.expects_response());
+    }
+
+    #[test]
+    fn acknowledged_request_ignores_output() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Save, &mut model)
+            .expect_one_effect()
+            .expect_store();
+
+        let event = app
+            .resolve(&mut request, StoreOutput::Value(None))
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Flushed);
+    }
+
+    #[test]
+    fn request_expecting_output_cant_be_acknowledged() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Read, &mut model)
+            .expect_one_effect()
+            .expect_store();
+
+        assert!(matches!(
+            app.acknowledge(&mut request),
+            Err(error) if matches!(
+                error.downcast_ref::<ResolveError>(),
+                Some(ResolveError::Unacknowledged)
+            )
+        ));
+        assert!(request.expects_response());
+    }
+}
+
+mod bridge_tests {
+    use bincode::Options;
+    use crux_core::{
+        bridge::{Bridge, ProtocolVersion, Request},
+        Core,
+    };
+
+    use crate::app::{App, Effect, EffectFfi, Event, ViewModel};
+    use crate::capability::StoreOperation;
+
+    fn options() -> impl Options {
+        bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+    }
+
+    // the shell speaks the current protocol, so requests carry all their fields
+    fn handshake(bridge: &Bridge<Effect, App>) {
+        let version = options().serialize(&ProtocolVersion::CURRENT).unwrap();
+        bridge.handshake(&version).unwrap();
+    }
+
+    #[test]
+    fn shell_acknowledges_with_empty_response() {
+        let bridge = Bridge::<Effect, App>::new(Core::new());
+        handshake(&bridge);
+
+        let event = options().serialize(&Event::Save).unwrap();
+        let requests: Vec<Request<EffectFfi>> = options()
+            .deserialize(&bridge.process_event(&event))
+            .unwrap();
+
+        let [request] = &requests[..] else {
+            panic!("expected a single request");
+        };
+        assert!(matches!(
+            request.effect,
+            EffectFfi::Store(StoreOperation::Flush)
+        ));
+
+        let requests: Vec<Request<EffectFfi>> = options()
+            .deserialize(&bridge.handle_response(request.id.0, &[]))
+            .unwrap();
+
+        let [render] = &requests[..] else {
+            panic!("expected a single request");
+        };
+        assert!(matches!(render.effect, EffectFfi::Render(_)));
+
+        let view: ViewModel = options().deserialize(&bridge.view()).unwrap();
+        assert!(view.saved);
+    }
+}
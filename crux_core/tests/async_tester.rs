@@ -0,0 +1,126 @@
+#[cfg(feature = "async_testing")]
+mod app {
+    use std::{thread, time::Duration};
+
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::{Capability, Effect};
+    use futures::channel::oneshot;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    pub struct Store {
+        pub value: usize,
+    }
+
+    impl Operation for Store {
+        type Output = usize;
+    }
+
+    // async work outside of the shell, running on another thread
+    async fn double(value: usize) -> usize {
+        let (sender, receiver) = oneshot::channel();
+
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            sender.send(value * 2).unwrap();
+        });
+
+        receiver.await.unwrap()
+    }
+
+    #[derive(Capability)]
+    pub struct Compute<Ev> {
+        context: CapabilityContext<Store, Ev>,
+    }
+
+    impl<Ev> Compute<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<Store, Ev>) -> Self {
+            Self { context }
+        }
+
+        // doubles the value, stores it with the shell, and doubles what the shell returns
+        pub fn run<F>(&self, value: usize, callback: F)
+        where
+            F: FnOnce(usize) -> Ev + Send + 'static,
+        {
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    let value = double(value).await;
+                    let stored = context.request_from_shell(Store { value }).await;
+                    let result = double(stored).await;
+
+                    context.update_app(callback(result));
+                }
+            });
+        }
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Debug, PartialEq)]
+    pub enum Event {
+        Start(usize),
+        Done(usize),
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = usize;
+        type ViewModel = usize;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut usize, caps: &Capabilities) {
+            match event {
+                Event::Start(value) => caps.compute.run(value, Event::Done),
+                Event::Done(value) => *model = value,
+            }
+        }
+
+        fn view(&self, model: &usize) -> usize {
+            *model
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub compute: Compute<Event>,
+    }
+}
+
+#[cfg(feature = "async_testing")]
+mod tests {
+    use crux_core::testing::AsyncAppTester;
+    use futures::executor::block_on;
+
+    use crate::app::{App, Effect, Event, Store};
+
+    #[test]
+    fn multi_stage_resolution() {
+        block_on(async {
+            let app = AsyncAppTester::<App, Effect>::default();
+            let mut model = 0;
+
+            let mut request = app
+                .update(Event::Start(1), &mut model)
+                .await
+                .expect_one_effect()
+                .expect_compute();
+            assert_eq!(request.operation, Store { value: 2 });
+
+            let event = app
+                .resolve(&mut request, 3)
+                .await
+                .unwrap()
+                .expect_one_event();
+            assert_eq!(event, Event::Done(6));
+
+            app.update(event, &mut model).await.assert_empty();
+            assert_eq!(app.view(&model), 6);
+        });
+    }
+}
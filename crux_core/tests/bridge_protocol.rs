@@ -0,0 +1,186 @@
+//! Tests for negotiating the protocol version between the shell and the core
+
+mod app {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub enum Event {
+        Increment,
+        #[serde(skip)]
+        Rejected(String),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub count: u32,
+        pub error: Option<String>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub struct ViewModel {
+        pub count: u32,
+        pub error: Option<String>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Increment => {
+                    model.count += 1;
+                    caps.render.render();
+                }
+                Event::Rejected(error) => model.error = Some(error),
+            }
+        }
+
+        fn view(&self, model: &Model) -> ViewModel {
+            ViewModel {
+                count: model.count,
+                error: model.error.clone(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use bincode::Options;
+    use crux_core::{
+        bridge::{Bridge, DecodeError, ProtocolError, ProtocolVersion, Request},
+        Core,
+    };
+
+    use crate::app::{App, Effect, EffectFfi, Event, ViewModel};
+
+    fn options() -> impl Options + Copy {
+        bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+    }
+
+    fn bridge(version: ProtocolVersion) -> Bridge<Effect, App> {
+        Bridge::new(Core::new()).with_protocol_version(version)
+    }
+
+    fn handshake(
+        bridge: &Bridge<Effect, App>,
+        shell: ProtocolVersion,
+    ) -> Result<ProtocolVersion, ProtocolError> {
+        let version = bridge.handshake(&options().serialize(&shell).unwrap())?;
+
+        Ok(options().deserialize(&version).unwrap())
+    }
+
+    #[test]
+    fn compatible_versions_use_the_lower_minor_version() {
+        let bridge = bridge(ProtocolVersion::new(1, 2));
+
+        assert_eq!(
+            handshake(&bridge, ProtocolVersion::new(1, 5)),
+            Ok(ProtocolVersion::new(1, 2))
+        );
+        assert_eq!(
+            handshake(&bridge, ProtocolVersion::new(1, 0)),
+            Ok(ProtocolVersion::new(1, 0))
+        );
+    }
+
+    #[test]
+    fn bridge_works_after_handshake() {
+        let bridge = Bridge::<Effect, App>::new(Core::new());
+        assert_eq!(
+            handshake(&bridge, ProtocolVersion::CURRENT),
+            Ok(ProtocolVersion::CURRENT)
+        );
+
+        let _requests = bridge.process_event(&options().serialize(&Event::Increment).unwrap());
+        let view: ViewModel = options().deserialize(&bridge.view()).unwrap();
+        assert_eq!(view.count, 1);
+    }
+
+    #[test]
+    fn incompatible_versions_are_rejected() {
+        let bridge = bridge(ProtocolVersion::new(2, 0));
+
+        let error = handshake(&bridge, ProtocolVersion::new(1, 3)).unwrap_err();
+        assert_eq!(
+            error,
+            ProtocolError::Incompatible {
+                shell: ProtocolVersion::new(1, 3),
+                core: ProtocolVersion::new(2, 0),
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "incompatible bridge protocol versions: the shell speaks 1.3 but the core speaks 2.0, update both to the same major version"
+        );
+    }
+
+    #[test]
+    fn shell_expecting_requests_without_versions_is_rejected() {
+        let bridge = Bridge::<Effect, App>::new(Core::new());
+
+        // requests carry the version of their operation since 2.0, which 1.x shells can't read
+        let error = handshake(&bridge, ProtocolVersion::new(1, 2)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "incompatible bridge protocol versions: the shell speaks 1.2 but the core speaks 2.0, update both to the same major version"
+        );
+    }
+
+    #[test]
+    fn invalid_version_is_rejected() {
+        let bridge = bridge(ProtocolVersion::CURRENT);
+
+        let error = bridge.handshake(&[1]).unwrap_err();
+        assert!(matches!(error, ProtocolError::InvalidVersion(_)));
+    }
+
+    #[test]
+    fn messages_after_failed_handshake_are_passed_to_the_app() {
+        let bridge = Bridge::<Effect, App>::new(Core::new())
+            .with_protocol_version(ProtocolVersion::new(2, 0))
+            .with_decode_error_handler(|error: DecodeError| Event::Rejected(error.to_string()));
+        let _error = handshake(&bridge, ProtocolVersion::new(1, 0)).unwrap_err();
+
+        let _requests = bridge.process_event(&options().serialize(&Event::Increment).unwrap());
+
+        let view: ViewModel = options().deserialize(&bridge.view()).unwrap();
+        assert_eq!(view.count, 0);
+        assert_eq!(
+            view.error.as_deref(),
+            Some(
+                "Message could not be deserialized: Bridge protocol negotiation failed: \
+                 incompatible bridge protocol versions: the shell speaks 1.0 but the core speaks \
+                 2.0, update both to the same major version"
+            )
+        );
+    }
+
+    #[test]
+    fn messages_after_failed_handshake_are_dropped_without_handler() {
+        let bridge = bridge(ProtocolVersion::new(2, 0));
+        let _error = handshake(&bridge, ProtocolVersion::new(1, 0)).unwrap_err();
+
+        let requests = bridge.process_event(&options().serialize(&Event::Increment).unwrap());
+        let requests: Vec<Request<EffectFfi>> = options().deserialize(&requests).unwrap();
+        assert!(requests.is_empty());
+
+        let view: ViewModel = options().deserialize(&bridge.view()).unwrap();
+        assert_eq!(view.count, 0);
+    }
+}
@@ -0,0 +1,78 @@
+mod app {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[cfg(any())]
+    use doctest_support::compose::capabilities::capability_one::CapabilityOne;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Render,
+        Done(usize),
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    // `any()` is never enabled, like a feature which isn't turned on
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub render: Render<Event>,
+        #[cfg(any())]
+        pub one: CapabilityOne<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = ();
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, _model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Render => caps.render.render(),
+                Event::Done(_) => {}
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+}
+
+mod tests {
+    use crux_core::testing::AppTester;
+
+    use crate::app::{App, Effect, EffectFfi, Event};
+
+    #[test]
+    fn cfg_gated_capability_is_excluded_from_effect() {
+        let app = AppTester::<App, Effect>::default();
+
+        let effect = app.update(Event::Render, &mut ()).expect_one_effect();
+
+        // exhaustive, so this only compiles without a variant for the gated capability
+        match effect {
+            Effect::Render(_) => {}
+        }
+    }
+
+    #[test]
+    fn cfg_gated_capability_is_excluded_from_ffi_effect() {
+        let (ffi, _) = crux_core::Effect::serialize(
+            AppTester::<App, Effect>::default()
+                .update(Event::Render, &mut ())
+                .expect_one_effect(),
+        );
+
+        match ffi {
+            EffectFfi::Render(_) => {}
+        }
+    }
+
+    #[test]
+    fn cfg_gated_capability_is_excluded_from_variant_names() {
+        assert_eq!(Effect::VARIANT_NAMES, ["Render"]);
+        assert_eq!(Effect::VARIANT_COUNT, 1);
+    }
+}
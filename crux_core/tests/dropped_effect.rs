@@ -0,0 +1,150 @@
+//! Test for apps recovering from effects the shell never handles
+
+mod app {
+    use std::time::Duration;
+
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::{Capability, Effect};
+    use crux_core::render::Render;
+    use crux_http::Http;
+    use serde::{Deserialize, Serialize};
+
+    /// A capability firing an event after a timeout, using only the passing of time
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct TimeoutOperation;
+
+    impl Operation for TimeoutOperation {
+        type Output = ();
+    }
+
+    #[derive(Capability)]
+    pub struct Timeout<Ev> {
+        context: CapabilityContext<TimeoutOperation, Ev>,
+    }
+
+    impl<Ev> Timeout<Ev>
+    where
+        Ev: Send + 'static,
+    {
+        pub fn new(context: CapabilityContext<TimeoutOperation, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn after(&self, duration: Duration, event: Ev) {
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    context.delay(duration).await;
+                    context.update_app(event);
+                }
+            });
+        }
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub enum Event {
+        Fetch,
+
+        #[serde(skip)]
+        Fetched(crux_http::Result<crux_http::Response<String>>),
+        #[serde(skip)]
+        TimedOut,
+    }
+
+    #[derive(Default, Debug, PartialEq)]
+    pub enum Model {
+        #[default]
+        Idle,
+        Fetching,
+        Fetched(String),
+        TimedOut,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = String;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Fetch => {
+                    *model = Model::Fetching;
+                    caps.http
+                        .get("http://example.com/")
+                        .expect_string()
+                        .send(Event::Fetched);
+                    caps.timeout.after(Duration::from_secs(10), Event::TimedOut);
+                }
+                Event::Fetched(Ok(mut response)) => {
+                    *model = Model::Fetched(response.take_body().unwrap());
+                    caps.render.render();
+                }
+                Event::Fetched(Err(_)) | Event::TimedOut => {
+                    if *model == Model::Fetching {
+                        *model = Model::TimedOut;
+                        caps.render.render();
+                    }
+                }
+            }
+        }
+
+        fn view(&self, model: &Model) -> String {
+            format!("{model:?}")
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub http: Http<Event>,
+        #[effect(skip)]
+        pub timeout: Timeout<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use std::time::Duration;
+
+    use crux_core::{assert_effect, testing::AppTester};
+
+    use crate::app::{App, Effect, Event, Model};
+
+    #[test]
+    fn timeout_fires_when_shell_drops_request() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = Model::default();
+
+        app.drop_effect(|effect| matches!(effect, Effect::Http(_)));
+
+        app.update(Event::Fetch, &mut model).assert_empty();
+        assert_eq!(model, Model::Fetching);
+
+        let update = app.advance_time(Duration::from_secs(9));
+        assert!(update.events.is_empty());
+
+        let event = app.advance_time(Duration::from_secs(1)).expect_one_event();
+        assert!(matches!(event, Event::TimedOut));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(model, Model::TimedOut);
+    }
+
+    #[test]
+    fn other_effects_are_not_dropped() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = Model::default();
+
+        app.drop_effect(|effect| matches!(effect, Effect::Render(_)));
+
+        let request = app
+            .update(Event::Fetch, &mut model)
+            .expect_one_effect()
+            .expect_http();
+        assert_eq!(request.operation.url, "http://example.com/");
+    }
+}
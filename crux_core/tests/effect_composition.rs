@@ -0,0 +1,228 @@
+//! Test for embedding a child app's effects in the parent's effect type
+
+mod child {
+    use crux_core::macros::Effect;
+    use crux_core::App;
+    use doctest_support::compose::capabilities::capability_one::CapabilityOne;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    pub enum Event {
+        Fetch(usize),
+        Fetched(usize),
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub one: CapabilityOne<Event>,
+        pub render: crux_core::render::Render<Event>,
+    }
+
+    #[derive(Default)]
+    pub struct Child;
+
+    impl App for Child {
+        type Event = Event;
+        type Model = usize;
+        type ViewModel = usize;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Fetch(number) => caps.one.one(number, Event::Fetched),
+                Event::Fetched(number) => {
+                    *model = number;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            *model
+        }
+    }
+}
+
+mod parent {
+    use crux_core::bridge::ResolveSerialized;
+    use crux_core::capability::ProtoContext;
+    use crux_core::render::{Render, RenderOperation};
+    use crux_core::{App, Request, WithContext};
+    use serde::{Deserialize, Serialize};
+
+    use super::child;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    pub enum Event {
+        Reset,
+        Child(child::Event),
+    }
+
+    #[derive(Debug)]
+    pub enum Effect {
+        Render(Request<RenderOperation>),
+        Child(child::Effect),
+    }
+
+    #[derive(Serialize)]
+    pub enum EffectFfi {
+        Render(RenderOperation),
+        Child(child::EffectFfi),
+    }
+
+    impl crux_core::Effect for Effect {
+        type Ffi = EffectFfi;
+
+        fn serialize(self) -> (Self::Ffi, ResolveSerialized) {
+            match self {
+                Effect::Render(request) => request.serialize(EffectFfi::Render),
+                Effect::Child(effect) => {
+                    let (ffi, resolve) = effect.serialize();
+                    (EffectFfi::Child(ffi), resolve)
+                }
+            }
+        }
+    }
+
+    pub struct Capabilities {
+        pub render: Render<Event>,
+        pub child: child::Capabilities,
+    }
+
+    impl WithContext<Event, Effect> for Capabilities {
+        fn new_with_context(context: ProtoContext<Effect, Event>) -> Capabilities {
+            Capabilities {
+                render: Render::new(context.specialize(Effect::Render)),
+                child: child::Capabilities::new_with_context(
+                    context.lift(Effect::Child, Event::Child),
+                ),
+            }
+        }
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub child: usize,
+    }
+
+    #[derive(Default)]
+    pub struct Parent {
+        child: child::Child,
+    }
+
+    impl App for Parent {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = usize;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Reset => {
+                    model.child = 0;
+                    caps.render.render();
+                }
+                Event::Child(event) => self.child.update(event, &mut model.child, &caps.child),
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            self.child.view(&model.child)
+        }
+    }
+}
+
+#[test]
+fn child_effect_resolution_round_trip() {
+    use crux_core::testing::AppTester;
+    use parent::{Effect, Event, Model, Parent};
+
+    let tester = AppTester::<Parent, Effect>::default();
+    let mut model = Model::default();
+
+    let Effect::Child(effect) = tester
+        .update(Event::Child(child::Event::Fetch(1)), &mut model)
+        .expect_one_effect()
+    else {
+        panic!("expected a child effect");
+    };
+    let mut request = effect.expect_one();
+
+    let event = tester.resolve(&mut request, 42).unwrap().expect_one_event();
+    assert_eq!(event, Event::Child(child::Event::Fetched(42)));
+
+    let effect = tester.update(event, &mut model).expect_one_effect();
+    assert!(matches!(effect, Effect::Child(child::Effect::Render(_))));
+    assert_eq!(tester.view(&model), 42);
+
+    let effect = tester.update(Event::Reset, &mut model).expect_one_effect();
+    assert!(matches!(effect, Effect::Render(_)));
+}
+
+#[test]
+fn child_tester_surfaces_lifted_effects() {
+    use crux_core::testing::AppTester;
+    use parent::{Effect, Event, Model, Parent};
+
+    let tester = AppTester::<Parent, Effect>::default();
+    let child = tester.child(child::Child, Effect::Child, Event::Child);
+    let mut model = Model::default();
+
+    // the child's request surfaces as the parent's effect
+    let Effect::Child(effect) = child
+        .update(child::Event::Fetch(1), &mut model.child)
+        .expect_one_effect()
+    else {
+        panic!("expected a child effect");
+    };
+    let mut request = effect.expect_one();
+
+    // and its response as the parent's event, which the parent routes into the child
+    let event = tester.resolve(&mut request, 42).unwrap().expect_one_event();
+    assert_eq!(event, Event::Child(child::Event::Fetched(42)));
+
+    let effect = tester.update(event, &mut model).expect_one_effect();
+    assert!(matches!(effect, Effect::Child(child::Effect::Render(_))));
+    assert_eq!(child.view(&model.child), 42);
+    assert_eq!(tester.view(&model), 42);
+
+    tester.assert_no_pending();
+}
+
+#[test]
+fn child_effect_bridge_round_trip() {
+    use crux_core::{bridge::BridgeWithSerializer, Core};
+    use parent::{Effect, Parent};
+    use serde_json::{json, Value};
+
+    let bridge = BridgeWithSerializer::<Effect, Parent>::new(Core::default());
+
+    let mut requests = vec![];
+    bridge.process_event(
+        json!({ "Child": { "Fetch": 1 } }),
+        &mut serde_json::Serializer::new(&mut requests),
+    );
+
+    let requests: Value = serde_json::from_slice(&requests).unwrap();
+    assert_eq!(
+        requests,
+        json!([{ "id": 0, "effect": { "Child": { "CapabilityOne": { "number": 1 } } } }])
+    );
+
+    let mut requests = vec![];
+    bridge.handle_response(
+        0,
+        &mut serde_json::Deserializer::from_str("42"),
+        &mut serde_json::Serializer::new(&mut requests),
+    );
+
+    let requests: Value = serde_json::from_slice(&requests).unwrap();
+    assert_eq!(
+        requests,
+        json!([{ "id": 0, "effect": { "Child": { "Render": null } } }])
+    );
+
+    let mut view = vec![];
+    bridge.view(&mut serde_json::Serializer::new(&mut view));
+    assert_eq!(serde_json::from_slice::<usize>(&view).unwrap(), 42);
+}
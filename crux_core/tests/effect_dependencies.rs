@@ -0,0 +1,235 @@
+//! Tests for requests depending on other requests, for the shell to order them
+
+mod app {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::{Capability, Effect};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    pub enum StoreOperation {
+        Write { key: String, value: String },
+        Read { key: String },
+    }
+
+    impl Operation for StoreOperation {
+        type Output = Option<String>;
+    }
+
+    #[derive(Capability)]
+    pub struct Store<Ev> {
+        context: CapabilityContext<StoreOperation, Ev>,
+    }
+
+    impl<Ev> Store<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<StoreOperation, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn write<F>(&self, key: &str, value: &str, callback: F)
+        where
+            F: FnOnce() -> Ev + Send + 'static,
+        {
+            self.request(
+                StoreOperation::Write {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                },
+                |_| callback(),
+            );
+        }
+
+        pub fn read<F>(&self, key: &str, callback: F)
+        where
+            F: FnOnce(Option<String>) -> Ev + Send + 'static,
+        {
+            self.request(
+                StoreOperation::Read {
+                    key: key.to_string(),
+                },
+                callback,
+            );
+        }
+
+        fn request<F>(&self, operation: StoreOperation, callback: F)
+        where
+            F: FnOnce(Option<String>) -> Ev + Send + 'static,
+        {
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    let output = context.request_from_shell(operation).await;
+                    context.update_app(callback(output));
+                }
+            });
+        }
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Save(String),
+        Reload,
+        #[serde(skip)]
+        Saved,
+        #[serde(skip)]
+        Loaded(Option<String>),
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Option<String>;
+        type ViewModel = Option<String>;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Option<String>, caps: &Capabilities) {
+            match event {
+                Event::Save(token) => {
+                    caps.store
+                        .tagged("write_token")
+                        .write("token", &token, || Event::Saved);
+                    caps.store
+                        .depending_on("write_token")
+                        .read("token", Event::Loaded);
+                }
+                Event::Reload => caps
+                    .store
+                    .depending_on("write_token")
+                    .read("token", Event::Loaded),
+                Event::Saved => {}
+                Event::Loaded(token) => *model = token,
+            }
+        }
+
+        fn view(&self, model: &Option<String>) -> Option<String> {
+            model.clone()
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub store: Store<Event>,
+    }
+}
+
+mod tests {
+    use bincode::Options;
+    use crux_core::{
+        bridge::{Bridge, BridgeWithSerializer, ProtocolVersion},
+        testing::AppTester,
+        Core, Effect as _,
+    };
+    use serde_json::{json, Value};
+
+    use crate::app::{App, Effect, EffectFfi, Event, StoreOperation};
+
+    fn options() -> impl Options {
+        bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+    }
+
+    // the shell speaks the current protocol, so requests carry all their fields
+    fn handshake(bridge: &Bridge<Effect, App>) {
+        let version = options().serialize(&ProtocolVersion::CURRENT).unwrap();
+        bridge.handshake(&version).unwrap();
+    }
+
+    fn deserialize_requests(bytes: &[u8]) -> Vec<crux_core::bridge::Request<EffectFfi>> {
+        options().deserialize(bytes).unwrap()
+    }
+
+    #[test]
+    fn dependent_request_records_dependency() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = None;
+
+        let mut effects = app
+            .update(Event::Save("secret".to_string()), &mut model)
+            .into_effects();
+
+        let write = effects.next().unwrap();
+        assert_eq!(write.tag(), Some("write_token"));
+        assert_eq!(write.depends_on(), None);
+
+        let read = effects.next().unwrap().expect_store();
+        assert_eq!(
+            read.operation,
+            StoreOperation::Read {
+                key: "token".to_string()
+            }
+        );
+        assert_eq!(read.depends_on(), Some("write_token"));
+        assert_eq!(read.tag(), None);
+    }
+
+    #[test]
+    fn bridge_request_carries_id_of_dependency() {
+        let bridge = Bridge::<Effect, App>::new(Core::new());
+        handshake(&bridge);
+
+        let event = options()
+            .serialize(&Event::Save("secret".to_string()))
+            .unwrap();
+        let requests = deserialize_requests(&bridge.process_event(&event));
+
+        let [write, read] = &requests[..] else {
+            panic!("expected two requests");
+        };
+        assert!(matches!(
+            write.effect,
+            EffectFfi::Store(StoreOperation::Write { .. })
+        ));
+        assert_eq!(write.depends_on, None);
+        assert!(matches!(
+            read.effect,
+            EffectFfi::Store(StoreOperation::Read { .. })
+        ));
+        assert_eq!(read.depends_on, Some(write.id));
+    }
+
+    #[test]
+    fn no_dependency_once_resolved() {
+        let bridge = Bridge::<Effect, App>::new(Core::new());
+        handshake(&bridge);
+
+        let event = options()
+            .serialize(&Event::Save("secret".to_string()))
+            .unwrap();
+        let requests = deserialize_requests(&bridge.process_event(&event));
+        let write = &requests[0];
+
+        let output = options().serialize(&None::<String>).unwrap();
+        let _ = bridge.handle_response(write.id.0, &output);
+
+        let event = options().serialize(&Event::Reload).unwrap();
+        let requests = deserialize_requests(&bridge.process_event(&event));
+
+        let [read] = &requests[..] else {
+            panic!("expected a single request");
+        };
+        assert_eq!(read.depends_on, None);
+    }
+
+    #[test]
+    fn dependency_is_only_sent_to_shells_supporting_it() {
+        let bridge = BridgeWithSerializer::<Effect, App>::new(Core::new())
+            .with_protocol_version(ProtocolVersion::new(1, 2));
+
+        // a shell speaking version 1.1 of the protocol expects requests without dependencies
+        bridge.handshake(json!({ "major": 1, "minor": 1 })).unwrap();
+
+        let mut requests = vec![];
+        bridge.process_event(
+            json!({ "Save": "secret" }),
+            &mut serde_json::Serializer::new(&mut requests),
+        );
+        let requests: Value = serde_json::from_slice(&requests).unwrap();
+        assert_eq!(requests[1].get("depends_on"), None);
+        assert_eq!(requests[0]["tag"], "write_token");
+    }
+}
@@ -0,0 +1,152 @@
+//! Tests for effect middleware, transforming every effect requested by the app
+
+mod app {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_http::Http;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub enum Event {
+        Fetch,
+
+        #[serde(skip)]
+        Fetched(crux_http::Result<crux_http::Response<String>>),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub body: String,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = String;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Fetch => caps
+                    .http
+                    .get("http://example.com/")
+                    .expect_string()
+                    .send(Event::Fetched),
+                Event::Fetched(Ok(mut response)) => {
+                    model.body = response.take_body().unwrap();
+                    caps.render.render();
+                }
+                Event::Fetched(Err(_)) => {}
+            }
+        }
+
+        fn view(&self, model: &Model) -> String {
+            model.body.clone()
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub http: Http<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crux_core::{assert_effect, testing::AppTester, Core};
+    use crux_http::protocol::{HttpHeader, HttpRequest, HttpResponse, HttpResult};
+
+    use crate::app::{App, Effect, Event, Model};
+
+    fn authorize(effect: &mut Effect) {
+        if let Effect::Http(request) = effect {
+            request.operation.headers.push(HttpHeader {
+                name: "authorization".to_string(),
+                value: "secret-token".to_string(),
+            });
+        }
+    }
+
+    #[test]
+    fn middleware_changes_effects_in_update() {
+        let app = AppTester::<App, Effect>::builder()
+            .effect_middleware(authorize)
+            .build();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Fetch, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        assert_eq!(
+            request.operation,
+            HttpRequest::get("http://example.com/")
+                .header("authorization", "secret-token")
+                .build()
+        );
+
+        // the changed request still resolves the app's callback
+        let event = app
+            .resolve(
+                &mut request,
+                HttpResult::Ok(HttpResponse::ok().body("hello").build()),
+            )
+            .unwrap()
+            .expect_one_event();
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(app.view(&model), "hello");
+    }
+
+    #[test]
+    fn middleware_runs_in_order() {
+        let app = AppTester::<App, Effect>::builder()
+            .effect_middleware(authorize)
+            .effect_middleware(|effect| {
+                if let Effect::Http(request) = effect {
+                    request
+                        .operation
+                        .headers
+                        .retain(|h| h.name != "authorization");
+                }
+            })
+            .build();
+        let mut model = Model::default();
+
+        let request = app
+            .update(Event::Fetch, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        assert!(request.operation.headers.is_empty());
+    }
+
+    #[test]
+    fn core_applies_middleware() {
+        let core: Core<Effect, App> = Core::new().with_effect_middleware(authorize);
+
+        let mut effects = core.process_event(Event::Fetch);
+        let Some(Effect::Http(mut request)) = effects.pop() else {
+            panic!("expected an HTTP request");
+        };
+        assert_eq!(
+            request.operation.headers,
+            vec![HttpHeader {
+                name: "authorization".to_string(),
+                value: "secret-token".to_string(),
+            }]
+        );
+
+        let effects = core.resolve(
+            &mut request,
+            HttpResult::Ok(HttpResponse::ok().body("hello").build()),
+        );
+        assert!(matches!(effects[..], [Effect::Render(_)]));
+        assert_eq!(core.view(), "hello");
+    }
+}
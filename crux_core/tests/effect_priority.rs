@@ -0,0 +1,110 @@
+mod app {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::{Capability, Effect};
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    pub struct Write {
+        pub key: String,
+    }
+
+    impl Operation for Write {
+        type Output = ();
+    }
+
+    #[derive(Capability)]
+    pub struct Writer<Ev> {
+        context: CapabilityContext<Write, Ev>,
+    }
+
+    impl<Ev> Writer<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<Write, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn write(&self, key: &str) {
+            let key = key.to_string();
+
+            self.context.spawn({
+                let context = self.context.clone();
+
+                async move { context.notify_shell(Write { key }).await }
+            });
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub enum Event {
+        Save,
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub writer: Writer<Event>,
+        #[effect(priority = 10)]
+        pub render: Render<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = ();
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, _event: Event, _model: &mut (), caps: &Capabilities) {
+            caps.writer.write("first");
+            caps.writer.write("second");
+            caps.render.render();
+            caps.writer.write("third");
+        }
+
+        fn view(&self, _model: &()) {}
+    }
+}
+
+mod tests {
+    use crux_core::{Core, Effect as _};
+
+    use crate::app::{App, Effect, Event, Write};
+
+    #[test]
+    fn high_priority_render_sorts_ahead_of_writes() {
+        let core: Core<Effect, App> = Core::default();
+
+        let effects = core.process_event(Event::Save);
+
+        assert_eq!(
+            effects.iter().map(Effect::priority).collect::<Vec<_>>(),
+            [10, 0, 0, 0]
+        );
+
+        let mut effects = effects.into_iter();
+        assert!(effects.next().unwrap().is_render());
+
+        // writes keep the order they were requested in
+        let keys: Vec<_> = effects
+            .map(|effect| effect.expect_writer().operation)
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                Write {
+                    key: "first".to_string()
+                },
+                Write {
+                    key: "second".to_string()
+                },
+                Write {
+                    key: "third".to_string()
+                },
+            ]
+        );
+    }
+}
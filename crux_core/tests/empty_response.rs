@@ -0,0 +1,147 @@
+//! Tests for responses from the shell without a body
+mod app {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::{Capability, Effect};
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    /// Asks the shell to present a frame, and completes once it has
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    pub struct PresentFrame;
+
+    impl Operation for PresentFrame {
+        type Output = ();
+    }
+
+    #[derive(Capability)]
+    pub struct Frames<Ev> {
+        context: CapabilityContext<PresentFrame, Ev>,
+    }
+
+    impl<Ev> Frames<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<PresentFrame, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn present(&self, event: Ev)
+        where
+            Ev: Send,
+        {
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    context.request_from_shell(PresentFrame).await;
+                    context.update_app(event);
+                }
+            });
+        }
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub enum Event {
+        Present,
+        Presented,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = ();
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, _model: &mut Self::Model, caps: &Capabilities) {
+            match event {
+                Event::Present => caps.frames.present(Event::Presented),
+                Event::Presented => caps.render.render(),
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub frames: Frames<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use bincode::Options;
+    use crux_core::{
+        bridge::{Bridge, BridgeWithSerializer, ProtocolVersion, Request},
+        Core,
+    };
+    use serde_json::{json, Value};
+
+    use crate::app::{App, Effect, EffectFfi, Event};
+
+    fn options() -> impl Options {
+        bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+    }
+
+    // the shell speaks the current protocol, so requests carry all their fields
+    fn handshake(bridge: &Bridge<Effect, App>) {
+        let version = options().serialize(&ProtocolVersion::CURRENT).unwrap();
+        bridge.handshake(&version).unwrap();
+    }
+
+    #[test]
+    fn resolve_unit_output_with_empty_response() {
+        let bridge = Bridge::<Effect, App>::new(Core::new());
+        handshake(&bridge);
+
+        let event = options().serialize(&Event::Present).unwrap();
+        let requests: Vec<Request<EffectFfi>> = options()
+            .deserialize(&bridge.process_event(&event))
+            .unwrap();
+
+        let [request] = &requests[..] else {
+            panic!("expected a single request");
+        };
+        assert!(matches!(request.effect, EffectFfi::Frames(_)));
+
+        // the shell completes the frame without a response body
+        let requests: Vec<Request<EffectFfi>> = options()
+            .deserialize(&bridge.handle_response(request.id.0, &[]))
+            .unwrap();
+
+        let [render] = &requests[..] else {
+            panic!("expected a single request");
+        };
+        assert!(matches!(render.effect, EffectFfi::Render(_)));
+    }
+
+    #[test]
+    fn resolve_unit_output_with_json_null() {
+        let bridge = BridgeWithSerializer::<Effect, App>::new(Core::default());
+
+        let mut effects_bytes = vec![];
+        bridge.process_event(
+            json!("Present"),
+            &mut serde_json::Serializer::new(&mut effects_bytes),
+        );
+
+        let requests: Value = serde_json::from_slice(&effects_bytes).unwrap();
+        assert_eq!(requests, json!([{ "id": 0, "effect": { "Frames": null } }]));
+
+        // JSON encodes unit as null
+        let mut effects_bytes = vec![];
+        bridge.handle_response(
+            0,
+            &mut serde_json::Deserializer::from_slice(b"null"),
+            &mut serde_json::Serializer::new(&mut effects_bytes),
+        );
+
+        let requests: Value = serde_json::from_slice(&effects_bytes).unwrap();
+        assert_eq!(requests, json!([{ "id": 0, "effect": { "Render": null } }]));
+    }
+}
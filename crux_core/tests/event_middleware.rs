@@ -0,0 +1,151 @@
+//! Tests for event middleware, deciding what happens to events before they reach `update`
+
+mod app {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App {
+        pub updates: Arc<AtomicUsize>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub enum Event {
+        Increment,
+        Reset,
+        Spam,
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub count: u32,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub struct ViewModel {
+        pub count: u32,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            self.updates.fetch_add(1, Ordering::SeqCst);
+
+            match event {
+                Event::Increment => model.count += 1,
+                Event::Reset => model.count = 0,
+                Event::Spam => model.count += 100,
+            }
+            caps.render.render();
+        }
+
+        fn view(&self, model: &Model) -> ViewModel {
+            ViewModel { count: model.count }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use crux_core::{testing::AppTester, Core, EventAction};
+
+    use crate::app::{App, Effect, Event, Model, ViewModel};
+
+    fn middleware(event: &Event) -> EventAction<Event> {
+        match event {
+            Event::Spam => EventAction::Drop,
+            Event::Reset => EventAction::Replace(Event::Increment),
+            Event::Increment => EventAction::Pass,
+        }
+    }
+
+    #[test]
+    fn dropped_event_never_reaches_update() {
+        let app = App::default();
+        let updates = app.updates.clone();
+        let app = AppTester::<App, Effect>::builder()
+            .app(app)
+            .event_middleware(middleware)
+            .build();
+        let mut model = Model::default();
+
+        let update = app.update(Event::Spam, &mut model);
+
+        assert!(update.effects.is_empty());
+        assert_eq!(model.count, 0);
+        assert_eq!(updates.load(Ordering::SeqCst), 0);
+
+        let update = app.update(Event::Increment, &mut model);
+
+        assert_eq!(update.effects.len(), 1);
+        assert_eq!(model.count, 1);
+        assert_eq!(updates.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn replaced_event_reaches_update_instead() {
+        let app = AppTester::<App, Effect>::builder()
+            .event_middleware(middleware)
+            .build();
+        let mut model = Model::default();
+
+        let _ = app.update(Event::Increment, &mut model);
+        let update = app.update(Event::Reset, &mut model);
+
+        assert_eq!(update.effects.len(), 1);
+        assert_eq!(model.count, 2);
+    }
+
+    #[test]
+    fn middleware_is_applied_in_order_until_dropped() {
+        let seen = Arc::new(AtomicUsize::new(0));
+        let app = AppTester::<App, Effect>::builder()
+            .event_middleware(|_: &Event| EventAction::Replace(Event::Spam))
+            .event_middleware(middleware)
+            .event_middleware({
+                let seen = seen.clone();
+                move |_: &Event| {
+                    seen.fetch_add(1, Ordering::SeqCst);
+                    EventAction::Pass
+                }
+            })
+            .build();
+        let mut model = Model::default();
+
+        let update = app.update(Event::Increment, &mut model);
+
+        assert!(update.effects.is_empty());
+        assert_eq!(model.count, 0);
+        assert_eq!(seen.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn core_applies_event_middleware() {
+        let core: Core<Effect, App> = Core::new().with_event_middleware(middleware);
+
+        assert!(core.process_event(Event::Spam).is_empty());
+        assert_eq!(core.view(), ViewModel { count: 0 });
+
+        assert_eq!(core.process_event(Event::Reset).len(), 1);
+        assert_eq!(core.view(), ViewModel { count: 1 });
+    }
+}
@@ -0,0 +1,198 @@
+mod capability {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::Capability;
+    use crux_core::ShellError;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    pub struct Fetch {
+        pub url: String,
+    }
+
+    impl Operation for Fetch {
+        type Output = String;
+    }
+
+    #[derive(Capability)]
+    pub struct Fetcher<Ev> {
+        context: CapabilityContext<Fetch, Ev>,
+    }
+
+    impl<Ev> Fetcher<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<Fetch, Ev>) -> Self {
+            Self { context }
+        }
+
+        /// Fetch `url`, dispatching the event from `on_ok` with the body, or the event
+        /// from `on_err` when the shell fails to fetch it
+        pub fn fetch<F, G>(&self, url: &str, on_ok: F, on_err: G)
+        where
+            F: FnOnce(String) -> Ev + Send + 'static,
+            G: FnOnce(ShellError) -> Ev + Send + 'static,
+        {
+            let url = url.to_string();
+
+            self.context.spawn({
+                let context = self.context.clone();
+
+                async move {
+                    match context.request_from_shell_fallible(Fetch { url }).await {
+                        Ok(body) => context.update_app(on_ok(body)),
+                        Err(error) => context.update_app(on_err(error)),
+                    }
+                }
+            });
+        }
+    }
+}
+
+mod app {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    use crate::capability::Fetcher;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Fetch(String),
+        Fetched(String),
+        Failed(String),
+    }
+
+    #[derive(Default, Debug, PartialEq)]
+    pub struct Model {
+        pub body: Option<String>,
+        pub error: Option<String>,
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub fetcher: Fetcher<Event>,
+        pub render: Render<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Fetch(url) => caps
+                    .fetcher
+                    .fetch(&url, Event::Fetched, |error| Event::Failed(error.message)),
+                Event::Fetched(body) => {
+                    model.body = Some(body);
+                    caps.render.render();
+                }
+                Event::Failed(error) => {
+                    model.error = Some(error);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+}
+
+mod tests {
+    use crux_core::{testing::AppTester, Core, ShellError};
+
+    use crate::app::{App, Effect, Event, Model};
+
+    #[test]
+    fn dispatches_ok_event_when_resolved() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Fetch("crux.dev".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_fetcher();
+
+        let event = app
+            .resolve(&mut request, "hello".to_string())
+            .unwrap()
+            .expect_one_event();
+
+        assert_eq!(event, Event::Fetched("hello".to_string()));
+    }
+
+    #[test]
+    fn dispatches_err_event_when_failed() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Fetch("crux.dev".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_fetcher();
+
+        let event = app
+            .resolve_failure(&mut request, ShellError::new("offline"))
+            .unwrap()
+            .expect_one_event();
+
+        assert_eq!(event, Event::Failed("offline".to_string()));
+        assert!(!request.expects_response());
+        assert!(app.resolve(&mut request, "late".to_string()).is_err());
+    }
+
+    #[test]
+    fn core_dispatches_err_event_when_failed() {
+        let core: Core<Effect, App> = Core::default();
+
+        let mut effects = core.process_event(Event::Fetch("crux.dev".to_string()));
+        let Some(Effect::Fetcher(mut request)) = effects.pop() else {
+            panic!("Expected a fetcher effect");
+        };
+
+        let effects = core.resolve_failure(&mut request, ShellError::new("offline"));
+
+        assert!(matches!(effects[..], [Effect::Render(_)]));
+    }
+}
+
+mod bridge_tests {
+    use crux_core::bridge::BridgeWithSerializer;
+    use crux_core::Core;
+    use serde_json::{json, Value};
+
+    use crate::app::{App, Effect};
+
+    #[test]
+    fn shell_fails_request() {
+        let bridge = BridgeWithSerializer::<Effect, App>::new(Core::default());
+
+        let mut effects_bytes = vec![];
+        bridge.process_event(
+            json!({ "Fetch": "crux.dev" }),
+            &mut serde_json::Serializer::new(&mut effects_bytes),
+        );
+
+        let requests: Value = serde_json::from_slice(&effects_bytes).unwrap();
+        assert_eq!(
+            requests,
+            json!([{ "id": 0, "effect": { "Fetcher": { "url": "crux.dev" } } }])
+        );
+
+        let mut effects_bytes = vec![];
+        bridge.handle_failure(
+            0,
+            json!({ "message": "offline" }),
+            &mut serde_json::Serializer::new(&mut effects_bytes),
+        );
+
+        let requests: Value = serde_json::from_slice(&effects_bytes).unwrap();
+        assert_eq!(requests, json!([{ "id": 0, "effect": { "Render": null } }]));
+    }
+}
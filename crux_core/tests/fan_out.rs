@@ -0,0 +1,143 @@
+mod capability {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::Capability;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Fetch {
+        pub id: usize,
+    }
+
+    impl Operation for Fetch {
+        type Output = Vec<usize>; // links to other items
+    }
+
+    #[derive(Capability)]
+    pub struct Fetcher<Ev> {
+        context: CapabilityContext<Fetch, Ev>,
+    }
+
+    impl<Ev> Fetcher<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<Fetch, Ev>) -> Self {
+            Self { context }
+        }
+
+        /// Fetch the item `id` and then each of the items it links to,
+        /// dispatching the event from `make_event` with the id of every item fetched
+        pub fn fetch_with_links<F>(&self, id: usize, make_event: F)
+        where
+            F: Fn(usize) -> Ev + Clone + Send + 'static,
+        {
+            self.context.spawn({
+                let context = self.context.clone();
+
+                async move {
+                    let links = context.request_from_shell(Fetch { id }).await;
+                    context.update_app(make_event(id));
+
+                    // fan out from within the resolved task
+                    for link in links {
+                        context.spawn({
+                            let context = context.clone();
+                            let make_event = make_event.clone();
+
+                            async move {
+                                context.request_from_shell(Fetch { id: link }).await;
+                                context.update_app(make_event(link));
+                            }
+                        });
+                    }
+                }
+            });
+        }
+    }
+}
+
+mod app {
+    use crux_core::macros::Effect;
+    use serde::{Deserialize, Serialize};
+
+    use crate::capability::Fetcher;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Fetch(usize),
+        Fetched(usize),
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub fetcher: Fetcher<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Vec<usize>;
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Fetch(id) => caps.fetcher.fetch_with_links(id, Event::Fetched),
+                Event::Fetched(id) => model.push(id),
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+}
+
+mod tests {
+    use crux_core::{testing::AppTester, Core};
+
+    use crate::app::{App, Effect, Event};
+    use crate::capability::Fetch;
+
+    #[test]
+    fn resolving_spawns_follow_up_requests() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = vec![];
+
+        let mut request = app
+            .update(Event::Fetch(1), &mut model)
+            .expect_one_effect()
+            .expect_fetcher();
+
+        let mut update = app.resolve(&mut request, vec![2, 3]).unwrap();
+
+        let follow_ups: Vec<Fetch> = update
+            .take_effects(|_| true)
+            .into_iter()
+            .map(|effect| effect.expect_fetcher().operation)
+            .collect();
+        assert_eq!(follow_ups, vec![Fetch { id: 2 }, Fetch { id: 3 }]);
+
+        for event in update.events {
+            app.update(event, &mut model).assert_empty();
+        }
+        assert_eq!(model, vec![1]);
+    }
+
+    #[test]
+    fn core_returns_follow_up_requests_when_resolving() {
+        let core: Core<Effect, App> = Core::default();
+
+        let mut effects = core.process_event(Event::Fetch(1));
+        let Some(Effect::Fetcher(mut request)) = effects.pop() else {
+            panic!("Expected a fetcher effect");
+        };
+
+        let effects = core.resolve(&mut request, vec![2]);
+
+        assert!(matches!(
+            &effects[..],
+            [Effect::Fetcher(request)] if request.operation == Fetch { id: 2 }
+        ));
+    }
+}
@@ -0,0 +1,88 @@
+//! Test for converting effects to their FFI counterparts and routing the responses back
+
+mod app {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_http::Http;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub enum Event {
+        Fetch,
+
+        #[serde(skip)]
+        Fetched(crux_http::Result<crux_http::Response<String>>),
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = String;
+        type ViewModel = String;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut String, caps: &Capabilities) {
+            match event {
+                Event::Fetch => caps
+                    .http
+                    .get("http://example.com/")
+                    .expect_string()
+                    .send(Event::Fetched),
+                Event::Fetched(Ok(mut response)) => {
+                    *model = response.take_body().unwrap();
+                    caps.render.render();
+                }
+                Event::Fetched(Err(_)) => {}
+            }
+        }
+
+        fn view(&self, model: &String) -> String {
+            model.clone()
+        }
+    }
+
+    #[derive(Effect)]
+    #[effect(convert)]
+    pub struct Capabilities {
+        pub http: Http<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crux_core::{testing::AppTester, ResolveError};
+    use crux_http::protocol::{HttpRequest, HttpResponse, HttpResult};
+
+    use crate::app::{App, Effect, EffectFfi, Event};
+
+    #[test]
+    fn effect_round_trip() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = String::new();
+
+        let mut effect = app.update(Event::Fetch, &mut model).expect_one_effect();
+
+        let ffi = EffectFfi::from(&effect);
+        let EffectFfi::Http(ref request) = ffi else {
+            panic!("expected an HTTP effect");
+        };
+        assert_eq!(request, &HttpRequest::get("http://example.com/").build());
+
+        let response = HttpResult::Ok(HttpResponse::ok().body("hello").build());
+        effect
+            .resolve_ffi(serde_json::to_value(response).unwrap())
+            .unwrap();
+
+        let event = app.step().expect_one_event();
+        let mut effect = app.update(event, &mut model).expect_one_effect();
+        assert_eq!(model, "hello");
+
+        assert!(matches!(EffectFfi::from(&effect), EffectFfi::Render(_)));
+        assert!(matches!(
+            effect.resolve_ffi(serde_json::Value::Null),
+            Err(ResolveError::Never)
+        ));
+    }
+}
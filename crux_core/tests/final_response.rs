@@ -0,0 +1,213 @@
+//! Tests for closing requests which expect a stream of responses
+
+mod app {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::{Capability, Effect};
+    use futures::StreamExt;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+    pub enum TickerOperation {
+        Subscribe,
+        Current,
+    }
+
+    impl Operation for TickerOperation {
+        type Output = u32;
+    }
+
+    #[derive(Capability)]
+    pub struct Ticker<Ev> {
+        context: CapabilityContext<TickerOperation, Ev>,
+    }
+
+    impl<Ev> Ticker<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<TickerOperation, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn current<F>(&self, on_tick: F)
+        where
+            F: FnOnce(u32) -> Ev + Send + 'static,
+        {
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    let tick = context.request_from_shell(TickerOperation::Current).await;
+                    context.update_app(on_tick(tick));
+                }
+            });
+        }
+
+        pub fn subscribe<F, G>(&self, on_tick: F, on_end: G)
+        where
+            F: Fn(u32) -> Ev + Send + 'static,
+            G: FnOnce() -> Ev + Send + 'static,
+        {
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    let mut ticks = context.stream_from_shell(TickerOperation::Subscribe);
+                    while let Some(tick) = ticks.next().await {
+                        context.update_app(on_tick(tick));
+                    }
+                    context.update_app(on_end());
+                }
+            });
+        }
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Subscribe,
+        Current,
+        #[serde(skip)]
+        Tick(u32),
+        #[serde(skip)]
+        Ended,
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub ticks: Vec<u32>,
+        pub ended: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub struct ViewModel {
+        pub ticks: Vec<u32>,
+        pub ended: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Subscribe => caps.ticker.subscribe(Event::Tick, || Event::Ended),
+                Event::Current => caps.ticker.current(Event::Tick),
+                Event::Tick(tick) => model.ticks.push(tick),
+                Event::Ended => model.ended = true,
+            }
+        }
+
+        fn view(&self, model: &Model) -> ViewModel {
+            ViewModel {
+                ticks: model.ticks.clone(),
+                ended: model.ended,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub ticker: Ticker<Event>,
+    }
+}
+
+mod tests {
+    use bincode::Options;
+    use crux_core::{
+        bridge::{Bridge, ProtocolVersion, Request},
+        testing::AppTester,
+        Core,
+    };
+
+    use crate::app::{App, Effect, EffectFfi, Event, Model, ViewModel};
+
+    fn options() -> impl Options {
+        bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+    }
+
+    #[test]
+    fn subscription_stays_open_until_resolved_final() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Subscribe, &mut model)
+            .expect_one_effect()
+            .expect_ticker();
+
+        for tick in [1, 2] {
+            let event = app.resolve(&mut request, tick).unwrap().expect_one_event();
+            assert_eq!(event, Event::Tick(tick));
+            assert!(request.expects_response());
+        }
+
+        let events = app.resolve_final(&mut request, 3).unwrap().events;
+        assert_eq!(events, vec![Event::Tick(3), Event::Ended]);
+        assert!(!request.expects_response());
+
+        assert!(app.resolve(&mut request, 4).is_err());
+    }
+
+    #[test]
+    fn resolve_with_status_reports_closed_requests() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = Model::default();
+
+        let mut subscription = app
+            .update(Event::Subscribe, &mut model)
+            .expect_one_effect()
+            .expect_ticker();
+
+        let resolved = app.resolve_with_status(&mut subscription, 1).unwrap();
+        assert_eq!(resolved.update.events, vec![Event::Tick(1)]);
+        assert!(!resolved.closed);
+
+        let mut current = app
+            .update(Event::Current, &mut model)
+            .expect_one_effect()
+            .expect_ticker();
+
+        let resolved = app.resolve_with_status(&mut current, 2).unwrap();
+        assert_eq!(resolved.update.events, vec![Event::Tick(2)]);
+        assert!(resolved.closed);
+
+        let resolved = app.resolve_with_status(&mut subscription, 3).unwrap();
+        assert_eq!(resolved.update.events, vec![Event::Tick(3)]);
+        assert!(!resolved.closed);
+    }
+
+    #[test]
+    fn bridge_closes_subscription_on_final_response() {
+        let bridge = Bridge::<Effect, App>::new(Core::new());
+        let version = options().serialize(&ProtocolVersion::CURRENT).unwrap();
+        bridge.handshake(&version).unwrap();
+
+        let event = options().serialize(&Event::Subscribe).unwrap();
+        let requests: Vec<Request<EffectFfi>> = options()
+            .deserialize(&bridge.process_event(&event))
+            .unwrap();
+        let [request] = &requests[..] else {
+            panic!("expected a single request");
+        };
+        let id = request.id.0;
+
+        for tick in [1_u32, 2] {
+            let _ = bridge.handle_response(id, &options().serialize(&tick).unwrap());
+        }
+        let _ = bridge.handle_response_final(id, &options().serialize(&3_u32).unwrap());
+
+        let view: ViewModel = options().deserialize(&bridge.view()).unwrap();
+        assert_eq!(
+            view,
+            ViewModel {
+                ticks: vec![1, 2, 3],
+                ended: true
+            }
+        );
+    }
+}
@@ -0,0 +1,75 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+
+    /// Capabilities shared by apps with different event types
+    #[derive(Effect)]
+    pub struct Capabilities<Ev> {
+        pub render: Render<Ev>,
+    }
+}
+
+mod app {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub enum Event {
+        Increment,
+    }
+
+    pub type Capabilities = crate::shared::Capabilities<Event>;
+    pub type Effect = crate::shared::Effect<Event>;
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = usize;
+        type ViewModel = usize;
+        type Capabilities = Capabilities;
+
+        fn update(&self, _event: Event, model: &mut usize, caps: &Capabilities) {
+            *model += 1;
+            caps.render.render();
+        }
+
+        fn view(&self, model: &usize) -> usize {
+            *model
+        }
+    }
+}
+
+mod tests {
+    use crux_core::bridge::BridgeWithSerializer;
+    use crux_core::testing::AppTester;
+    use crux_core::Core;
+    use serde_json::{json, Value};
+
+    use crate::app::{App, Effect, Event};
+
+    #[test]
+    fn generic_capabilities_in_app_tester() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = 0;
+
+        let update = app.update(Event::Increment, &mut model);
+
+        assert!(update.effects[0].is_render());
+        assert_eq!(app.view(&model), 1);
+    }
+
+    #[test]
+    fn generic_capabilities_across_the_bridge() {
+        let bridge = BridgeWithSerializer::<Effect, App>::new(Core::default());
+
+        let mut effects_bytes = vec![];
+        bridge.process_event(
+            json!("Increment"),
+            &mut serde_json::Serializer::new(&mut effects_bytes),
+        );
+
+        let requests: Value = serde_json::from_slice(&effects_bytes).unwrap();
+        assert_eq!(requests, json!([{ "id": 0, "effect": { "Render": null } }]));
+    }
+}
@@ -0,0 +1,116 @@
+mod app {
+    use std::sync::{Arc, Mutex};
+
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::{Capability, Effect};
+    use crux_core::render::Render;
+    use futures::FutureExt;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    pub struct Lookup {
+        pub key: String,
+    }
+
+    impl Operation for Lookup {
+        type Output = u32;
+    }
+
+    #[derive(Capability)]
+    pub struct Lookups<Ev> {
+        context: CapabilityContext<Lookup, Ev>,
+    }
+
+    impl<Ev> Lookups<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<Lookup, Ev>) -> Self {
+            Self { context }
+        }
+
+        /// Look up the `key`, recording the output in `log` on its way to the `callback`
+        pub fn lookup<F>(&self, key: &str, log: Arc<Mutex<Vec<u32>>>, callback: F)
+        where
+            F: FnOnce(u32) -> Ev + Send + 'static,
+        {
+            let key = key.to_string();
+
+            self.context.spawn({
+                let context = self.context.clone();
+
+                async move {
+                    let value = context
+                        .request_from_shell(Lookup { key })
+                        .inspect(|value| log.lock().unwrap().push(*value))
+                        .await;
+
+                    context.update_app(callback(value));
+                }
+            });
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum Event {
+        Lookup(String),
+        Found(u32),
+    }
+
+    #[derive(Default)]
+    pub struct App {
+        pub log: Arc<Mutex<Vec<u32>>>,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub lookups: Lookups<Event>,
+        pub render: Render<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Option<u32>;
+        type ViewModel = Option<u32>;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Lookup(key) => caps.lookups.lookup(&key, self.log.clone(), Event::Found),
+                Event::Found(value) => {
+                    *model = Some(value);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            *model
+        }
+    }
+}
+
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crux_core::testing::AppTester;
+
+    use crate::app::{App, Effect, Event};
+
+    #[test]
+    fn inspected_output_is_observed_and_passed_on() {
+        let log = Arc::new(Mutex::new(vec![]));
+        let app = AppTester::<App, Effect>::new(App { log: log.clone() });
+        let mut model = None;
+
+        let mut request = app
+            .update(Event::Lookup("answer".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_lookups();
+
+        let event = app.resolve(&mut request, 42).unwrap().expect_one_event();
+
+        assert_eq!(*log.lock().unwrap(), vec![42]);
+        assert_eq!(event, Event::Found(42));
+    }
+}
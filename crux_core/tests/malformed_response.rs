@@ -0,0 +1,375 @@
+//! Tests for responses from the shell which can't be deserialized
+
+mod app {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::{Capability, Effect};
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    pub struct Fetch {
+        pub url: String,
+    }
+
+    impl Operation for Fetch {
+        type Output = String;
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    pub struct Lookup {
+        pub key: String,
+    }
+
+    impl Operation for Lookup {
+        type Output = Option<String>;
+    }
+
+    #[derive(Capability)]
+    pub struct Fetcher<Ev> {
+        context: CapabilityContext<Fetch, Ev>,
+    }
+
+    impl<Ev> Fetcher<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<Fetch, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn fetch<F>(&self, url: &str, callback: F)
+        where
+            F: FnOnce(String) -> Ev + Send + 'static,
+        {
+            let url = url.to_string();
+
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    let body = context.request_from_shell(Fetch { url }).await;
+                    context.update_app(callback(body));
+                }
+            });
+        }
+
+        pub fn try_fetch<F>(&self, url: &str, callback: F)
+        where
+            F: FnOnce(Result<String, String>) -> Ev + Send + 'static,
+        {
+            let url = url.to_string();
+
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    let body = context.request_from_shell_fallible(Fetch { url }).await;
+                    context.update_app(callback(body.map_err(|error| error.message)));
+                }
+            });
+        }
+    }
+
+    #[derive(Capability)]
+    pub struct Cache<Ev> {
+        context: CapabilityContext<Lookup, Ev>,
+    }
+
+    impl<Ev> Cache<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<Lookup, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn lookup<F>(&self, key: &str, callback: F)
+        where
+            F: FnOnce(Option<String>) -> Ev + Send + 'static,
+        {
+            let key = key.to_string();
+
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    let value = context.request_from_shell(Lookup { key }).await;
+                    context.update_app(callback(value));
+                }
+            });
+        }
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Fetch,
+        TryFetch,
+        Lookup,
+        #[serde(skip)]
+        Fetched(Result<String, String>),
+        #[serde(skip)]
+        Found(Option<String>),
+        #[serde(skip)]
+        Malformed(String),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub body: Option<String>,
+        pub cached: Option<Option<String>>,
+        pub error: Option<String>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub struct ViewModel {
+        pub body: Option<String>,
+        pub cached: Option<Option<String>>,
+        pub error: Option<String>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Fetch => caps
+                    .fetcher
+                    .fetch("crux.dev", |body| Event::Fetched(Ok(body))),
+                Event::TryFetch => caps.fetcher.try_fetch("crux.dev", Event::Fetched),
+                Event::Lookup => caps.cache.lookup("crux.dev", Event::Found),
+                Event::Fetched(Ok(body)) => {
+                    model.body = Some(body);
+                    caps.render.render();
+                }
+                Event::Found(value) => {
+                    model.cached = Some(value);
+                    caps.render.render();
+                }
+                Event::Fetched(Err(error)) | Event::Malformed(error) => {
+                    model.error = Some(error);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Model) -> ViewModel {
+            ViewModel {
+                body: model.body.clone(),
+                cached: model.cached.clone(),
+                error: model.error.clone(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub fetcher: Fetcher<Event>,
+        pub cache: Cache<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use bincode::Options;
+    use crux_core::{
+        bridge::{Bridge, DecodeError, ProtocolVersion, Request},
+        Core,
+    };
+
+    use crate::app::{App, Effect, EffectFfi, Event, ViewModel};
+
+    // a string is encoded with its length first, which this is too short to hold
+    const MALFORMED: &[u8] = &[0xff];
+
+    fn options() -> impl Options {
+        bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+    }
+
+    // the shell speaks the current protocol, so requests carry all their fields
+    fn handshake(bridge: &Bridge<Effect, App>) {
+        let version = options().serialize(&ProtocolVersion::CURRENT).unwrap();
+        bridge.handshake(&version).unwrap();
+    }
+
+    fn send(bridge: &Bridge<Effect, App>, event: &Event) -> Vec<Request<EffectFfi>> {
+        let event = options().serialize(event).unwrap();
+        options()
+            .deserialize(&bridge.process_event(&event))
+            .unwrap()
+    }
+
+    fn view(bridge: &Bridge<Effect, App>) -> ViewModel {
+        options().deserialize(&bridge.view()).unwrap()
+    }
+
+    #[test]
+    fn malformed_response_is_passed_to_the_app() {
+        let bridge = Bridge::<Effect, App>::new(Core::new())
+            .with_decode_error_handler(|error: DecodeError| Event::Malformed(error.to_string()));
+        handshake(&bridge);
+
+        let [request] = &send(&bridge, &Event::Fetch)[..] else {
+            panic!("expected a single request");
+        };
+
+        let requests: Vec<Request<EffectFfi>> = options()
+            .deserialize(&bridge.handle_response(request.id.0, MALFORMED))
+            .unwrap();
+
+        let [render] = &requests[..] else {
+            panic!("expected a single request");
+        };
+        assert!(matches!(render.effect, EffectFfi::Render(_)));
+
+        let error = view(&bridge).error.unwrap();
+        assert!(
+            error.starts_with(&format!(
+                "Response to request {} could not be deserialized",
+                request.id.0
+            )),
+            "unexpected error: {error}"
+        );
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn malformed_response_names_operation_in_debug_builds() {
+        let bridge = Bridge::<Effect, App>::new(Core::new())
+            .with_decode_error_handler(|error: DecodeError| Event::Malformed(error.message));
+        handshake(&bridge);
+
+        let [request] = &send(&bridge, &Event::Fetch)[..] else {
+            panic!("expected a single request");
+        };
+
+        // a number instead of a string
+        let output = options().serialize(&42_u8).unwrap();
+        let _ = bridge.handle_response(request.id.0, &output);
+
+        let error = view(&bridge).error.unwrap();
+        assert!(
+            error.starts_with(
+                "response to malformed_response::app::Fetch doesn't match its output type \
+                 alloc::string::String"
+            ),
+            "unexpected error: {error}"
+        );
+    }
+
+    #[test]
+    fn malformed_response_fails_fallible_request() {
+        let bridge = Bridge::<Effect, App>::new(Core::new());
+        handshake(&bridge);
+
+        let [request] = &send(&bridge, &Event::TryFetch)[..] else {
+            panic!("expected a single request");
+        };
+
+        let requests: Vec<Request<EffectFfi>> = options()
+            .deserialize(&bridge.handle_response(request.id.0, MALFORMED))
+            .unwrap();
+
+        assert_eq!(requests.len(), 1);
+        assert!(view(&bridge).error.is_some());
+        assert_eq!(view(&bridge).body, None);
+    }
+
+    #[test]
+    fn malformed_response_without_handler_is_dropped() {
+        let bridge = Bridge::<Effect, App>::new(Core::new());
+        handshake(&bridge);
+
+        let [request] = &send(&bridge, &Event::Fetch)[..] else {
+            panic!("expected a single request");
+        };
+
+        let requests: Vec<Request<EffectFfi>> = options()
+            .deserialize(&bridge.handle_response(request.id.0, MALFORMED))
+            .unwrap();
+
+        assert!(requests.is_empty());
+        assert_eq!(
+            view(&bridge),
+            ViewModel {
+                body: None,
+                cached: None,
+                error: None
+            }
+        );
+    }
+
+    #[test]
+    fn malformed_failure_is_passed_to_the_app() {
+        let bridge = Bridge::<Effect, App>::new(Core::new())
+            .with_decode_error_handler(|error: DecodeError| Event::Malformed(error.to_string()));
+        handshake(&bridge);
+
+        let [request] = &send(&bridge, &Event::TryFetch)[..] else {
+            panic!("expected a single request");
+        };
+
+        let requests: Vec<Request<EffectFfi>> = options()
+            .deserialize(&bridge.handle_failure(request.id.0, MALFORMED))
+            .unwrap();
+
+        let [render] = &requests[..] else {
+            panic!("expected a single request");
+        };
+        assert!(matches!(render.effect, EffectFfi::Render(_)));
+
+        let error = view(&bridge).error.unwrap();
+        assert!(
+            error.starts_with(&format!(
+                "Response to request {} could not be deserialized",
+                request.id.0
+            )),
+            "unexpected error: {error}"
+        );
+    }
+
+    #[test]
+    fn malformed_response_to_optional_output_is_not_none() {
+        let bridge = Bridge::<Effect, App>::new(Core::new())
+            .with_decode_error_handler(|error: DecodeError| Event::Malformed(error.to_string()));
+        handshake(&bridge);
+
+        let [request] = &send(&bridge, &Event::Lookup)[..] else {
+            panic!("expected a single request");
+        };
+
+        let _ = bridge.handle_response(request.id.0, MALFORMED);
+
+        let view = view(&bridge);
+        assert_eq!(view.cached, None);
+        let error = view.error.unwrap();
+        assert!(
+            error.starts_with(&format!(
+                "Response to request {} could not be deserialized",
+                request.id.0
+            )),
+            "unexpected error: {error}"
+        );
+    }
+
+    #[test]
+    fn empty_response_to_optional_output_is_not_none() {
+        let bridge = Bridge::<Effect, App>::new(Core::new())
+            .with_decode_error_handler(|error: DecodeError| Event::Malformed(error.message));
+        handshake(&bridge);
+
+        let [request] = &send(&bridge, &Event::Lookup)[..] else {
+            panic!("expected a single request");
+        };
+
+        let _ = bridge.handle_response(request.id.0, &[]);
+
+        let view = view(&bridge);
+        assert_eq!(view.cached, None);
+        assert!(view.error.is_some());
+    }
+}
@@ -0,0 +1,106 @@
+//! Tests for memoizing the view model while the model doesn't change
+
+mod app {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App {
+        pub views: Arc<AtomicUsize>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub enum Event {
+        Add(u32),
+    }
+
+    #[derive(Default, Clone, PartialEq)]
+    pub struct Model {
+        pub numbers: Vec<u32>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    pub struct ViewModel {
+        pub total: u32,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Add(number) => model.numbers.push(number),
+            }
+            caps.render.render();
+        }
+
+        fn view(&self, model: &Model) -> ViewModel {
+            self.views.fetch_add(1, Ordering::SeqCst);
+
+            ViewModel {
+                total: model.numbers.iter().sum(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use crux_core::{assert_effect, memo::Memoized, testing::AppTester};
+
+    use crate::app::{App, Effect, Event, Model, ViewModel};
+
+    fn app() -> (AppTester<Memoized<App>, Effect>, Arc<AtomicUsize>) {
+        let app = App::default();
+        let views = app.views.clone();
+
+        (AppTester::new(Memoized::new(app)), views)
+    }
+
+    #[test]
+    fn view_is_computed_once_for_the_same_model() {
+        let (app, views) = app();
+        let model = Model {
+            numbers: vec![1, 2],
+        };
+
+        assert_eq!(app.view(&model), ViewModel { total: 3 });
+        assert_eq!(app.view(&model.clone()), ViewModel { total: 3 });
+
+        assert_eq!(views.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn view_is_recomputed_when_the_model_changes() {
+        let (app, views) = app();
+        let mut model = Model::default();
+
+        assert_eq!(app.view(&model), ViewModel { total: 0 });
+
+        let update = app.update(Event::Add(5), &mut model);
+        assert_effect!(update, Effect::Render(_));
+
+        assert_eq!(app.view(&model), ViewModel { total: 5 });
+        assert_eq!(app.view(&model), ViewModel { total: 5 });
+
+        assert_eq!(views.load(Ordering::SeqCst), 2);
+    }
+}
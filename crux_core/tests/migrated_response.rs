@@ -0,0 +1,167 @@
+//! Test for responses sent by the shell in an older format of the operation's output
+
+mod app {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::{Capability, Effect};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    pub struct LoadProfile;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+    pub struct Profile {
+        pub name: String,
+        pub age: Option<u32>,
+    }
+
+    impl Operation for LoadProfile {
+        type Output = Profile;
+    }
+
+    #[derive(Capability)]
+    pub struct Profiles<Ev> {
+        context: CapabilityContext<LoadProfile, Ev>,
+    }
+
+    impl<Ev> Profiles<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<LoadProfile, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn load<F>(&self, callback: F)
+        where
+            F: FnOnce(Profile) -> Ev + Send + 'static,
+        {
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    let profile = context.request_from_shell(LoadProfile).await;
+                    context.update_app(callback(profile));
+                }
+            });
+        }
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub enum Event {
+        Load,
+        #[serde(skip)]
+        Loaded(Profile),
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Profile;
+        type ViewModel = Profile;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Self::Model, caps: &Capabilities) {
+            match event {
+                Event::Load => caps.profiles.load(Event::Loaded),
+                Event::Loaded(profile) => {
+                    *model = profile;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            model.clone()
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub profiles: Profiles<Event>,
+        pub render: crux_core::render::Render<Event>,
+    }
+}
+
+mod tests {
+    use bincode::Options;
+    use crux_core::{
+        bridge::{Bridge, ProtocolVersion},
+        Core,
+    };
+    use serde::{Deserialize, Serialize};
+
+    use crate::app::{App, Effect, EffectFfi, Event, Profile};
+
+    type Requests = Vec<crux_core::bridge::Request<EffectFfi>>;
+
+    // the output type as sent by an older version of the shell
+    #[derive(Serialize, Deserialize)]
+    struct ProfileV1 {
+        name: String,
+    }
+
+    fn options() -> impl Options {
+        bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+    }
+
+    // the shell speaks the current protocol, so requests carry all their fields
+    fn handshake(bridge: &Bridge<Effect, App>) {
+        let version = options().serialize(&ProtocolVersion::CURRENT).unwrap();
+        bridge.handshake(&version).unwrap();
+    }
+
+    fn migrate(bytes: Vec<u8>) -> Vec<u8> {
+        let ProfileV1 { name } = options().deserialize(&bytes).unwrap();
+
+        options().serialize(&Profile { name, age: None }).unwrap()
+    }
+
+    #[test]
+    fn old_response_is_migrated_before_decoding() {
+        let bridge = Bridge::<Effect, App>::new(Core::default());
+        handshake(&bridge);
+
+        let event = options().serialize(&Event::Load).unwrap();
+        let requests: Requests = options()
+            .deserialize(&bridge.process_event(&event))
+            .unwrap();
+
+        let [request] = &requests[..] else {
+            panic!("expected a single request");
+        };
+        assert!(matches!(request.effect, EffectFfi::Profiles(_)));
+
+        let old_response = options()
+            .serialize(&ProfileV1 {
+                name: "Ferris".to_string(),
+            })
+            .unwrap();
+        let requests: Requests = options()
+            .deserialize(&bridge.handle_response_with_migrator(
+                request.id.0,
+                &old_response,
+                migrate,
+            ))
+            .unwrap();
+
+        assert!(matches!(
+            requests[..],
+            [crux_core::bridge::Request {
+                effect: EffectFfi::Render(_),
+                ..
+            }]
+        ));
+
+        let view: Profile = options().deserialize(&bridge.view()).unwrap();
+        assert_eq!(
+            view,
+            Profile {
+                name: "Ferris".to_string(),
+                age: None
+            }
+        );
+    }
+}
@@ -0,0 +1,130 @@
+mod capability {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::Capability;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    pub struct Search {
+        pub query: String,
+    }
+
+    impl Operation for Search {
+        type Output = Vec<String>;
+    }
+
+    #[derive(Capability)]
+    pub struct Searcher<Ev> {
+        context: CapabilityContext<Search, Ev>,
+    }
+
+    impl<Ev> Searcher<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<Search, Ev>) -> Self {
+            Self { context }
+        }
+
+        /// Search for `query`, the `callback` can discard the results by returning `None`
+        pub fn search<F>(&self, query: &str, callback: F)
+        where
+            F: FnOnce(Vec<String>) -> Option<Ev> + Send + 'static,
+        {
+            let query = query.to_string();
+
+            self.context.spawn({
+                let context = self.context.clone();
+
+                async move {
+                    let results = context.request_from_shell(Search { query }).await;
+                    context.update_app_optional(callback(results));
+                }
+            });
+        }
+    }
+}
+
+mod app {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+
+    use crate::capability::Searcher;
+
+    #[derive(Debug, PartialEq)]
+    pub enum Event {
+        Search(String),
+        Found(Vec<String>),
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub searcher: Searcher<Event>,
+        pub render: Render<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Vec<String>;
+        type ViewModel = Vec<String>;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                // nothing to show for no results, keep the previous ones
+                Event::Search(query) => caps.searcher.search(&query, |results| {
+                    (!results.is_empty()).then_some(Event::Found(results))
+                }),
+                Event::Found(results) => {
+                    *model = results;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            model.clone()
+        }
+    }
+}
+
+mod tests {
+    use crux_core::testing::AppTester;
+
+    use crate::app::{App, Effect, Event};
+
+    #[test]
+    fn dispatches_some_event() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = vec![];
+
+        let mut request = app
+            .update(Event::Search("crux".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_searcher();
+
+        let event = app
+            .resolve(&mut request, vec!["crux_core".to_string()])
+            .unwrap()
+            .expect_one_event();
+
+        assert_eq!(event, Event::Found(vec!["crux_core".to_string()]));
+    }
+
+    #[test]
+    fn discards_none() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = vec!["crux_core".to_string()];
+
+        let mut request = app
+            .update(Event::Search("nothing".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_searcher();
+
+        app.resolve(&mut request, vec![]).unwrap().assert_empty();
+
+        assert_eq!(app.view(&model), vec!["crux_core".to_string()]);
+    }
+}
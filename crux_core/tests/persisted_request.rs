@@ -0,0 +1,143 @@
+mod capability {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::Capability;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    pub struct Download {
+        pub url: String,
+    }
+
+    impl Operation for Download {
+        type Output = String; // path of the downloaded file
+    }
+
+    #[derive(Capability)]
+    pub struct Downloader<Ev> {
+        context: CapabilityContext<Download, Ev>,
+    }
+
+    impl<Ev> Downloader<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<Download, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn download<F>(&self, url: &str, make_event: F)
+        where
+            F: FnOnce(String) -> Ev + Send + 'static,
+        {
+            let url = url.to_string();
+
+            self.context.spawn({
+                let context = self.context.clone();
+
+                async move {
+                    let path = context.request_from_shell(Download { url }).await;
+                    context.update_app(make_event(path));
+                }
+            });
+        }
+    }
+}
+
+mod app {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    use crate::capability::Downloader;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Download(String),
+        Downloaded(String),
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub downloader: Downloader<Event>,
+        pub render: Render<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Vec<String>;
+        type ViewModel = Vec<String>;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Download(url) => caps.downloader.download(&url, Event::Downloaded),
+                Event::Downloaded(path) => {
+                    model.push(path);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            model.clone()
+        }
+    }
+}
+
+mod tests {
+    use crux_core::bridge::{BridgeWithSerializer, Request};
+    use crux_core::Core;
+    use serde_json::{json, Value};
+
+    use crate::app::{App, Effect};
+
+    type Bridge = BridgeWithSerializer<Effect, App>;
+
+    fn process_event(bridge: &Bridge, event: Value) -> Vec<Request<Value>> {
+        let mut requests = vec![];
+        bridge.process_event(event, &mut serde_json::Serializer::new(&mut requests));
+
+        serde_json::from_slice(&requests).unwrap()
+    }
+
+    #[test]
+    fn resolves_persisted_request_after_relaunch() {
+        let bridge = Bridge::new(Core::default());
+
+        let mut requests = process_event(&bridge, json!({ "Download": "crux.dev/video" }));
+        let request = requests.pop().unwrap();
+
+        // the shell persists the pending request before the app is suspended
+        let persisted = serde_json::to_string(&request).unwrap();
+        drop(bridge);
+
+        // after the relaunch, the app issues the download again on startup
+        let bridge = Bridge::new(Core::default());
+        let requests = process_event(&bridge, json!({ "Download": "crux.dev/video" }));
+
+        // and the shell resolves the request for the same effect with its new id
+        let persisted: Request<Value> = serde_json::from_str(&persisted).unwrap();
+        let request = requests
+            .iter()
+            .find(|request| request.effect == persisted.effect)
+            .expect("the effect should be issued again");
+
+        let mut effects_bytes = vec![];
+        bridge.handle_response(
+            request.id.0,
+            json!("/downloads/video.mp4"),
+            &mut serde_json::Serializer::new(&mut effects_bytes),
+        );
+
+        let requests: Value = serde_json::from_slice(&effects_bytes).unwrap();
+        assert_eq!(requests, json!([{ "id": 0, "effect": { "Render": null } }]));
+
+        let mut view_bytes = vec![];
+        bridge.view(&mut serde_json::Serializer::new(&mut view_bytes));
+        let view: Value = serde_json::from_slice(&view_bytes).unwrap();
+        assert_eq!(view, json!(["/downloads/video.mp4"]));
+    }
+}
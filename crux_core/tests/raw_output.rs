@@ -0,0 +1,149 @@
+//! Test for operations whose output is passed by the shell as raw bytes
+
+mod app {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::{Capability, Effect};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    pub struct LoadBlob {
+        pub key: String,
+    }
+
+    impl Operation for LoadBlob {
+        type Output = Vec<u8>;
+
+        const RAW_OUTPUT: bool = true;
+    }
+
+    #[derive(Capability)]
+    pub struct Blobs<Ev> {
+        context: CapabilityContext<LoadBlob, Ev>,
+    }
+
+    impl<Ev> Blobs<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<LoadBlob, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn load<F>(&self, key: &str, callback: F)
+        where
+            F: FnOnce(Vec<u8>) -> Ev + Send + 'static,
+        {
+            let key = key.to_string();
+
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    let blob = context.request_from_shell(LoadBlob { key }).await;
+                    context.update_app(callback(blob));
+                }
+            });
+        }
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub enum Event {
+        Load,
+        #[serde(skip)]
+        Loaded(Vec<u8>),
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Vec<u8>;
+        type ViewModel = Vec<u8>;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Self::Model, caps: &Capabilities) {
+            match event {
+                Event::Load => caps.blobs.load("document", Event::Loaded),
+                Event::Loaded(blob) => {
+                    *model = blob;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            model.clone()
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub blobs: Blobs<Event>,
+        pub render: crux_core::render::Render<Event>,
+    }
+}
+
+mod tests {
+    use bincode::Options;
+    use crux_core::{
+        bridge::{Bridge, ProtocolVersion},
+        Core,
+    };
+
+    use crate::app::{App, Effect, EffectFfi, Event, LoadBlob};
+
+    type Requests = Vec<crux_core::bridge::Request<EffectFfi>>;
+
+    fn options() -> impl Options {
+        bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+    }
+
+    // the shell speaks the current protocol, so requests carry all their fields
+    fn handshake(bridge: &Bridge<Effect, App>) {
+        let version = options().serialize(&ProtocolVersion::CURRENT).unwrap();
+        bridge.handshake(&version).unwrap();
+    }
+
+    #[test]
+    fn raw_output_is_passed_as_is() {
+        let bridge = Bridge::<Effect, App>::new(Core::default());
+        handshake(&bridge);
+
+        let event = options().serialize(&Event::Load).unwrap();
+        let requests: Requests = options()
+            .deserialize(&bridge.process_event(&event))
+            .unwrap();
+
+        let [request] = &requests[..] else {
+            panic!("expected a single request");
+        };
+        let EffectFfi::Blobs(operation) = &request.effect else {
+            panic!("expected a blobs request");
+        };
+        assert_eq!(
+            operation,
+            &LoadBlob {
+                key: "document".to_string()
+            }
+        );
+
+        // already serialized data, not wrapped in bincode
+        let blob = br#"{"title":"Crux"}"#;
+        let requests: Requests = options()
+            .deserialize(&bridge.handle_response(request.id.0, blob))
+            .unwrap();
+
+        assert!(matches!(
+            requests[..],
+            [crux_core::bridge::Request {
+                effect: EffectFfi::Render(_),
+                ..
+            }]
+        ));
+
+        let view: Vec<u8> = options().deserialize(&bridge.view()).unwrap();
+        assert_eq!(view, blob);
+    }
+}
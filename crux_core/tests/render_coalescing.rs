@@ -0,0 +1,140 @@
+mod app {
+    use crux_core::macros::Effect;
+    use crux_core::render::{Render, RenderChunks};
+    use serde::{Deserialize, Serialize};
+
+    use doctest_support::compose::capabilities::capability_one::CapabilityOne;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        RenderThrice,
+        RenderAroundRequest,
+        RenderPages(u32),
+        Done(usize),
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub one: CapabilityOne<Event>,
+        pub render: Render<Event>,
+        pub render_chunks: RenderChunks<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = ();
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, _model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::RenderThrice => {
+                    caps.render.render();
+                    caps.render.render();
+                    caps.render.render();
+                }
+                Event::RenderAroundRequest => {
+                    caps.render.render();
+                    caps.one.one(1, Event::Done);
+                    caps.render.render();
+                }
+                Event::RenderPages(pages) => {
+                    for page in 0..pages {
+                        caps.render_chunks.render_chunk(page, pages);
+                    }
+                    caps.render.render();
+                }
+                Event::Done(_) => {}
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+}
+
+mod tests {
+    use crux_core::{assert_effects_match, render::RenderChunkOperation, testing::AppTester, Core};
+
+    use crate::app::{App, Effect, Event};
+
+    #[test]
+    fn consecutive_renders_are_coalesced() {
+        let app = AppTester::<App, Effect>::builder().coalescing().build();
+
+        let update = app.update(Event::RenderThrice, &mut ());
+
+        assert_effects_match!(update, [Effect::Render(_)]);
+    }
+
+    #[test]
+    fn renders_separated_by_other_effects_are_kept() {
+        let app = AppTester::<App, Effect>::builder().coalescing().build();
+
+        let update = app.update(Event::RenderAroundRequest, &mut ());
+
+        assert_effects_match!(
+            update,
+            [
+                Effect::Render(_),
+                Effect::CapabilityOne(_),
+                Effect::Render(_)
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_are_not_coalesced_by_default() {
+        let app = AppTester::<App, Effect>::default();
+
+        let update = app.update(Event::RenderThrice, &mut ());
+
+        assert_effects_match!(
+            update,
+            [Effect::Render(_), Effect::Render(_), Effect::Render(_)]
+        );
+
+        let core: Core<Effect, App> = Core::default();
+
+        let effects = core.process_event(Event::RenderThrice);
+
+        assert_eq!(effects.len(), 3);
+    }
+
+    #[test]
+    fn core_coalesces_consecutive_renders() {
+        let core: Core<Effect, App> = Core::new().with_coalescing();
+
+        let effects = core.process_event(Event::RenderThrice);
+
+        assert!(matches!(effects[..], [Effect::Render(_)]));
+    }
+
+    #[test]
+    fn chunks_are_rendered_in_order() {
+        let app = AppTester::<App, Effect>::builder().coalescing().build();
+
+        let update = app.update(Event::RenderPages(3), &mut ());
+
+        let mut effects: Vec<_> = update.into_effects().collect();
+
+        // the full render isn't coalesced into the last chunk
+        assert!(matches!(effects.pop(), Some(Effect::Render(_))));
+
+        let chunks: Vec<_> = effects
+            .into_iter()
+            .map(|effect| effect.expect_render_chunks().operation)
+            .collect();
+
+        assert_eq!(
+            chunks,
+            [
+                RenderChunkOperation { index: 0, total: 3 },
+                RenderChunkOperation { index: 1, total: 3 },
+                RenderChunkOperation { index: 2, total: 3 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! Tests for racing requests to the shell against a timeout
+
+mod app {
+    use std::time::Duration;
+
+    use crux_core::compose::Compose;
+    use crux_core::macros::Effect;
+    use doctest_support::compose::capabilities::capability_one::CapabilityOne;
+    use serde::{Deserialize, Serialize};
+
+    pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Fetch(usize),
+        #[serde(skip)]
+        Fetched(usize),
+        #[serde(skip)]
+        TimedOut,
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub one: CapabilityOne<Event>,
+        #[effect(skip)]
+        pub compose: Compose<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Option<usize>;
+        type ViewModel = Option<usize>;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Option<usize>, caps: &Capabilities) {
+            match event {
+                Event::Fetch(number) => caps.compose.spawn(|context| {
+                    let one = caps.one.clone();
+
+                    async move {
+                        match context.with_timeout(TIMEOUT, one.one_async(number)).await {
+                            Some(result) => context.update_app(Event::Fetched(result)),
+                            None => context.update_app(Event::TimedOut),
+                        }
+                    }
+                }),
+                Event::Fetched(result) => *model = Some(result),
+                Event::TimedOut => *model = None,
+            }
+        }
+
+        fn view(&self, model: &Option<usize>) -> Option<usize> {
+            *model
+        }
+    }
+}
+
+mod tests {
+    use std::time::Duration;
+
+    use crux_core::testing::AppTester;
+
+    use crate::app::{App, Effect, Event, TIMEOUT};
+
+    #[test]
+    fn unresolved_request_times_out() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = None;
+
+        let mut request = app
+            .update(Event::Fetch(1), &mut model)
+            .expect_one_effect()
+            .expect_one();
+
+        app.advance_time(TIMEOUT - Duration::from_millis(1))
+            .assert_empty();
+
+        let event = app
+            .advance_time(Duration::from_millis(1))
+            .expect_one_event();
+        assert_eq!(event, Event::TimedOut);
+
+        // a late response is ignored
+        app.resolve(&mut request, 10).unwrap().assert_empty();
+    }
+
+    #[test]
+    fn request_resolved_in_time() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = None;
+
+        let mut request = app
+            .update(Event::Fetch(1), &mut model)
+            .expect_one_effect()
+            .expect_one();
+
+        let event = app.resolve(&mut request, 10).unwrap().expect_one_event();
+        assert_eq!(event, Event::Fetched(10));
+
+        // the timeout was cancelled
+        app.advance_time(TIMEOUT).assert_empty();
+    }
+}
@@ -0,0 +1,170 @@
+//! Tests for tagging requests, to correlate them in logs and traces
+
+mod app {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::{Capability, Effect};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    pub struct LoadProfile;
+
+    impl Operation for LoadProfile {
+        type Output = String;
+    }
+
+    #[derive(Capability)]
+    pub struct Profiles<Ev> {
+        context: CapabilityContext<LoadProfile, Ev>,
+    }
+
+    impl<Ev> Profiles<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<LoadProfile, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn load<F>(&self, callback: F)
+        where
+            F: FnOnce(String) -> Ev + Send + 'static,
+        {
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    let profile = context.request_from_shell(LoadProfile).await;
+                    context.update_app(callback(profile));
+                }
+            });
+        }
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Load,
+        LoadTagged,
+        #[serde(skip)]
+        Loaded(String),
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = String;
+        type ViewModel = String;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut String, caps: &Capabilities) {
+            match event {
+                Event::Load => caps.profiles.load(Event::Loaded),
+                Event::LoadTagged => caps.profiles.tagged("profile").load(Event::Loaded),
+                Event::Loaded(profile) => {
+                    *model = profile;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &String) -> String {
+            model.clone()
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub profiles: Profiles<Event>,
+        pub render: crux_core::render::Render<Event>,
+    }
+}
+
+mod tests {
+    use bincode::Options;
+    use crux_core::{
+        bridge::{Bridge, BridgeWithSerializer, ProtocolVersion},
+        testing::AppTester,
+        Core, Effect as _,
+    };
+    use serde_json::{json, Value};
+
+    use crate::app::{App, Effect, EffectFfi, Event};
+
+    #[test]
+    fn tagged_request_carries_tag() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = String::new();
+
+        let effect = app
+            .update(Event::LoadTagged, &mut model)
+            .expect_one_effect();
+        assert_eq!(effect.tag(), Some("profile"));
+
+        let mut request = effect.expect_profiles();
+        assert_eq!(request.tag(), Some("profile"));
+
+        // the tag doesn't change how the request resolves
+        let event = app
+            .resolve(&mut request, "Ferris".to_string())
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Loaded("Ferris".to_string()));
+    }
+
+    #[test]
+    fn untagged_request_has_no_tag() {
+        let app = AppTester::<App, Effect>::default();
+        let mut model = String::new();
+
+        let request = app
+            .update(Event::Load, &mut model)
+            .expect_one_effect()
+            .expect_profiles();
+        assert_eq!(request.tag(), None);
+    }
+
+    #[test]
+    fn bridge_request_carries_tag() {
+        let options = bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes();
+        let bridge = Bridge::<Effect, App>::new(Core::new());
+        bridge
+            .handshake(&options.serialize(&ProtocolVersion::CURRENT).unwrap())
+            .unwrap();
+
+        let event = options.serialize(&Event::LoadTagged).unwrap();
+        let requests: Vec<crux_core::bridge::Request<EffectFfi>> =
+            options.deserialize(&bridge.process_event(&event)).unwrap();
+
+        let [request] = &requests[..] else {
+            panic!("expected a single request");
+        };
+        assert!(matches!(request.effect, EffectFfi::Profiles(_)));
+        assert_eq!(request.tag.as_deref(), Some("profile"));
+    }
+
+    #[test]
+    fn tag_is_only_sent_after_handshake() {
+        let bridge = BridgeWithSerializer::<Effect, App>::new(Core::new());
+
+        // a shell which predates the handshake expects requests without tags
+        let mut requests = vec![];
+        bridge.process_event(
+            json!("LoadTagged"),
+            &mut serde_json::Serializer::new(&mut requests),
+        );
+        let requests: Value = serde_json::from_slice(&requests).unwrap();
+        assert_eq!(requests[0].get("tag"), None);
+
+        bridge.handshake(json!(ProtocolVersion::CURRENT)).unwrap();
+
+        let mut requests = vec![];
+        bridge.process_event(
+            json!("LoadTagged"),
+            &mut serde_json::Serializer::new(&mut requests),
+        );
+        let requests: Value = serde_json::from_slice(&requests).unwrap();
+        assert_eq!(requests[0]["tag"], "profile");
+    }
+}
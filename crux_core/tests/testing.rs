@@ -49,3 +49,1354 @@ fn app_tester_new() {
 
     assert_eq!(effects.count(), 1);
 }
+
+#[test]
+fn assert_effects_of_capability() {
+    let tester = AppTester::<app::MyApp, app::Effect>::default();
+    let mut model = String::new();
+
+    let update = tester.update(app::Event::Hello, &mut model);
+
+    update.assert_has_effect_of("Render");
+    update.assert_no_effects_of("KeyValue");
+}
+
+#[test]
+#[should_panic(expected = "Expected no Render effects but found 1, the update contains: [Render]")]
+fn assert_no_effects_of_capability_present() {
+    let tester = AppTester::<app::MyApp, app::Effect>::default();
+    let mut model = String::new();
+
+    tester
+        .update(app::Event::Hello, &mut model)
+        .assert_no_effects_of("Render");
+}
+
+#[test]
+#[should_panic(expected = "Expected a KeyValue effect, but the update contains: [Render]")]
+fn assert_has_effect_of_capability_missing() {
+    let tester = AppTester::<app::MyApp, app::Effect>::default();
+    let mut model = String::new();
+
+    tester
+        .update(app::Event::Hello, &mut model)
+        .assert_has_effect_of("KeyValue");
+}
+
+mod chained_app {
+    use crux_core::macros::Effect;
+    use crux_core::App;
+    use doctest_support::compose::capabilities::{
+        capability_one::CapabilityOne, capability_two::CapabilityTwo,
+    };
+
+    #[derive(Debug, PartialEq)]
+    pub enum Event {
+        Trigger,
+        Finished(usize),
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        one: CapabilityOne<Event>,
+        two: CapabilityTwo<Event>,
+        #[effect(skip)]
+        compose: crux_core::compose::Compose<Event>,
+    }
+
+    #[derive(Default)]
+    pub struct MyApp;
+
+    impl App for MyApp {
+        type Event = Event;
+        type Model = usize;
+        type ViewModel = usize;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Trigger => caps.compose.spawn(|context| {
+                    let one = caps.one.clone();
+                    let two = caps.two.clone();
+
+                    async move {
+                        let first = one.one_async(1).await;
+                        let second = two.two_async(first).await;
+
+                        context.update_app(Event::Finished(second));
+                    }
+                }),
+                Event::Finished(result) => *model = result,
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            *model
+        }
+    }
+}
+
+#[test]
+fn app_tester_step_through_chained_task() {
+    use chained_app::{Effect, Event, MyApp};
+
+    let tester = AppTester::<MyApp, Effect>::default();
+    let mut model = 0;
+
+    tester.update_without_running(Event::Trigger, &mut model);
+    assert!(tester.has_pending_tasks());
+
+    // the first step runs the task up to the first request
+    let mut request = tester.step().expect_one_effect().expect_one();
+    assert!(!tester.has_pending_tasks());
+
+    tester
+        .resolve_without_running(&mut request, 10)
+        .expect("request should resolve");
+
+    // the second step continues the task up to the second request
+    let mut request = tester.step().expect_one_effect().expect_two();
+    assert!(!tester.has_pending_tasks());
+
+    tester
+        .resolve_without_running(&mut request, 20)
+        .expect("request should resolve");
+
+    let event = tester.step().expect_one_event();
+    assert_eq!(event, Event::Finished(20));
+
+    tester.step().assert_empty();
+}
+
+#[test]
+fn app_tester_trace_of_chained_task() {
+    use chained_app::{Effect, Event, MyApp};
+
+    let tester = AppTester::<MyApp, Effect>::default();
+    let mut model = 0;
+
+    tester.trace(true);
+
+    let mut request = tester
+        .update(Event::Trigger, &mut model)
+        .expect_one_effect()
+        .expect_one();
+
+    let mut request = tester
+        .resolve(&mut request, 10)
+        .expect("request should resolve")
+        .expect_one_effect()
+        .expect_two();
+
+    let _update = tester.resolve_to_event_then_update(&mut request, 20, &mut model);
+
+    insta::assert_debug_snapshot!(tester.take_trace(), @r###"
+    [
+        Event(
+            "Trigger",
+        ),
+        Effect(
+            "CapabilityOne(Request { operation: OpOne { number: 1 }, resolve: Once })",
+        ),
+        Effect(
+            "CapabilityTwo(Request { operation: OpTwo { number: 10 }, resolve: Once })",
+        ),
+        Event(
+            "Finished(20)",
+        ),
+    ]
+    "###);
+
+    assert!(tester.take_trace().is_empty());
+}
+
+#[test]
+fn app_tester_no_pending_requests_once_resolved() {
+    use chained_app::{Effect, Event, MyApp};
+
+    let tester = AppTester::<MyApp, Effect>::default();
+    let mut model = 0;
+
+    let mut request = tester
+        .update(Event::Trigger, &mut model)
+        .expect_one_effect()
+        .expect_one();
+
+    let mut request = tester
+        .resolve(&mut request, 10)
+        .expect("request should resolve")
+        .expect_one_effect()
+        .expect_two();
+
+    let _update = tester.resolve_to_event_then_update(&mut request, 20, &mut model);
+
+    tester.assert_no_pending();
+}
+
+#[test]
+#[should_panic(expected = "1 effect request(s) were never resolved")]
+fn app_tester_unresolved_request_is_pending() {
+    use chained_app::{Effect, Event, MyApp};
+
+    let tester = AppTester::<MyApp, Effect>::default();
+    let mut model = 0;
+
+    let mut request = tester
+        .update(Event::Trigger, &mut model)
+        .expect_one_effect()
+        .expect_one();
+
+    // the second request, for capability two, is never resolved
+    let _update = tester
+        .resolve(&mut request, 10)
+        .expect("request should resolve");
+
+    tester.assert_no_pending();
+}
+
+mod fallible_view_app {
+    use crux_core::macros::Effect;
+    use crux_core::{App, ViewError};
+
+    pub enum Event {
+        Select(usize),
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        render: crux_core::render::Render<Event>,
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub items: Vec<String>,
+        pub selected: usize,
+    }
+
+    #[derive(Default)]
+    pub struct MyApp;
+
+    impl App for MyApp {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = String;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Select(index) => model.selected = index,
+            }
+            caps.render.render()
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            self.try_view(model).expect("selected item should exist")
+        }
+
+        fn try_view(&self, model: &Self::Model) -> Result<Self::ViewModel, ViewError> {
+            model
+                .items
+                .get(model.selected)
+                .cloned()
+                .ok_or_else(|| ViewError::new(format!("no item at {}", model.selected)))
+        }
+    }
+}
+
+#[test]
+fn app_tester_try_view() {
+    use crux_core::ViewError;
+    use fallible_view_app::{Effect, Event, Model, MyApp};
+
+    let tester = AppTester::<MyApp, Effect>::default();
+    let mut model = Model {
+        items: vec!["first".to_string()],
+        ..Default::default()
+    };
+
+    assert_eq!(tester.try_view(&model), Ok("first".to_string()));
+
+    let _update = tester.update(Event::Select(1), &mut model);
+
+    assert_eq!(tester.try_view(&model), Err(ViewError::new("no item at 1")));
+}
+
+mod delayed_app {
+    use std::time::Duration;
+
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::{Capability, Effect};
+    use crux_core::App;
+    use serde::{Deserialize, Serialize};
+
+    /// A capability which doesn't need the shell, only the passing of time
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct ReminderOperation;
+
+    impl Operation for ReminderOperation {
+        type Output = ();
+    }
+
+    #[derive(Capability)]
+    pub struct Reminder<Ev> {
+        context: CapabilityContext<ReminderOperation, Ev>,
+    }
+
+    impl<Ev> Reminder<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<ReminderOperation, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn remind_after(&self, duration: Duration, event: Ev)
+        where
+            Ev: Send,
+        {
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    context.delay(duration).await;
+                    context.update_app(event);
+                }
+            });
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum Event {
+        Start,
+        Reminded,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        #[effect(skip)]
+        reminder: Reminder<Event>,
+        render: crux_core::render::Render<Event>,
+    }
+
+    #[derive(Default)]
+    pub struct MyApp;
+
+    impl App for MyApp {
+        type Event = Event;
+        type Model = bool;
+        type ViewModel = bool;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Start => caps
+                    .reminder
+                    .remind_after(Duration::from_secs(5), Event::Reminded),
+                Event::Reminded => {
+                    *model = true;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            *model
+        }
+    }
+}
+
+#[test]
+fn app_tester_advance_time() {
+    use delayed_app::{Event, MyApp};
+    use std::time::Duration;
+
+    let tester = AppTester::<MyApp, delayed_app::Effect>::default();
+    let mut model = false;
+
+    let update = tester.update(Event::Start, &mut model);
+    assert!(update.events.is_empty());
+    assert!(update.effects.is_empty());
+
+    let update = tester.advance_time(Duration::from_secs(4));
+    assert!(update.events.is_empty());
+
+    let update = tester.advance_time(Duration::from_secs(1));
+    assert_eq!(update.events, vec![Event::Reminded]);
+
+    for event in update.events {
+        let _update = tester.update(event, &mut model);
+    }
+    assert!(model);
+}
+
+mod effects_match {
+    use crux_core::assert_effects_match;
+    use crux_core::testing::Update;
+
+    #[derive(Debug)]
+    pub enum Effect {
+        Render,
+        Http(String),
+    }
+
+    fn update() -> Update<Effect, ()> {
+        Update {
+            effects: vec![
+                Effect::Render,
+                Effect::Http("https://example.com".to_string()),
+                Effect::Render,
+            ],
+            events: vec![],
+        }
+    }
+
+    #[test]
+    fn assert_effects_match_in_order() {
+        assert_effects_match!(
+            update(),
+            [Effect::Render, Effect::Http(url) if url.ends_with(".com"), Effect::Render]
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "expected: [Effect::Http(_), Effect::Render, Effect::Render]\n  \
+                               actual: [Render, Http(\"https://example.com\"), Render]"
+    )]
+    fn assert_effects_match_out_of_order() {
+        assert_effects_match!(update(), [Effect::Http(_), Effect::Render, Effect::Render]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Effects don't match")]
+    fn assert_effects_match_wrong_count() {
+        assert_effects_match!(update(), [Effect::Render, Effect::Http(_)]);
+    }
+
+    #[test]
+    fn find_effect_among_several() {
+        let update = update();
+
+        let effect = update.find_effect(|effect| matches!(effect, Effect::Http(_)));
+        assert!(matches!(effect, Some(Effect::Http(url)) if url == "https://example.com"));
+
+        assert!(update
+            .find_effect(|effect| matches!(effect, Effect::Http(url) if url.ends_with(".org")))
+            .is_none());
+
+        assert_eq!(update.effect_count(), 3);
+    }
+
+    #[test]
+    fn take_one_effect_among_several() {
+        let mut update = update();
+
+        let effect = update.take_one_effect(|effect| matches!(effect, Effect::Http(_)));
+        assert!(matches!(effect, Some(Effect::Http(url)) if url == "https://example.com"));
+
+        assert_effects_match!(update, [Effect::Render, Effect::Render]);
+    }
+
+    #[test]
+    fn take_one_effect_without_a_match() {
+        let mut update = update();
+
+        assert!(update
+            .take_one_effect(|effect| matches!(effect, Effect::Http(url) if url.ends_with(".org")))
+            .is_none());
+
+        assert_eq!(update.effect_count(), 3);
+    }
+}
+
+mod injected_app {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::Effect;
+    use crux_core::App;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct LogOperation {
+        pub message: String,
+    }
+
+    impl Operation for LogOperation {
+        type Output = ();
+    }
+
+    /// A capability configured by the parent app with a prefix for every message
+    pub struct Log<Ev> {
+        context: CapabilityContext<LogOperation, Ev>,
+        prefix: String,
+    }
+
+    impl<Ev> crux_core::Capability<Ev> for Log<Ev> {
+        type Operation = LogOperation;
+        type MappedSelf<MappedEv> = Log<MappedEv>;
+
+        fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+        where
+            F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+            Ev: 'static,
+            NewEv: 'static + Send,
+        {
+            Log::with_prefix(self.context.map_event(f), self.prefix.clone())
+        }
+    }
+
+    impl<Ev> Log<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<LogOperation, Ev>) -> Self {
+            Self::with_prefix(context, "app".to_string())
+        }
+
+        pub fn with_prefix(context: CapabilityContext<LogOperation, Ev>, prefix: String) -> Self {
+            Self { context, prefix }
+        }
+
+        pub fn log(&self, message: &str) {
+            let message = format!("{}: {message}", self.prefix);
+
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    context.notify_shell(LogOperation { message }).await;
+                }
+            });
+        }
+    }
+
+    pub enum Event {
+        Hello,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub log: Log<Event>,
+    }
+
+    #[derive(Default)]
+    pub struct MyApp;
+
+    impl App for MyApp {
+        type Event = Event;
+        type Model = ();
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, _model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Hello => caps.log.log("hello"),
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+}
+
+#[test]
+fn app_tester_with_injected_capabilities() {
+    use injected_app::{Capabilities, Effect, Event, Log, LogOperation, MyApp};
+
+    let tester = AppTester::with_capabilities(MyApp, |context| Capabilities {
+        log: Log::with_prefix(context.specialize(Effect::Log), "child".to_string()),
+    });
+
+    let request = tester
+        .update(Event::Hello, &mut ())
+        .expect_one_effect()
+        .expect_log();
+
+    assert_eq!(
+        request.operation,
+        LogOperation {
+            message: "child: hello".to_string()
+        }
+    );
+}
+
+#[test]
+fn app_tester_run_pending_discards_effects() {
+    use injected_app::{Effect, Event, MyApp};
+
+    let tester = AppTester::<MyApp, Effect>::default();
+
+    tester.update_without_running(Event::Hello, &mut ());
+    assert!(tester.has_pending_tasks());
+
+    tester.run_pending();
+    assert!(!tester.has_pending_tasks());
+
+    // the log effect was discarded rather than left for the next update
+    tester.step().assert_empty();
+    tester.assert_no_pending();
+}
+
+mod mixed_app {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_core::App;
+
+    use crate::injected_app::Log;
+
+    pub enum Event {
+        Hello,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub log: Log<Event>,
+        pub render: Render<Event>,
+    }
+
+    #[derive(Default)]
+    pub struct MyApp;
+
+    impl App for MyApp {
+        type Event = Event;
+        type Model = ();
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, _model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Hello => {
+                    caps.log.log("hello");
+                    caps.render.render();
+                    caps.log.log("world");
+                }
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+}
+
+#[test]
+fn update_effects_named() {
+    use mixed_app::{Effect, Event, MyApp};
+
+    let tester = AppTester::<MyApp, Effect>::default();
+
+    let update = tester.update(Event::Hello, &mut ());
+
+    let named: Vec<_> = update
+        .effects_named()
+        .map(|(name, effect)| match effect {
+            Effect::Log(request) => (name, request.operation.message.as_str()),
+            Effect::Render(_) => (name, ""),
+        })
+        .collect();
+
+    assert_eq!(
+        named,
+        [("Log", "app: hello"), ("Render", ""), ("Log", "app: world")]
+    );
+}
+
+mod panicking_app {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::{Capability, Effect};
+    use crux_core::App;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct JobOperation;
+
+    impl Operation for JobOperation {
+        type Output = ();
+    }
+
+    /// A capability running a job in a task, which fails for odd job numbers
+    #[derive(Capability)]
+    pub struct Jobs<Ev> {
+        context: CapabilityContext<JobOperation, Ev>,
+    }
+
+    impl<Ev> Jobs<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<JobOperation, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn run(&self, job: usize, event: Ev)
+        where
+            Ev: Send,
+        {
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    if job % 2 == 1 {
+                        panic!("job {job} failed");
+                    }
+                    context.update_app(event);
+                }
+            });
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum Event {
+        Start,
+        Done(usize),
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        #[effect(skip)]
+        jobs: Jobs<Event>,
+        render: crux_core::render::Render<Event>,
+    }
+
+    #[derive(Default)]
+    pub struct MyApp;
+
+    impl App for MyApp {
+        type Event = Event;
+        type Model = ();
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, _model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Start => {
+                    for job in 0..3 {
+                        caps.jobs.run(job, Event::Done(job));
+                    }
+                }
+                Event::Done(_) => caps.render.render(),
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+}
+
+#[test]
+fn app_tester_reports_panicking_task() {
+    use panicking_app::{Event, MyApp};
+
+    let tester = AppTester::<MyApp, panicking_app::Effect>::builder()
+        .catch_panics(true)
+        .build();
+
+    let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        tester.update(Event::Start, &mut ())
+    }))
+    .expect_err("the panicking task should be reported");
+    assert_eq!(
+        panic.downcast_ref::<String>().map(String::as_str),
+        Some("1 capability task(s) panicked:\n  - job 1 failed")
+    );
+
+    // the other jobs still ran, their events are collected by the next step
+    let update = tester.step();
+    assert_eq!(update.events, vec![Event::Done(0), Event::Done(2)]);
+}
+
+mod configured_app {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_core::App;
+
+    pub enum Event {
+        Greet,
+    }
+
+    // Deliberately not `Default`: the capabilities are only ever built from a context
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub render: Render<Event>,
+    }
+
+    #[derive(Default)]
+    pub struct MyApp {
+        pub greeting: String,
+    }
+
+    impl App for MyApp {
+        type Event = Event;
+        type Model = String;
+        type ViewModel = String;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Greet => {
+                    model.clone_from(&self.greeting);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            model.clone()
+        }
+    }
+}
+
+#[test]
+fn app_tester_new_with_capabilities_without_default() {
+    use configured_app::{Effect, Event, MyApp};
+
+    let tester = AppTester::<_, Effect>::new(MyApp {
+        greeting: "Hello".to_string(),
+    });
+    let mut model = String::new();
+
+    let update = tester.update(Event::Greet, &mut model);
+    crux_core::assert_effect!(update, Effect::Render(_));
+
+    assert_eq!(tester.view(&model), "Hello");
+}
+
+#[test]
+fn app_tester_builder_drains_effects() {
+    let tester = AppTester::<app::MyApp, app::Effect>::builder()
+        .channel_capacity(1)
+        .build();
+    let mut model = String::new();
+
+    // the effects are collected after each update, so never exceed the capacity
+    for _ in 0..3 {
+        let update = tester.update(app::Event::Hello, &mut model);
+        crux_core::assert_effect!(update, app::Effect::Render(_));
+        assert_eq!(update.effect_count(), 1);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Channel capacity of 1 exceeded")]
+fn app_tester_builder_channel_capacity_exceeded() {
+    use panicking_app::{Effect, Event, MyApp};
+
+    // jobs 0 and 2 each dispatch an event in the same update
+    let tester = AppTester::<MyApp, Effect>::builder()
+        .channel_capacity(1)
+        .catch_panics(true)
+        .build();
+
+    let _update = tester.update(Event::Start, &mut ());
+}
+
+#[test]
+fn app_tester_builder_clamps_zero_channel_capacity() {
+    let tester = AppTester::<app::MyApp, app::Effect>::builder()
+        .channel_capacity(0)
+        .build();
+    let mut model = String::new();
+
+    let update = tester.update(app::Event::Hello, &mut model);
+    crux_core::assert_effect!(update, app::Effect::Render(_));
+}
+
+#[test]
+fn app_tester_builder_task_order() {
+    use crux_core::testing::TaskOrder;
+    use panicking_app::{Effect, Event, MyApp};
+
+    let finished_jobs = |task_order| {
+        let tester = AppTester::<MyApp, Effect>::builder()
+            .catch_panics(true)
+            .task_order(task_order)
+            .build();
+
+        // job 1 panics, the other jobs dispatch their events in the order they ran
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            tester.update(Event::Start, &mut ())
+        }));
+        tester.step().events
+    };
+
+    assert_eq!(
+        finished_jobs(TaskOrder::Fifo),
+        vec![Event::Done(0), Event::Done(2)]
+    );
+    assert_eq!(
+        finished_jobs(TaskOrder::Lifo),
+        vec![Event::Done(2), Event::Done(0)]
+    );
+}
+
+#[test]
+#[should_panic(expected = "job 1 failed")]
+fn app_tester_propagates_panics_by_default() {
+    use panicking_app::{Effect, Event, MyApp};
+
+    let tester = AppTester::<MyApp, Effect>::default();
+
+    let _update = tester.update(Event::Start, &mut ());
+}
+
+mod time_app {
+    use crux_core::macros::Effect;
+    use crux_core::App;
+    use crux_time::{Time, TimeResponse};
+
+    #[derive(Debug, PartialEq)]
+    pub enum Event {
+        Get,
+        Set(TimeResponse),
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub time: Time<Event>,
+        pub render: crux_core::render::Render<Event>,
+    }
+
+    #[derive(Default)]
+    pub struct MyApp;
+
+    impl App for MyApp {
+        type Event = Event;
+        type Model = Option<u64>;
+        type ViewModel = Option<u64>;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Get => caps.time.now(Event::Set),
+                Event::Set(TimeResponse::Now(instant)) => {
+                    *model = Some(instant.seconds);
+                    caps.render.render();
+                }
+                Event::Set(_) => {}
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            *model
+        }
+    }
+}
+
+#[test]
+fn update_resolve_one() {
+    use crux_time::{Instant, TimeResponse};
+    use time_app::{Effect, Event, MyApp};
+
+    let tester = AppTester::<MyApp, Effect>::default();
+    let mut model = None;
+    let now = TimeResponse::Now(Instant::new(1_700_000_000, 0).unwrap());
+
+    let event = tester
+        .update(Event::Get, &mut model)
+        .resolve_one(&tester, Effect::expect_time, now)
+        .expect_one_event();
+    assert_eq!(event, Event::Set(now));
+
+    let _update = tester.update(event, &mut model);
+    assert_eq!(tester.view(&model), Some(1_700_000_000));
+}
+
+#[test]
+#[should_panic(expected = "Expected one effect but found 0 effect(s) and 1 event(s)")]
+fn update_resolve_one_without_an_effect() {
+    use crux_time::{Instant, TimeResponse};
+    use time_app::{Effect, Event, MyApp};
+
+    let tester = AppTester::<MyApp, Effect>::default();
+    let mut model = None;
+    let now = TimeResponse::Now(Instant::new(1_700_000_000, 0).unwrap());
+
+    let _update = tester
+        .update(Event::Get, &mut model)
+        .resolve_one(&tester, Effect::expect_time, now)
+        .resolve_one(&tester, Effect::expect_time, now);
+}
+
+#[test]
+fn scenario_two_step_flow() {
+    use crux_time::{Instant, TimeResponse};
+    use time_app::{Effect, Event, MyApp};
+
+    let tester = AppTester::<MyApp, Effect>::default();
+    let mut model = None;
+    let now = TimeResponse::Now(Instant::new(1_700_000_000, 0).unwrap());
+
+    let effect = tester
+        .when(Event::Get, &mut model)
+        .then_resolve(Effect::expect_time, now)
+        .then_update()
+        .expect_effect(|effect| matches!(effect, Effect::Render(_)));
+    assert!(effect.is_render());
+
+    assert_eq!(tester.view(&model), Some(1_700_000_000));
+}
+
+#[test]
+#[should_panic(expected = "Expected a matching effect but none of 1 effect(s) matched")]
+fn scenario_expect_effect_without_a_match() {
+    use time_app::{Effect, Event, MyApp};
+
+    let tester = AppTester::<MyApp, Effect>::default();
+    let mut model = None;
+
+    tester
+        .when(Event::Get, &mut model)
+        .expect_effect(|effect| matches!(effect, Effect::Render(_)));
+}
+
+mod recording_instrumentation {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use crux_core::testing::{Instrumentation, UpdateMeasurement};
+
+    #[derive(Default, Clone)]
+    pub struct Recording {
+        pub updates: Arc<Mutex<Vec<UpdateMeasurement>>>,
+        pub views: Arc<Mutex<Vec<Duration>>>,
+    }
+
+    impl<Ev: std::fmt::Debug> Instrumentation<Ev> for Recording {
+        fn event_name(&self, event: &Ev) -> String {
+            let name = format!("{event:?}");
+            name.split('(').next().unwrap_or_default().to_string()
+        }
+
+        fn update(&self, measurement: UpdateMeasurement) {
+            self.updates.lock().unwrap().push(measurement);
+        }
+
+        fn view(&self, elapsed: Duration) {
+            self.views.lock().unwrap().push(elapsed);
+        }
+    }
+}
+
+#[test]
+fn app_tester_instrumentation() {
+    use crux_time::{Instant, TimeResponse};
+    use recording_instrumentation::Recording;
+    use time_app::{Effect, Event, MyApp};
+
+    let tester = AppTester::<MyApp, Effect>::default();
+    let recording = Recording::default();
+    tester.instrument(recording.clone());
+
+    let mut model = None;
+    let now = TimeResponse::Now(Instant::new(1_700_000_000, 0).unwrap());
+
+    let event = tester
+        .update(Event::Get, &mut model)
+        .resolve_one(&tester, Effect::expect_time, now)
+        .expect_one_event();
+    let _update = tester.update(event, &mut model);
+    let _view = tester.view(&model);
+
+    let updates: Vec<_> = recording
+        .updates
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|measurement| {
+            (
+                measurement.event.clone(),
+                measurement.effects,
+                measurement.events,
+            )
+        })
+        .collect();
+    assert_eq!(
+        updates,
+        vec![("Get".to_string(), 1, 0), ("Set".to_string(), 1, 0)]
+    );
+    assert_eq!(recording.views.lock().unwrap().len(), 1);
+}
+
+mod counter_app {
+    use crux_core::macros::Effect;
+    use crux_core::App;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub enum Event {
+        Increment,
+        Decrement,
+        Reset,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub render: crux_core::render::Render<Event>,
+    }
+
+    #[derive(Default)]
+    pub struct MyApp;
+
+    impl App for MyApp {
+        type Event = Event;
+        type Model = i32;
+        type ViewModel = String;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Increment => *model += 1,
+                Event::Decrement => *model -= 1,
+                Event::Reset => *model = 0,
+            }
+            caps.render.render();
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            format!("Count is: {model}")
+        }
+    }
+}
+
+#[test]
+fn app_tester_replay_recorded_events() {
+    use counter_app::{Effect, Event, MyApp};
+
+    let tester = AppTester::<MyApp, Effect>::default();
+    let mut model = 5;
+
+    let recorded = r#"["Reset", "Increment", "Increment"]"#;
+    let events: Vec<Event> = serde_json::from_str(recorded).unwrap();
+
+    let update = tester.replay(events, &mut model);
+
+    assert_eq!(update.effect_count(), 3);
+    assert_eq!(update.event_count(), 0);
+    assert_eq!(tester.view(&model), "Count is: 2");
+}
+
+mod weather_app {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::{Capability, Effect};
+    use crux_core::App;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct ForecastRequest {
+        pub city: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct Forecast {
+        pub temperature: i32,
+        pub summary: String,
+    }
+
+    impl Operation for ForecastRequest {
+        type Output = Forecast;
+    }
+
+    #[derive(Capability)]
+    pub struct Weather<Ev> {
+        context: CapabilityContext<ForecastRequest, Ev>,
+    }
+
+    impl<Ev> Weather<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<ForecastRequest, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn forecast<F>(&self, city: &str, callback: F)
+        where
+            F: FnOnce(Forecast) -> Ev + Send + 'static,
+        {
+            let city = city.to_string();
+
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    let forecast = context.request_from_shell(ForecastRequest { city }).await;
+                    context.update_app(callback(forecast));
+                }
+            });
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    pub enum Event {
+        Get,
+        #[serde(skip)]
+        Forecast(Forecast),
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub weather: Weather<Event>,
+    }
+
+    #[derive(Default)]
+    pub struct MyApp;
+
+    impl App for MyApp {
+        type Event = Event;
+        type Model = ();
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, _model: &mut Self::Model, caps: &Self::Capabilities) {
+            if let Event::Get = event {
+                caps.weather.forecast("London", Event::Forecast);
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+}
+
+#[test]
+fn app_tester_resolve_serialized() {
+    use weather_app::{Event, Forecast, MyApp};
+
+    let tester = AppTester::<MyApp, _>::default();
+
+    let mut request = tester
+        .update(Event::Get, &mut ())
+        .expect_one_effect()
+        .expect_weather();
+
+    // what the shell sends, without using the core's types
+    let event = tester
+        .resolve_serialized(&mut request, (12_i32, "Drizzle"))
+        .unwrap()
+        .expect_one_event();
+
+    assert_eq!(
+        event,
+        Event::Forecast(Forecast {
+            temperature: 12,
+            summary: "Drizzle".to_string()
+        })
+    );
+}
+
+#[test]
+fn app_tester_resolve_serialized_with_mismatched_output() {
+    use weather_app::{Event, MyApp};
+
+    let tester = AppTester::<MyApp, _>::default();
+
+    let mut request = tester
+        .update(Event::Get, &mut ())
+        .expect_one_effect()
+        .expect_weather();
+
+    // the summary is missing
+    let error = tester
+        .resolve_serialized(&mut request, 12_i32)
+        .unwrap_err()
+        .downcast::<crux_core::ResolveError>()
+        .unwrap();
+
+    assert!(matches!(error, crux_core::ResolveError::Deserialization(_)));
+    assert!(request.expects_response());
+}
+
+mod wrapping_app {
+    use crux_core::compose::Compose;
+    use crux_core::macros::Effect;
+    use crux_core::App;
+    use serde::{Deserialize, Serialize};
+
+    pub const MAX: u8 = 5;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub enum Event {
+        Increment,
+        Decrement,
+        Reset,
+        #[serde(skip)]
+        Wrapped,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        #[effect(skip)]
+        pub compose: Compose<Event>,
+        pub render: crux_core::render::Render<Event>,
+    }
+
+    #[derive(Debug, Default, Clone, PartialEq)]
+    pub struct Model {
+        pub count: u8,
+        pub wraps: usize,
+    }
+
+    #[derive(Default)]
+    pub struct MyApp;
+
+    impl App for MyApp {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = String;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Increment if model.count == MAX => caps
+                    .compose
+                    .spawn(|context| async move { context.update_app(Event::Wrapped) }),
+                Event::Increment => model.count += 1,
+                Event::Decrement => model.count = model.count.saturating_sub(1),
+                Event::Reset => model.count = 0,
+                Event::Wrapped => {
+                    model.count = 0;
+                    model.wraps += 1;
+                }
+            }
+            caps.render.render();
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            format!("Count is: {}", model.count)
+        }
+    }
+}
+
+#[test]
+fn app_tester_run_threads_model() {
+    use wrapping_app::{Effect, Event, Model, MyApp};
+
+    let tester = AppTester::<MyApp, Effect>::default();
+
+    let events = std::iter::repeat_with(|| Event::Increment).take(7);
+    let (model, update) = tester.run(events, Model::default());
+
+    // the wrap is run straight away, and renders too
+    assert_eq!(model, Model { count: 1, wraps: 1 });
+    assert_eq!(update.effect_count(), 8);
+    assert_eq!(update.event_count(), 0);
+}
+
+#[test]
+fn app_tester_run_random_events_keep_model_valid() {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use wrapping_app::{Effect, Event, Model, MyApp, MAX};
+
+    let tester = AppTester::<MyApp, Effect>::default();
+    let mut rng = StdRng::seed_from_u64(655);
+
+    for _ in 0..200 {
+        let model = Model {
+            count: rng.gen_range(0..=MAX),
+            wraps: 0,
+        };
+        let length = rng.gen_range(0..50);
+        let events: Vec<Event> = (0..length)
+            .map(|_| match rng.gen_range(0..3) {
+                0 => Event::Increment,
+                1 => Event::Decrement,
+                _ => Event::Reset,
+            })
+            .collect();
+
+        let (model, update) = tester.run(events, model);
+
+        assert!(model.count <= MAX, "invalid model: {model:?}");
+        assert_eq!(update.effect_count(), length + model.wraps);
+    }
+}
@@ -36,7 +36,7 @@ mod shared {
 #[cfg(feature = "typegen")]
 mod test {
     use super::shared::{App, Event};
-    use crux_core::typegen::TypeGen;
+    use crux_core::typegen::{CsOptions, TypeGen};
     use uuid::Uuid;
 
     // FIXME this test is quite slow
@@ -62,6 +62,93 @@ mod test {
             .expect("typescript type gen failed");
     }
 
+    #[test]
+    fn generate_dart_types() {
+        let mut gen = TypeGen::new();
+
+        let sample_events = vec![Event::SendUuid(Uuid::new_v4())];
+        gen.register_type_with_samples(sample_events).unwrap();
+
+        gen.register_app::<App>().unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        let output_root = temp.join("crux_core_typegen_test");
+
+        gen.dart("shared_types", output_root.join("dart"))
+            .expect("dart type gen failed");
+
+        let package = output_root.join("dart").join("shared_types");
+        assert!(package.join("pubspec.yaml").exists());
+        assert!(package.join("lib/src/requests.dart").exists());
+
+        let library = std::fs::read_to_string(package.join("lib/shared_types.dart")).unwrap();
+        assert!(library.contains("export 'src/requests.dart';"));
+    }
+
+    #[test]
+    fn generate_csharp_types() {
+        let mut gen = TypeGen::new();
+
+        let sample_events = vec![Event::SendUuid(Uuid::new_v4())];
+        gen.register_type_with_samples(sample_events).unwrap();
+
+        gen.register_app::<App>().unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        let output_root = temp.join("crux_core_typegen_test");
+
+        gen.csharp("Example.SharedTypes", output_root.join("csharp"))
+            .expect("csharp type gen failed");
+
+        let csharp = output_root.join("csharp");
+        assert!(csharp.join("Serde/Serde.csproj").exists());
+        assert!(csharp.join("Bincode/Bincode.csproj").exists());
+
+        let project = csharp.join("Example/SharedTypes");
+        assert!(project.join("Example.SharedTypes.csproj").exists());
+        assert!(project.join("Event.cs").exists());
+
+        let requests = std::fs::read_to_string(project.join("Requests.cs")).unwrap();
+        assert!(requests.contains("namespace Example.SharedTypes {"));
+    }
+
+    #[test]
+    fn generate_csharp_types_with_guids() {
+        let mut gen = TypeGen::new();
+
+        let sample_events = vec![Event::SendUuid(Uuid::new_v4())];
+        gen.register_type_with_samples(sample_events).unwrap();
+
+        gen.register_app::<App>().unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        let output_root = temp.join("crux_core_typegen_test");
+
+        gen.csharp_with_options(
+            "SharedTypes",
+            output_root.join("csharp"),
+            CsOptions {
+                uuids: vec!["Event::SendUuid".to_string()],
+            },
+        )
+        .expect("csharp type gen failed");
+
+        let csharp = output_root.join("csharp");
+        let runtime = std::fs::read_to_string(csharp.join("Serde/GuidExtensions.cs")).unwrap();
+        assert!(runtime
+            .contains("public static void Serialize(this Guid value, ISerializer serializer)"));
+        assert!(runtime.contains("public static Guid Deserialize(IDeserializer deserializer)"));
+
+        let event = std::fs::read_to_string(csharp.join("SharedTypes/Event.cs")).unwrap();
+        assert!(event.contains("using Serde;"));
+        assert!(event.contains("public System.Guid value;"));
+        assert!(event.contains("public SendUuid(System.Guid _value) {"));
+        assert!(!event.contains("if (_value == null)"));
+        assert!(event.contains("value.Serialize(serializer);"));
+        assert!(event.contains("Serde.GuidExtensions.Deserialize(deserializer)"));
+        assert!(!event.contains("Serde.ValueArray<byte>"));
+    }
+
     // TODO: instead of using the Render capability here, it would be better to also test against a custom
     // capability that has an output type
     #[test]
@@ -74,14 +161,7 @@ mod test {
         gen.register_app::<App>()
             .expect("Should register types in App");
 
-        let registry = match gen.state {
-            crux_core::typegen::State::Registering(tracer, _) => {
-                tracer.registry().expect("Should get registry")
-            }
-            crux_core::typegen::State::Generating(_) => {
-                panic!("Expected to still be in registering stage")
-            }
-        };
+        let registry = gen.registry_snapshot().expect("Should get registry");
 
         dbg!(&registry);
 
@@ -91,4 +171,417 @@ mod test {
         assert!(registry.contains_key("Effect"));
         assert!(registry.contains_key("RenderOperation"));
     }
+
+    #[test]
+    fn registry_snapshot_finishes_registration() {
+        let mut gen = TypeGen::new();
+
+        gen.register_samples(vec![Event::SendUuid(Uuid::new_v4())])
+            .unwrap();
+        gen.register_app::<App>().unwrap();
+
+        let registry = gen.registry_snapshot().unwrap();
+        assert!(registry.contains_key("Event"));
+        assert!(registry.contains_key("ViewModel"));
+
+        // taking another snapshot returns the same registry
+        assert_eq!(gen.registry_snapshot().unwrap(), registry);
+
+        assert!(matches!(
+            gen.register_type::<Event>(),
+            Err(crux_core::typegen::TypeGenError::LateRegistration)
+        ));
+    }
+}
+
+#[cfg(feature = "typegen")]
+mod manifest {
+    use crux_core::macros::{Effect, Export};
+    use crux_core::render::Render;
+    use crux_core::typegen::TypeGen;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Event {
+        Get,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = ();
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+        fn update(&self, _event: Event, _model: &mut Self::Model, _caps: &Capabilities) {}
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+
+    #[derive(Effect, Export)]
+    pub struct Capabilities {
+        #[allow(dead_code)]
+        pub http: crux_http::Http<Event>,
+        #[allow(dead_code)]
+        pub render: Render<Event>,
+    }
+
+    #[test]
+    fn generate_manifest() {
+        let mut gen = TypeGen::new();
+
+        // registered by crux_http itself when its `typegen` feature is enabled
+        gen.register_type::<crux_http::HttpError>().unwrap();
+
+        gen.register_app::<App>().unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        let path = temp.join("manifest.json");
+
+        gen.manifest(&path).expect("manifest generation failed");
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+
+        assert_eq!(manifest["effect"], "Effect");
+        assert_eq!(
+            manifest["effects"],
+            serde_json::json!([
+                {
+                    "name": "Http",
+                    "operation": { "TYPENAME": "HttpRequest" },
+                    "output": { "TYPENAME": "HttpResult" },
+                },
+                {
+                    "name": "Render",
+                    "operation": { "TYPENAME": "RenderOperation" },
+                    "output": "UNIT",
+                },
+            ])
+        );
+    }
+}
+
+#[cfg(feature = "typegen")]
+mod manifest_with_samples {
+    use crux_core::capability::{Capability, CapabilityContext, Operation};
+    use crux_core::macros::{Effect, Export};
+    use crux_core::typegen::TypeGen;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+    pub struct ReadMeter;
+
+    #[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+    pub struct Reading(u8);
+
+    // zero is not a valid reading, so the output can't be traced without a sample
+    impl<'de> Deserialize<'de> for Reading {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            #[derive(Deserialize)]
+            #[serde(rename = "Reading")]
+            struct Raw(u8);
+
+            let Raw(value) = Raw::deserialize(deserializer)?;
+            if value == 0 {
+                return Err(serde::de::Error::custom("reading must not be zero"));
+            }
+            Ok(Reading(value))
+        }
+    }
+
+    impl Operation for ReadMeter {
+        type Output = Reading;
+    }
+
+    pub struct Meter<Ev> {
+        context: CapabilityContext<ReadMeter, Ev>,
+    }
+
+    impl<Ev> Meter<Ev> {
+        pub fn new(context: CapabilityContext<ReadMeter, Ev>) -> Self {
+            Self { context }
+        }
+    }
+
+    impl<Ev> Capability<Ev> for Meter<Ev> {
+        type Operation = ReadMeter;
+        type MappedSelf<MappedEv> = Meter<MappedEv>;
+
+        fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+        where
+            F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+            Ev: 'static,
+            NewEv: 'static + Send,
+        {
+            Meter::new(self.context.map_event(f))
+        }
+
+        fn register_types(generator: &mut TypeGen) -> crux_core::typegen::Result {
+            generator.register_type::<ReadMeter>()?;
+            generator.register_type_with_samples(vec![Reading(1)])?;
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Event {
+        Read,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = ();
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+        fn update(&self, _event: Event, _model: &mut Self::Model, _caps: &Capabilities) {}
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+
+    #[derive(Effect, Export)]
+    pub struct Capabilities {
+        #[allow(dead_code)]
+        pub meter: Meter<Event>,
+    }
+
+    #[test]
+    fn generate_manifest_for_output_needing_samples() {
+        let mut gen = TypeGen::new();
+
+        gen.register_app::<App>().unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        let path = temp.join("manifest.json");
+
+        gen.manifest(&path).expect("manifest generation failed");
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+
+        assert_eq!(
+            manifest["effects"],
+            serde_json::json!([
+                {
+                    "name": "Meter",
+                    "operation": { "TYPENAME": "ReadMeter" },
+                    "output": { "TYPENAME": "Reading" },
+                },
+            ])
+        );
+    }
+}
+
+#[cfg(feature = "typegen")]
+mod dto {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Customer {
+        pub name: String,
+        pub address: Address,
+        pub status: Status,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Address {
+        pub city: String,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Status {
+        Active,
+        Suspended { reason: String },
+    }
+}
+
+#[cfg(feature = "typegen")]
+mod dto_test {
+    use super::dto::{Address, Customer, Status};
+    use crux_core::typegen::TypeGen;
+
+    #[test]
+    fn generate_swift_types_without_app() {
+        let mut gen = TypeGen::from_types::<(Customer, Address, Status)>().unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        let output_root = temp.join("crux_core_typegen_test");
+
+        gen.swift("Dto", output_root.join("swift"))
+            .expect("swift type gen failed");
+
+        let sources = output_root.join("swift/Dto/Sources/Dto");
+        let types = std::fs::read_to_string(sources.join("Dto.swift")).unwrap();
+        assert!(types.contains("public struct Customer: Hashable"));
+        assert!(types.contains("public struct Address: Hashable"));
+        assert!(types.contains("public enum Status: Hashable"));
+
+        // there are no bridge requests without an app
+        assert!(!sources.join("Requests.swift").exists());
+    }
+}
+
+#[cfg(feature = "typegen")]
+mod composed {
+    mod child {
+        use crux_core::macros::{Effect, Export};
+        use crux_core::render::Render;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Default)]
+        pub struct Child;
+
+        #[derive(Serialize, Deserialize)]
+        #[serde(rename = "ChildEvent")]
+        pub enum Event {
+            Increment,
+        }
+
+        impl crux_core::App for Child {
+            type Event = Event;
+            type Model = ();
+            type ViewModel = ();
+            type Capabilities = Capabilities;
+            fn update(&self, _event: Event, _model: &mut Self::Model, _caps: &Capabilities) {}
+            fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+        }
+
+        impl crux_core::typegen::ComposedApp for Child {
+            type Children = ();
+        }
+
+        #[derive(Effect, Export)]
+        #[effect(name = "ChildEffect")]
+        pub struct Capabilities {
+            #[allow(dead_code)]
+            pub render: Render<Event>,
+        }
+    }
+
+    mod parent {
+        use crux_core::bridge::ResolveSerialized;
+        use crux_core::capability::ProtoContext;
+        use crux_core::render::{Render, RenderOperation};
+        use crux_core::typegen::{ComposedApp, Export, TypeGen};
+        use crux_core::{Request, WithContext};
+        use serde::{Deserialize, Serialize};
+
+        use super::child;
+
+        #[derive(Default)]
+        pub struct Parent;
+
+        #[derive(Serialize, Deserialize)]
+        pub enum Event {
+            Reset,
+            Child(child::Event),
+        }
+
+        impl crux_core::App for Parent {
+            type Event = Event;
+            type Model = ();
+            type ViewModel = ();
+            type Capabilities = Capabilities;
+            fn update(&self, _event: Event, _model: &mut Self::Model, _caps: &Capabilities) {}
+            fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+        }
+
+        impl ComposedApp for Parent {
+            type Children = (child::Child,);
+        }
+
+        #[derive(Debug)]
+        pub enum Effect {
+            Render(Request<RenderOperation>),
+            Child(child::ChildEffect),
+        }
+
+        #[derive(Serialize, Deserialize)]
+        #[serde(rename = "Effect")]
+        pub enum EffectFfi {
+            Render(RenderOperation),
+            Child(child::ChildEffectFfi),
+        }
+
+        impl crux_core::Effect for Effect {
+            type Ffi = EffectFfi;
+
+            fn serialize(self) -> (Self::Ffi, ResolveSerialized) {
+                match self {
+                    Effect::Render(request) => request.serialize(EffectFfi::Render),
+                    Effect::Child(effect) => {
+                        let (ffi, resolve) = effect.serialize();
+                        (EffectFfi::Child(ffi), resolve)
+                    }
+                }
+            }
+        }
+
+        #[allow(dead_code)]
+        pub struct Capabilities {
+            pub render: Render<Event>,
+            pub child: child::Capabilities,
+        }
+
+        impl WithContext<Event, Effect> for Capabilities {
+            fn new_with_context(context: ProtoContext<Effect, Event>) -> Capabilities {
+                Capabilities {
+                    render: Render::new(context.specialize(Effect::Render)),
+                    child: child::Capabilities::new_with_context(
+                        context.lift(Effect::Child, Event::Child),
+                    ),
+                }
+            }
+        }
+
+        impl Export for Capabilities {
+            fn register_types(generator: &mut TypeGen) -> crux_core::typegen::Result {
+                generator.register_operation::<RenderOperation>()?;
+                generator.register_effect::<EffectFfi>()
+            }
+        }
+    }
+
+    use crux_core::typegen::{ContainerFormat, Format, State, TypeGen};
+
+    #[test]
+    fn register_composed_app() {
+        let mut gen = TypeGen::new();
+
+        gen.register_composed_app::<parent::Parent>()
+            .expect("Should register types of the parent and the child");
+
+        gen.validate()
+            .expect("Should register all the referenced types");
+
+        let State::Generating(registry) = &gen.state else {
+            panic!("Expected to be in generating stage");
+        };
+
+        for name in [
+            "Event",
+            "ChildEvent",
+            "Effect",
+            "ChildEffect",
+            "RenderOperation",
+        ] {
+            assert!(registry.contains_key(name), "{name} is not registered");
+        }
+
+        // the requests carry the parent's effect
+        let Some(ContainerFormat::Struct(fields)) = registry.get("Request") else {
+            panic!("Request is not registered");
+        };
+        let effect = fields.iter().find(|field| field.name == "effect").unwrap();
+        assert_eq!(effect.value, Format::TypeName("Effect".to_string()));
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        gen.swift("SharedTypes", temp.join("swift"))
+            .expect("swift type gen failed");
+    }
 }
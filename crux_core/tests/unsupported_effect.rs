@@ -0,0 +1,261 @@
+//! Tests for requests the shell doesn't support, e.g. because it's older than the core
+
+mod app {
+    use crux_core::capability::{CapabilityContext, Operation};
+    use crux_core::macros::{Capability, Effect};
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    pub struct Scan {
+        pub barcode: bool,
+    }
+
+    impl Operation for Scan {
+        type Output = String;
+
+        // the shell needs to support scanning barcodes, added in version 2
+        const VERSION: u32 = 2;
+    }
+
+    #[derive(Capability)]
+    pub struct Scanner<Ev> {
+        context: CapabilityContext<Scan, Ev>,
+    }
+
+    impl<Ev> Scanner<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<Scan, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn scan<F>(&self, callback: F)
+        where
+            F: FnOnce(String) -> Ev + Send + 'static,
+        {
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    let code = context.request_from_shell(Scan { barcode: true }).await;
+                    context.update_app(callback(code));
+                }
+            });
+        }
+
+        pub fn try_scan<F>(&self, callback: F)
+        where
+            F: FnOnce(Result<String, String>) -> Ev + Send + 'static,
+        {
+            self.context.spawn({
+                let context = self.context.clone();
+                async move {
+                    let code = context
+                        .request_from_shell_fallible(Scan { barcode: true })
+                        .await;
+                    context.update_app(callback(code.map_err(|error| error.message)));
+                }
+            });
+        }
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Scan,
+        TryScan,
+        #[serde(skip)]
+        Scanned(Result<String, String>),
+        #[serde(skip)]
+        Unsupported(String),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub code: Option<String>,
+        pub error: Option<String>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub struct ViewModel {
+        pub code: Option<String>,
+        pub error: Option<String>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Scan => caps.scanner.scan(|code| Event::Scanned(Ok(code))),
+                Event::TryScan => caps.scanner.try_scan(Event::Scanned),
+                Event::Scanned(Ok(code)) => {
+                    model.code = Some(code);
+                    caps.render.render();
+                }
+                Event::Scanned(Err(error)) | Event::Unsupported(error) => {
+                    model.error = Some(error);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Model) -> ViewModel {
+            ViewModel {
+                code: model.code.clone(),
+                error: model.error.clone(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub scanner: Scanner<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use bincode::Options;
+    use crux_core::{
+        bridge::{Bridge, ProtocolVersion, Request, UnsupportedEffect},
+        Core,
+    };
+
+    use crate::app::{App, Effect, EffectFfi, Event, ViewModel};
+
+    // the version of the Scanner effect the shell was built against
+    const SHELL_SCANNER_VERSION: u32 = 1;
+
+    fn options() -> impl Options {
+        bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+    }
+
+    // the shell speaks the current protocol, so requests carry all their fields
+    fn handshake(bridge: &Bridge<Effect, App>) {
+        let version = options().serialize(&ProtocolVersion::CURRENT).unwrap();
+        bridge.handshake(&version).unwrap();
+    }
+
+    fn send(bridge: &Bridge<Effect, App>, event: &Event) -> Vec<Request<EffectFfi>> {
+        let event = options().serialize(event).unwrap();
+        options()
+            .deserialize(&bridge.process_event(&event))
+            .unwrap()
+    }
+
+    fn view(bridge: &Bridge<Effect, App>) -> ViewModel {
+        options().deserialize(&bridge.view()).unwrap()
+    }
+
+    #[test]
+    fn requests_carry_the_operation_version() {
+        let bridge = Bridge::<Effect, App>::new(Core::new());
+        handshake(&bridge);
+
+        let [request] = &send(&bridge, &Event::Scan)[..] else {
+            panic!("expected a single request");
+        };
+        assert!(matches!(request.effect, EffectFfi::Scanner(_)));
+        assert_eq!(request.version, 2);
+
+        let requests: Vec<Request<EffectFfi>> = options()
+            .deserialize(
+                &bridge
+                    .handle_response(request.id.0, &options().serialize("4006381333931").unwrap()),
+            )
+            .unwrap();
+
+        let [render] = &requests[..] else {
+            panic!("expected a single request");
+        };
+        assert!(matches!(render.effect, EffectFfi::Render(_)));
+        assert_eq!(render.version, 1);
+    }
+
+    #[test]
+    fn unsupported_request_is_passed_to_the_app() {
+        let bridge = Bridge::<Effect, App>::new(Core::new()).with_unsupported_effect_handler(
+            |unsupported: UnsupportedEffect| Event::Unsupported(unsupported.to_string()),
+        );
+        handshake(&bridge);
+
+        let [request] = &send(&bridge, &Event::Scan)[..] else {
+            panic!("expected a single request");
+        };
+
+        // the shell only knows an older version of the effect
+        assert!(request.version > SHELL_SCANNER_VERSION);
+
+        let requests: Vec<Request<EffectFfi>> = options()
+            .deserialize(&bridge.handle_unsupported(request.id.0))
+            .unwrap();
+
+        let [render] = &requests[..] else {
+            panic!("expected a single request");
+        };
+        assert!(matches!(render.effect, EffectFfi::Render(_)));
+
+        assert_eq!(
+            view(&bridge),
+            ViewModel {
+                code: None,
+                error: Some(format!(
+                    "Request {} for version 2 of the Scanner effect is not supported by the shell",
+                    request.id.0
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn unsupported_fallible_request_fails() {
+        let bridge = Bridge::<Effect, App>::new(Core::new()).with_unsupported_effect_handler(
+            |_: UnsupportedEffect| Event::Unsupported("handler called".to_string()),
+        );
+        handshake(&bridge);
+
+        let [request] = &send(&bridge, &Event::TryScan)[..] else {
+            panic!("expected a single request");
+        };
+
+        let _ = bridge.handle_unsupported(request.id.0);
+
+        let error = view(&bridge).error.unwrap();
+        assert!(
+            error.ends_with("of the Scanner effect is not supported by the shell"),
+            "unexpected error: {error}"
+        );
+    }
+
+    #[test]
+    fn unsupported_request_is_dropped_without_handler() {
+        let bridge = Bridge::<Effect, App>::new(Core::new());
+        handshake(&bridge);
+
+        let [request] = &send(&bridge, &Event::Scan)[..] else {
+            panic!("expected a single request");
+        };
+
+        let requests: Vec<Request<EffectFfi>> = options()
+            .deserialize(&bridge.handle_unsupported(request.id.0))
+            .unwrap();
+
+        assert!(requests.is_empty());
+        assert_eq!(
+            view(&bridge),
+            ViewModel {
+                code: None,
+                error: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,191 @@
+//! Tests for diffing view models, to send the shell only what changed
+
+mod app {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub enum Event {
+        Rename(String),
+        Add(String),
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+    pub struct ViewModel {
+        pub title: String,
+        pub items: Vec<String>,
+        pub subtitle: Option<String>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = ViewModel;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut ViewModel, caps: &Capabilities) {
+            match event {
+                Event::Rename(title) => model.title = title,
+                Event::Add(item) => model.items.push(item),
+            }
+            caps.render.render();
+        }
+
+        fn view(&self, model: &ViewModel) -> ViewModel {
+            model.clone()
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use std::collections::BTreeMap;
+
+    use bincode::Options;
+    use crux_core::{
+        bridge::Bridge,
+        diff::{Diffable, Patch, PatchOperation},
+        Core,
+    };
+
+    use crate::app::{App, Effect, Event, ViewModel};
+
+    fn view_model() -> ViewModel {
+        ViewModel {
+            title: "Groceries".to_string(),
+            items: vec!["milk".to_string(), "eggs".to_string()],
+            subtitle: None,
+        }
+    }
+
+    #[test]
+    fn diff_contains_only_changed_fields() {
+        let previous = view_model();
+        let current = ViewModel {
+            items: vec!["milk".to_string(), "bread".to_string()],
+            subtitle: Some("for Saturday".to_string()),
+            ..previous.clone()
+        };
+
+        let patch = current.diff(&previous);
+
+        assert_eq!(
+            patch.operations,
+            vec![
+                PatchOperation::Set {
+                    path: "/items/1".to_string(),
+                    value: r#""bread""#.to_string()
+                },
+                PatchOperation::Set {
+                    path: "/subtitle".to_string(),
+                    value: r#""for Saturday""#.to_string()
+                },
+            ]
+        );
+
+        let mut target = serde_json::to_value(&previous).unwrap();
+        patch.apply(&mut target);
+        assert_eq!(target, serde_json::to_value(&current).unwrap());
+    }
+
+    #[test]
+    fn diff_of_unchanged_value_is_empty() {
+        assert!(view_model().diff(&view_model()).is_empty());
+    }
+
+    #[test]
+    fn resized_array_is_replaced() {
+        let previous = view_model();
+        let mut current = previous.clone();
+        current.items.push("flour".to_string());
+
+        assert_eq!(
+            current.diff(&previous).operations,
+            vec![PatchOperation::Set {
+                path: "/items".to_string(),
+                value: r#"["milk","eggs","flour"]"#.to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn removed_map_entries_are_removed() {
+        let previous = BTreeMap::from([("a/b", 1), ("c", 2)]);
+        let current = BTreeMap::from([("c", 3), ("d", 4)]);
+
+        let patch = current.diff(&previous);
+        assert_eq!(
+            patch.operations,
+            vec![
+                PatchOperation::Remove {
+                    path: "/a~1b".to_string()
+                },
+                PatchOperation::Set {
+                    path: "/c".to_string(),
+                    value: "3".to_string()
+                },
+                PatchOperation::Set {
+                    path: "/d".to_string(),
+                    value: "4".to_string()
+                },
+            ]
+        );
+
+        let mut target = serde_json::to_value(&previous).unwrap();
+        patch.apply(&mut target);
+        assert_eq!(target, serde_json::to_value(&current).unwrap());
+    }
+
+    #[test]
+    fn core_diffs_against_last_view() {
+        let core: Core<Effect, App> = Core::new();
+
+        let patch = core.view_diff();
+        assert_eq!(
+            patch.operations,
+            vec![PatchOperation::Set {
+                path: String::new(),
+                value: r#"{"items":[],"subtitle":null,"title":""}"#.to_string()
+            }]
+        );
+        assert!(core.view_diff().is_empty());
+
+        let _effects = core.process_event(Event::Rename("Groceries".to_string()));
+        assert_eq!(
+            core.view_diff().operations,
+            vec![PatchOperation::Set {
+                path: "/title".to_string(),
+                value: r#""Groceries""#.to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn bridge_serializes_view_diff() {
+        let options = bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes();
+        let bridge = Bridge::<Effect, App>::new(Core::new());
+        let _patch = bridge.view_diff();
+
+        let event = options.serialize(&Event::Add("milk".to_string())).unwrap();
+        let _requests = bridge.process_event(&event);
+
+        let patch: Patch = options.deserialize(&bridge.view_diff()).unwrap();
+        assert_eq!(
+            patch.operations,
+            vec![PatchOperation::Set {
+                path: "/items".to_string(),
+                value: r#"["milk"]"#.to_string()
+            }]
+        );
+    }
+}
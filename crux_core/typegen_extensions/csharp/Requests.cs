@@ -0,0 +1,33 @@
+using System;
+using System.Collections.Generic;
+
+namespace SharedTypes {
+
+    public static class Requests {
+
+        public static List<Request> BincodeDeserialize(byte[] input) => BincodeDeserialize(new ArraySegment<byte>(input));
+
+        public static List<Request> BincodeDeserialize(ArraySegment<byte> input) {
+            if (input == null) {
+                throw new Serde.DeserializationException("Cannot deserialize null array");
+            }
+            Serde.IDeserializer deserializer = new Bincode.BincodeDeserializer(input);
+            deserializer.increase_container_depth();
+
+            long length = deserializer.deserialize_len();
+
+            var value = new List<Request>();
+
+            for (long i = 0; i < length; i++) {
+                value.Add(Request.Deserialize(deserializer));
+            }
+
+            deserializer.decrease_container_depth();
+
+            if (deserializer.get_buffer_offset() < input.Count) {
+                throw new Serde.DeserializationException("Some input bytes were not read");
+            }
+            return value;
+        }
+    }
+}
@@ -0,0 +1,22 @@
+import 'dart:typed_data';
+
+import 'bincode/bincode.dart';
+import 'shared_types/shared_types.dart';
+
+class Requests {
+  static List<Request> bincodeDeserialize(Uint8List input) {
+    final deserializer = BincodeDeserializer(input);
+    final length = deserializer.deserializeLength();
+
+    final requests = <Request>[];
+    for (var i = 0; i < length; i++) {
+      requests.add(Request.deserialize(deserializer));
+    }
+
+    if (deserializer.offset < input.length) {
+      throw Exception('Some input bytes were not read');
+    }
+
+    return requests;
+  }
+}
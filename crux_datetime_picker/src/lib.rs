@@ -0,0 +1,129 @@
+//! Native date and time pickers for Crux apps
+//!
+//! `crux_datetime_picker` allows Crux apps to ask the Shell to present the platform's native
+//! date and/or time picker and let the user pick a time, which the app receives as the portable
+//! [`Instant`] type from `crux_time`, or learns that the user cancelled.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_time::Instant;
+use serde::{Deserialize, Serialize};
+
+/// What the user picks
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PickerMode {
+    /// A calendar date
+    #[default]
+    Date,
+    /// A time of day
+    Time,
+    /// A calendar date and a time of day
+    DateTime,
+}
+
+/// How the Shell should set up the picker
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PickerConfig {
+    pub mode: PickerMode,
+    /// The earliest time the user can pick
+    pub min: Option<Instant>,
+    /// The latest time the user can pick
+    pub max: Option<Instant>,
+    /// The time the picker starts at, the current time if not set
+    pub initial: Option<Instant>,
+}
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DateTimePickerOperation {
+    /// Present the picker set up with the config
+    Pick { config: PickerConfig },
+}
+
+/// The outcome of presenting the picker
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PickerResponse {
+    /// The user picked a time
+    Picked { instant: Instant },
+    /// The user dismissed the picker without picking a time
+    Cancelled,
+}
+
+impl Operation for DateTimePickerOperation {
+    type Output = PickerResponse;
+}
+
+pub struct DateTimePicker<Ev> {
+    context: CapabilityContext<DateTimePickerOperation, Ev>,
+}
+
+impl<Ev> crux_core::Capability<Ev> for DateTimePicker<Ev> {
+    type Operation = DateTimePickerOperation;
+
+    type MappedSelf<MappedEv> = DateTimePicker<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static + Send,
+    {
+        DateTimePicker::new(self.context.map_event(f))
+    }
+
+    #[cfg(feature = "typegen")]
+    fn register_types(generator: &mut crux_core::typegen::TypeGen) -> crux_core::typegen::Result {
+        generator.register_type::<Instant>()?;
+        generator.register_type::<PickerMode>()?;
+        generator.register_type::<PickerConfig>()?;
+        generator.register_type::<Self::Operation>()?;
+        generator.register_type::<<Self::Operation as Operation>::Output>()?;
+        Ok(())
+    }
+}
+
+impl<Ev> Clone for DateTimePicker<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> DateTimePicker<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<DateTimePickerOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Present the native picker set up with the `config`. The picked time is passed to the
+    /// app wrapped in the event produced by the `callback`, or `None` if the user cancelled.
+    pub fn pick<F>(&self, config: PickerConfig, callback: F)
+    where
+        F: FnOnce(Option<Instant>) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.pick_async(config).await));
+            }
+        });
+    }
+
+    /// Present the native picker set up with the `config`, returning the picked time,
+    /// or `None` if the user cancelled.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn pick_async(&self, config: PickerConfig) -> Option<Instant> {
+        match self
+            .context
+            .request_from_shell(DateTimePickerOperation::Pick { config })
+            .await
+        {
+            PickerResponse::Picked { instant } => Some(instant),
+            PickerResponse::Cancelled => None,
+        }
+    }
+}
@@ -0,0 +1,140 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_datetime_picker::{DateTimePicker, PickerConfig, PickerMode};
+    use crux_time::Instant;
+    use serde::{Deserialize, Serialize};
+
+    pub const TODAY: Instant = Instant {
+        seconds: 1_700_000_000,
+        nanos: 0,
+    };
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        ChooseDeadline,
+
+        #[serde(skip)]
+        DeadlinePicked(Instant),
+        #[serde(skip)]
+        PickCancelled,
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub deadline: Option<Instant>,
+        pub cancelled: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub deadline: Option<u64>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::ChooseDeadline => {
+                    let config = PickerConfig {
+                        mode: PickerMode::Date,
+                        min: Some(TODAY),
+                        max: None,
+                        initial: model.deadline,
+                    };
+
+                    caps.picker.pick(config, |picked| match picked {
+                        Some(instant) => Event::DeadlinePicked(instant),
+                        None => Event::PickCancelled,
+                    });
+                }
+                Event::DeadlinePicked(instant) => {
+                    model.deadline = Some(instant);
+                    caps.render.render();
+                }
+                Event::PickCancelled => model.cancelled = true,
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                deadline: model.deadline.map(|deadline| deadline.seconds),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub picker: DateTimePicker<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model, TODAY};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_datetime_picker::{DateTimePickerOperation, PickerConfig, PickerMode, PickerResponse};
+    use crux_time::Instant;
+
+    #[test]
+    pub fn test_pick_date() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::ChooseDeadline, &mut model)
+            .expect_one_effect()
+            .expect_picker();
+
+        assert_eq!(
+            request.operation,
+            DateTimePickerOperation::Pick {
+                config: PickerConfig {
+                    mode: PickerMode::Date,
+                    min: Some(TODAY),
+                    max: None,
+                    initial: None,
+                }
+            }
+        );
+
+        let instant = Instant::new(1_700_086_400, 0).unwrap();
+        let event = app
+            .resolve(&mut request, PickerResponse::Picked { instant })
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::DeadlinePicked(instant));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(app.view(&model).deadline, Some(1_700_086_400));
+    }
+
+    #[test]
+    pub fn test_pick_cancelled() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::ChooseDeadline, &mut model)
+            .expect_one_effect()
+            .expect_picker();
+
+        let event = app
+            .resolve(&mut request, PickerResponse::Cancelled)
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::PickCancelled);
+
+        app.update(event, &mut model).assert_empty();
+        assert!(model.cancelled);
+        assert_eq!(app.view(&model).deadline, None);
+    }
+}
@@ -0,0 +1,175 @@
+//! Debouncing of rapid event streams for Crux apps
+//!
+//! The shell is asked to start a timer for every call to [`Debounce::emit`]. Calls sharing a key
+//! within the timer's duration supersede each other: the capability asks the shell to cancel the
+//! pending timer, and only the event of the last call is dispatched to the app once its timer elapses.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use crux_core::capability::{CapabilityContext, Operation};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DebounceRequest {
+    Start { id: DebounceId, millis: u64 },
+    Cancel { id: DebounceId },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DebounceId(pub usize);
+
+fn get_debounce_id() -> DebounceId {
+    static COUNTER: AtomicUsize = AtomicUsize::new(1);
+    DebounceId(COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DebounceResponse {
+    Elapsed { id: DebounceId },
+}
+
+impl Operation for DebounceRequest {
+    type Output = DebounceResponse;
+}
+
+/// The Debounce capability API
+///
+/// This capability coalesces calls to [`Debounce::emit`] which share a key, so that only the
+/// last one within the requested duration results in an event.
+pub struct Debounce<Ev> {
+    context: CapabilityContext<DebounceRequest, Ev>,
+    pending: Arc<Mutex<HashMap<String, DebounceId>>>,
+}
+
+impl<Ev> crux_core::Capability<Ev> for Debounce<Ev> {
+    type Operation = DebounceRequest;
+    type MappedSelf<MappedEv> = Debounce<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static + Send,
+    {
+        Debounce {
+            context: self.context.map_event(f),
+            pending: self.pending.clone(),
+        }
+    }
+}
+
+impl<Ev> Clone for Debounce<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+            pending: self.pending.clone(),
+        }
+    }
+}
+
+impl<Ev> Debounce<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<DebounceRequest, Ev>) -> Self {
+        Self {
+            context,
+            pending: Arc::default(),
+        }
+    }
+
+    /// Dispatch the event produced by `callback` once `duration` has elapsed, unless another call
+    /// with the same `key` is made in the meantime, in which case this one is cancelled.
+    pub fn emit<F>(&self, key: impl Into<String>, duration: Duration, callback: F)
+    where
+        F: FnOnce() -> Ev + Send + Sync + 'static,
+    {
+        let key = key.into();
+        let id = get_debounce_id();
+
+        let superseded = self
+            .pending
+            .lock()
+            .expect("debounce state poisoned")
+            .insert(key.clone(), id);
+
+        if let Some(superseded) = superseded {
+            self.cancel(superseded);
+        }
+
+        let millis = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+
+        self.context.spawn({
+            let context = self.context.clone();
+            let pending = self.pending.clone();
+
+            async move {
+                context
+                    .request_from_shell(DebounceRequest::Start { id, millis })
+                    .await;
+
+                // the shell may still resolve a timer it was asked to cancel, in which case
+                // a later call has superseded this one and there is nothing to dispatch
+                let is_latest = {
+                    let mut pending = pending.lock().expect("debounce state poisoned");
+                    if pending.get(&key) == Some(&id) {
+                        pending.remove(&key);
+                        true
+                    } else {
+                        false
+                    }
+                };
+
+                if is_latest {
+                    context.update_app(callback());
+                }
+            }
+        });
+    }
+
+    fn cancel(&self, id: DebounceId) {
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                context.notify_shell(DebounceRequest::Cancel { id }).await;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_serializing_the_request_types_as_json() {
+        let start = DebounceRequest::Start {
+            id: DebounceId(1),
+            millis: 300,
+        };
+
+        let serialized = serde_json::to_string(&start).unwrap();
+        assert_eq!(&serialized, r#"{"start":{"id":1,"millis":300}}"#);
+
+        let deserialized: DebounceRequest = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(start, deserialized);
+
+        let cancel = DebounceRequest::Cancel { id: DebounceId(1) };
+
+        let serialized = serde_json::to_string(&cancel).unwrap();
+        assert_eq!(&serialized, r#"{"cancel":{"id":1}}"#);
+
+        let deserialized: DebounceRequest = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(cancel, deserialized);
+    }
+}
@@ -0,0 +1,120 @@
+mod shared {
+    use std::time::Duration;
+
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_debounce::Debounce;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    pub enum Event {
+        Type(String),
+        Search(String),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub query: String,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub query: String,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Type(text) => {
+                    caps.debounce
+                        .emit("search", Duration::from_millis(300), move || {
+                            Event::Search(text)
+                        });
+                }
+                Event::Search(query) => {
+                    model.query = query;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                query: model.query.clone(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub debounce: Debounce<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_debounce::{DebounceId, DebounceRequest, DebounceResponse};
+
+    #[test]
+    pub fn rapid_emits_with_the_same_key_dispatch_one_event() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut starts = vec![];
+        let mut cancelled = vec![];
+
+        for text in ["c", "cr", "cru"] {
+            let update = app.update(Event::Type(text.to_string()), &mut model);
+
+            for effect in update.into_effects() {
+                let Effect::Debounce(request) = effect else {
+                    panic!("unexpected effect");
+                };
+
+                match request.operation {
+                    DebounceRequest::Start { id, millis } => {
+                        assert_eq!(millis, 300);
+                        starts.push((id, request));
+                    }
+                    DebounceRequest::Cancel { id } => cancelled.push(id),
+                }
+            }
+        }
+
+        assert_eq!(starts.len(), 3);
+        assert_eq!(
+            cancelled,
+            starts[..2]
+                .iter()
+                .map(|(id, _)| *id)
+                .collect::<Vec<DebounceId>>()
+        );
+
+        // the shell resolves every timer, including the cancelled ones
+        let mut events = vec![];
+        for (id, mut request) in starts {
+            let update = app
+                .resolve(&mut request, DebounceResponse::Elapsed { id })
+                .expect("to resolve");
+            events.extend(update.events);
+        }
+
+        assert_eq!(events, vec![Event::Search("cru".to_string())]);
+
+        for event in events {
+            let update = app.update(event, &mut model);
+            assert_effect!(update, Effect::Render(_));
+        }
+        assert_eq!(app.view(&model).query, "cru");
+    }
+}
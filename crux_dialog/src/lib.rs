@@ -0,0 +1,89 @@
+//! Native dialogs for Crux apps
+//!
+//! `crux_dialog` allows Crux apps to ask the user a question in a native alert dialog,
+//! e.g. to confirm deleting an item or discarding changes. The dialog has a title, a
+//! message and a list of buttons, and resolves with the index of the button the user
+//! tapped.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use serde::{Deserialize, Serialize};
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DialogOperation {
+    /// Present an alert dialog with the `title`, the `message` and the `buttons`, in order.
+    /// The Shell resolves it with the index of the button the user tapped in `buttons`.
+    Alert {
+        title: String,
+        message: String,
+        buttons: Vec<String>,
+    },
+}
+
+impl Operation for DialogOperation {
+    type Output = usize;
+}
+
+#[derive(Capability)]
+pub struct Dialog<Ev> {
+    context: CapabilityContext<DialogOperation, Ev>,
+}
+
+impl<Ev> Clone for Dialog<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Dialog<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<DialogOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Ask the Shell to present an alert dialog with the `title`, the `message` and the
+    /// `buttons`. The index of the button the user tapped in `buttons` will be passed to the
+    /// app wrapped in the event produced by the `callback`.
+    pub fn alert<F>(
+        &self,
+        title: impl Into<String>,
+        message: impl Into<String>,
+        buttons: Vec<String>,
+        callback: F,
+    ) where
+        F: FnOnce(usize) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+            let (title, message) = (title.into(), message.into());
+
+            async move {
+                context.update_app(callback(this.alert_async(title, message, buttons).await));
+            }
+        });
+    }
+
+    /// Ask the Shell to present an alert dialog with the `title`, the `message` and the
+    /// `buttons`, and return the index of the button the user tapped in `buttons`.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn alert_async(
+        &self,
+        title: impl Into<String>,
+        message: impl Into<String>,
+        buttons: Vec<String>,
+    ) -> usize {
+        self.context
+            .request_from_shell(DialogOperation::Alert {
+                title: title.into(),
+                message: message.into(),
+                buttons,
+            })
+            .await
+    }
+}
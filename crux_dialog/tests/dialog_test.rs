@@ -0,0 +1,134 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_dialog::Dialog;
+    use serde::{Deserialize, Serialize};
+
+    pub const DELETE: usize = 1;
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Delete(String),
+
+        #[serde(skip)]
+        DeleteConfirmed(String, usize),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub items: Vec<String>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub items: Vec<String>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Delete(item) => caps.dialog.alert(
+                    "Delete item?",
+                    format!("\"{item}\" will be deleted."),
+                    vec!["Cancel".to_string(), "Delete".to_string()],
+                    move |button| Event::DeleteConfirmed(item, button),
+                ),
+                Event::DeleteConfirmed(item, DELETE) => {
+                    model.items.retain(|i| *i != item);
+                    caps.render.render();
+                }
+                Event::DeleteConfirmed(_, _) => {}
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                items: model.items.clone(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub dialog: Dialog<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model, DELETE};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_dialog::DialogOperation;
+
+    fn model() -> Model {
+        Model {
+            items: vec!["milk".to_string(), "eggs".to_string()],
+        }
+    }
+
+    #[test]
+    pub fn test_alert() {
+        let app = AppTester::<App, _>::default();
+        let mut model = model();
+
+        let request = app
+            .update(Event::Delete("milk".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_dialog();
+
+        assert_eq!(
+            request.operation,
+            DialogOperation::Alert {
+                title: "Delete item?".to_string(),
+                message: "\"milk\" will be deleted.".to_string(),
+                buttons: vec!["Cancel".to_string(), "Delete".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_confirmed() {
+        let app = AppTester::<App, _>::default();
+        let mut model = model();
+
+        let mut request = app
+            .update(Event::Delete("milk".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_dialog();
+
+        let event = app
+            .resolve(&mut request, DELETE)
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::DeleteConfirmed("milk".to_string(), DELETE));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(app.view(&model).items, vec!["eggs".to_string()]);
+    }
+
+    #[test]
+    pub fn test_cancelled() {
+        let app = AppTester::<App, _>::default();
+        let mut model = model();
+
+        let mut request = app
+            .update(Event::Delete("milk".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_dialog();
+
+        let event = app.resolve(&mut request, 0).unwrap().expect_one_event();
+        assert_eq!(event, Event::DeleteConfirmed("milk".to_string(), 0));
+
+        app.update(event, &mut model).assert_empty();
+        assert_eq!(app.view(&model).items.len(), 2);
+    }
+}
@@ -0,0 +1,117 @@
+//! The system theme and screen brightness for Crux apps
+//!
+//! `crux_display` allows Crux apps to read the system theme and subscribe to its changes,
+//! which resolves every time the user switches between light and dark mode. Apps can also
+//! ask the Shell to change the screen brightness, e.g. while reading.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+/// The system theme
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DisplayOperation {
+    /// Read the current theme
+    GetTheme,
+    /// Start delivering the theme every time it changes
+    SubscribeTheme,
+    /// Set the screen brightness to the `level`, from 0.0 (dimmest) to 1.0 (brightest).
+    /// This isn't resolved.
+    SetBrightness { level: f32 },
+}
+
+impl Operation for DisplayOperation {
+    type Output = Theme;
+}
+
+#[derive(Capability)]
+pub struct Display<Ev> {
+    context: CapabilityContext<DisplayOperation, Ev>,
+}
+
+impl<Ev> Clone for Display<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Display<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<DisplayOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Read the current theme, which will be passed to the app wrapped in the event
+    /// produced by the `callback`.
+    pub fn theme<F>(&self, callback: F)
+    where
+        F: FnOnce(Theme) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.theme_async().await));
+            }
+        });
+    }
+
+    /// Read the current theme.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn theme_async(&self) -> Theme {
+        self.context
+            .request_from_shell(DisplayOperation::GetTheme)
+            .await
+    }
+
+    /// Receive the theme every time it changes. The new theme is passed to the app
+    /// wrapped in the event produced by the `callback`.
+    pub fn on_theme_change<F>(&self, callback: F)
+    where
+        F: Fn(Theme) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                let mut stream = context.stream_from_shell(DisplayOperation::SubscribeTheme);
+
+                while let Some(theme) = stream.next().await {
+                    context.update_app(callback(theme));
+                }
+            }
+        });
+    }
+
+    /// Ask the Shell to set the screen brightness to the `level`, from 0.0 (dimmest)
+    /// to 1.0 (brightest). Levels out of the range are clamped to it, and a level which
+    /// isn't a number is ignored.
+    pub fn set_brightness(&self, level: f32) {
+        if level.is_nan() {
+            return;
+        }
+        let level = level.clamp(0.0, 1.0);
+
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                context
+                    .notify_shell(DisplayOperation::SetBrightness { level })
+                    .await;
+            }
+        });
+    }
+}
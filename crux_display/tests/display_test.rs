@@ -0,0 +1,123 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_display::{Display, Theme};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Open,
+        StartReading(f32),
+
+        #[serde(skip)]
+        SetTheme(Theme),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub theme: Option<Theme>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub dark: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Open => caps.display.theme(Event::SetTheme),
+                Event::StartReading(level) => caps.display.set_brightness(level),
+                Event::SetTheme(theme) => {
+                    model.theme = Some(theme);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                dark: model.theme == Some(Theme::Dark),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub display: Display<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_display::{DisplayOperation, Theme};
+
+    #[test]
+    pub fn test_theme() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Open, &mut model)
+            .expect_one_effect()
+            .expect_display();
+
+        assert_eq!(request.operation, DisplayOperation::GetTheme);
+
+        let event = app
+            .resolve(&mut request, Theme::Dark)
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::SetTheme(Theme::Dark));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert!(app.view(&model).dark);
+    }
+
+    #[test]
+    pub fn test_set_brightness() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = app
+            .update(Event::StartReading(0.4), &mut model)
+            .expect_one_effect()
+            .expect_display();
+
+        assert_eq!(
+            request.operation,
+            DisplayOperation::SetBrightness { level: 0.4 }
+        );
+        assert!(!request.expects_response());
+    }
+
+    #[test]
+    pub fn test_set_brightness_clamps_level() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = app
+            .update(Event::StartReading(1.5), &mut model)
+            .expect_one_effect()
+            .expect_display();
+
+        assert_eq!(
+            request.operation,
+            DisplayOperation::SetBrightness { level: 1.0 }
+        );
+
+        app.update(Event::StartReading(f32::NAN), &mut model)
+            .assert_empty();
+    }
+}
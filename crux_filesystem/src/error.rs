@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Error type for FileSystem operations
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Error)]
+#[serde(rename_all = "camelCase")]
+pub enum FileSystemError {
+    #[error("file not found")]
+    NotFound,
+    #[error("permission denied")]
+    PermissionDenied,
+    #[error("IO error: {message}")]
+    Io { message: String },
+    #[error("invalid path: {path}")]
+    InvalidPath { path: String },
+}
@@ -0,0 +1,285 @@
+//! App-scoped file storage for Crux apps
+//!
+//! `crux_filesystem` allows Crux apps to read, write and delete files by asking the Shell to
+//! access them in storage scoped to the app. Files are identified by relative paths, which the
+//! Shell resolves inside a location of its choosing (e.g. the app's documents directory).
+//! Absolute paths and paths escaping that location with `..` are rejected by the capability,
+//! without involving the Shell.
+
+pub mod error;
+
+use serde::{Deserialize, Serialize};
+
+use crux_core::capability::{CapabilityContext, Operation};
+
+pub use error::FileSystemError;
+
+/// Supported operations
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FileSystemOperation {
+    /// Read the contents of the file at `path`
+    Read { path: String },
+    /// Write `bytes` to the file at `path`, replacing its contents
+    Write {
+        path: String,
+        #[serde(with = "serde_bytes")]
+        bytes: Vec<u8>,
+    },
+    /// Delete the file at `path`
+    Delete { path: String },
+}
+
+impl std::fmt::Debug for FileSystemOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileSystemOperation::Read { path } => {
+                f.debug_struct("Read").field("path", path).finish()
+            }
+            FileSystemOperation::Write { path, bytes } => f
+                .debug_struct("Write")
+                .field("path", path)
+                .field("bytes", &format_args!("<{} bytes>", bytes.len()))
+                .finish(),
+            FileSystemOperation::Delete { path } => {
+                f.debug_struct("Delete").field("path", path).finish()
+            }
+        }
+    }
+}
+
+/// The result of an operation on the file system.
+///
+/// Note: we can't use `Result` here because generics are not currently
+/// supported across the FFI boundary, when using the builtin typegen.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum FileSystemResult {
+    Ok { response: FileSystemResponse },
+    Err { error: FileSystemError },
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FileSystemResponse {
+    /// Response to a `FileSystemOperation::Read`, returning the contents of the file
+    Read {
+        #[serde(with = "serde_bytes")]
+        bytes: Vec<u8>,
+    },
+    /// Response to a `FileSystemOperation::Write`
+    Write,
+    /// Response to a `FileSystemOperation::Delete`
+    Delete,
+}
+
+impl std::fmt::Debug for FileSystemResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileSystemResponse::Read { bytes } => f
+                .debug_struct("Read")
+                .field("bytes", &format_args!("<{} bytes>", bytes.len()))
+                .finish(),
+            FileSystemResponse::Write => f.write_str("Write"),
+            FileSystemResponse::Delete => f.write_str("Delete"),
+        }
+    }
+}
+
+impl Operation for FileSystemOperation {
+    type Output = FileSystemResult;
+}
+
+pub struct FileSystem<Ev> {
+    context: CapabilityContext<FileSystemOperation, Ev>,
+}
+
+impl<Ev> crux_core::Capability<Ev> for FileSystem<Ev> {
+    type Operation = FileSystemOperation;
+
+    type MappedSelf<MappedEv> = FileSystem<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static + Send,
+    {
+        FileSystem::new(self.context.map_event(f))
+    }
+
+    #[cfg(feature = "typegen")]
+    fn register_types(generator: &mut crux_core::typegen::TypeGen) -> crux_core::typegen::Result {
+        generator.register_type::<FileSystemResponse>()?;
+        generator.register_type::<FileSystemError>()?;
+        generator.register_type::<Self::Operation>()?;
+        generator.register_type::<<Self::Operation as Operation>::Output>()?;
+        Ok(())
+    }
+}
+
+impl<Ev> Clone for FileSystem<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> FileSystem<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<FileSystemOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Read the contents of the file at `path`, will dispatch the event with the
+    /// bytes, or a `FileSystemError::NotFound` if there is no such file
+    pub fn read<F>(&self, path: impl Into<String>, make_event: F)
+    where
+        F: FnOnce(Result<Vec<u8>, FileSystemError>) -> Ev + Send + Sync + 'static,
+    {
+        let path = path.into();
+
+        self.context.spawn({
+            let this = self.clone();
+            async move {
+                let response = this.read_async(path).await;
+                this.context.update_app(make_event(response));
+            }
+        });
+    }
+
+    /// Read the contents of the file at `path`, while in an async context. This is used together with
+    /// [`crux_core::compose::Compose`].
+    pub async fn read_async(&self, path: impl Into<String>) -> Result<Vec<u8>, FileSystemError> {
+        let path = validate(path.into())?;
+
+        match self.request(FileSystemOperation::Read { path }).await? {
+            FileSystemResponse::Read { bytes } => Ok(bytes),
+            response => Err(unexpected(&response)),
+        }
+    }
+
+    /// Write `bytes` to the file at `path`, creating it if needed and replacing its
+    /// contents otherwise. Will dispatch the event once the file has been written.
+    pub fn write<F>(&self, path: impl Into<String>, bytes: Vec<u8>, make_event: F)
+    where
+        F: FnOnce(Result<(), FileSystemError>) -> Ev + Send + Sync + 'static,
+    {
+        let path = path.into();
+
+        self.context.spawn({
+            let this = self.clone();
+            async move {
+                let response = this.write_async(path, bytes).await;
+                this.context.update_app(make_event(response));
+            }
+        });
+    }
+
+    /// Write `bytes` to the file at `path`, while in an async context. This is used together with
+    /// [`crux_core::compose::Compose`].
+    pub async fn write_async(
+        &self,
+        path: impl Into<String>,
+        bytes: Vec<u8>,
+    ) -> Result<(), FileSystemError> {
+        let path = validate(path.into())?;
+
+        match self
+            .request(FileSystemOperation::Write { path, bytes })
+            .await?
+        {
+            FileSystemResponse::Write => Ok(()),
+            response => Err(unexpected(&response)),
+        }
+    }
+
+    /// Delete the file at `path`, will dispatch the event once the file has been deleted
+    pub fn delete<F>(&self, path: impl Into<String>, make_event: F)
+    where
+        F: FnOnce(Result<(), FileSystemError>) -> Ev + Send + Sync + 'static,
+    {
+        let path = path.into();
+
+        self.context.spawn({
+            let this = self.clone();
+            async move {
+                let response = this.delete_async(path).await;
+                this.context.update_app(make_event(response));
+            }
+        });
+    }
+
+    /// Delete the file at `path`, while in an async context. This is used together with
+    /// [`crux_core::compose::Compose`].
+    pub async fn delete_async(&self, path: impl Into<String>) -> Result<(), FileSystemError> {
+        let path = validate(path.into())?;
+
+        match self.request(FileSystemOperation::Delete { path }).await? {
+            FileSystemResponse::Delete => Ok(()),
+            response => Err(unexpected(&response)),
+        }
+    }
+
+    async fn request(
+        &self,
+        operation: FileSystemOperation,
+    ) -> Result<FileSystemResponse, FileSystemError> {
+        match self.context.request_from_shell(operation).await {
+            FileSystemResult::Ok { response } => Ok(response),
+            FileSystemResult::Err { error } => Err(error),
+        }
+    }
+}
+
+/// Check that `path` is relative and stays inside the app's storage
+fn validate(path: String) -> Result<String, FileSystemError> {
+    let is_absolute = path.starts_with(['/', '\\']) || path.contains(':');
+    let escapes = path.split(['/', '\\']).any(|segment| segment == "..");
+
+    if path.is_empty() || is_absolute || escapes {
+        Err(FileSystemError::InvalidPath { path })
+    } else {
+        Ok(path)
+    }
+}
+
+fn unexpected(response: &FileSystemResponse) -> FileSystemError {
+    FileSystemError::Io {
+        message: format!("unexpected response: {response:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_path() {
+        assert_eq!(
+            validate("photo.jpg".to_string()),
+            Ok("photo.jpg".to_string())
+        );
+        assert_eq!(
+            validate("exports/2024.csv".to_string()),
+            Ok("exports/2024.csv".to_string())
+        );
+
+        for path in [
+            "",
+            "/etc/passwd",
+            "C:\\data",
+            "\\\\server\\share",
+            "../secret",
+            "a/../../b",
+        ] {
+            assert_eq!(
+                validate(path.to_string()),
+                Err(FileSystemError::InvalidPath {
+                    path: path.to_string()
+                }),
+                "{path} should be rejected"
+            );
+        }
+    }
+}
@@ -0,0 +1,185 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_filesystem::{FileSystem, FileSystemError};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Open(String),
+        Export(String),
+
+        #[serde(skip)]
+        Opened(Result<Vec<u8>, FileSystemError>),
+        #[serde(skip)]
+        Exported(Result<(), FileSystemError>),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub contents: Option<Vec<u8>>,
+        pub error: Option<FileSystemError>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub size: usize,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Open(path) => caps.file_system.read(path, Event::Opened),
+                Event::Export(path) => {
+                    caps.file_system
+                        .write(path, b"a,b,c".to_vec(), Event::Exported);
+                }
+                Event::Opened(Ok(bytes)) => {
+                    model.contents = Some(bytes);
+                    caps.render.render();
+                }
+                Event::Opened(Err(error)) | Event::Exported(Err(error)) => {
+                    model.error = Some(error);
+                    caps.render.render();
+                }
+                Event::Exported(Ok(())) => caps.render.render(),
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                size: model.contents.as_ref().map_or(0, Vec::len),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub file_system: FileSystem<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_filesystem::{
+        FileSystemError, FileSystemOperation, FileSystemResponse, FileSystemResult,
+    };
+
+    #[test]
+    pub fn test_read() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Open("images/cat.png".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_file_system();
+
+        assert_eq!(
+            request.operation,
+            FileSystemOperation::Read {
+                path: "images/cat.png".to_string()
+            }
+        );
+
+        let event = app
+            .resolve(
+                &mut request,
+                FileSystemResult::Ok {
+                    response: FileSystemResponse::Read {
+                        bytes: vec![1, 2, 3],
+                    },
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Opened(Ok(vec![1, 2, 3])));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(model.contents, Some(vec![1, 2, 3]));
+    }
+
+    #[test]
+    pub fn test_read_not_found() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Open("missing.png".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_file_system();
+
+        let event = app
+            .resolve(
+                &mut request,
+                FileSystemResult::Err {
+                    error: FileSystemError::NotFound,
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Opened(Err(FileSystemError::NotFound)));
+
+        let _update = app.update(event, &mut model);
+        assert_eq!(model.contents, None);
+        assert_eq!(model.error, Some(FileSystemError::NotFound));
+    }
+
+    #[test]
+    pub fn test_write() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Export("exports/data.csv".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_file_system();
+
+        assert_eq!(
+            request.operation,
+            FileSystemOperation::Write {
+                path: "exports/data.csv".to_string(),
+                bytes: b"a,b,c".to_vec(),
+            }
+        );
+
+        let event = app
+            .resolve(
+                &mut request,
+                FileSystemResult::Ok {
+                    response: FileSystemResponse::Write,
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Exported(Ok(())));
+    }
+
+    #[test]
+    pub fn test_absolute_path_is_rejected_without_shell() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let event = app
+            .update(Event::Open("/etc/passwd".to_string()), &mut model)
+            .expect_one_event();
+
+        assert_eq!(
+            event,
+            Event::Opened(Err(FileSystemError::InvalidPath {
+                path: "/etc/passwd".to_string()
+            }))
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! Periodic heartbeats for Crux apps
+//!
+//! `crux_heartbeat` allows Crux apps to dispatch an event on an interval, e.g. to tell a server
+//! the user is still present. The app starts the [`Heartbeat`] with the endpoint to report to,
+//! turns every [`Beat`] into a request with the capability of its choice, and stops the
+//! heartbeat when it's backgrounded.
+//!
+//! The heartbeat runs on the core's virtual clock, advanced by the Shell with
+//! [`Core::advance_time`](crux_core::Core::advance_time), and doesn't request any effects,
+//! so skip it in the app's capabilities with `#[effect(skip)]`:
+//!
+//! ```rust
+//! # use crux_core::macros::Effect;
+//! # use crux_core::render::Render;
+//! # use crux_heartbeat::{Beat, Heartbeat};
+//! # enum Event { Beat(Beat) }
+//! #[derive(Effect)]
+//! pub struct Capabilities {
+//!     pub render: Render<Event>,
+//!     #[effect(skip)]
+//!     pub heartbeat: Heartbeat<Event>,
+//! }
+//! ```
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crux_core::capability::{CapabilityContext, Never};
+use futures::{
+    channel::oneshot::{self, Sender},
+    future::{self, Either},
+};
+
+/// A beat of the running heartbeat
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Beat {
+    /// The endpoint the heartbeat was started with
+    pub endpoint: String,
+    /// The number of the beat since the heartbeat started, starting from 1
+    pub count: u64,
+}
+
+/// The Heartbeat capability API
+///
+/// At most one heartbeat runs at a time: starting it again replaces the running one.
+pub struct Heartbeat<Ev> {
+    context: CapabilityContext<Never, Ev>,
+    // dropping the sender stops the running heartbeat
+    running: Arc<Mutex<Option<Sender<()>>>>,
+}
+
+impl<Ev> crux_core::Capability<Ev> for Heartbeat<Ev> {
+    type Operation = Never;
+    type MappedSelf<MappedEv> = Heartbeat<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static + Send,
+    {
+        Heartbeat {
+            context: self.context.map_event(f),
+            running: self.running.clone(),
+        }
+    }
+
+    #[cfg(feature = "typegen")]
+    fn register_types(_generator: &mut crux_core::typegen::TypeGen) -> crux_core::typegen::Result {
+        panic!("The Heartbeat capability doesn't request effects, skip it with #[effect(skip)]");
+    }
+}
+
+impl<Ev> Clone for Heartbeat<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+            running: self.running.clone(),
+        }
+    }
+}
+
+impl<Ev> Heartbeat<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<Never, Ev>) -> Self {
+        Self {
+            context,
+            running: Arc::default(),
+        }
+    }
+
+    /// Start the heartbeat, dispatching the event produced by `callback` every `interval`
+    /// until [`Heartbeat::stop`] is called. A heartbeat which is already running is replaced.
+    ///
+    /// The next beat is scheduled once the previous one was dispatched, so advancing the
+    /// clock by several intervals at once produces a single beat.
+    pub fn start<F>(&self, interval: Duration, endpoint: impl Into<String>, callback: F)
+    where
+        F: Fn(Beat) -> Ev + Send + 'static,
+    {
+        let endpoint = endpoint.into();
+        let (sender, mut stopped) = oneshot::channel();
+
+        // dropping the previous sender stops the heartbeat it belongs to
+        *self.running.lock().expect("heartbeat state poisoned") = Some(sender);
+
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                let mut count = 0;
+
+                loop {
+                    match future::select(Box::pin(context.delay(interval)), &mut stopped).await {
+                        Either::Left(((), _)) => {
+                            count += 1;
+                            context.update_app(callback(Beat {
+                                endpoint: endpoint.clone(),
+                                count,
+                            }));
+                        }
+                        // the heartbeat was stopped or replaced
+                        Either::Right(_) => return,
+                    }
+                }
+            }
+        });
+    }
+
+    /// Stop the running heartbeat, e.g. when the app is backgrounded.
+    pub fn stop(&self) {
+        self.running
+            .lock()
+            .expect("heartbeat state poisoned")
+            .take();
+    }
+}
@@ -0,0 +1,141 @@
+mod shared {
+    use std::time::Duration;
+
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_heartbeat::{Beat, Heartbeat};
+    use serde::{Deserialize, Serialize};
+
+    pub const INTERVAL: Duration = Duration::from_secs(30);
+    pub const ENDPOINT: &str = "https://example.com/presence";
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Foregrounded,
+        Backgrounded,
+
+        #[serde(skip)]
+        Beat(Beat),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub pings: Vec<String>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub pings: usize,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Foregrounded => caps.heartbeat.start(INTERVAL, ENDPOINT, Event::Beat),
+                Event::Backgrounded => caps.heartbeat.stop(),
+                Event::Beat(beat) => {
+                    model
+                        .pings
+                        .push(format!("{}?beat={}", beat.endpoint, beat.count));
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                pings: model.pings.len(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        #[effect(skip)]
+        pub heartbeat: Heartbeat<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use std::time::Duration;
+
+    use crate::shared::{App, Effect, Event, Model, ENDPOINT, INTERVAL};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_heartbeat::Beat;
+
+    #[test]
+    pub fn test_beats_on_interval() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        app.update(Event::Foregrounded, &mut model).assert_empty();
+
+        for count in 1..=3 {
+            app.advance_time(INTERVAL - Duration::from_millis(1))
+                .assert_empty();
+
+            let event = app
+                .advance_time(Duration::from_millis(1))
+                .expect_one_event();
+            assert_eq!(
+                event,
+                Event::Beat(Beat {
+                    endpoint: ENDPOINT.to_string(),
+                    count
+                })
+            );
+
+            let update = app.update(event, &mut model);
+            assert_effect!(update, Effect::Render(_));
+        }
+
+        assert_eq!(model.pings.last(), Some(&format!("{ENDPOINT}?beat=3")));
+        assert_eq!(app.view(&model).pings, 3);
+    }
+
+    #[test]
+    pub fn test_stop_ends_beats() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        app.update(Event::Foregrounded, &mut model).assert_empty();
+        let _event = app.advance_time(INTERVAL).expect_one_event();
+
+        app.update(Event::Backgrounded, &mut model).assert_empty();
+
+        app.advance_time(INTERVAL).assert_empty();
+        app.advance_time(INTERVAL).assert_empty();
+        app.assert_no_pending();
+    }
+
+    #[test]
+    pub fn test_start_replaces_running_heartbeat() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        app.update(Event::Foregrounded, &mut model).assert_empty();
+        app.advance_time(INTERVAL / 2).assert_empty();
+        app.update(Event::Foregrounded, &mut model).assert_empty();
+
+        // the first heartbeat would have beaten here
+        app.advance_time(INTERVAL / 2).assert_empty();
+
+        let event = app.advance_time(INTERVAL / 2).expect_one_event();
+        assert_eq!(
+            event,
+            Event::Beat(Beat {
+                endpoint: ENDPOINT.to_string(),
+                count: 1
+            })
+        );
+    }
+}
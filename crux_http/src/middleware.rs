@@ -60,6 +60,7 @@ use async_trait::async_trait;
 use futures_util::future::BoxFuture;
 
 /// Middleware that wraps around remaining middleware chain.
+#[allow(clippy::double_must_use)]
 #[async_trait]
 pub trait Middleware: 'static + Send + Sync {
     /// Asynchronously handle the request, and return a response.
@@ -164,6 +164,7 @@ impl crux_core::capability::Operation for HttpRequest {
     type Output = HttpResult;
 }
 
+#[allow(clippy::double_must_use)]
 #[async_trait]
 pub(crate) trait EffectSender {
     async fn send(&self, effect: HttpRequest) -> HttpResult;
@@ -179,6 +180,7 @@ where
     }
 }
 
+#[allow(clippy::double_must_use)]
 #[async_trait]
 pub(crate) trait ProtocolRequestBuilder {
     async fn into_protocol_request(mut self) -> crate::Result<HttpRequest>;
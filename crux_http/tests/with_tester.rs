@@ -4,6 +4,7 @@ mod shared {
 
     use crux_core::compose::Compose;
     use crux_core::macros::Effect;
+    use crux_core::render::Render;
     use crux_http::Http;
     use futures_util::join;
     use http_types::StatusCode;
@@ -18,6 +19,7 @@ mod shared {
         Post,
         GetPostChain,
         ConcurrentGets,
+        GetTwiceAndRender,
         ComposeComplete(StatusCode),
 
         // events local to the core
@@ -100,6 +102,17 @@ mod shared {
                         ctx.update_app(Event::ComposeComplete(status))
                     }
                 }),
+                Event::GetTwiceAndRender => {
+                    caps.http
+                        .get("http://example.com/one")
+                        .expect_string()
+                        .send(Event::Set);
+                    caps.render.render();
+                    caps.http
+                        .get("http://example.com/two")
+                        .expect_string()
+                        .send(Event::Set);
+                }
                 Event::ComposeComplete(status) => {
                     model.values.push(status.to_string());
                 }
@@ -128,6 +141,7 @@ mod shared {
         pub http: Http<Event>,
         #[effect(skip)]
         pub compose: Compose<Event>,
+        pub render: Render<Event>,
     }
 }
 
@@ -332,4 +346,38 @@ mod tests {
 
         assert_eq!(error, "Socket shenanigans prevented the request")
     }
+
+    #[test]
+    fn partition_by_capability() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::GetTwiceAndRender, &mut model);
+        let mut effects = update.partition_by_capability();
+
+        assert_eq!(
+            effects.keys().copied().collect::<Vec<_>>(),
+            ["Http", "Render"]
+        );
+
+        let requests = effects
+            .remove("Http")
+            .unwrap()
+            .into_iter()
+            .map(|effect| effect.expect_http().operation)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            requests,
+            vec![
+                HttpRequest::get("http://example.com/one").build(),
+                HttpRequest::get("http://example.com/two").build(),
+            ]
+        );
+
+        let renders = effects.remove("Render").unwrap();
+        assert_eq!(renders.len(), 1);
+        assert_matches!(renders[0], Effect::Render(_));
+
+        update.assert_empty();
+    }
 }
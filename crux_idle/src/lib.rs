@@ -0,0 +1,135 @@
+//! Inactivity timeouts for Crux apps
+//!
+//! `crux_idle` allows Crux apps to find out when the user has been inactive for a given
+//! duration, e.g. to lock a screen showing sensitive information. The app starts the
+//! [`IdleTimer`] and resets it on every user activity. Once the duration passes without
+//! a reset, the timer dispatches its event and stops.
+//!
+//! The timer runs on the core's virtual clock, advanced by the Shell with
+//! [`Core::advance_time`](crux_core::Core::advance_time), and doesn't request any effects,
+//! so skip it in the app's capabilities with `#[effect(skip)]`:
+//!
+//! ```rust
+//! # use crux_core::macros::Effect;
+//! # use crux_core::render::Render;
+//! # use crux_idle::IdleTimer;
+//! # enum Event { Lock }
+//! #[derive(Effect)]
+//! pub struct Capabilities {
+//!     pub render: Render<Event>,
+//!     #[effect(skip)]
+//!     pub idle: IdleTimer<Event>,
+//! }
+//! ```
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crux_core::capability::{CapabilityContext, Never};
+use futures::{
+    channel::mpsc::{self, UnboundedSender},
+    future::{self, Either},
+    StreamExt,
+};
+
+/// The IdleTimer capability API
+///
+/// At most one timer runs at a time: starting the timer again replaces the running one.
+pub struct IdleTimer<Ev> {
+    context: CapabilityContext<Never, Ev>,
+    // notifies the running timer of user activity, dropping it stops the timer
+    activity: Arc<Mutex<Option<UnboundedSender<()>>>>,
+}
+
+impl<Ev> crux_core::Capability<Ev> for IdleTimer<Ev> {
+    type Operation = Never;
+    type MappedSelf<MappedEv> = IdleTimer<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static + Send,
+    {
+        IdleTimer {
+            context: self.context.map_event(f),
+            activity: self.activity.clone(),
+        }
+    }
+
+    #[cfg(feature = "typegen")]
+    fn register_types(_generator: &mut crux_core::typegen::TypeGen) -> crux_core::typegen::Result {
+        panic!("The IdleTimer capability doesn't request effects, skip it with #[effect(skip)]");
+    }
+}
+
+impl<Ev> Clone for IdleTimer<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+            activity: self.activity.clone(),
+        }
+    }
+}
+
+impl<Ev> IdleTimer<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<Never, Ev>) -> Self {
+        Self {
+            context,
+            activity: Arc::default(),
+        }
+    }
+
+    /// Start the timer, dispatching the event produced by `callback` once `duration` has passed
+    /// without a call to [`IdleTimer::reset`]. A timer which is already running is replaced.
+    pub fn start<F>(&self, duration: Duration, callback: F)
+    where
+        F: FnOnce() -> Ev + Send + 'static,
+    {
+        let (sender, mut activity) = mpsc::unbounded();
+
+        // dropping the previous sender stops the timer it belongs to
+        *self.activity.lock().expect("idle timer state poisoned") = Some(sender);
+
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                loop {
+                    match future::select(Box::pin(context.delay(duration)), activity.next()).await {
+                        Either::Left(((), _)) => {
+                            context.update_app(callback());
+                            return;
+                        }
+                        // there was activity, wait for the full duration again
+                        Either::Right((Some(()), _)) => {}
+                        // the timer was stopped or replaced
+                        Either::Right((None, _)) => return,
+                    }
+                }
+            }
+        });
+    }
+
+    /// Restart the running timer, on user activity. This does nothing if the timer
+    /// isn't running, or has already dispatched its event.
+    pub fn reset(&self) {
+        if let Some(sender) = &*self.activity.lock().expect("idle timer state poisoned") {
+            // the timer may have already elapsed, leaving nobody to notify
+            let _ = sender.unbounded_send(());
+        }
+    }
+
+    /// Stop the running timer without dispatching its event.
+    pub fn stop(&self) {
+        self.activity
+            .lock()
+            .expect("idle timer state poisoned")
+            .take();
+    }
+}
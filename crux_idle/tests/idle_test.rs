@@ -0,0 +1,143 @@
+mod shared {
+    use std::time::Duration;
+
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_idle::IdleTimer;
+    use serde::{Deserialize, Serialize};
+
+    pub const TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Unlock,
+        Activity,
+        SignOut,
+
+        #[serde(skip)]
+        Lock,
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub locked: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub locked: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Unlock => {
+                    model.locked = false;
+                    caps.idle.start(TIMEOUT, || Event::Lock);
+                    caps.render.render();
+                }
+                Event::Activity => caps.idle.reset(),
+                Event::SignOut => caps.idle.stop(),
+                Event::Lock => {
+                    model.locked = true;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                locked: model.locked,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        #[effect(skip)]
+        pub idle: IdleTimer<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use std::time::Duration;
+
+    use crate::shared::{App, Effect, Event, Model, TIMEOUT};
+    use crux_core::{assert_effect, testing::AppTester};
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    #[test]
+    pub fn test_locks_once_idle() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let _update = app.update(Event::Unlock, &mut model);
+
+        app.advance_time(TIMEOUT - Duration::from_millis(1))
+            .assert_empty();
+
+        let event = app
+            .advance_time(Duration::from_millis(1))
+            .expect_one_event();
+        assert_eq!(event, Event::Lock);
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert!(app.view(&model).locked);
+    }
+
+    #[test]
+    pub fn test_reset_before_expiry_prevents_lock() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let _update = app.update(Event::Unlock, &mut model);
+
+        app.advance_time(4 * MINUTE).assert_empty();
+        app.update(Event::Activity, &mut model).assert_empty();
+
+        // the timer restarted on activity, so the original expiry passes without a lock
+        app.advance_time(4 * MINUTE).assert_empty();
+        assert!(!app.view(&model).locked);
+
+        let event = app.advance_time(MINUTE).expect_one_event();
+        assert_eq!(event, Event::Lock);
+    }
+
+    #[test]
+    pub fn test_stop_prevents_lock() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let _update = app.update(Event::Unlock, &mut model);
+        app.update(Event::SignOut, &mut model).assert_empty();
+
+        app.advance_time(2 * TIMEOUT).assert_empty();
+        assert!(!app.view(&model).locked);
+    }
+
+    #[test]
+    pub fn test_start_replaces_running_timer() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let _update = app.update(Event::Unlock, &mut model);
+        app.advance_time(4 * MINUTE).assert_empty();
+        let _update = app.update(Event::Unlock, &mut model);
+
+        app.advance_time(4 * MINUTE).assert_empty();
+
+        let event = app.advance_time(MINUTE).expect_one_event();
+        assert_eq!(event, Event::Lock);
+    }
+}
@@ -0,0 +1,73 @@
+//! Focus and keyboard control for Crux apps
+//!
+//! `crux_keyboard` allows Crux apps to move the focus between the fields of a form, and to
+//! dismiss the on-screen keyboard, e.g. after the form has been submitted. Fields are identified
+//! by a stable `field_id`, which the Shell maps to one of its views. All the operations are
+//! fire-and-forget.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use serde::{Deserialize, Serialize};
+
+/// Supported operations, none of which are resolved
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyboardOperation {
+    /// Move the focus to the field identified by `field_id`
+    Focus { field_id: String },
+    /// Remove the focus from the focused field
+    Blur,
+    /// Dismiss the on-screen keyboard
+    HideKeyboard,
+}
+
+impl Operation for KeyboardOperation {
+    type Output = ();
+}
+
+#[derive(Capability)]
+pub struct Keyboard<Ev> {
+    context: CapabilityContext<KeyboardOperation, Ev>,
+}
+
+impl<Ev> Clone for Keyboard<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Keyboard<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<KeyboardOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Ask the Shell to move the focus to the field identified by `field_id`
+    pub fn focus(&self, field_id: impl Into<String>) {
+        self.notify(KeyboardOperation::Focus {
+            field_id: field_id.into(),
+        });
+    }
+
+    /// Ask the Shell to remove the focus from the focused field
+    pub fn blur(&self) {
+        self.notify(KeyboardOperation::Blur);
+    }
+
+    /// Ask the Shell to dismiss the on-screen keyboard
+    pub fn hide_keyboard(&self) {
+        self.notify(KeyboardOperation::HideKeyboard);
+    }
+
+    fn notify(&self, operation: KeyboardOperation) {
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                context.notify_shell(operation).await;
+            }
+        });
+    }
+}
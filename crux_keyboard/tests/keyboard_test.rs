@@ -0,0 +1,119 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_keyboard::Keyboard;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        EmailEntered(String),
+        Cancel,
+        Submit,
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub email: String,
+        pub submitted: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub submitted: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::EmailEntered(email) => {
+                    model.email = email;
+                    caps.keyboard.focus("password");
+                }
+                Event::Cancel => caps.keyboard.blur(),
+                Event::Submit => {
+                    model.submitted = true;
+                    caps.keyboard.hide_keyboard();
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                submitted: model.submitted,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub keyboard: Keyboard<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_keyboard::KeyboardOperation;
+
+    #[test]
+    pub fn test_focus() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = app
+            .update(
+                Event::EmailEntered("ferris@example.com".to_string()),
+                &mut model,
+            )
+            .expect_one_effect()
+            .expect_keyboard();
+
+        assert_eq!(
+            request.operation,
+            KeyboardOperation::Focus {
+                field_id: "password".to_string()
+            }
+        );
+        assert!(!request.expects_response());
+    }
+
+    #[test]
+    pub fn test_blur() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = app
+            .update(Event::Cancel, &mut model)
+            .expect_one_effect()
+            .expect_keyboard();
+
+        assert_eq!(request.operation, KeyboardOperation::Blur);
+    }
+
+    #[test]
+    pub fn test_hide_keyboard() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::Submit, &mut model);
+        assert_effect!(update, Effect::Render(_));
+
+        let request = update
+            .take_one_effect(|effect| matches!(effect, Effect::Keyboard(_)))
+            .unwrap()
+            .expect_keyboard();
+
+        assert_eq!(request.operation, KeyboardOperation::HideKeyboard);
+        assert!(app.view(&model).submitted);
+    }
+}
@@ -4,6 +4,7 @@
 //! persist the data using platform native capabilities (e.g. disk or web localStorage)
 
 pub mod error;
+pub mod typed;
 pub mod value;
 
 use serde::{Deserialize, Serialize};
@@ -11,6 +12,7 @@ use serde::{Deserialize, Serialize};
 use crux_core::capability::{CapabilityContext, Operation};
 
 use error::KeyValueError;
+use typed::TypedStore;
 use value::Value;
 
 /// Supported operations
@@ -41,27 +43,57 @@ pub enum KeyValueOperation {
         /// a `KeyValueError::CursorNotFound` error.
         cursor: u64,
     },
+    /// Apply a batch of writes atomically, in order
+    Transaction { ops: Vec<KvOp> },
+}
+
+/// A single write within a [`KeyValueOperation::Transaction`]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum KvOp {
+    /// Write bytes under a key
+    Set {
+        key: String,
+        #[serde(with = "serde_bytes")]
+        value: Vec<u8>,
+    },
+    /// Remove a key and its value
+    Delete { key: String },
+}
+
+fn bytes_repr(value: &[u8]) -> String {
+    if let Ok(s) = std::str::from_utf8(value) {
+        if s.len() < 50 {
+            format!("\"{s}\"")
+        } else {
+            format!("\"{}\"...", s.chars().take(50).collect::<String>())
+        }
+    } else {
+        format!("<binary data - {} bytes>", value.len())
+    }
+}
+
+impl std::fmt::Debug for KvOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KvOp::Set { key, value } => f
+                .debug_struct("Set")
+                .field("key", key)
+                .field("value", &format_args!("{}", bytes_repr(value)))
+                .finish(),
+            KvOp::Delete { key } => f.debug_struct("Delete").field("key", key).finish(),
+        }
+    }
 }
 
 impl std::fmt::Debug for KeyValueOperation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             KeyValueOperation::Get { key } => f.debug_struct("Get").field("key", key).finish(),
-            KeyValueOperation::Set { key, value } => {
-                let body_repr = if let Ok(s) = std::str::from_utf8(value) {
-                    if s.len() < 50 {
-                        format!("\"{s}\"")
-                    } else {
-                        format!("\"{}\"...", s.chars().take(50).collect::<String>())
-                    }
-                } else {
-                    format!("<binary data - {} bytes>", value.len())
-                };
-                f.debug_struct("Set")
-                    .field("key", key)
-                    .field("value", &format_args!("{}", body_repr))
-                    .finish()
-            }
+            KeyValueOperation::Set { key, value } => f
+                .debug_struct("Set")
+                .field("key", key)
+                .field("value", &format_args!("{}", bytes_repr(value)))
+                .finish(),
             KeyValueOperation::Delete { key } => {
                 f.debug_struct("Delete").field("key", key).finish()
             }
@@ -73,6 +105,9 @@ impl std::fmt::Debug for KeyValueOperation {
                 .field("prefix", prefix)
                 .field("cursor", cursor)
                 .finish(),
+            KeyValueOperation::Transaction { ops } => {
+                f.debug_struct("Transaction").field("ops", ops).finish()
+            }
         }
     }
 }
@@ -113,6 +148,24 @@ pub enum KeyValueResponse {
         /// include a `KeyValueError::CursorNotFound` error.
         next_cursor: u64,
     },
+    /// Response to a `KeyValueOperation::Transaction`,
+    /// returning whether the batch was committed
+    Transaction { outcome: TransactionOutcome },
+}
+
+/// The outcome of a `KeyValueOperation::Transaction`
+///
+/// Shells backed by a store that supports atomic writes should either commit all the
+/// operations or roll them all back. Shells without atomic stores should apply the
+/// operations best-effort, in order, and report the ones which failed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransactionOutcome {
+    /// All the operations were applied
+    Committed,
+    /// None of the operations were applied
+    RolledBack,
+    /// Some operations were applied, but those at the `failed` indices were not
+    PartiallyApplied { failed: Vec<u64> },
 }
 
 impl Operation for KeyValueOperation {
@@ -140,6 +193,8 @@ impl<Ev> crux_core::Capability<Ev> for KeyValue<Ev> {
     #[cfg(feature = "typegen")]
     fn register_types(generator: &mut crux_core::typegen::TypeGen) -> crux_core::typegen::Result {
         generator.register_type::<KeyValueResponse>()?;
+        generator.register_type::<TransactionOutcome>()?;
+        generator.register_type::<KvOp>()?;
         generator.register_type::<KeyValueError>()?;
         generator.register_type::<Value>()?;
         generator.register_type::<Self::Operation>()?;
@@ -164,6 +219,15 @@ where
         Self { context }
     }
 
+    /// A view of the store holding values of type `T`, which are encoded and decoded
+    /// automatically, see [`TypedStore`].
+    pub fn typed<T>(&self) -> TypedStore<T, Ev>
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + Send + 'static,
+    {
+        TypedStore::new(self.clone())
+    }
+
     /// Read a value under `key`, will dispatch the event with a
     /// `KeyValueResult::Get { value: Vec<u8> }` as payload
     pub fn get<F>(&self, key: String, make_event: F)
@@ -303,6 +367,32 @@ where
     ) -> Result<(Vec<String>, u64), KeyValueError> {
         list_keys(&self.context, prefix, cursor).await
     }
+
+    /// Apply the `ops` atomically and in order, will dispatch the event with the
+    /// [`TransactionOutcome`] reported by the shell as payload
+    pub fn transaction<F>(&self, ops: Vec<KvOp>, make_event: F)
+    where
+        F: FnOnce(Result<TransactionOutcome, KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                let response = transaction(&context, ops).await;
+                context.update_app(make_event(response))
+            }
+        });
+    }
+
+    /// Apply the `ops` atomically and in order, while in an async context. This is used
+    /// together with [`crux_core::compose::Compose`].
+    ///
+    /// Returns the [`TransactionOutcome`] reported by the shell.
+    pub async fn transaction_async(
+        &self,
+        ops: Vec<KvOp>,
+    ) -> Result<TransactionOutcome, KeyValueError> {
+        transaction(&self.context, ops).await
+    }
 }
 
 async fn get<Ev: 'static>(
@@ -357,6 +447,16 @@ async fn list_keys<Ev: 'static>(
         .unwrap_list_keys()
 }
 
+async fn transaction<Ev: 'static>(
+    context: &CapabilityContext<KeyValueOperation, Ev>,
+    ops: Vec<KvOp>,
+) -> Result<TransactionOutcome, KeyValueError> {
+    context
+        .request_from_shell(KeyValueOperation::Transaction { ops })
+        .await
+        .unwrap_transaction()
+}
+
 impl KeyValueResult {
     fn unwrap_get(self) -> Result<Option<Vec<u8>>, KeyValueError> {
         match self {
@@ -418,6 +518,18 @@ impl KeyValueResult {
             KeyValueResult::Err { error } => Err(error.clone()),
         }
     }
+
+    fn unwrap_transaction(self) -> Result<TransactionOutcome, KeyValueError> {
+        match self {
+            KeyValueResult::Ok { response } => match response {
+                KeyValueResponse::Transaction { outcome } => Ok(outcome),
+                _ => panic!(
+                    "attempt to convert KeyValueResponse other than Transaction to TransactionOutcome"
+                ),
+            },
+            KeyValueResult::Err { error } => Err(error.clone()),
+        }
+    }
 }
 
 #[cfg(test)]
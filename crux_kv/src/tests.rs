@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     error::KeyValueError, value::Value, KeyValue, KeyValueOperation, KeyValueResponse,
-    KeyValueResult,
+    KeyValueResult, KvOp, TransactionOutcome,
 };
 
 #[derive(Default)]
@@ -18,11 +18,13 @@ pub enum Event {
     Exists,
     ListKeys,
     GetThenSet,
+    Transaction,
 
     GetResponse(Result<Option<Vec<u8>>, KeyValueError>),
     SetResponse(Result<Option<Vec<u8>>, KeyValueError>),
     ExistsResponse(Result<bool, KeyValueError>),
     ListKeysResponse(Result<(Vec<String>, u64), KeyValueError>),
+    TransactionResponse(Result<TransactionOutcome, KeyValueError>),
 }
 
 #[derive(Debug, Default)]
@@ -60,6 +62,23 @@ impl crux_core::App for App {
                     .list_keys("test:".to_string(), 0, Event::ListKeysResponse)
             }
 
+            Event::Transaction => caps.key_value.transaction(
+                vec![
+                    KvOp::Set {
+                        key: "test:1".to_string(),
+                        value: 1i32.to_ne_bytes().to_vec(),
+                    },
+                    KvOp::Delete {
+                        key: "test:2".to_string(),
+                    },
+                    KvOp::Set {
+                        key: "test:3".to_string(),
+                        value: 3i32.to_ne_bytes().to_vec(),
+                    },
+                ],
+                Event::TransactionResponse,
+            ),
+
             Event::GetThenSet => caps.compose.spawn(|ctx| {
                 let kv = caps.key_value.clone();
 
@@ -102,6 +121,11 @@ impl crux_core::App for App {
                 caps.render.render()
             }
 
+            Event::TransactionResponse(Ok(outcome)) => {
+                model.successful = outcome == TransactionOutcome::Committed;
+                caps.render.render()
+            }
+
             Event::GetResponse(Err(error)) => {
                 panic!("error: {:?}", error);
             }
@@ -114,6 +138,9 @@ impl crux_core::App for App {
             Event::ListKeysResponse(Err(error)) => {
                 panic!("Error: {:?}", error);
             }
+            Event::TransactionResponse(Err(error)) => {
+                panic!("Error: {:?}", error);
+            }
         }
     }
 
@@ -176,7 +203,7 @@ fn test_set() {
         request.operation,
         KeyValueOperation::Set {
             key: "test".to_string(),
-            value: 42i32.to_ne_bytes().to_vec().into(),
+            value: 42i32.to_ne_bytes().to_vec(),
         }
     );
 
@@ -284,6 +311,48 @@ fn test_list_keys() {
     assert_eq!(model.cursor, 2);
 }
 
+#[test]
+fn test_transaction() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::Transaction, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::Transaction {
+            ops: vec![
+                KvOp::Set {
+                    key: "test:1".to_string(),
+                    value: 1i32.to_ne_bytes().to_vec(),
+                },
+                KvOp::Delete {
+                    key: "test:2".to_string(),
+                },
+                KvOp::Set {
+                    key: "test:3".to_string(),
+                    value: 3i32.to_ne_bytes().to_vec(),
+                },
+            ]
+        }
+    );
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::Transaction {
+                outcome: TransactionOutcome::Committed,
+            },
+        },
+        &mut model,
+    );
+
+    assert!(model.successful);
+}
+
 #[test]
 pub fn test_kv_async() -> Result<()> {
     let app = AppTester::<App, _>::default();
@@ -318,7 +387,7 @@ pub fn test_kv_async() -> Result<()> {
         request.operation,
         KeyValueOperation::Set {
             key: "test_num".to_string(),
-            value: 18u32.to_ne_bytes().to_vec().into(),
+            value: 18u32.to_ne_bytes().to_vec(),
         }
     );
 
@@ -385,4 +454,24 @@ fn test_kv_operation_debug_repr() {
             r#"Set { key: "my key", value: <binary data - 2 bytes> }"#
         );
     }
+
+    {
+        // transaction
+        let op = KeyValueOperation::Transaction {
+            ops: vec![
+                KvOp::Set {
+                    key: "my key".into(),
+                    value: b"my value".to_vec(),
+                },
+                KvOp::Delete {
+                    key: "other key".into(),
+                },
+            ],
+        };
+        let repr = format!("{op:?}");
+        assert_eq!(
+            repr,
+            r#"Transaction { ops: [Set { key: "my key", value: "my value" }, Delete { key: "other key" }] }"#
+        );
+    }
 }
@@ -0,0 +1,111 @@
+//! A strongly typed view of the Key-Value store
+
+use std::marker::PhantomData;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{error::KeyValueError, KeyValue};
+
+/// A view of the [`KeyValue`] store holding values of type `T`, created with [`KeyValue::typed`].
+///
+/// Values are encoded with bincode before they're stored, and decoded when they're read,
+/// so call sites don't need to handle the bytes. A value which can't be decoded as a `T`
+/// is reported as a [`KeyValueError::Other`].
+pub struct TypedStore<T, Ev> {
+    key_value: KeyValue<Ev>,
+    value: PhantomData<fn() -> T>,
+}
+
+impl<T, Ev> Clone for TypedStore<T, Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            key_value: self.key_value.clone(),
+            value: PhantomData,
+        }
+    }
+}
+
+impl<T, Ev> TypedStore<T, Ev>
+where
+    T: Serialize + DeserializeOwned + Send + 'static,
+    Ev: 'static,
+{
+    pub fn new(key_value: KeyValue<Ev>) -> Self {
+        Self {
+            key_value,
+            value: PhantomData,
+        }
+    }
+
+    /// Read the value under `key`, will dispatch the event with the decoded value,
+    /// or `None` if the key is not present
+    pub fn get<F>(&self, key: String, make_event: F)
+    where
+        F: FnOnce(Result<Option<T>, KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        let this = self.clone();
+
+        self.key_value.context.spawn({
+            let context = self.key_value.context.clone();
+            async move {
+                let response = this.get_async(key).await;
+                context.update_app(make_event(response));
+            }
+        });
+    }
+
+    /// Read the value under `key`, while in an async context. This is used together with
+    /// [`crux_core::compose::Compose`].
+    ///
+    /// Returns the decoded value, or `None` if the key is not present.
+    pub async fn get_async(&self, key: String) -> Result<Option<T>, KeyValueError> {
+        self.key_value.get_async(key).await.and_then(decode)
+    }
+
+    /// Set `key` to be the encoded `value`, will dispatch the event with the
+    /// previous value, if any
+    pub fn set<F>(&self, key: String, value: &T, make_event: F)
+    where
+        F: FnOnce(Result<Option<T>, KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        let this = self.clone();
+        let value = encode(value);
+
+        self.key_value.context.spawn({
+            let context = self.key_value.context.clone();
+            async move {
+                let response = match value {
+                    Ok(value) => this.key_value.set_async(key, value).await.and_then(decode),
+                    Err(error) => Err(error),
+                };
+                context.update_app(make_event(response));
+            }
+        });
+    }
+
+    /// Set `key` to be the encoded `value`, while in an async context. This is used together with
+    /// [`crux_core::compose::Compose`].
+    ///
+    /// Returns the previous value stored under the key, if any.
+    pub async fn set_async(&self, key: String, value: &T) -> Result<Option<T>, KeyValueError> {
+        let value = encode(value)?;
+
+        self.key_value.set_async(key, value).await.and_then(decode)
+    }
+}
+
+fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, KeyValueError> {
+    bincode::serialize(value).map_err(|e| KeyValueError::Other {
+        message: format!("failed to encode value: {e}"),
+    })
+}
+
+fn decode<T: DeserializeOwned>(bytes: Option<Vec<u8>>) -> Result<Option<T>, KeyValueError> {
+    bytes
+        .map(|bytes| {
+            bincode::deserialize(&bytes).map_err(|e| KeyValueError::Other {
+                message: format!("failed to decode value: {e}"),
+            })
+        })
+        .transpose()
+}
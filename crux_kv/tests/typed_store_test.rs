@@ -0,0 +1,177 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_kv::{error::KeyValueError, KeyValue};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Settings {
+        pub theme: String,
+        pub font_size: u8,
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Load,
+        Save(Settings),
+
+        #[serde(skip)]
+        Loaded(Result<Option<Settings>, KeyValueError>),
+        #[serde(skip)]
+        Saved(Result<Option<Settings>, KeyValueError>),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub settings: Option<Settings>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            let settings = caps.key_value.typed::<Settings>();
+
+            match event {
+                Event::Load => settings.get("settings".to_string(), Event::Loaded),
+                Event::Save(value) => settings.set("settings".to_string(), &value, Event::Saved),
+                Event::Loaded(Ok(value)) => {
+                    model.settings = value;
+                    caps.render.render();
+                }
+                Event::Saved(Ok(_)) => caps.render.render(),
+                Event::Loaded(Err(_)) | Event::Saved(Err(_)) => {}
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub key_value: KeyValue<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Event, Model, Settings};
+    use crux_core::testing::AppTester;
+    use crux_kv::{value::Value, KeyValueOperation, KeyValueResponse, KeyValueResult};
+
+    fn settings() -> Settings {
+        Settings {
+            theme: "dark".to_string(),
+            font_size: 14,
+        }
+    }
+
+    #[test]
+    fn stores_and_retrieves_a_struct() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Save(settings()), &mut model)
+            .expect_one_effect()
+            .expect_key_value();
+
+        let KeyValueOperation::Set { key, value: bytes } = request.operation.clone() else {
+            panic!("Expected a set operation");
+        };
+        assert_eq!(key, "settings");
+
+        let event = app
+            .resolve(
+                &mut request,
+                KeyValueResult::Ok {
+                    response: KeyValueResponse::Set {
+                        previous: Value::None,
+                    },
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Saved(Ok(None)));
+
+        let mut request = app
+            .update(Event::Load, &mut model)
+            .expect_one_effect()
+            .expect_key_value();
+
+        assert_eq!(
+            request.operation,
+            KeyValueOperation::Get {
+                key: "settings".to_string()
+            }
+        );
+
+        let event = app
+            .resolve(
+                &mut request,
+                KeyValueResult::Ok {
+                    response: KeyValueResponse::Get {
+                        value: Value::Bytes(bytes),
+                    },
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Loaded(Ok(Some(settings()))));
+
+        let _update = app.update(event, &mut model);
+        assert_eq!(model.settings, Some(settings()));
+    }
+
+    #[test]
+    fn missing_key_resolves_to_none() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Load, &mut model)
+            .expect_one_effect()
+            .expect_key_value();
+
+        let event = app
+            .resolve(
+                &mut request,
+                KeyValueResult::Ok {
+                    response: KeyValueResponse::Get { value: Value::None },
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Loaded(Ok(None)));
+    }
+
+    #[test]
+    fn undecodable_value_is_an_error() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Load, &mut model)
+            .expect_one_effect()
+            .expect_key_value();
+
+        let event = app
+            .resolve(
+                &mut request,
+                KeyValueResult::Ok {
+                    response: KeyValueResponse::Get {
+                        value: Value::Bytes(vec![1]),
+                    },
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert!(matches!(event, Event::Loaded(Err(_))));
+    }
+}
@@ -0,0 +1,128 @@
+//! Localized strings for Crux apps
+//!
+//! `crux_localization` allows Crux apps to look up localized strings in the Shell's native string
+//! catalogs by key. The Shell interpolates the named arguments into the string before returning it.
+//! Apps can also read the active locale, as a BCP 47 language tag (e.g. `en-GB`).
+
+use std::collections::BTreeMap;
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use serde::{Deserialize, Serialize};
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LocalizationOperation {
+    /// Look up the string for the `key`, interpolating the named `args`
+    String {
+        key: String,
+        args: BTreeMap<String, String>,
+    },
+    /// Read the active locale
+    Locale,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LocalizationResponse {
+    /// Response to a `LocalizationOperation::String`, with the arguments interpolated
+    String(String),
+    /// Response to a `LocalizationOperation::Locale`, the BCP 47 tag of the active locale
+    Locale(String),
+}
+
+impl Operation for LocalizationOperation {
+    type Output = LocalizationResponse;
+}
+
+#[derive(Capability)]
+pub struct Localization<Ev> {
+    context: CapabilityContext<LocalizationOperation, Ev>,
+}
+
+impl<Ev> Clone for Localization<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Localization<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<LocalizationOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Look up the localized string for the `key`, with the named `args` interpolated.
+    /// The string is passed to the app wrapped in the event produced by the `callback`.
+    pub fn string<F>(&self, key: impl Into<String>, args: BTreeMap<String, String>, callback: F)
+    where
+        F: FnOnce(String) -> Ev + Send + 'static,
+    {
+        let key = key.into();
+
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.string_async(key, args).await));
+            }
+        });
+    }
+
+    /// Look up the localized string for the `key`, with the named `args` interpolated.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn string_async(
+        &self,
+        key: impl Into<String>,
+        args: BTreeMap<String, String>,
+    ) -> String {
+        let key = key.into();
+
+        match self
+            .context
+            .request_from_shell(LocalizationOperation::String {
+                key: key.clone(),
+                args,
+            })
+            .await
+        {
+            LocalizationResponse::String(value) => value,
+            // fall back to the key, like a missing translation
+            LocalizationResponse::Locale(_) => key,
+        }
+    }
+
+    /// Read the active locale, which will be passed to the app as a BCP 47 language tag
+    /// wrapped in the event produced by the `callback`.
+    pub fn locale<F>(&self, callback: F)
+    where
+        F: FnOnce(String) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.locale_async().await));
+            }
+        });
+    }
+
+    /// Read the active locale as a BCP 47 language tag.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn locale_async(&self) -> String {
+        match self
+            .context
+            .request_from_shell(LocalizationOperation::Locale)
+            .await
+        {
+            LocalizationResponse::Locale(tag) => tag,
+            // "und" is the tag for an undetermined language
+            LocalizationResponse::String(_) => "und".to_string(),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+mod shared {
+    use std::collections::BTreeMap;
+
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_localization::Localization;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Greet(String),
+        DetectLocale,
+
+        #[serde(skip)]
+        SetGreeting(String),
+        #[serde(skip)]
+        SetLocale(String),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub greeting: String,
+        pub locale: String,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub greeting: String,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Greet(name) => caps.localization.string(
+                    "greeting",
+                    BTreeMap::from([("name".to_string(), name)]),
+                    Event::SetGreeting,
+                ),
+                Event::DetectLocale => caps.localization.locale(Event::SetLocale),
+                Event::SetGreeting(greeting) => {
+                    model.greeting = greeting;
+                    caps.render.render();
+                }
+                Event::SetLocale(locale) => {
+                    model.locale = locale;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                greeting: model.greeting.clone(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub localization: Localization<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_localization::{LocalizationOperation, LocalizationResponse};
+
+    #[test]
+    pub fn test_string_with_args() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Greet("Ada".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_localization();
+
+        assert_eq!(
+            request.operation,
+            LocalizationOperation::String {
+                key: "greeting".to_string(),
+                args: BTreeMap::from([("name".to_string(), "Ada".to_string())]),
+            }
+        );
+
+        let event = app
+            .resolve(
+                &mut request,
+                LocalizationResponse::String("Bonjour, Ada !".to_string()),
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::SetGreeting("Bonjour, Ada !".to_string()));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(app.view(&model).greeting, "Bonjour, Ada !");
+    }
+
+    #[test]
+    pub fn test_locale() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::DetectLocale, &mut model)
+            .expect_one_effect()
+            .expect_localization();
+
+        assert_eq!(request.operation, LocalizationOperation::Locale);
+
+        let event = app
+            .resolve(
+                &mut request,
+                LocalizationResponse::Locale("fr-FR".to_string()),
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::SetLocale("fr-FR".to_string()));
+    }
+}
@@ -45,6 +45,23 @@ impl ToTokens for CapabilityStructReceiver {
               #name::new(self.context.map_event(f))
             }
           }
+
+          impl<Ev> #name<Ev>
+          where
+            Ev: 'static + Send,
+          {
+            /// A copy of this capability attaching the `tag` to every request it sends
+            /// to the shell, to correlate the requests in logs and traces
+            pub fn tagged(&self, tag: &'static str) -> Self {
+              #name::new(self.context.tagged(tag))
+            }
+
+            /// A copy of this capability whose requests depend on the request tagged with
+            /// `tag`, for the shell to carry them out once that one is resolved
+            pub fn depending_on(&self, tag: &'static str) -> Self {
+              #name::new(self.context.depending_on(tag))
+            }
+          }
         })
     }
 }
@@ -109,7 +126,7 @@ mod tests {
 
         let actual = quote!(#input);
 
-        insta::assert_snapshot!(pretty_print(&actual), @r###"
+        insta::assert_snapshot!(pretty_print(&actual), @"
         impl<Ev> crux_core::capability::Capability<Ev> for Render<Ev> {
             type Operation = RenderOperation;
             type MappedSelf<MappedEv> = Render<MappedEv>;
@@ -122,7 +139,22 @@ mod tests {
                 Render::new(self.context.map_event(f))
             }
         }
-        "###);
+        impl<Ev> Render<Ev>
+        where
+            Ev: 'static + Send,
+        {
+            /// A copy of this capability attaching the `tag` to every request it sends
+            /// to the shell, to correlate the requests in logs and traces
+            pub fn tagged(&self, tag: &'static str) -> Self {
+                Render::new(self.context.tagged(tag))
+            }
+            /// A copy of this capability whose requests depend on the request tagged with
+            /// `tag`, for the shell to carry them out once that one is resolved
+            pub fn depending_on(&self, tag: &'static str) -> Self {
+                Render::new(self.context.depending_on(tag))
+            }
+        }
+        ");
     }
 
     #[test]
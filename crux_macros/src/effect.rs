@@ -3,23 +3,34 @@ use proc_macro2::{Literal, TokenStream};
 use proc_macro_error::{abort_call_site, OptionExt};
 use quote::{format_ident, quote};
 use std::collections::BTreeMap;
-use syn::{DeriveInput, GenericArgument, Ident, PathArguments, Type};
+use syn::{
+    parse_quote, Attribute, DeriveInput, GenericArgument, Generics, Ident, PathArguments, Type,
+};
 
 #[derive(FromDeriveInput, Debug)]
 #[darling(attributes(effect), supports(struct_named))]
 struct EffectStructReceiver {
     ident: Ident,
+    generics: Generics,
     name: Option<Ident>,
+    #[darling(default)]
+    hash: bool,
+    #[darling(default)]
+    convert: bool,
     data: ast::Data<util::Ignored, EffectFieldReceiver>,
 }
 
 #[derive(FromField, Debug)]
-#[darling(attributes(effect))]
+#[darling(attributes(effect), forward_attrs(cfg))]
 pub struct EffectFieldReceiver {
     ident: Option<Ident>,
     ty: Type,
+    attrs: Vec<Attribute>,
     #[darling(default)]
     skip: bool,
+    variant: Option<Ident>,
+    discriminant: Option<u32>,
+    priority: Option<i32>,
 }
 
 struct Field {
@@ -27,16 +38,23 @@ struct Field {
     variant: Ident,
     event: Type,
     skip: bool,
+    discriminant: Option<u32>,
+    priority: Option<i32>,
+    // `#[cfg]` attributes, repeated on everything generated for the field
+    cfgs: Vec<Attribute>,
 }
 
 impl From<&EffectFieldReceiver> for Field {
     fn from(f: &EffectFieldReceiver) -> Self {
-        let (capability, variant, event) = split_on_generic(&f.ty);
+        let (capability, type_name, event) = split_on_generic(&f.ty);
         Field {
             capability,
-            variant,
+            variant: f.variant.clone().unwrap_or(type_name),
             event,
             skip: f.skip,
+            discriminant: f.discriminant,
+            priority: f.priority,
+            cfgs: f.attrs.clone(),
         }
     }
 }
@@ -78,10 +96,104 @@ impl ToTokens for EffectStructReceiver {
             .first()
             .expect_or_abort("Capabilities struct has no fields");
 
+        // Variants are ordered by field name, unless their discriminants are declared,
+        // in which case those decide the position of each variant in the FFI enum
+        let mut fields: Vec<(&Ident, &Field)> = fields.iter().collect();
+        // the names of all the capabilities, skipped or not, in the order they're declared
+        let capability_names: Vec<_> = fields
+            .iter()
+            .map(|(field_name, Field { cfgs, .. })| {
+                let field_name = field_name.to_string();
+                quote! { #(#cfgs)* #field_name }
+            })
+            .collect();
+        let discriminants: Vec<Option<u32>> = fields
+            .iter()
+            .filter(|(_, field)| !field.skip)
+            .map(|(_, field)| field.discriminant)
+            .collect();
+        if discriminants.iter().any(Option::is_some) {
+            let mut declared: Vec<u32> = discriminants
+                .iter()
+                .map(|discriminant| {
+                    discriminant.unwrap_or_else(|| {
+                        abort_call_site!("either all or none of the effect variants should declare a discriminant")
+                    })
+                })
+                .collect();
+            declared.sort_unstable();
+            if !declared.iter().copied().eq(0..declared.len() as u32) {
+                abort_call_site!(
+                    "effect variant discriminants should be unique and numbered from 0 without gaps"
+                );
+            }
+
+            fields.sort_by_key(|(_, field)| field.discriminant);
+        }
+
+        // A capabilities struct generic over the event type makes for a generic Effect,
+        // e.g. `Effect<Ev>`. The type parameters are only used by the operation types
+        // of the variants, so the enums carry them in an extra, uninhabited variant
+        if self.generics.lifetimes().next().is_some() {
+            abort_call_site!(
+                "capabilities can't be generic over lifetimes, effects must be 'static"
+            );
+        }
+        let mut generics = self.generics.clone();
+        let type_params: Vec<_> = self.generics.type_params().map(|p| &p.ident).collect();
+        for param in &type_params {
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote!(#param: Send + 'static));
+        }
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+        let (phantom_variant, ffi_phantom_variant, phantom_arm, ffi_serde_bound) = if type_params
+            .is_empty()
+        {
+            (quote!(), quote!(), quote!(), quote!())
+        } else {
+            // serde would require the type parameters themselves to be (de)serializable,
+            // when only the operations need to be
+            let deserialize_bound = fields
+                .iter()
+                .filter(|(_, field)| !field.skip)
+                .map(|(_, Field { capability, event, .. })| {
+                    quote!(<#capability<#event> as ::crux_core::capability::Capability<#event>>::Operation: ::serde::Deserialize<'de>)
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            (
+                quote! {
+                    #[doc(hidden)]
+                    __Phantom(::std::marker::PhantomData<fn() -> (#(#type_params ,)*)>, ::std::convert::Infallible),
+                },
+                quote! {
+                    #[doc(hidden)]
+                    #[serde(skip)]
+                    __Phantom(::std::marker::PhantomData<fn() -> (#(#type_params ,)*)>, ::std::convert::Infallible),
+                },
+                quote! { #effect_name::__Phantom(_, never) => match never {}, },
+                quote! { #[serde(bound(serialize = "", deserialize = #deserialize_bound))] },
+            )
+        };
+
         let mut variants = Vec::new();
         let mut with_context_fields = Vec::new();
         let mut ffi_variants = Vec::new();
         let mut match_arms = Vec::new();
+        let mut raw_output_arms = Vec::new();
+        let mut coalesce_arms = Vec::new();
+        let mut version_arms = Vec::new();
+        let mut priority_arms = Vec::new();
+        let mut name_arms = Vec::new();
+        let mut tag_arms = Vec::new();
+        let mut depends_on_arms = Vec::new();
+        let mut variant_names = Vec::new();
+        let mut from_arms = Vec::new();
+        let mut resolve_ffi_arms = Vec::new();
         let mut filters = Vec::new();
 
         for (
@@ -91,33 +203,58 @@ impl ToTokens for EffectStructReceiver {
                 variant,
                 event,
                 skip,
+                priority,
+                cfgs,
+                ..
             },
-        ) in fields.iter()
+        ) in fields
         {
             if *skip {
                 let msg = format!("Requesting effects from capability \"{variant}\" is impossible because it was skipped",);
                 with_context_fields.push(quote! {
+                    #(#cfgs)*
                     #field_name: #capability::new(context.specialize(|_| unreachable!(#msg)))
                 });
             } else {
                 with_context_fields.push(quote! {
+                    #(#cfgs)*
                     #field_name: #capability::new(context.specialize(#effect_name::#variant))
                 });
 
                 variants.push(quote! {
+                    #(#cfgs)*
                     #variant(::crux_core::Request<<#capability<#event> as ::crux_core::capability::Capability<#event>>::Operation>)
                 });
 
-                ffi_variants.push(quote! { #variant(<#capability<#event> as ::crux_core::capability::Capability<#event>>::Operation) });
+                ffi_variants.push(quote! { #(#cfgs)* #variant(<#capability<#event> as ::crux_core::capability::Capability<#event>>::Operation) });
 
-                match_arms.push(quote! { #effect_name::#variant(request) => request.serialize(#ffi_effect_name::#variant) });
+                match_arms.push(quote! { #(#cfgs)* #effect_name::#variant(request) => request.serialize(#ffi_effect_name::#variant) });
+                raw_output_arms.push(quote! { #(#cfgs)* #effect_name::#variant(_) => <<#capability<#event> as ::crux_core::capability::Capability<#event>>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT });
+                coalesce_arms.push(quote! { #(#cfgs)* #effect_name::#variant(_) => <<#capability<#event> as ::crux_core::capability::Capability<#event>>::Operation as ::crux_core::capability::Operation>::COALESCE });
+                version_arms.push(quote! { #(#cfgs)* #effect_name::#variant(_) => <<#capability<#event> as ::crux_core::capability::Capability<#event>>::Operation as ::crux_core::capability::Operation>::VERSION });
+                let variant_name = variant.to_string();
+                name_arms.push(quote! { #(#cfgs)* #effect_name::#variant(_) => #variant_name });
+                tag_arms.push(
+                    quote! { #(#cfgs)* #effect_name::#variant(ref request) => request.tag() },
+                );
+                depends_on_arms.push(
+                    quote! { #(#cfgs)* #effect_name::#variant(ref request) => request.depends_on() },
+                );
+                variant_names.push(quote! { #(#cfgs)* #variant_name });
+                from_arms.push(quote! { #(#cfgs)* #effect_name::#variant(ref request) => #ffi_effect_name::#variant(request.operation.clone()) });
+                resolve_ffi_arms.push(quote! { #(#cfgs)* #effect_name::#variant(ref mut request) => request.resolve_deserialized(output) });
+                if let Some(priority) = priority {
+                    priority_arms.push(quote! { #(#cfgs)* #effect_name::#variant(_) => #priority });
+                }
 
                 let filter_fn = format_ident!("is_{}", field_name);
+                let as_fn = format_ident!("as_{}", field_name);
                 let map_fn = format_ident!("into_{}", field_name);
                 let expect_fn = format_ident!("expect_{}", field_name);
                 let name_as_str = field_name.to_string();
                 filters.push(quote! {
-                    impl #effect_name {
+                    #(#cfgs)*
+                    impl #impl_generics #effect_name #type_generics #where_clause {
                         pub fn #filter_fn(&self) -> bool {
                             if let #effect_name::#variant(_) = self {
                                 true
@@ -125,6 +262,13 @@ impl ToTokens for EffectStructReceiver {
                                 false
                             }
                         }
+                        pub fn #as_fn(&self) -> Option<&<#capability<#event> as ::crux_core::capability::Capability<#event>>::Operation> {
+                            if let #effect_name::#variant(request) = self {
+                                Some(&request.operation)
+                            } else {
+                                None
+                            }
+                        }
                         pub fn #map_fn(self) -> Option<crux_core::Request<<#capability<#event> as ::crux_core::capability::Capability<#event>>::Operation>> {
                             if let #effect_name::#variant(request) = self {
                                 Some(request)
@@ -144,36 +288,153 @@ impl ToTokens for EffectStructReceiver {
             }
         }
 
+        // Only effects declaring a priority need the method, the rest keep the default
+        let priority_fn = if priority_arms.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                fn priority(&self) -> i32 {
+                    match *self {
+                        #(#priority_arms ,)*
+                        #[allow(unreachable_patterns)]
+                        _ => 0,
+                    }
+                }
+            }
+        };
+
+        let ffi_derive_hash = if self.hash {
+            quote! { #[derive(PartialEq, Eq, Hash)] }
+        } else {
+            quote! {}
+        };
+
+        // Only effects with the `convert` option get the conversion to and from the FFI enum
+        let convert = if self.convert {
+            quote! {
+                impl #impl_generics ::std::convert::From<&#effect_name #type_generics> for #ffi_effect_name #type_generics #where_clause {
+                    fn from(effect: &#effect_name #type_generics) -> Self {
+                        match *effect {
+                            #(#from_arms ,)*
+                            #phantom_arm
+                        }
+                    }
+                }
+
+                impl #impl_generics #effect_name #type_generics #where_clause {
+                    /// Resolve the request carried by this effect with the shell's response to
+                    /// its FFI counterpart, deserialized from `output`
+                    pub fn resolve_ffi<'de, D>(&mut self, output: D) -> ::std::result::Result<(), ::crux_core::ResolveError>
+                    where
+                        D: ::serde::Deserializer<'de>,
+                    {
+                        match *self {
+                            #(#resolve_ffi_arms ,)*
+                            #phantom_arm
+                        }
+                    }
+                }
+            }
+        } else {
+            quote! {}
+        };
+
         tokens.extend(quote! {
             #[derive(Debug)]
-            pub enum #effect_name {
+            pub enum #effect_name #impl_generics #where_clause {
                 #(#variants ,)*
+                #phantom_variant
             }
 
             #[derive(::serde::Serialize, ::serde::Deserialize)]
+            #ffi_derive_hash
             #[serde(rename = #ffi_effect_rename)]
-            pub enum #ffi_effect_name {
+            #ffi_serde_bound
+            pub enum #ffi_effect_name #impl_generics #where_clause {
                 #(#ffi_variants ,)*
+                #ffi_phantom_variant
             }
 
-            impl ::crux_core::Effect for #effect_name {
-                type Ffi = #ffi_effect_name;
+            impl #impl_generics ::crux_core::Effect for #effect_name #type_generics #where_clause {
+                type Ffi = #ffi_effect_name #type_generics;
 
                 fn serialize(self) -> (Self::Ffi, ::crux_core::bridge::ResolveSerialized) {
                     match self {
                         #(#match_arms ,)*
+                        #phantom_arm
+                    }
+                }
+
+                fn has_raw_output(&self) -> bool {
+                    match *self {
+                        #(#raw_output_arms ,)*
+                        #phantom_arm
+                    }
+                }
+
+                fn coalesces(&self) -> bool {
+                    match *self {
+                        #(#coalesce_arms ,)*
+                        #phantom_arm
+                    }
+                }
+
+                fn version(&self) -> u32 {
+                    match *self {
+                        #(#version_arms ,)*
+                        #phantom_arm
+                    }
+                }
+
+                #priority_fn
+
+                fn name(&self) -> &'static str {
+                    match *self {
+                        #(#name_arms ,)*
+                        #phantom_arm
+                    }
+                }
+
+                fn tag(&self) -> Option<&'static str> {
+                    match *self {
+                        #(#tag_arms ,)*
+                        #phantom_arm
+                    }
+                }
+
+                fn depends_on(&self) -> Option<&'static str> {
+                    match *self {
+                        #(#depends_on_arms ,)*
+                        #phantom_arm
                     }
                 }
             }
 
-            impl ::crux_core::WithContext<#event, #effect_name> for #ident {
-                fn new_with_context(context: ::crux_core::capability::ProtoContext<#effect_name, #event>) -> #ident {
+            impl #impl_generics #effect_name #type_generics #where_clause {
+                /// The names of the effect variants, in the order of the FFI enum
+                pub const VARIANT_NAMES: &'static [&'static str] = &[#(#variant_names),*];
+                /// The number of effect variants, one for each capability which isn't skipped
+                pub const VARIANT_COUNT: usize = Self::VARIANT_NAMES.len();
+            }
+
+            impl #impl_generics #ident #type_generics #where_clause {
+                /// The names of the capabilities, in the order they're declared, including
+                /// those skipped with `#[effect(skip)]`
+                pub fn capabilities() -> &'static [&'static str] {
+                    &[#(#capability_names),*]
+                }
+            }
+
+            impl #impl_generics ::crux_core::WithContext<#event, #effect_name #type_generics> for #ident #type_generics #where_clause {
+                fn new_with_context(context: ::crux_core::capability::ProtoContext<#effect_name #type_generics, #event>) -> #ident #type_generics {
                     #ident {
                         #(#with_context_fields ,)*
                     }
                 }
             }
 
+            #convert
+
             #(#filters)*
         })
     }
@@ -261,6 +522,67 @@ mod tests {
                     Effect::Render(request) => request.serialize(EffectFfi::Render),
                 }
             }
+            fn has_raw_output(&self) -> bool {
+                match *self {
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                }
+            }
+            fn coalesces(&self) -> bool {
+                match *self {
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                }
+            }
+            fn version(&self) -> u32 {
+                match *self {
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                }
+            }
+            fn name(&self) -> &'static str {
+                match *self {
+                    Effect::Render(_) => "Render",
+                }
+            }
+            fn tag(&self) -> Option<&'static str> {
+                match *self {
+                    Effect::Render(ref request) => request.tag(),
+                }
+            }
+            fn depends_on(&self) -> Option<&'static str> {
+                match *self {
+                    Effect::Render(ref request) => request.depends_on(),
+                }
+            }
+        }
+        impl Effect {
+            /// The names of the effect variants, in the order of the FFI enum
+            pub const VARIANT_NAMES: &'static [&'static str] = &["Render"];
+            /// The number of effect variants, one for each capability which isn't skipped
+            pub const VARIANT_COUNT: usize = Self::VARIANT_NAMES.len();
+        }
+        impl Capabilities {
+            /// The names of the capabilities, in the order they're declared, including
+            /// those skipped with `#[effect(skip)]`
+            pub fn capabilities() -> &'static [&'static str] {
+                &["render"]
+            }
         }
         impl ::crux_core::WithContext<Event, Effect> for Capabilities {
             fn new_with_context(
@@ -275,6 +597,13 @@ mod tests {
             pub fn is_render(&self) -> bool {
                 if let Effect::Render(_) = self { true } else { false }
             }
+            pub fn as_render(
+                &self,
+            ) -> Option<
+                &<Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::Render(request) = self { Some(&request.operation) } else { None }
+            }
             pub fn into_render(
                 self,
             ) -> Option<
@@ -335,6 +664,67 @@ mod tests {
                     Effect::Render(request) => request.serialize(EffectFfi::Render),
                 }
             }
+            fn has_raw_output(&self) -> bool {
+                match *self {
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                }
+            }
+            fn coalesces(&self) -> bool {
+                match *self {
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                }
+            }
+            fn version(&self) -> u32 {
+                match *self {
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                }
+            }
+            fn name(&self) -> &'static str {
+                match *self {
+                    Effect::Render(_) => "Render",
+                }
+            }
+            fn tag(&self) -> Option<&'static str> {
+                match *self {
+                    Effect::Render(ref request) => request.tag(),
+                }
+            }
+            fn depends_on(&self) -> Option<&'static str> {
+                match *self {
+                    Effect::Render(ref request) => request.depends_on(),
+                }
+            }
+        }
+        impl Effect {
+            /// The names of the effect variants, in the order of the FFI enum
+            pub const VARIANT_NAMES: &'static [&'static str] = &["Render"];
+            /// The number of effect variants, one for each capability which isn't skipped
+            pub const VARIANT_COUNT: usize = Self::VARIANT_NAMES.len();
+        }
+        impl Capabilities {
+            /// The names of the capabilities, in the order they're declared, including
+            /// those skipped with `#[effect(skip)]`
+            pub fn capabilities() -> &'static [&'static str] {
+                &["compose", "render"]
+            }
         }
         impl ::crux_core::WithContext<Event, Effect> for Capabilities {
             fn new_with_context(
@@ -357,6 +747,13 @@ mod tests {
             pub fn is_render(&self) -> bool {
                 if let Effect::Render(_) = self { true } else { false }
             }
+            pub fn as_render(
+                &self,
+            ) -> Option<
+                &<Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::Render(request) = self { Some(&request.operation) } else { None }
+            }
             pub fn into_render(
                 self,
             ) -> Option<
@@ -462,6 +859,169 @@ mod tests {
                     MyEffect::Time(request) => request.serialize(MyEffectFfi::Time),
                 }
             }
+            fn has_raw_output(&self) -> bool {
+                match *self {
+                    MyEffect::Http(_) => {
+                        <<crux_http::Http<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                    MyEffect::KeyValue(_) => {
+                        <<KeyValue<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                    MyEffect::Platform(_) => {
+                        <<Platform<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                    MyEffect::Render(_) => {
+                        <<Render<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                    MyEffect::Time(_) => {
+                        <<Time<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                }
+            }
+            fn coalesces(&self) -> bool {
+                match *self {
+                    MyEffect::Http(_) => {
+                        <<crux_http::Http<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                    MyEffect::KeyValue(_) => {
+                        <<KeyValue<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                    MyEffect::Platform(_) => {
+                        <<Platform<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                    MyEffect::Render(_) => {
+                        <<Render<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                    MyEffect::Time(_) => {
+                        <<Time<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                }
+            }
+            fn version(&self) -> u32 {
+                match *self {
+                    MyEffect::Http(_) => {
+                        <<crux_http::Http<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                    MyEffect::KeyValue(_) => {
+                        <<KeyValue<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                    MyEffect::Platform(_) => {
+                        <<Platform<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                    MyEffect::Render(_) => {
+                        <<Render<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                    MyEffect::Time(_) => {
+                        <<Time<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                }
+            }
+            fn name(&self) -> &'static str {
+                match *self {
+                    MyEffect::Http(_) => "Http",
+                    MyEffect::KeyValue(_) => "KeyValue",
+                    MyEffect::Platform(_) => "Platform",
+                    MyEffect::Render(_) => "Render",
+                    MyEffect::Time(_) => "Time",
+                }
+            }
+            fn tag(&self) -> Option<&'static str> {
+                match *self {
+                    MyEffect::Http(ref request) => request.tag(),
+                    MyEffect::KeyValue(ref request) => request.tag(),
+                    MyEffect::Platform(ref request) => request.tag(),
+                    MyEffect::Render(ref request) => request.tag(),
+                    MyEffect::Time(ref request) => request.tag(),
+                }
+            }
+            fn depends_on(&self) -> Option<&'static str> {
+                match *self {
+                    MyEffect::Http(ref request) => request.depends_on(),
+                    MyEffect::KeyValue(ref request) => request.depends_on(),
+                    MyEffect::Platform(ref request) => request.depends_on(),
+                    MyEffect::Render(ref request) => request.depends_on(),
+                    MyEffect::Time(ref request) => request.depends_on(),
+                }
+            }
+        }
+        impl MyEffect {
+            /// The names of the effect variants, in the order of the FFI enum
+            pub const VARIANT_NAMES: &'static [&'static str] = &[
+                "Http",
+                "KeyValue",
+                "Platform",
+                "Render",
+                "Time",
+            ];
+            /// The number of effect variants, one for each capability which isn't skipped
+            pub const VARIANT_COUNT: usize = Self::VARIANT_NAMES.len();
+        }
+        impl MyCapabilities {
+            /// The names of the capabilities, in the order they're declared, including
+            /// those skipped with `#[effect(skip)]`
+            pub fn capabilities() -> &'static [&'static str] {
+                &["http", "key_value", "platform", "render", "time"]
+            }
         }
         impl ::crux_core::WithContext<MyEvent, MyEffect> for MyCapabilities {
             fn new_with_context(
@@ -480,6 +1040,15 @@ mod tests {
             pub fn is_http(&self) -> bool {
                 if let MyEffect::Http(_) = self { true } else { false }
             }
+            pub fn as_http(
+                &self,
+            ) -> Option<
+                &<crux_http::Http<
+                    MyEvent,
+                > as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            > {
+                if let MyEffect::Http(request) = self { Some(&request.operation) } else { None }
+            }
             pub fn into_http(
                 self,
             ) -> Option<
@@ -509,6 +1078,17 @@ mod tests {
             pub fn is_key_value(&self) -> bool {
                 if let MyEffect::KeyValue(_) = self { true } else { false }
             }
+            pub fn as_key_value(
+                &self,
+            ) -> Option<
+                &<KeyValue<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            > {
+                if let MyEffect::KeyValue(request) = self {
+                    Some(&request.operation)
+                } else {
+                    None
+                }
+            }
             pub fn into_key_value(
                 self,
             ) -> Option<
@@ -536,6 +1116,17 @@ mod tests {
             pub fn is_platform(&self) -> bool {
                 if let MyEffect::Platform(_) = self { true } else { false }
             }
+            pub fn as_platform(
+                &self,
+            ) -> Option<
+                &<Platform<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            > {
+                if let MyEffect::Platform(request) = self {
+                    Some(&request.operation)
+                } else {
+                    None
+                }
+            }
             pub fn into_platform(
                 self,
             ) -> Option<
@@ -563,6 +1154,17 @@ mod tests {
             pub fn is_render(&self) -> bool {
                 if let MyEffect::Render(_) = self { true } else { false }
             }
+            pub fn as_render(
+                &self,
+            ) -> Option<
+                &<Render<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            > {
+                if let MyEffect::Render(request) = self {
+                    Some(&request.operation)
+                } else {
+                    None
+                }
+            }
             pub fn into_render(
                 self,
             ) -> Option<
@@ -588,6 +1190,13 @@ mod tests {
             pub fn is_time(&self) -> bool {
                 if let MyEffect::Time(_) = self { true } else { false }
             }
+            pub fn as_time(
+                &self,
+            ) -> Option<
+                &<Time<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            > {
+                if let MyEffect::Time(request) = self { Some(&request.operation) } else { None }
+            }
             pub fn into_time(
                 self,
             ) -> Option<
@@ -612,6 +1221,1516 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn full_with_convert() {
+        let input = r#"
+            #[derive(Effect)]
+            #[effect(name = "MyEffect", convert)]
+            pub struct MyCapabilities {
+                pub http: crux_http::Http<MyEvent>,
+                pub key_value: KeyValue<MyEvent>,
+                pub platform: Platform<MyEvent>,
+                pub render: Render<MyEvent>,
+                pub time: Time<MyEvent>,
+            }
+        "#;
+        let input = parse_str(input).unwrap();
+        let input = EffectStructReceiver::from_derive_input(&input).unwrap();
+
+        let actual = quote!(#input);
+
+        insta::assert_snapshot!(pretty_print(&actual), @r###"
+        #[derive(Debug)]
+        pub enum MyEffect {
+            Http(
+                ::crux_core::Request<
+                    <crux_http::Http<
+                        MyEvent,
+                    > as ::crux_core::capability::Capability<MyEvent>>::Operation,
+                >,
+            ),
+            KeyValue(
+                ::crux_core::Request<
+                    <KeyValue<
+                        MyEvent,
+                    > as ::crux_core::capability::Capability<MyEvent>>::Operation,
+                >,
+            ),
+            Platform(
+                ::crux_core::Request<
+                    <Platform<
+                        MyEvent,
+                    > as ::crux_core::capability::Capability<MyEvent>>::Operation,
+                >,
+            ),
+            Render(
+                ::crux_core::Request<
+                    <Render<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+                >,
+            ),
+            Time(
+                ::crux_core::Request<
+                    <Time<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+                >,
+            ),
+        }
+        #[derive(::serde::Serialize, ::serde::Deserialize)]
+        #[serde(rename = "MyEffect")]
+        pub enum MyEffectFfi {
+            Http(
+                <crux_http::Http<
+                    MyEvent,
+                > as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            ),
+            KeyValue(
+                <KeyValue<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            ),
+            Platform(
+                <Platform<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            ),
+            Render(<Render<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation),
+            Time(<Time<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation),
+        }
+        impl ::crux_core::Effect for MyEffect {
+            type Ffi = MyEffectFfi;
+            fn serialize(self) -> (Self::Ffi, ::crux_core::bridge::ResolveSerialized) {
+                match self {
+                    MyEffect::Http(request) => request.serialize(MyEffectFfi::Http),
+                    MyEffect::KeyValue(request) => request.serialize(MyEffectFfi::KeyValue),
+                    MyEffect::Platform(request) => request.serialize(MyEffectFfi::Platform),
+                    MyEffect::Render(request) => request.serialize(MyEffectFfi::Render),
+                    MyEffect::Time(request) => request.serialize(MyEffectFfi::Time),
+                }
+            }
+            fn has_raw_output(&self) -> bool {
+                match *self {
+                    MyEffect::Http(_) => {
+                        <<crux_http::Http<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                    MyEffect::KeyValue(_) => {
+                        <<KeyValue<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                    MyEffect::Platform(_) => {
+                        <<Platform<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                    MyEffect::Render(_) => {
+                        <<Render<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                    MyEffect::Time(_) => {
+                        <<Time<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                }
+            }
+            fn coalesces(&self) -> bool {
+                match *self {
+                    MyEffect::Http(_) => {
+                        <<crux_http::Http<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                    MyEffect::KeyValue(_) => {
+                        <<KeyValue<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                    MyEffect::Platform(_) => {
+                        <<Platform<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                    MyEffect::Render(_) => {
+                        <<Render<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                    MyEffect::Time(_) => {
+                        <<Time<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                }
+            }
+            fn version(&self) -> u32 {
+                match *self {
+                    MyEffect::Http(_) => {
+                        <<crux_http::Http<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                    MyEffect::KeyValue(_) => {
+                        <<KeyValue<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                    MyEffect::Platform(_) => {
+                        <<Platform<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                    MyEffect::Render(_) => {
+                        <<Render<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                    MyEffect::Time(_) => {
+                        <<Time<
+                            MyEvent,
+                        > as ::crux_core::capability::Capability<
+                            MyEvent,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                }
+            }
+            fn name(&self) -> &'static str {
+                match *self {
+                    MyEffect::Http(_) => "Http",
+                    MyEffect::KeyValue(_) => "KeyValue",
+                    MyEffect::Platform(_) => "Platform",
+                    MyEffect::Render(_) => "Render",
+                    MyEffect::Time(_) => "Time",
+                }
+            }
+            fn tag(&self) -> Option<&'static str> {
+                match *self {
+                    MyEffect::Http(ref request) => request.tag(),
+                    MyEffect::KeyValue(ref request) => request.tag(),
+                    MyEffect::Platform(ref request) => request.tag(),
+                    MyEffect::Render(ref request) => request.tag(),
+                    MyEffect::Time(ref request) => request.tag(),
+                }
+            }
+            fn depends_on(&self) -> Option<&'static str> {
+                match *self {
+                    MyEffect::Http(ref request) => request.depends_on(),
+                    MyEffect::KeyValue(ref request) => request.depends_on(),
+                    MyEffect::Platform(ref request) => request.depends_on(),
+                    MyEffect::Render(ref request) => request.depends_on(),
+                    MyEffect::Time(ref request) => request.depends_on(),
+                }
+            }
+        }
+        impl MyEffect {
+            /// The names of the effect variants, in the order of the FFI enum
+            pub const VARIANT_NAMES: &'static [&'static str] = &[
+                "Http",
+                "KeyValue",
+                "Platform",
+                "Render",
+                "Time",
+            ];
+            /// The number of effect variants, one for each capability which isn't skipped
+            pub const VARIANT_COUNT: usize = Self::VARIANT_NAMES.len();
+        }
+        impl MyCapabilities {
+            /// The names of the capabilities, in the order they're declared, including
+            /// those skipped with `#[effect(skip)]`
+            pub fn capabilities() -> &'static [&'static str] {
+                &["http", "key_value", "platform", "render", "time"]
+            }
+        }
+        impl ::crux_core::WithContext<MyEvent, MyEffect> for MyCapabilities {
+            fn new_with_context(
+                context: ::crux_core::capability::ProtoContext<MyEffect, MyEvent>,
+            ) -> MyCapabilities {
+                MyCapabilities {
+                    http: crux_http::Http::new(context.specialize(MyEffect::Http)),
+                    key_value: KeyValue::new(context.specialize(MyEffect::KeyValue)),
+                    platform: Platform::new(context.specialize(MyEffect::Platform)),
+                    render: Render::new(context.specialize(MyEffect::Render)),
+                    time: Time::new(context.specialize(MyEffect::Time)),
+                }
+            }
+        }
+        impl ::std::convert::From<&MyEffect> for MyEffectFfi {
+            fn from(effect: &MyEffect) -> Self {
+                match *effect {
+                    MyEffect::Http(ref request) => MyEffectFfi::Http(request.operation.clone()),
+                    MyEffect::KeyValue(ref request) => {
+                        MyEffectFfi::KeyValue(request.operation.clone())
+                    }
+                    MyEffect::Platform(ref request) => {
+                        MyEffectFfi::Platform(request.operation.clone())
+                    }
+                    MyEffect::Render(ref request) => {
+                        MyEffectFfi::Render(request.operation.clone())
+                    }
+                    MyEffect::Time(ref request) => MyEffectFfi::Time(request.operation.clone()),
+                }
+            }
+        }
+        impl MyEffect {
+            /// Resolve the request carried by this effect with the shell's response to
+            /// its FFI counterpart, deserialized from `output`
+            pub fn resolve_ffi<'de, D>(
+                &mut self,
+                output: D,
+            ) -> ::std::result::Result<(), ::crux_core::ResolveError>
+            where
+                D: ::serde::Deserializer<'de>,
+            {
+                match *self {
+                    MyEffect::Http(ref mut request) => request.resolve_deserialized(output),
+                    MyEffect::KeyValue(ref mut request) => request.resolve_deserialized(output),
+                    MyEffect::Platform(ref mut request) => request.resolve_deserialized(output),
+                    MyEffect::Render(ref mut request) => request.resolve_deserialized(output),
+                    MyEffect::Time(ref mut request) => request.resolve_deserialized(output),
+                }
+            }
+        }
+        impl MyEffect {
+            pub fn is_http(&self) -> bool {
+                if let MyEffect::Http(_) = self { true } else { false }
+            }
+            pub fn as_http(
+                &self,
+            ) -> Option<
+                &<crux_http::Http<
+                    MyEvent,
+                > as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            > {
+                if let MyEffect::Http(request) = self { Some(&request.operation) } else { None }
+            }
+            pub fn into_http(
+                self,
+            ) -> Option<
+                crux_core::Request<
+                    <crux_http::Http<
+                        MyEvent,
+                    > as ::crux_core::capability::Capability<MyEvent>>::Operation,
+                >,
+            > {
+                if let MyEffect::Http(request) = self { Some(request) } else { None }
+            }
+            pub fn expect_http(
+                self,
+            ) -> crux_core::Request<
+                <crux_http::Http<
+                    MyEvent,
+                > as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            > {
+                if let MyEffect::Http(request) = self {
+                    request
+                } else {
+                    panic!("not a {} effect", "http")
+                }
+            }
+        }
+        impl MyEffect {
+            pub fn is_key_value(&self) -> bool {
+                if let MyEffect::KeyValue(_) = self { true } else { false }
+            }
+            pub fn as_key_value(
+                &self,
+            ) -> Option<
+                &<KeyValue<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            > {
+                if let MyEffect::KeyValue(request) = self {
+                    Some(&request.operation)
+                } else {
+                    None
+                }
+            }
+            pub fn into_key_value(
+                self,
+            ) -> Option<
+                crux_core::Request<
+                    <KeyValue<
+                        MyEvent,
+                    > as ::crux_core::capability::Capability<MyEvent>>::Operation,
+                >,
+            > {
+                if let MyEffect::KeyValue(request) = self { Some(request) } else { None }
+            }
+            pub fn expect_key_value(
+                self,
+            ) -> crux_core::Request<
+                <KeyValue<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            > {
+                if let MyEffect::KeyValue(request) = self {
+                    request
+                } else {
+                    panic!("not a {} effect", "key_value")
+                }
+            }
+        }
+        impl MyEffect {
+            pub fn is_platform(&self) -> bool {
+                if let MyEffect::Platform(_) = self { true } else { false }
+            }
+            pub fn as_platform(
+                &self,
+            ) -> Option<
+                &<Platform<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            > {
+                if let MyEffect::Platform(request) = self {
+                    Some(&request.operation)
+                } else {
+                    None
+                }
+            }
+            pub fn into_platform(
+                self,
+            ) -> Option<
+                crux_core::Request<
+                    <Platform<
+                        MyEvent,
+                    > as ::crux_core::capability::Capability<MyEvent>>::Operation,
+                >,
+            > {
+                if let MyEffect::Platform(request) = self { Some(request) } else { None }
+            }
+            pub fn expect_platform(
+                self,
+            ) -> crux_core::Request<
+                <Platform<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            > {
+                if let MyEffect::Platform(request) = self {
+                    request
+                } else {
+                    panic!("not a {} effect", "platform")
+                }
+            }
+        }
+        impl MyEffect {
+            pub fn is_render(&self) -> bool {
+                if let MyEffect::Render(_) = self { true } else { false }
+            }
+            pub fn as_render(
+                &self,
+            ) -> Option<
+                &<Render<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            > {
+                if let MyEffect::Render(request) = self {
+                    Some(&request.operation)
+                } else {
+                    None
+                }
+            }
+            pub fn into_render(
+                self,
+            ) -> Option<
+                crux_core::Request<
+                    <Render<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+                >,
+            > {
+                if let MyEffect::Render(request) = self { Some(request) } else { None }
+            }
+            pub fn expect_render(
+                self,
+            ) -> crux_core::Request<
+                <Render<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            > {
+                if let MyEffect::Render(request) = self {
+                    request
+                } else {
+                    panic!("not a {} effect", "render")
+                }
+            }
+        }
+        impl MyEffect {
+            pub fn is_time(&self) -> bool {
+                if let MyEffect::Time(_) = self { true } else { false }
+            }
+            pub fn as_time(
+                &self,
+            ) -> Option<
+                &<Time<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            > {
+                if let MyEffect::Time(request) = self { Some(&request.operation) } else { None }
+            }
+            pub fn into_time(
+                self,
+            ) -> Option<
+                crux_core::Request<
+                    <Time<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+                >,
+            > {
+                if let MyEffect::Time(request) = self { Some(request) } else { None }
+            }
+            pub fn expect_time(
+                self,
+            ) -> crux_core::Request<
+                <Time<MyEvent> as ::crux_core::capability::Capability<MyEvent>>::Operation,
+            > {
+                if let MyEffect::Time(request) = self {
+                    request
+                } else {
+                    panic!("not a {} effect", "time")
+                }
+            }
+        }
+        "###);
+    }
+
+    #[test]
+    fn effect_hash() {
+        let input = r#"
+            #[derive(Effect)]
+            #[effect(hash)]
+            pub struct Capabilities {
+                pub render: Render<Event>,
+            }
+        "#;
+        let input = parse_str(input).unwrap();
+        let input = EffectStructReceiver::from_derive_input(&input).unwrap();
+
+        let actual = quote!(#input);
+
+        insta::assert_snapshot!(pretty_print(&actual), @r###"
+        #[derive(Debug)]
+        pub enum Effect {
+            Render(
+                ::crux_core::Request<
+                    <Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+                >,
+            ),
+        }
+        #[derive(::serde::Serialize, ::serde::Deserialize)]
+        #[derive(PartialEq, Eq, Hash)]
+        #[serde(rename = "Effect")]
+        pub enum EffectFfi {
+            Render(<Render<Event> as ::crux_core::capability::Capability<Event>>::Operation),
+        }
+        impl ::crux_core::Effect for Effect {
+            type Ffi = EffectFfi;
+            fn serialize(self) -> (Self::Ffi, ::crux_core::bridge::ResolveSerialized) {
+                match self {
+                    Effect::Render(request) => request.serialize(EffectFfi::Render),
+                }
+            }
+            fn has_raw_output(&self) -> bool {
+                match *self {
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                }
+            }
+            fn coalesces(&self) -> bool {
+                match *self {
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                }
+            }
+            fn version(&self) -> u32 {
+                match *self {
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                }
+            }
+            fn name(&self) -> &'static str {
+                match *self {
+                    Effect::Render(_) => "Render",
+                }
+            }
+            fn tag(&self) -> Option<&'static str> {
+                match *self {
+                    Effect::Render(ref request) => request.tag(),
+                }
+            }
+            fn depends_on(&self) -> Option<&'static str> {
+                match *self {
+                    Effect::Render(ref request) => request.depends_on(),
+                }
+            }
+        }
+        impl Effect {
+            /// The names of the effect variants, in the order of the FFI enum
+            pub const VARIANT_NAMES: &'static [&'static str] = &["Render"];
+            /// The number of effect variants, one for each capability which isn't skipped
+            pub const VARIANT_COUNT: usize = Self::VARIANT_NAMES.len();
+        }
+        impl Capabilities {
+            /// The names of the capabilities, in the order they're declared, including
+            /// those skipped with `#[effect(skip)]`
+            pub fn capabilities() -> &'static [&'static str] {
+                &["render"]
+            }
+        }
+        impl ::crux_core::WithContext<Event, Effect> for Capabilities {
+            fn new_with_context(
+                context: ::crux_core::capability::ProtoContext<Effect, Event>,
+            ) -> Capabilities {
+                Capabilities {
+                    render: Render::new(context.specialize(Effect::Render)),
+                }
+            }
+        }
+        impl Effect {
+            pub fn is_render(&self) -> bool {
+                if let Effect::Render(_) = self { true } else { false }
+            }
+            pub fn as_render(
+                &self,
+            ) -> Option<
+                &<Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::Render(request) = self { Some(&request.operation) } else { None }
+            }
+            pub fn into_render(
+                self,
+            ) -> Option<
+                crux_core::Request<
+                    <Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+                >,
+            > {
+                if let Effect::Render(request) = self { Some(request) } else { None }
+            }
+            pub fn expect_render(
+                self,
+            ) -> crux_core::Request<
+                <Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::Render(request) = self {
+                    request
+                } else {
+                    panic!("not a {} effect", "render")
+                }
+            }
+        }
+        "###);
+    }
+
+    #[test]
+    fn cfg_gated_field() {
+        let input = r#"
+            #[derive(Effect)]
+            pub struct Capabilities {
+                pub render: Render<Event>,
+                #[cfg(feature = "bluetooth")]
+                pub bluetooth: Bluetooth<Event>,
+            }
+        "#;
+        let input = parse_str(input).unwrap();
+        let input = EffectStructReceiver::from_derive_input(&input).unwrap();
+
+        let actual = quote!(#input);
+
+        insta::assert_snapshot!(pretty_print(&actual), @r###"
+        #[derive(Debug)]
+        pub enum Effect {
+            #[cfg(feature = "bluetooth")]
+            Bluetooth(
+                ::crux_core::Request<
+                    <Bluetooth<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+                >,
+            ),
+            Render(
+                ::crux_core::Request<
+                    <Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+                >,
+            ),
+        }
+        #[derive(::serde::Serialize, ::serde::Deserialize)]
+        #[serde(rename = "Effect")]
+        pub enum EffectFfi {
+            #[cfg(feature = "bluetooth")]
+            Bluetooth(
+                <Bluetooth<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            ),
+            Render(<Render<Event> as ::crux_core::capability::Capability<Event>>::Operation),
+        }
+        impl ::crux_core::Effect for Effect {
+            type Ffi = EffectFfi;
+            fn serialize(self) -> (Self::Ffi, ::crux_core::bridge::ResolveSerialized) {
+                match self {
+                    #[cfg(feature = "bluetooth")]
+                    Effect::Bluetooth(request) => request.serialize(EffectFfi::Bluetooth),
+                    Effect::Render(request) => request.serialize(EffectFfi::Render),
+                }
+            }
+            fn has_raw_output(&self) -> bool {
+                match *self {
+                    #[cfg(feature = "bluetooth")]
+                    Effect::Bluetooth(_) => {
+                        <<Bluetooth<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                }
+            }
+            fn coalesces(&self) -> bool {
+                match *self {
+                    #[cfg(feature = "bluetooth")]
+                    Effect::Bluetooth(_) => {
+                        <<Bluetooth<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                }
+            }
+            fn version(&self) -> u32 {
+                match *self {
+                    #[cfg(feature = "bluetooth")]
+                    Effect::Bluetooth(_) => {
+                        <<Bluetooth<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                }
+            }
+            fn name(&self) -> &'static str {
+                match *self {
+                    #[cfg(feature = "bluetooth")]
+                    Effect::Bluetooth(_) => "Bluetooth",
+                    Effect::Render(_) => "Render",
+                }
+            }
+            fn tag(&self) -> Option<&'static str> {
+                match *self {
+                    #[cfg(feature = "bluetooth")]
+                    Effect::Bluetooth(ref request) => request.tag(),
+                    Effect::Render(ref request) => request.tag(),
+                }
+            }
+            fn depends_on(&self) -> Option<&'static str> {
+                match *self {
+                    #[cfg(feature = "bluetooth")]
+                    Effect::Bluetooth(ref request) => request.depends_on(),
+                    Effect::Render(ref request) => request.depends_on(),
+                }
+            }
+        }
+        impl Effect {
+            /// The names of the effect variants, in the order of the FFI enum
+            pub const VARIANT_NAMES: &'static [&'static str] = &[
+                #[cfg(feature = "bluetooth")]
+                "Bluetooth",
+                "Render",
+            ];
+            /// The number of effect variants, one for each capability which isn't skipped
+            pub const VARIANT_COUNT: usize = Self::VARIANT_NAMES.len();
+        }
+        impl Capabilities {
+            /// The names of the capabilities, in the order they're declared, including
+            /// those skipped with `#[effect(skip)]`
+            pub fn capabilities() -> &'static [&'static str] {
+                &[#[cfg(feature = "bluetooth")] "bluetooth", "render"]
+            }
+        }
+        impl ::crux_core::WithContext<Event, Effect> for Capabilities {
+            fn new_with_context(
+                context: ::crux_core::capability::ProtoContext<Effect, Event>,
+            ) -> Capabilities {
+                Capabilities {
+                    #[cfg(feature = "bluetooth")]
+                    bluetooth: Bluetooth::new(context.specialize(Effect::Bluetooth)),
+                    render: Render::new(context.specialize(Effect::Render)),
+                }
+            }
+        }
+        #[cfg(feature = "bluetooth")]
+        impl Effect {
+            pub fn is_bluetooth(&self) -> bool {
+                if let Effect::Bluetooth(_) = self { true } else { false }
+            }
+            pub fn as_bluetooth(
+                &self,
+            ) -> Option<
+                &<Bluetooth<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::Bluetooth(request) = self {
+                    Some(&request.operation)
+                } else {
+                    None
+                }
+            }
+            pub fn into_bluetooth(
+                self,
+            ) -> Option<
+                crux_core::Request<
+                    <Bluetooth<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+                >,
+            > {
+                if let Effect::Bluetooth(request) = self { Some(request) } else { None }
+            }
+            pub fn expect_bluetooth(
+                self,
+            ) -> crux_core::Request<
+                <Bluetooth<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::Bluetooth(request) = self {
+                    request
+                } else {
+                    panic!("not a {} effect", "bluetooth")
+                }
+            }
+        }
+        impl Effect {
+            pub fn is_render(&self) -> bool {
+                if let Effect::Render(_) = self { true } else { false }
+            }
+            pub fn as_render(
+                &self,
+            ) -> Option<
+                &<Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::Render(request) = self { Some(&request.operation) } else { None }
+            }
+            pub fn into_render(
+                self,
+            ) -> Option<
+                crux_core::Request<
+                    <Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+                >,
+            > {
+                if let Effect::Render(request) = self { Some(request) } else { None }
+            }
+            pub fn expect_render(
+                self,
+            ) -> crux_core::Request<
+                <Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::Render(request) = self {
+                    request
+                } else {
+                    panic!("not a {} effect", "render")
+                }
+            }
+        }
+        "###);
+    }
+
+    #[test]
+    fn effect_priority() {
+        let input = r#"
+            #[derive(Effect)]
+            pub struct Capabilities {
+                #[effect(priority = 10)]
+                pub render: Render<Event>,
+                pub key_value: KeyValue<Event>,
+            }
+        "#;
+        let input = parse_str(input).unwrap();
+        let input = EffectStructReceiver::from_derive_input(&input).unwrap();
+
+        let actual = quote!(#input);
+
+        insta::assert_snapshot!(pretty_print(&actual), @r###"
+        #[derive(Debug)]
+        pub enum Effect {
+            KeyValue(
+                ::crux_core::Request<
+                    <KeyValue<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+                >,
+            ),
+            Render(
+                ::crux_core::Request<
+                    <Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+                >,
+            ),
+        }
+        #[derive(::serde::Serialize, ::serde::Deserialize)]
+        #[serde(rename = "Effect")]
+        pub enum EffectFfi {
+            KeyValue(<KeyValue<Event> as ::crux_core::capability::Capability<Event>>::Operation),
+            Render(<Render<Event> as ::crux_core::capability::Capability<Event>>::Operation),
+        }
+        impl ::crux_core::Effect for Effect {
+            type Ffi = EffectFfi;
+            fn serialize(self) -> (Self::Ffi, ::crux_core::bridge::ResolveSerialized) {
+                match self {
+                    Effect::KeyValue(request) => request.serialize(EffectFfi::KeyValue),
+                    Effect::Render(request) => request.serialize(EffectFfi::Render),
+                }
+            }
+            fn has_raw_output(&self) -> bool {
+                match *self {
+                    Effect::KeyValue(_) => {
+                        <<KeyValue<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                }
+            }
+            fn coalesces(&self) -> bool {
+                match *self {
+                    Effect::KeyValue(_) => {
+                        <<KeyValue<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                }
+            }
+            fn version(&self) -> u32 {
+                match *self {
+                    Effect::KeyValue(_) => {
+                        <<KeyValue<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                }
+            }
+            fn priority(&self) -> i32 {
+                match *self {
+                    Effect::Render(_) => 10i32,
+                    #[allow(unreachable_patterns)]
+                    _ => 0,
+                }
+            }
+            fn name(&self) -> &'static str {
+                match *self {
+                    Effect::KeyValue(_) => "KeyValue",
+                    Effect::Render(_) => "Render",
+                }
+            }
+            fn tag(&self) -> Option<&'static str> {
+                match *self {
+                    Effect::KeyValue(ref request) => request.tag(),
+                    Effect::Render(ref request) => request.tag(),
+                }
+            }
+            fn depends_on(&self) -> Option<&'static str> {
+                match *self {
+                    Effect::KeyValue(ref request) => request.depends_on(),
+                    Effect::Render(ref request) => request.depends_on(),
+                }
+            }
+        }
+        impl Effect {
+            /// The names of the effect variants, in the order of the FFI enum
+            pub const VARIANT_NAMES: &'static [&'static str] = &["KeyValue", "Render"];
+            /// The number of effect variants, one for each capability which isn't skipped
+            pub const VARIANT_COUNT: usize = Self::VARIANT_NAMES.len();
+        }
+        impl Capabilities {
+            /// The names of the capabilities, in the order they're declared, including
+            /// those skipped with `#[effect(skip)]`
+            pub fn capabilities() -> &'static [&'static str] {
+                &["key_value", "render"]
+            }
+        }
+        impl ::crux_core::WithContext<Event, Effect> for Capabilities {
+            fn new_with_context(
+                context: ::crux_core::capability::ProtoContext<Effect, Event>,
+            ) -> Capabilities {
+                Capabilities {
+                    key_value: KeyValue::new(context.specialize(Effect::KeyValue)),
+                    render: Render::new(context.specialize(Effect::Render)),
+                }
+            }
+        }
+        impl Effect {
+            pub fn is_key_value(&self) -> bool {
+                if let Effect::KeyValue(_) = self { true } else { false }
+            }
+            pub fn as_key_value(
+                &self,
+            ) -> Option<
+                &<KeyValue<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::KeyValue(request) = self {
+                    Some(&request.operation)
+                } else {
+                    None
+                }
+            }
+            pub fn into_key_value(
+                self,
+            ) -> Option<
+                crux_core::Request<
+                    <KeyValue<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+                >,
+            > {
+                if let Effect::KeyValue(request) = self { Some(request) } else { None }
+            }
+            pub fn expect_key_value(
+                self,
+            ) -> crux_core::Request<
+                <KeyValue<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::KeyValue(request) = self {
+                    request
+                } else {
+                    panic!("not a {} effect", "key_value")
+                }
+            }
+        }
+        impl Effect {
+            pub fn is_render(&self) -> bool {
+                if let Effect::Render(_) = self { true } else { false }
+            }
+            pub fn as_render(
+                &self,
+            ) -> Option<
+                &<Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::Render(request) = self { Some(&request.operation) } else { None }
+            }
+            pub fn into_render(
+                self,
+            ) -> Option<
+                crux_core::Request<
+                    <Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+                >,
+            > {
+                if let Effect::Render(request) = self { Some(request) } else { None }
+            }
+            pub fn expect_render(
+                self,
+            ) -> crux_core::Request<
+                <Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::Render(request) = self {
+                    request
+                } else {
+                    panic!("not a {} effect", "render")
+                }
+            }
+        }
+        "###);
+    }
+
+    #[test]
+    fn variant_names_and_discriminants() {
+        let input = r#"
+            #[derive(Effect)]
+            pub struct Capabilities {
+                #[effect(discriminant = 0)]
+                pub render: Render<Event>,
+                #[effect(variant = "Fetch", discriminant = 1)]
+                pub http: crux_http::Http<Event>,
+            }
+        "#;
+        let input = parse_str(input).unwrap();
+        let input = EffectStructReceiver::from_derive_input(&input).unwrap();
+
+        let actual = quote!(#input);
+
+        let reordered = r#"
+            #[derive(Effect)]
+            pub struct Capabilities {
+                #[effect(variant = "Fetch", discriminant = 1)]
+                pub http: crux_http::Http<Event>,
+                #[effect(discriminant = 0)]
+                pub render: Render<Event>,
+            }
+        "#;
+        let reordered = parse_str(reordered).unwrap();
+        let reordered = EffectStructReceiver::from_derive_input(&reordered).unwrap();
+
+        assert_eq!(
+            pretty_print(&actual),
+            pretty_print(&quote!(#reordered)),
+            "field order should not change the generated code"
+        );
+
+        insta::assert_snapshot!(pretty_print(&actual), @r###"
+        #[derive(Debug)]
+        pub enum Effect {
+            Render(
+                ::crux_core::Request<
+                    <Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+                >,
+            ),
+            Fetch(
+                ::crux_core::Request<
+                    <crux_http::Http<
+                        Event,
+                    > as ::crux_core::capability::Capability<Event>>::Operation,
+                >,
+            ),
+        }
+        #[derive(::serde::Serialize, ::serde::Deserialize)]
+        #[serde(rename = "Effect")]
+        pub enum EffectFfi {
+            Render(<Render<Event> as ::crux_core::capability::Capability<Event>>::Operation),
+            Fetch(
+                <crux_http::Http<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            ),
+        }
+        impl ::crux_core::Effect for Effect {
+            type Ffi = EffectFfi;
+            fn serialize(self) -> (Self::Ffi, ::crux_core::bridge::ResolveSerialized) {
+                match self {
+                    Effect::Render(request) => request.serialize(EffectFfi::Render),
+                    Effect::Fetch(request) => request.serialize(EffectFfi::Fetch),
+                }
+            }
+            fn has_raw_output(&self) -> bool {
+                match *self {
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                    Effect::Fetch(_) => {
+                        <<crux_http::Http<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                }
+            }
+            fn coalesces(&self) -> bool {
+                match *self {
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                    Effect::Fetch(_) => {
+                        <<crux_http::Http<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                }
+            }
+            fn version(&self) -> u32 {
+                match *self {
+                    Effect::Render(_) => {
+                        <<Render<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                    Effect::Fetch(_) => {
+                        <<crux_http::Http<
+                            Event,
+                        > as ::crux_core::capability::Capability<
+                            Event,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                }
+            }
+            fn name(&self) -> &'static str {
+                match *self {
+                    Effect::Render(_) => "Render",
+                    Effect::Fetch(_) => "Fetch",
+                }
+            }
+            fn tag(&self) -> Option<&'static str> {
+                match *self {
+                    Effect::Render(ref request) => request.tag(),
+                    Effect::Fetch(ref request) => request.tag(),
+                }
+            }
+            fn depends_on(&self) -> Option<&'static str> {
+                match *self {
+                    Effect::Render(ref request) => request.depends_on(),
+                    Effect::Fetch(ref request) => request.depends_on(),
+                }
+            }
+        }
+        impl Effect {
+            /// The names of the effect variants, in the order of the FFI enum
+            pub const VARIANT_NAMES: &'static [&'static str] = &["Render", "Fetch"];
+            /// The number of effect variants, one for each capability which isn't skipped
+            pub const VARIANT_COUNT: usize = Self::VARIANT_NAMES.len();
+        }
+        impl Capabilities {
+            /// The names of the capabilities, in the order they're declared, including
+            /// those skipped with `#[effect(skip)]`
+            pub fn capabilities() -> &'static [&'static str] {
+                &["http", "render"]
+            }
+        }
+        impl ::crux_core::WithContext<Event, Effect> for Capabilities {
+            fn new_with_context(
+                context: ::crux_core::capability::ProtoContext<Effect, Event>,
+            ) -> Capabilities {
+                Capabilities {
+                    render: Render::new(context.specialize(Effect::Render)),
+                    http: crux_http::Http::new(context.specialize(Effect::Fetch)),
+                }
+            }
+        }
+        impl Effect {
+            pub fn is_render(&self) -> bool {
+                if let Effect::Render(_) = self { true } else { false }
+            }
+            pub fn as_render(
+                &self,
+            ) -> Option<
+                &<Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::Render(request) = self { Some(&request.operation) } else { None }
+            }
+            pub fn into_render(
+                self,
+            ) -> Option<
+                crux_core::Request<
+                    <Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+                >,
+            > {
+                if let Effect::Render(request) = self { Some(request) } else { None }
+            }
+            pub fn expect_render(
+                self,
+            ) -> crux_core::Request<
+                <Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::Render(request) = self {
+                    request
+                } else {
+                    panic!("not a {} effect", "render")
+                }
+            }
+        }
+        impl Effect {
+            pub fn is_http(&self) -> bool {
+                if let Effect::Fetch(_) = self { true } else { false }
+            }
+            pub fn as_http(
+                &self,
+            ) -> Option<
+                &<crux_http::Http<
+                    Event,
+                > as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::Fetch(request) = self { Some(&request.operation) } else { None }
+            }
+            pub fn into_http(
+                self,
+            ) -> Option<
+                crux_core::Request<
+                    <crux_http::Http<
+                        Event,
+                    > as ::crux_core::capability::Capability<Event>>::Operation,
+                >,
+            > {
+                if let Effect::Fetch(request) = self { Some(request) } else { None }
+            }
+            pub fn expect_http(
+                self,
+            ) -> crux_core::Request<
+                <crux_http::Http<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::Fetch(request) = self {
+                    request
+                } else {
+                    panic!("not a {} effect", "http")
+                }
+            }
+        }
+        "###);
+    }
+
+    #[test]
+    fn generic_event() {
+        let input = r#"
+            #[derive(Effect)]
+            pub struct Capabilities<Ev> {
+                pub render: Render<Ev>,
+            }
+        "#;
+        let input = parse_str(input).unwrap();
+        let input = EffectStructReceiver::from_derive_input(&input).unwrap();
+
+        let actual = quote!(#input);
+
+        insta::assert_snapshot!(pretty_print(&actual), @r###"
+        #[derive(Debug)]
+        pub enum Effect<Ev>
+        where
+            Ev: Send + 'static,
+        {
+            Render(
+                ::crux_core::Request<
+                    <Render<Ev> as ::crux_core::capability::Capability<Ev>>::Operation,
+                >,
+            ),
+            #[doc(hidden)]
+            __Phantom(::std::marker::PhantomData<fn() -> (Ev,)>, ::std::convert::Infallible),
+        }
+        #[derive(::serde::Serialize, ::serde::Deserialize)]
+        #[serde(rename = "Effect")]
+        #[serde(
+            bound(
+                serialize = "",
+                deserialize = "< Render < Ev > as :: crux_core :: capability :: Capability < Ev >> :: Operation : :: serde :: Deserialize < 'de >"
+            )
+        )]
+        pub enum EffectFfi<Ev>
+        where
+            Ev: Send + 'static,
+        {
+            Render(<Render<Ev> as ::crux_core::capability::Capability<Ev>>::Operation),
+            #[doc(hidden)]
+            #[serde(skip)]
+            __Phantom(::std::marker::PhantomData<fn() -> (Ev,)>, ::std::convert::Infallible),
+        }
+        impl<Ev> ::crux_core::Effect for Effect<Ev>
+        where
+            Ev: Send + 'static,
+        {
+            type Ffi = EffectFfi<Ev>;
+            fn serialize(self) -> (Self::Ffi, ::crux_core::bridge::ResolveSerialized) {
+                match self {
+                    Effect::Render(request) => request.serialize(EffectFfi::Render),
+                    Effect::__Phantom(_, never) => match never {}
+                }
+            }
+            fn has_raw_output(&self) -> bool {
+                match *self {
+                    Effect::Render(_) => {
+                        <<Render<
+                            Ev,
+                        > as ::crux_core::capability::Capability<
+                            Ev,
+                        >>::Operation as ::crux_core::capability::Operation>::RAW_OUTPUT
+                    }
+                    Effect::__Phantom(_, never) => match never {}
+                }
+            }
+            fn coalesces(&self) -> bool {
+                match *self {
+                    Effect::Render(_) => {
+                        <<Render<
+                            Ev,
+                        > as ::crux_core::capability::Capability<
+                            Ev,
+                        >>::Operation as ::crux_core::capability::Operation>::COALESCE
+                    }
+                    Effect::__Phantom(_, never) => match never {}
+                }
+            }
+            fn version(&self) -> u32 {
+                match *self {
+                    Effect::Render(_) => {
+                        <<Render<
+                            Ev,
+                        > as ::crux_core::capability::Capability<
+                            Ev,
+                        >>::Operation as ::crux_core::capability::Operation>::VERSION
+                    }
+                    Effect::__Phantom(_, never) => match never {}
+                }
+            }
+            fn name(&self) -> &'static str {
+                match *self {
+                    Effect::Render(_) => "Render",
+                    Effect::__Phantom(_, never) => match never {}
+                }
+            }
+            fn tag(&self) -> Option<&'static str> {
+                match *self {
+                    Effect::Render(ref request) => request.tag(),
+                    Effect::__Phantom(_, never) => match never {}
+                }
+            }
+            fn depends_on(&self) -> Option<&'static str> {
+                match *self {
+                    Effect::Render(ref request) => request.depends_on(),
+                    Effect::__Phantom(_, never) => match never {}
+                }
+            }
+        }
+        impl<Ev> Effect<Ev>
+        where
+            Ev: Send + 'static,
+        {
+            /// The names of the effect variants, in the order of the FFI enum
+            pub const VARIANT_NAMES: &'static [&'static str] = &["Render"];
+            /// The number of effect variants, one for each capability which isn't skipped
+            pub const VARIANT_COUNT: usize = Self::VARIANT_NAMES.len();
+        }
+        impl<Ev> Capabilities<Ev>
+        where
+            Ev: Send + 'static,
+        {
+            /// The names of the capabilities, in the order they're declared, including
+            /// those skipped with `#[effect(skip)]`
+            pub fn capabilities() -> &'static [&'static str] {
+                &["render"]
+            }
+        }
+        impl<Ev> ::crux_core::WithContext<Ev, Effect<Ev>> for Capabilities<Ev>
+        where
+            Ev: Send + 'static,
+        {
+            fn new_with_context(
+                context: ::crux_core::capability::ProtoContext<Effect<Ev>, Ev>,
+            ) -> Capabilities<Ev> {
+                Capabilities {
+                    render: Render::new(context.specialize(Effect::Render)),
+                }
+            }
+        }
+        impl<Ev> Effect<Ev>
+        where
+            Ev: Send + 'static,
+        {
+            pub fn is_render(&self) -> bool {
+                if let Effect::Render(_) = self { true } else { false }
+            }
+            pub fn as_render(
+                &self,
+            ) -> Option<&<Render<Ev> as ::crux_core::capability::Capability<Ev>>::Operation> {
+                if let Effect::Render(request) = self { Some(&request.operation) } else { None }
+            }
+            pub fn into_render(
+                self,
+            ) -> Option<
+                crux_core::Request<
+                    <Render<Ev> as ::crux_core::capability::Capability<Ev>>::Operation,
+                >,
+            > {
+                if let Effect::Render(request) = self { Some(request) } else { None }
+            }
+            pub fn expect_render(
+                self,
+            ) -> crux_core::Request<
+                <Render<Ev> as ::crux_core::capability::Capability<Ev>>::Operation,
+            > {
+                if let Effect::Render(request) = self {
+                    request
+                } else {
+                    panic!("not a {} effect", "render")
+                }
+            }
+        }
+        "###);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_panic_when_generic_over_lifetimes() {
+        let input = r#"
+            #[derive(Effect)]
+            pub struct Capabilities<'a> {
+                pub render: Render<Event<'a>>,
+            }
+        "#;
+        let input = parse_str(input).unwrap();
+        let input = EffectStructReceiver::from_derive_input(&input).unwrap();
+
+        let mut actual = quote!();
+        input.to_tokens(&mut actual);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_panic_when_discriminants_have_gaps() {
+        let input = r#"
+            #[derive(Effect)]
+            pub struct Capabilities {
+                #[effect(discriminant = 0)]
+                pub render: Render<Event>,
+                #[effect(discriminant = 2)]
+                pub time: Time<Event>,
+            }
+        "#;
+        let input = parse_str(input).unwrap();
+        let input = EffectStructReceiver::from_derive_input(&input).unwrap();
+
+        let mut actual = quote!();
+        input.to_tokens(&mut actual);
+    }
+
     #[test]
     #[should_panic]
     fn should_panic_when_multiple_event_types() {
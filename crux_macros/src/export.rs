@@ -2,22 +2,33 @@ use darling::{ast, util, FromDeriveInput, FromField, ToTokens};
 use proc_macro2::TokenStream;
 use proc_macro_error::OptionExt;
 use quote::{format_ident, quote};
-use syn::{DeriveInput, GenericArgument, Ident, PathArguments, Type};
+use syn::{Attribute, DeriveInput, GenericArgument, Ident, PathArguments, Type};
 
 #[derive(FromDeriveInput, Debug)]
 #[darling(attributes(effect), supports(struct_named))]
 struct ExportStructReceiver {
     ident: Ident,
     name: Option<Ident>, // also used by the effect derive macro to name the effect
+    #[darling(default)]
+    #[allow(dead_code)]
+    hash: bool, // only used by the effect derive macro
     data: ast::Data<util::Ignored, ExportFieldReceiver>,
 }
 
 #[derive(FromField, Debug)]
-#[darling(attributes(effect))]
+#[darling(attributes(effect), forward_attrs(cfg))]
 pub struct ExportFieldReceiver {
     ty: Type,
+    attrs: Vec<Attribute>,
     #[darling(default)]
     skip: bool,
+    // only used by the effect derive macro
+    #[allow(dead_code)]
+    variant: Option<Ident>,
+    #[allow(dead_code)]
+    discriminant: Option<u32>,
+    #[allow(dead_code)]
+    priority: Option<i32>,
 }
 
 impl ToTokens for ExportStructReceiver {
@@ -45,9 +56,15 @@ impl ToTokens for ExportStructReceiver {
 
         let mut output_type_exports = Vec::new();
 
-        for (capability, event) in fields.iter().map(|f| split_on_generic(&f.ty)) {
+        for field in fields {
+            let (capability, event) = split_on_generic(&field.ty);
+            let cfgs = &field.attrs;
+
             output_type_exports.push(quote! {
+                #(#cfgs)*
                 #capability::<#event>::register_types(generator)?;
+                #(#cfgs)*
+                generator.register_operation::<<#capability<#event> as Capability<#event>>::Operation>()?;
             });
         }
 
@@ -58,8 +75,7 @@ impl ToTokens for ExportStructReceiver {
                 fn register_types(generator: &mut ::crux_core::typegen::TypeGen) -> ::crux_core::typegen::Result {
                     use ::crux_core::capability::Capability;
                     #(#output_type_exports)*
-                    generator.register_type::<#ffi_export_name>()?;
-                    generator.register_type::<::crux_core::bridge::Request<#ffi_export_name>>()?;
+                    generator.register_effect::<#ffi_export_name>()?;
 
                     Ok(())
                 }
@@ -136,8 +152,46 @@ mod tests {
             ) -> ::crux_core::typegen::Result {
                 use ::crux_core::capability::Capability;
                 Render::<Event>::register_types(generator)?;
-                generator.register_type::<EffectFfi>()?;
-                generator.register_type::<::crux_core::bridge::Request<EffectFfi>>()?;
+                generator
+                    .register_operation::<<Render<Event> as Capability<Event>>::Operation>()?;
+                generator.register_effect::<EffectFfi>()?;
+                Ok(())
+            }
+        }
+        "###);
+    }
+
+    #[test]
+    fn export_macro_respects_cfg_attrs() {
+        let input = r#"
+            #[derive(Export)]
+            pub struct Capabilities {
+                pub render: Render<Event>,
+                #[cfg(feature = "bluetooth")]
+                #[effect(priority = 1)]
+                pub bluetooth: Bluetooth<Event>,
+            }
+        "#;
+        let input = parse_str(input).unwrap();
+        let input = ExportStructReceiver::from_derive_input(&input).unwrap();
+
+        let actual = quote!(#input);
+
+        insta::assert_snapshot!(pretty_print(&actual), @r###"
+        impl ::crux_core::typegen::Export for Capabilities {
+            #[cfg(feature = "typegen")]
+            fn register_types(
+                generator: &mut ::crux_core::typegen::TypeGen,
+            ) -> ::crux_core::typegen::Result {
+                use ::crux_core::capability::Capability;
+                Render::<Event>::register_types(generator)?;
+                generator
+                    .register_operation::<<Render<Event> as Capability<Event>>::Operation>()?;
+                #[cfg(feature = "bluetooth")] Bluetooth::<Event>::register_types(generator)?;
+                #[cfg(feature = "bluetooth")]
+                generator
+                    .register_operation::<<Bluetooth<Event> as Capability<Event>>::Operation>()?;
+                generator.register_effect::<EffectFfi>()?;
                 Ok(())
             }
         }
@@ -184,11 +238,26 @@ mod tests {
             ) -> ::crux_core::typegen::Result {
                 use ::crux_core::capability::Capability;
                 crux_http::Http::<MyEvent>::register_types(generator)?;
+                generator
+                    .register_operation::<
+                        <crux_http::Http<MyEvent> as Capability<MyEvent>>::Operation,
+                    >()?;
                 KeyValue::<MyEvent>::register_types(generator)?;
+                generator
+                    .register_operation::<
+                        <KeyValue<MyEvent> as Capability<MyEvent>>::Operation,
+                    >()?;
                 Platform::<MyEvent>::register_types(generator)?;
+                generator
+                    .register_operation::<
+                        <Platform<MyEvent> as Capability<MyEvent>>::Operation,
+                    >()?;
                 Render::<MyEvent>::register_types(generator)?;
-                generator.register_type::<EffectFfi>()?;
-                generator.register_type::<::crux_core::bridge::Request<EffectFfi>>()?;
+                generator
+                    .register_operation::<
+                        <Render<MyEvent> as Capability<MyEvent>>::Operation,
+                    >()?;
+                generator.register_effect::<EffectFfi>()?;
                 Ok(())
             }
         }
@@ -220,12 +289,29 @@ mod tests {
             ) -> ::crux_core::typegen::Result {
                 use ::crux_core::capability::Capability;
                 crux_http::Http::<MyEvent>::register_types(generator)?;
+                generator
+                    .register_operation::<
+                        <crux_http::Http<MyEvent> as Capability<MyEvent>>::Operation,
+                    >()?;
                 KeyValue::<MyEvent>::register_types(generator)?;
+                generator
+                    .register_operation::<
+                        <KeyValue<MyEvent> as Capability<MyEvent>>::Operation,
+                    >()?;
                 Platform::<MyEvent>::register_types(generator)?;
+                generator
+                    .register_operation::<
+                        <Platform<MyEvent> as Capability<MyEvent>>::Operation,
+                    >()?;
                 Render::<MyEvent>::register_types(generator)?;
+                generator
+                    .register_operation::<
+                        <Render<MyEvent> as Capability<MyEvent>>::Operation,
+                    >()?;
                 Time::<MyEvent>::register_types(generator)?;
-                generator.register_type::<EffectFfi>()?;
-                generator.register_type::<::crux_core::bridge::Request<EffectFfi>>()?;
+                generator
+                    .register_operation::<<Time<MyEvent> as Capability<MyEvent>>::Operation>()?;
+                generator.register_effect::<EffectFfi>()?;
                 Ok(())
             }
         }
@@ -255,8 +341,9 @@ mod tests {
             ) -> ::crux_core::typegen::Result {
                 use ::crux_core::capability::Capability;
                 Render::<Event>::register_types(generator)?;
-                generator.register_type::<MyEffectFfi>()?;
-                generator.register_type::<::crux_core::bridge::Request<MyEffectFfi>>()?;
+                generator
+                    .register_operation::<<Render<Event> as Capability<Event>>::Operation>()?;
+                generator.register_effect::<MyEffectFfi>()?;
                 Ok(())
             }
         }
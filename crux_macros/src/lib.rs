@@ -18,9 +18,48 @@ use syn::parse_macro_input;
 /// The default name of the app struct is "App", but this can be
 /// overridden with the `app` attribute.
 ///
+/// With the `hash` attribute, e.g. `#[effect(hash)]`, the FFI version of the Effect
+/// enum (`EffectFfi`) also derives `PartialEq`, `Eq` and `Hash`, so that it can be used
+/// as a key in a `HashMap`. This requires all the operation types to implement them.
+///
+/// With the `convert` attribute, e.g. `#[effect(convert)]`, the FFI enum also implements
+/// `From<&Effect>`, copying the operation of the effect's request, and the Effect enum
+/// gets a `resolve_ffi` method, which resolves its request with the shell's response to
+/// the FFI effect, deserialized from any serde `Deserializer`. Together they allow sending
+/// effects to a shell without the `Bridge`.
+///
+/// The capabilities struct can be generic over the event type, e.g.
+/// `Capabilities<Ev>` with fields like `Render<Ev>`, to share it between apps with
+/// different events. The Effect enum is then generic too (`Effect<Ev>`), as is the
+/// `WithContext` implementation.
+///
 /// No Effect variant will be generated for fields annotated with
 /// `#[effect(skip)]`.
 ///
+/// Each variant is named after its capability type (e.g. `Http`), which can be
+/// overridden with `#[effect(variant = "Fetch")]`. The variants are ordered by field
+/// name, so moving fields around doesn't change the order the shell sees. To pin it
+/// down completely, declare the position of every variant with
+/// `#[effect(discriminant = 0)]`, numbering them from 0.
+///
+/// Effects requested in the same update are handed to the shell in the order the
+/// capabilities requested them. To deliver some of them first, e.g. a render ahead of
+/// background writes, give their field a priority with `#[effect(priority = 10)]`.
+/// Effects with a higher priority go first, and the default priority is 0.
+///
+/// `#[cfg(...)]` attributes on a field are carried over to everything generated for it,
+/// so a capability behind a disabled feature has no Effect variant, and the shell doesn't
+/// need to handle it.
+///
+/// For each variant, the Effect enum also gets helper methods named after the
+/// capability field, e.g. for a field called `http`: `is_http`, `as_http`
+/// (borrowing the operation), `into_http` and `expect_http` (returning the request).
+/// These are handy in tests, e.g. `update.take_effects(MyEffect::is_http)`.
+///
+/// The Effect enum also has the constants `VARIANT_NAMES`, listing the names of its
+/// variants in the order of the FFI enum, and `VARIANT_COUNT`, e.g. to check that a
+/// dispatch table in the shell covers every effect.
+///
 /// e.g.
 /// ```rust
 /// # use crux_core::{Capability, render::Render, compose::Compose};
@@ -53,7 +92,7 @@ use syn::parse_macro_input;
 ///     #[effect(skip)]
 ///     pub compose: Compose<MyEvent>,
 /// }
-
+/// ```
 #[proc_macro_derive(Effect, attributes(effect))]
 #[proc_macro_error]
 pub fn effect(input: TokenStream) -> TokenStream {
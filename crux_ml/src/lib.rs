@@ -0,0 +1,133 @@
+//! On-device machine learning inference for Crux apps
+//!
+//! `crux_ml` allows Crux apps to request an inference from a model hosted by the Shell,
+//! using the platform's native ML runtime. Inputs and outputs are opaque bytes, encoded
+//! and decoded by the app in whatever format the model expects.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Request to run an inference on the model identified by `model_id`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InferenceRequest {
+    pub model_id: String,
+    #[serde(with = "serde_bytes")]
+    pub input: Vec<u8>,
+}
+
+/// The result of an inference.
+///
+/// Note: we can't use `Result` here because generics are not currently
+/// supported across the FFI boundary, when using the builtin typegen.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InferenceResult {
+    Ok {
+        #[serde(with = "serde_bytes")]
+        output: Vec<u8>,
+    },
+    Err {
+        error: MlError,
+    },
+}
+
+/// Error type for inference requests
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Error)]
+#[serde(rename_all = "camelCase")]
+pub enum MlError {
+    #[error("model {model_id} is not loaded")]
+    ModelNotLoaded { model_id: String },
+    #[error("inference failed: {message}")]
+    InferenceFailed { message: String },
+}
+
+impl From<InferenceResult> for Result<Vec<u8>, MlError> {
+    fn from(result: InferenceResult) -> Self {
+        match result {
+            InferenceResult::Ok { output } => Ok(output),
+            InferenceResult::Err { error } => Err(error),
+        }
+    }
+}
+
+impl Operation for InferenceRequest {
+    type Output = InferenceResult;
+}
+
+pub struct Ml<Ev> {
+    context: CapabilityContext<InferenceRequest, Ev>,
+}
+
+impl<Ev> crux_core::Capability<Ev> for Ml<Ev> {
+    type Operation = InferenceRequest;
+
+    type MappedSelf<MappedEv> = Ml<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static + Send,
+    {
+        Ml::new(self.context.map_event(f))
+    }
+
+    #[cfg(feature = "typegen")]
+    fn register_types(generator: &mut crux_core::typegen::TypeGen) -> crux_core::typegen::Result {
+        generator.register_type::<MlError>()?;
+        generator.register_type::<Self::Operation>()?;
+        generator.register_type::<<Self::Operation as Operation>::Output>()?;
+        Ok(())
+    }
+}
+
+impl<Ev> Clone for Ml<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Ml<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<InferenceRequest, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Run an inference with the `input` on the model identified by `model_id`,
+    /// dispatching the event with the output bytes, or the error if the model
+    /// isn't loaded or the inference failed.
+    pub fn infer<F>(&self, model_id: impl Into<String>, input: Vec<u8>, make_event: F)
+    where
+        F: FnOnce(Result<Vec<u8>, MlError>) -> Ev + Send + 'static,
+    {
+        let model_id = model_id.into();
+
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(make_event(this.infer_async(model_id, input).await));
+            }
+        });
+    }
+
+    /// Run an inference with the `input` on the model identified by `model_id`,
+    /// while in an async context. This is used together with [`crux_core::compose::Compose`].
+    pub async fn infer_async(
+        &self,
+        model_id: impl Into<String>,
+        input: Vec<u8>,
+    ) -> Result<Vec<u8>, MlError> {
+        let request = InferenceRequest {
+            model_id: model_id.into(),
+            input,
+        };
+
+        self.context.request_from_shell(request).await.into()
+    }
+}
@@ -0,0 +1,143 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_ml::{Ml, MlError};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Classify(Vec<u8>),
+
+        #[serde(skip)]
+        Classified(Vec<u8>),
+        #[serde(skip)]
+        Failed(MlError),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub label: Option<String>,
+        pub error: Option<String>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub label: String,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Classify(image) => {
+                    caps.ml.infer("classifier", image, |result| match result {
+                        Ok(output) => Event::Classified(output),
+                        Err(error) => Event::Failed(error),
+                    })
+                }
+                Event::Classified(output) => {
+                    model.label = Some(String::from_utf8_lossy(&output).to_string());
+                    caps.render.render();
+                }
+                Event::Failed(error) => {
+                    model.error = Some(error.to_string());
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                label: model.label.clone().unwrap_or_default(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub ml: Ml<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_ml::{InferenceRequest, InferenceResult, MlError};
+
+    #[test]
+    pub fn test_inference() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Classify(vec![1, 2, 3]), &mut model)
+            .expect_one_effect()
+            .expect_ml();
+
+        assert_eq!(
+            request.operation,
+            InferenceRequest {
+                model_id: "classifier".to_string(),
+                input: vec![1, 2, 3],
+            }
+        );
+
+        let event = app
+            .resolve(
+                &mut request,
+                InferenceResult::Ok {
+                    output: b"cat".to_vec(),
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Classified(b"cat".to_vec()));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(app.view(&model).label, "cat");
+    }
+
+    #[test]
+    pub fn test_model_not_loaded() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Classify(vec![1, 2, 3]), &mut model)
+            .expect_one_effect()
+            .expect_ml();
+
+        let event = app
+            .resolve(
+                &mut request,
+                InferenceResult::Err {
+                    error: MlError::ModelNotLoaded {
+                        model_id: "classifier".to_string(),
+                    },
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(
+            event,
+            Event::Failed(MlError::ModelNotLoaded {
+                model_id: "classifier".to_string()
+            })
+        );
+
+        let _ = app.update(event, &mut model);
+        assert_eq!(
+            model.error.as_deref(),
+            Some("model classifier is not loaded")
+        );
+    }
+}
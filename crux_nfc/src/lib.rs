@@ -0,0 +1,198 @@
+//! NFC tag access for Crux apps
+//!
+//! `crux_nfc` allows Crux apps to read the payload of an NFC tag and to write one. Both are
+//! one-shot operations: the Shell presents the platform's scanning UI and resolves the request
+//! once a tag was read or written. If NFC isn't available on the device, the user hasn't granted
+//! permission to use it, or cancels, the app receives an [`NfcError`] instead.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Supported operations
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NfcOperation {
+    /// Read the payload of a tag
+    Read,
+    /// Write the `bytes` to a tag
+    Write {
+        #[serde(with = "serde_bytes")]
+        bytes: Vec<u8>,
+    },
+}
+
+impl std::fmt::Debug for NfcOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NfcOperation::Read => f.write_str("Read"),
+            NfcOperation::Write { bytes } => f
+                .debug_struct("Write")
+                .field("bytes", &format_args!("<{} bytes>", bytes.len()))
+                .finish(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NfcResponse {
+    /// The payload of the tag read
+    Read {
+        #[serde(with = "serde_bytes")]
+        bytes: Vec<u8>,
+    },
+    /// The payload was written to a tag
+    Written,
+    /// NFC isn't available on the device, or is turned off
+    Unavailable,
+    /// The user hasn't granted permission to use NFC
+    PermissionDenied,
+    /// The user dismissed the scanning UI
+    UserCancelled,
+}
+
+impl std::fmt::Debug for NfcResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NfcResponse::Read { bytes } => f
+                .debug_struct("Read")
+                .field("bytes", &format_args!("<{} bytes>", bytes.len()))
+                .finish(),
+            NfcResponse::Written => f.write_str("Written"),
+            NfcResponse::Unavailable => f.write_str("Unavailable"),
+            NfcResponse::PermissionDenied => f.write_str("PermissionDenied"),
+            NfcResponse::UserCancelled => f.write_str("UserCancelled"),
+        }
+    }
+}
+
+impl Operation for NfcOperation {
+    type Output = NfcResponse;
+}
+
+pub type ReadResult = Result<Vec<u8>, NfcError>;
+pub type WriteResult = Result<(), NfcError>;
+
+/// Error type for NFC operations
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Error)]
+#[serde(rename_all = "camelCase")]
+pub enum NfcError {
+    #[error("NFC is unavailable")]
+    Unavailable,
+    #[error("permission to use NFC denied")]
+    PermissionDenied,
+    #[error("cancelled by the user")]
+    UserCancelled,
+    #[error("unexpected response from the shell")]
+    UnexpectedResponse,
+}
+
+/// The Nfc capability API
+///
+/// This capability allows the app to read and write NFC tags.
+pub struct Nfc<Ev> {
+    context: CapabilityContext<NfcOperation, Ev>,
+}
+
+impl<Ev> crux_core::Capability<Ev> for Nfc<Ev> {
+    type Operation = NfcOperation;
+    type MappedSelf<MappedEv> = Nfc<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static + Send,
+    {
+        Nfc::new(self.context.map_event(f))
+    }
+
+    #[cfg(feature = "typegen")]
+    fn register_types(generator: &mut crux_core::typegen::TypeGen) -> crux_core::typegen::Result {
+        generator.register_type::<NfcError>()?;
+        generator.register_type::<Self::Operation>()?;
+        generator.register_type::<<Self::Operation as Operation>::Output>()?;
+        Ok(())
+    }
+}
+
+impl<Ev> Clone for Nfc<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Nfc<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<NfcOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Read the payload of a tag. The payload, or an [`NfcError`] if no tag was read, is passed
+    /// to the app wrapped in the event produced by the `callback`.
+    pub fn read<F>(&self, callback: F)
+    where
+        F: FnOnce(ReadResult) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.read_async().await));
+            }
+        });
+    }
+
+    /// Read the payload of a tag, returning its bytes.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn read_async(&self) -> ReadResult {
+        match self.context.request_from_shell(NfcOperation::Read).await {
+            NfcResponse::Read { bytes } => Ok(bytes),
+            response => Err(error(response)),
+        }
+    }
+
+    /// Write the `bytes` to a tag. Whether they were written, or an [`NfcError`] if not,
+    /// is passed to the app wrapped in the event produced by the `callback`.
+    pub fn write<F>(&self, bytes: Vec<u8>, callback: F)
+    where
+        F: FnOnce(WriteResult) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.write_async(bytes).await));
+            }
+        });
+    }
+
+    /// Write the `bytes` to a tag.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn write_async(&self, bytes: Vec<u8>) -> WriteResult {
+        match self
+            .context
+            .request_from_shell(NfcOperation::Write { bytes })
+            .await
+        {
+            NfcResponse::Written => Ok(()),
+            response => Err(error(response)),
+        }
+    }
+}
+
+fn error(response: NfcResponse) -> NfcError {
+    match response {
+        NfcResponse::Unavailable => NfcError::Unavailable,
+        NfcResponse::PermissionDenied => NfcError::PermissionDenied,
+        NfcResponse::UserCancelled => NfcError::UserCancelled,
+        NfcResponse::Read { .. } | NfcResponse::Written => NfcError::UnexpectedResponse,
+    }
+}
@@ -0,0 +1,155 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_nfc::{Nfc, NfcError, ReadResult, WriteResult};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        ScanTicket,
+        IssueTicket(String),
+
+        #[serde(skip)]
+        TicketScanned(ReadResult),
+        #[serde(skip)]
+        TicketIssued(WriteResult),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub ticket: Option<String>,
+        pub issued: bool,
+        pub error: Option<NfcError>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub ticket: Option<String>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::ScanTicket => caps.nfc.read(Event::TicketScanned),
+                Event::IssueTicket(ticket) => {
+                    caps.nfc.write(ticket.into_bytes(), Event::TicketIssued)
+                }
+                Event::TicketScanned(Ok(bytes)) => {
+                    model.ticket = Some(String::from_utf8_lossy(&bytes).into_owned());
+                    caps.render.render();
+                }
+                Event::TicketIssued(Ok(())) => {
+                    model.issued = true;
+                    caps.render.render();
+                }
+                Event::TicketScanned(Err(error)) | Event::TicketIssued(Err(error)) => {
+                    model.error = Some(error);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                ticket: model.ticket.clone(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub nfc: Nfc<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_nfc::{NfcError, NfcOperation, NfcResponse};
+
+    #[test]
+    pub fn test_read() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::ScanTicket, &mut model)
+            .expect_one_effect()
+            .expect_nfc();
+
+        assert_eq!(request.operation, NfcOperation::Read);
+
+        let payload = b"TICKET-42".to_vec();
+        let event = app
+            .resolve(
+                &mut request,
+                NfcResponse::Read {
+                    bytes: payload.clone(),
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::TicketScanned(Ok(payload)));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(app.view(&model).ticket, Some("TICKET-42".to_string()));
+    }
+
+    #[test]
+    pub fn test_read_unavailable() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::ScanTicket, &mut model)
+            .expect_one_effect()
+            .expect_nfc();
+
+        let event = app
+            .resolve(&mut request, NfcResponse::Unavailable)
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::TicketScanned(Err(NfcError::Unavailable)));
+
+        let _update = app.update(event, &mut model);
+        assert_eq!(model.ticket, None);
+        assert_eq!(model.error, Some(NfcError::Unavailable));
+    }
+
+    #[test]
+    pub fn test_write() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::IssueTicket("TICKET-7".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_nfc();
+
+        assert_eq!(
+            request.operation,
+            NfcOperation::Write {
+                bytes: b"TICKET-7".to_vec()
+            }
+        );
+
+        let event = app
+            .resolve(&mut request, NfcResponse::Written)
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::TicketIssued(Ok(())));
+
+        let _update = app.update(event, &mut model);
+        assert!(model.issued);
+    }
+}
@@ -0,0 +1,91 @@
+//! Device orientation for Crux apps
+//!
+//! `crux_orientation` allows Crux apps to subscribe to changes of the device's orientation,
+//! e.g. to adapt layout hints when the device is turned to landscape. Unlike the raw readings
+//! of `crux_sensors`, the Shell reports the orientation the platform derives from the
+//! accelerometer, every time it changes.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+/// The orientation of the device
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeviceOrientation {
+    /// Upright, with the top of the device up
+    Portrait,
+    /// Upright, with the top of the device down
+    PortraitUpsideDown,
+    /// On its side, with the top of the device to the left
+    LandscapeLeft,
+    /// On its side, with the top of the device to the right
+    LandscapeRight,
+    /// Lying flat, with the screen facing up
+    FaceUp,
+    /// Lying flat, with the screen facing down
+    FaceDown,
+}
+
+impl DeviceOrientation {
+    /// Whether the device is on its side
+    pub fn is_landscape(&self) -> bool {
+        matches!(
+            self,
+            DeviceOrientation::LandscapeLeft | DeviceOrientation::LandscapeRight
+        )
+    }
+}
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrientationOperation {
+    /// Subscribe to changes of the device's orientation. The Shell resolves it with the new
+    /// orientation every time it changes.
+    Subscribe,
+}
+
+impl Operation for OrientationOperation {
+    type Output = DeviceOrientation;
+}
+
+#[derive(Capability)]
+pub struct Orientation<Ev> {
+    context: CapabilityContext<OrientationOperation, Ev>,
+}
+
+impl<Ev> Clone for Orientation<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Orientation<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<OrientationOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Subscribe to changes of the device's orientation. Every new orientation will be passed
+    /// to the app wrapped in the event produced by the `callback`.
+    pub fn on_change<F>(&self, callback: F)
+    where
+        F: Fn(DeviceOrientation) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                let mut stream = context.stream_from_shell(OrientationOperation::Subscribe);
+
+                while let Some(orientation) = stream.next().await {
+                    context.update_app(callback(orientation));
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,95 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_orientation::{DeviceOrientation, Orientation};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        WatchOrientation,
+
+        #[serde(skip)]
+        OrientationChanged(DeviceOrientation),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub orientation: Option<DeviceOrientation>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub two_columns: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::WatchOrientation => caps.orientation.on_change(Event::OrientationChanged),
+                Event::OrientationChanged(orientation) => {
+                    model.orientation = Some(orientation);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                two_columns: model
+                    .orientation
+                    .map_or(false, |orientation| orientation.is_landscape()),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub orientation: Orientation<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_orientation::{DeviceOrientation, OrientationOperation};
+
+    #[test]
+    pub fn test_on_change() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::WatchOrientation, &mut model)
+            .expect_one_effect()
+            .expect_orientation();
+
+        assert_eq!(request.operation, OrientationOperation::Subscribe);
+        assert!(request.expects_response());
+
+        for (orientation, two_columns) in [
+            (DeviceOrientation::LandscapeLeft, true),
+            (DeviceOrientation::FaceUp, false),
+        ] {
+            let event = app
+                .resolve(&mut request, orientation)
+                .unwrap()
+                .expect_one_event();
+            assert_eq!(event, Event::OrientationChanged(orientation));
+
+            let update = app.update(event, &mut model);
+            assert_effect!(update, Effect::Render(_));
+            assert_eq!(app.view(&model).two_columns, two_columns);
+        }
+
+        assert!(request.expects_response());
+    }
+}
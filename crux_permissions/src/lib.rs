@@ -0,0 +1,116 @@
+//! Runtime permissions for Crux apps
+//!
+//! `crux_permissions` allows Crux apps to check the status of the runtime permissions
+//! the platform grants (e.g. camera or location access), and to ask the user for them.
+//! Other capabilities can then be used once the permission they need has been granted.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use serde::{Deserialize, Serialize};
+
+/// A runtime permission, which the user grants the app
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Permission {
+    Camera,
+    Location,
+    Notifications,
+    Microphone,
+    Contacts,
+    Photos,
+}
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PermissionsOperation {
+    /// Ask the user to grant the `permission`, if they haven't been asked yet
+    Request { permission: Permission },
+    /// Check the current status of the `permission`, without asking the user
+    Status { permission: Permission },
+}
+
+/// The status of a permission
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PermissionStatus {
+    /// The user granted the permission
+    Granted,
+    /// The user denied the permission
+    Denied,
+    /// The permission can't be granted, e.g. because of parental controls
+    Restricted,
+    /// The user hasn't been asked for the permission yet
+    NotDetermined,
+}
+
+impl Operation for PermissionsOperation {
+    type Output = PermissionStatus;
+}
+
+#[derive(Capability)]
+pub struct Permissions<Ev> {
+    context: CapabilityContext<PermissionsOperation, Ev>,
+}
+
+impl<Ev> Clone for Permissions<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Permissions<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<PermissionsOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Ask the user to grant the `permission`. The resulting status is passed to
+    /// the app wrapped in the event produced by the `callback`.
+    pub fn request<F>(&self, permission: Permission, callback: F)
+    where
+        F: FnOnce(PermissionStatus) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.request_async(permission).await));
+            }
+        });
+    }
+
+    /// Ask the user to grant the `permission`, returning the resulting status.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn request_async(&self, permission: Permission) -> PermissionStatus {
+        self.context
+            .request_from_shell(PermissionsOperation::Request { permission })
+            .await
+    }
+
+    /// Check the current status of the `permission`, which will be passed to
+    /// the app wrapped in the event produced by the `callback`.
+    pub fn status<F>(&self, permission: Permission, callback: F)
+    where
+        F: FnOnce(PermissionStatus) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.status_async(permission).await));
+            }
+        });
+    }
+
+    /// Check the current status of the `permission`.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn status_async(&self, permission: Permission) -> PermissionStatus {
+        self.context
+            .request_from_shell(PermissionsOperation::Status { permission })
+            .await
+    }
+}
@@ -0,0 +1,113 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_permissions::{Permission, PermissionStatus, Permissions};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        EnableCamera,
+
+        #[serde(skip)]
+        CameraPermission(PermissionStatus),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub camera_enabled: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub camera_enabled: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::EnableCamera => caps
+                    .permissions
+                    .request(Permission::Camera, Event::CameraPermission),
+                Event::CameraPermission(status) => {
+                    model.camera_enabled = status == PermissionStatus::Granted;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                camera_enabled: model.camera_enabled,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub permissions: Permissions<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_permissions::{Permission, PermissionStatus, PermissionsOperation};
+
+    #[test]
+    pub fn test_camera_permission_granted() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::EnableCamera, &mut model)
+            .expect_one_effect()
+            .expect_permissions();
+
+        assert_eq!(
+            request.operation,
+            PermissionsOperation::Request {
+                permission: Permission::Camera
+            }
+        );
+
+        let event = app
+            .resolve(&mut request, PermissionStatus::Granted)
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::CameraPermission(PermissionStatus::Granted));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert!(app.view(&model).camera_enabled);
+    }
+
+    #[test]
+    pub fn test_camera_permission_denied() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::EnableCamera, &mut model)
+            .expect_one_effect()
+            .expect_permissions();
+
+        let event = app
+            .resolve(&mut request, PermissionStatus::Denied)
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::CameraPermission(PermissionStatus::Denied));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert!(!app.view(&model).camera_enabled);
+    }
+}
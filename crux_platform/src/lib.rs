@@ -1,5 +1,7 @@
 //! TODO mod docs
 
+use std::sync::{Arc, Mutex};
+
 use crux_core::capability::{CapabilityContext, Operation};
 use crux_core::macros::Capability;
 use serde::{Deserialize, Serialize};
@@ -42,3 +44,84 @@ where
         });
     }
 }
+
+/// Ask for details of the device and operating system, answered with a [`PlatformInfo`]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlatformInfoRequest;
+
+/// Details of the device and operating system the shell is running on
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlatformInfo {
+    /// The operating system, e.g. "iOS", "Android" or "Web"
+    pub os: String,
+    pub os_version: String,
+    pub device_model: Option<String>,
+}
+
+impl Operation for PlatformInfoRequest {
+    type Output = PlatformInfo;
+}
+
+pub struct DeviceInfo<Ev> {
+    context: CapabilityContext<PlatformInfoRequest, Ev>,
+    // the platform info doesn't change while the app is running, so we only ask for it once
+    info: Arc<Mutex<Option<PlatformInfo>>>,
+}
+
+impl<Ev> crux_core::Capability<Ev> for DeviceInfo<Ev> {
+    type Operation = PlatformInfoRequest;
+    type MappedSelf<MappedEv> = DeviceInfo<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static + Send,
+    {
+        DeviceInfo {
+            context: self.context.map_event(f),
+            info: self.info.clone(),
+        }
+    }
+}
+
+impl<Ev> DeviceInfo<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<PlatformInfoRequest, Ev>) -> Self {
+        Self {
+            context,
+            info: Arc::default(),
+        }
+    }
+
+    /// Request details of the device and operating system, which will be passed to the app
+    /// as a [`PlatformInfo`] wrapped in the event produced by the `callback`.
+    ///
+    /// The shell is only asked once, later calls are answered from the first response.
+    pub fn get<F>(&self, callback: F)
+    where
+        F: FnOnce(PlatformInfo) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let cache = self.info.clone();
+            async move {
+                let cached = cache.lock().expect("platform info lock poisoned").clone();
+
+                let info = match cached {
+                    Some(info) => info,
+                    None => {
+                        let info = context.request_from_shell(PlatformInfoRequest).await;
+
+                        *cache.lock().expect("platform info lock poisoned") = Some(info.clone());
+                        info
+                    }
+                };
+
+                context.update_app(callback(info));
+            }
+        });
+    }
+}
@@ -1,7 +1,7 @@
 mod shared {
     use crux_core::macros::Effect;
     use crux_core::render::Render;
-    use crux_platform::{Platform, PlatformResponse};
+    use crux_platform::{DeviceInfo, Platform, PlatformInfo, PlatformResponse};
     use serde::{Deserialize, Serialize};
 
     #[derive(Default)]
@@ -11,11 +11,14 @@ mod shared {
     pub enum Event {
         PlatformGet,
         PlatformSet(PlatformResponse),
+        InfoGet,
+        InfoSet(PlatformInfo),
     }
 
     #[derive(Default, Serialize, Deserialize)]
     pub struct Model {
         pub platform: String,
+        pub info: Option<PlatformInfo>,
     }
 
     #[derive(Serialize, Deserialize, Default)]
@@ -36,6 +39,11 @@ mod shared {
                     model.platform = platform.0;
                     caps.render.render()
                 }
+                Event::InfoGet => caps.device_info.get(Event::InfoSet),
+                Event::InfoSet(info) => {
+                    model.info = Some(info);
+                    caps.render.render()
+                }
             }
         }
 
@@ -49,6 +57,7 @@ mod shared {
     #[derive(Effect)]
     pub struct Capabilities {
         pub platform: Platform<Event>,
+        pub device_info: DeviceInfo<Event>,
         pub render: Render<Event>,
     }
 }
@@ -99,10 +108,11 @@ mod shell {
 
 mod tests {
     use crate::{
-        shared::{App, Effect},
+        shared::{App, Effect, Event, Model},
         shell::run,
     };
-    use crux_core::Core;
+    use crux_core::{assert_effect, testing::AppTester, Core};
+    use crux_platform::{PlatformInfo, PlatformInfoRequest};
 
     #[test]
     pub fn test_platform() {
@@ -112,4 +122,33 @@ mod tests {
 
         assert_eq!(core.view().platform, "test shell");
     }
+
+    #[test]
+    pub fn test_platform_info() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let info = PlatformInfo {
+            os: "iOS".to_string(),
+            os_version: "18.1".to_string(),
+            device_model: Some("iPhone16,1".to_string()),
+        };
+
+        let mut request = app
+            .update(Event::InfoGet, &mut model)
+            .expect_one_effect()
+            .expect_device_info();
+
+        assert_eq!(request.operation, PlatformInfoRequest);
+
+        let update = app.resolve_to_event_then_update(&mut request, info.clone(), &mut model);
+
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(model.info, Some(info.clone()));
+
+        // the second call is answered without asking the shell again
+        let event = app.update(Event::InfoGet, &mut model).expect_one_event();
+
+        assert!(matches!(event, Event::InfoSet(cached) if cached == info));
+    }
 }
@@ -0,0 +1,150 @@
+//! User preferences for Crux apps
+//!
+//! `crux_preferences` allows Crux apps to read and write the app's user preferences, kept
+//! by the Shell in the platform's preference store (e.g. `UserDefaults` on iOS or
+//! `SharedPreferences` on Android). Unlike key-value storage, preferences can also be
+//! changed outside of the app, e.g. in the OS settings, so apps can subscribe to changes
+//! of a preference, which resolve every time its value changes.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+/// The value of a preference
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PreferenceValue {
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+}
+
+impl From<bool> for PreferenceValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i64> for PreferenceValue {
+    fn from(value: i64) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<f64> for PreferenceValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<String> for PreferenceValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for PreferenceValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PreferencesOperation {
+    /// Read the value of the preference with the `key`, `None` if it isn't set
+    Get { key: String },
+    /// Set the preference with the `key` to the `value`. This isn't resolved.
+    Set { key: String, value: PreferenceValue },
+    /// Start delivering the value of the preference with the `key` every time it changes,
+    /// `None` if it's been removed
+    Subscribe { key: String },
+}
+
+impl Operation for PreferencesOperation {
+    type Output = Option<PreferenceValue>;
+}
+
+#[derive(Capability)]
+pub struct Preferences<Ev> {
+    context: CapabilityContext<PreferencesOperation, Ev>,
+}
+
+impl<Ev> Clone for Preferences<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Preferences<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<PreferencesOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Read the value of the preference with the `key`, which will be passed to the app
+    /// wrapped in the event produced by the `callback`, `None` if the preference isn't set.
+    pub fn get<F>(&self, key: impl Into<String>, callback: F)
+    where
+        F: FnOnce(Option<PreferenceValue>) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+            let key = key.into();
+
+            async move {
+                context.update_app(callback(this.get_async(key).await));
+            }
+        });
+    }
+
+    /// Read the value of the preference with the `key`, `None` if the preference isn't set.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn get_async(&self, key: impl Into<String>) -> Option<PreferenceValue> {
+        self.context
+            .request_from_shell(PreferencesOperation::Get { key: key.into() })
+            .await
+    }
+
+    /// Ask the Shell to set the preference with the `key` to the `value`
+    pub fn set(&self, key: impl Into<String>, value: impl Into<PreferenceValue>) {
+        let operation = PreferencesOperation::Set {
+            key: key.into(),
+            value: value.into(),
+        };
+
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                context.notify_shell(operation).await;
+            }
+        });
+    }
+
+    /// Receive the value of the preference with the `key` every time it changes, whether
+    /// by the app or outside of it. The new value is passed to the app wrapped in the event
+    /// produced by the `callback`, `None` if the preference has been removed.
+    pub fn on_change<F>(&self, key: impl Into<String>, callback: F)
+    where
+        F: Fn(Option<PreferenceValue>) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let key = key.into();
+
+            async move {
+                let mut stream = context.stream_from_shell(PreferencesOperation::Subscribe { key });
+
+                while let Some(value) = stream.next().await {
+                    context.update_app(callback(value));
+                }
+            }
+        });
+    }
+}
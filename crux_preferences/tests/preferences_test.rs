@@ -0,0 +1,160 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_preferences::{PreferenceValue, Preferences};
+    use serde::{Deserialize, Serialize};
+
+    pub const DARK_MODE: &str = "dark_mode";
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Start,
+        ToggleDarkMode,
+
+        #[serde(skip)]
+        DarkMode(Option<PreferenceValue>),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub dark_mode: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub dark_mode: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Start => {
+                    caps.preferences.get(DARK_MODE, Event::DarkMode);
+                    caps.preferences.on_change(DARK_MODE, Event::DarkMode);
+                }
+                Event::ToggleDarkMode => {
+                    model.dark_mode = !model.dark_mode;
+                    caps.preferences.set(DARK_MODE, model.dark_mode);
+                    caps.render.render();
+                }
+                Event::DarkMode(value) => {
+                    model.dark_mode = value == Some(PreferenceValue::Bool(true));
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                dark_mode: model.dark_mode,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub preferences: Preferences<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model, DARK_MODE};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_preferences::{PreferenceValue, PreferencesOperation};
+
+    #[test]
+    pub fn test_set_and_get() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::ToggleDarkMode, &mut model);
+        assert_effect!(update, Effect::Render(_));
+
+        let request = update
+            .take_one_effect(|effect| matches!(effect, Effect::Preferences(_)))
+            .unwrap()
+            .expect_preferences();
+        assert_eq!(
+            request.operation,
+            PreferencesOperation::Set {
+                key: DARK_MODE.to_string(),
+                value: PreferenceValue::Bool(true)
+            }
+        );
+        assert!(!request.expects_response());
+
+        let mut model = Model::default();
+        let mut update = app.update(Event::Start, &mut model);
+        let mut request = update
+            .take_one_effect(|effect| {
+                matches!(
+                    effect,
+                    Effect::Preferences(request)
+                        if matches!(request.operation, PreferencesOperation::Get { .. })
+                )
+            })
+            .unwrap()
+            .expect_preferences();
+        assert_eq!(
+            request.operation,
+            PreferencesOperation::Get {
+                key: DARK_MODE.to_string()
+            }
+        );
+
+        let event = app
+            .resolve(&mut request, Some(PreferenceValue::Bool(true)))
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::DarkMode(Some(PreferenceValue::Bool(true))));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert!(app.view(&model).dark_mode);
+    }
+
+    #[test]
+    pub fn test_external_change() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::Start, &mut model);
+        let mut request = update
+            .take_one_effect(|effect| {
+                matches!(
+                    effect,
+                    Effect::Preferences(request)
+                        if matches!(request.operation, PreferencesOperation::Subscribe { .. })
+                )
+            })
+            .unwrap()
+            .expect_preferences();
+        assert_eq!(
+            request.operation,
+            PreferencesOperation::Subscribe {
+                key: DARK_MODE.to_string()
+            }
+        );
+
+        for (value, dark_mode) in [(Some(PreferenceValue::Bool(true)), true), (None, false)] {
+            let event = app
+                .resolve(&mut request, value.clone())
+                .unwrap()
+                .expect_one_event();
+            assert_eq!(event, Event::DarkMode(value));
+
+            let update = app.update(event, &mut model);
+            assert_effect!(update, Effect::Render(_));
+            assert_eq!(app.view(&model).dark_mode, dark_mode);
+        }
+    }
+}
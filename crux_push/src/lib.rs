@@ -0,0 +1,156 @@
+//! Remote push notifications for Crux apps
+//!
+//! `crux_push` allows Crux apps to register the device for remote push notifications and to
+//! receive the notifications delivered to it. Registering resolves once, with the device token
+//! issued by the platform's push service (which the app typically sends to its server), or with
+//! a [`PushError`]. Subscribing to messages resolves every time a notification is delivered.
+
+use std::collections::BTreeMap;
+
+use crux_core::capability::{CapabilityContext, Operation};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PushRequest {
+    /// Register the device with the push service
+    Register,
+    /// Start delivering incoming notifications to the app
+    Subscribe,
+}
+
+/// A notification delivered to the device
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PushMessage {
+    pub title: String,
+    pub body: String,
+    /// Custom data sent along with the notification
+    pub data: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PushResponse {
+    /// The device was registered and can be reached with the `token`
+    Registered { token: String },
+    /// The device couldn't be registered, e.g. because the user denied the permission
+    RegistrationFailed { message: String },
+    /// A notification was delivered
+    Message(PushMessage),
+}
+
+impl Operation for PushRequest {
+    type Output = PushResponse;
+}
+
+pub type PushResult = Result<String, PushError>;
+
+/// Error type for push registration
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Error)]
+#[serde(rename_all = "camelCase")]
+pub enum PushError {
+    #[error("registration failed: {message}")]
+    RegistrationFailed { message: String },
+    #[error("unexpected response from the shell")]
+    UnexpectedResponse,
+}
+
+/// The PushNotifications capability API
+///
+/// This capability allows the app to register for remote push notifications and to
+/// receive the notifications delivered to the device.
+pub struct PushNotifications<Ev> {
+    context: CapabilityContext<PushRequest, Ev>,
+}
+
+impl<Ev> crux_core::Capability<Ev> for PushNotifications<Ev> {
+    type Operation = PushRequest;
+    type MappedSelf<MappedEv> = PushNotifications<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static + Send,
+    {
+        PushNotifications::new(self.context.map_event(f))
+    }
+
+    #[cfg(feature = "typegen")]
+    fn register_types(generator: &mut crux_core::typegen::TypeGen) -> crux_core::typegen::Result {
+        generator.register_type::<PushError>()?;
+        generator.register_type::<Self::Operation>()?;
+        generator.register_type::<<Self::Operation as Operation>::Output>()?;
+        Ok(())
+    }
+}
+
+impl<Ev> Clone for PushNotifications<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> PushNotifications<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<PushRequest, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Register the device for remote push notifications. The device token, or a [`PushError`]
+    /// if the registration failed, is passed to the app wrapped in the event produced by the `callback`.
+    pub fn register<F>(&self, callback: F)
+    where
+        F: FnOnce(PushResult) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.register_async().await));
+            }
+        });
+    }
+
+    /// Register the device for remote push notifications, returning the device token.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn register_async(&self) -> PushResult {
+        match self.context.request_from_shell(PushRequest::Register).await {
+            PushResponse::Registered { token } => Ok(token),
+            PushResponse::RegistrationFailed { message } => {
+                Err(PushError::RegistrationFailed { message })
+            }
+            PushResponse::Message(_) => Err(PushError::UnexpectedResponse),
+        }
+    }
+
+    /// Receive the notifications delivered to the device. Every notification is passed
+    /// to the app wrapped in the event produced by the `callback`.
+    pub fn on_message<F>(&self, callback: F)
+    where
+        F: Fn(PushMessage) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                let mut stream = context.stream_from_shell(PushRequest::Subscribe);
+
+                while let Some(response) = stream.next().await {
+                    // only messages are delivered to a subscription
+                    if let PushResponse::Message(message) = response {
+                        context.update_app(callback(message));
+                    }
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,170 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_push::{PushMessage, PushNotifications, PushResult};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        EnableNotifications,
+        Listen,
+
+        #[serde(skip)]
+        Registered(PushResult),
+        #[serde(skip)]
+        Received(PushMessage),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub token: Option<String>,
+        pub error: Option<String>,
+        pub inbox: Vec<PushMessage>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub unread: usize,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::EnableNotifications => caps.push.register(Event::Registered),
+                Event::Listen => caps.push.on_message(Event::Received),
+                Event::Registered(Ok(token)) => {
+                    model.token = Some(token);
+                    caps.render.render();
+                }
+                Event::Registered(Err(error)) => {
+                    model.error = Some(error.to_string());
+                    caps.render.render();
+                }
+                Event::Received(message) => {
+                    model.inbox.push(message);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                unread: model.inbox.len(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub push: PushNotifications<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_push::{PushMessage, PushRequest, PushResponse};
+
+    #[test]
+    pub fn test_register() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::EnableNotifications, &mut model)
+            .expect_one_effect()
+            .expect_push();
+
+        assert_eq!(request.operation, PushRequest::Register);
+
+        let event = app
+            .resolve(
+                &mut request,
+                PushResponse::Registered {
+                    token: "device-token".to_string(),
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Registered(Ok("device-token".to_string())));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(model.token, Some("device-token".to_string()));
+
+        // registration is one-shot
+        app.assert_no_pending();
+    }
+
+    #[test]
+    pub fn test_registration_failed() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::EnableNotifications, &mut model)
+            .expect_one_effect()
+            .expect_push();
+
+        let event = app
+            .resolve(
+                &mut request,
+                PushResponse::RegistrationFailed {
+                    message: "permission denied".to_string(),
+                },
+            )
+            .unwrap()
+            .expect_one_event();
+
+        let _update = app.update(event, &mut model);
+        assert_eq!(model.token, None);
+        assert_eq!(
+            model.error,
+            Some("registration failed: permission denied".to_string())
+        );
+    }
+
+    #[test]
+    pub fn test_delivered_messages() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Listen, &mut model)
+            .expect_one_effect()
+            .expect_push();
+
+        assert_eq!(request.operation, PushRequest::Subscribe);
+
+        let message = PushMessage {
+            title: "New order".to_string(),
+            body: "Order 42 has shipped".to_string(),
+            data: BTreeMap::from([("order".to_string(), "42".to_string())]),
+        };
+
+        for _ in 0..2 {
+            let event = app
+                .resolve(&mut request, PushResponse::Message(message.clone()))
+                .unwrap()
+                .expect_one_event();
+            assert_eq!(event, Event::Received(message.clone()));
+
+            let update = app.update(event, &mut model);
+            assert_effect!(update, Effect::Render(_));
+        }
+
+        assert_eq!(model.inbox, vec![message.clone(), message]);
+        assert_eq!(app.view(&model).unread, 2);
+    }
+}
@@ -0,0 +1,80 @@
+//! App review prompts for Crux apps
+//!
+//! `crux_review` allows Crux apps to ask the Shell to prompt the user to review or rate
+//! the app, at a moment decided by the app. Whether the prompt is actually shown is up to
+//! the Shell and the OS, which limit how often apps can prompt, so apps can also ask
+//! whether prompting is currently allowed.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use serde::{Deserialize, Serialize};
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReviewOperation {
+    /// Prompt the user to review the app. This isn't resolved.
+    RequestReview,
+    /// Check whether prompting the user to review the app is currently allowed
+    CanRequest,
+}
+
+impl Operation for ReviewOperation {
+    type Output = bool;
+}
+
+#[derive(Capability)]
+pub struct Review<Ev> {
+    context: CapabilityContext<ReviewOperation, Ev>,
+}
+
+impl<Ev> Clone for Review<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Review<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<ReviewOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Ask the Shell to prompt the user to review the app. The Shell (or the OS) may
+    /// decide not to show the prompt, and the app isn't told either way.
+    pub fn request_review(&self) {
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                context.notify_shell(ReviewOperation::RequestReview).await;
+            }
+        });
+    }
+
+    /// Check whether prompting the user to review the app is currently allowed. The answer
+    /// is passed to the app wrapped in the event produced by the `callback`.
+    pub fn can_request<F>(&self, callback: F)
+    where
+        F: FnOnce(bool) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.can_request_async().await));
+            }
+        });
+    }
+
+    /// Check whether prompting the user to review the app is currently allowed.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn can_request_async(&self) -> bool {
+        self.context
+            .request_from_shell(ReviewOperation::CanRequest)
+            .await
+    }
+}
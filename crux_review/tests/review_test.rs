@@ -0,0 +1,109 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_review::Review;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        OrderCompleted,
+
+        #[serde(skip)]
+        ReviewAllowed(bool),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub prompted: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub prompted: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::OrderCompleted => caps.review.can_request(Event::ReviewAllowed),
+                Event::ReviewAllowed(true) => {
+                    model.prompted = true;
+                    caps.review.request_review();
+                    caps.render.render();
+                }
+                Event::ReviewAllowed(false) => {}
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                prompted: model.prompted,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub review: Review<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_review::ReviewOperation;
+
+    #[test]
+    pub fn test_can_request() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::OrderCompleted, &mut model)
+            .expect_one_effect()
+            .expect_review();
+
+        assert_eq!(request.operation, ReviewOperation::CanRequest);
+
+        let event = app.resolve(&mut request, true).unwrap().expect_one_event();
+        assert_eq!(event, Event::ReviewAllowed(true));
+    }
+
+    #[test]
+    pub fn test_request_review() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::ReviewAllowed(true), &mut model);
+        assert_effect!(update, Effect::Render(_));
+
+        let request = update
+            .take_effects(|effect| matches!(effect, Effect::Review(_)))
+            .pop_front()
+            .unwrap()
+            .expect_review();
+
+        assert_eq!(request.operation, ReviewOperation::RequestReview);
+        assert!(!request.expects_response());
+        assert!(app.view(&model).prompted);
+    }
+
+    #[test]
+    pub fn test_request_not_allowed() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        app.update(Event::ReviewAllowed(false), &mut model)
+            .assert_empty();
+        assert!(!app.view(&model).prompted);
+    }
+}
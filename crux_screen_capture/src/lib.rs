@@ -0,0 +1,119 @@
+//! Screen capture notifications for Crux apps
+//!
+//! `crux_screen_capture` allows Crux apps to find out when the user takes a screenshot, or
+//! starts or stops recording the screen, and to ask the Shell to prevent capturing the screen
+//! while it shows something sensitive.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScreenCaptureOperation {
+    /// Subscribe to screenshots. The Shell resolves it with
+    /// [`ScreenCaptureEvent::ScreenshotTaken`] every time the user takes one.
+    SubscribeScreenshots,
+    /// Subscribe to screen recording. The Shell resolves it with
+    /// [`ScreenCaptureEvent::RecordingChanged`] every time recording starts or stops.
+    SubscribeRecording,
+    /// Prevent (or allow again) capturing the screen. This isn't resolved.
+    SetSecure { secure: bool },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScreenCaptureEvent {
+    ScreenshotTaken,
+    RecordingChanged { recording: bool },
+}
+
+impl Operation for ScreenCaptureOperation {
+    type Output = ScreenCaptureEvent;
+}
+
+#[derive(Capability)]
+pub struct ScreenCapture<Ev> {
+    context: CapabilityContext<ScreenCaptureOperation, Ev>,
+}
+
+impl<Ev> Clone for ScreenCapture<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> ScreenCapture<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<ScreenCaptureOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Subscribe to screenshots. Every time the user takes one, the event produced by the
+    /// `callback` is passed to the app.
+    pub fn on_screenshot<F>(&self, callback: F)
+    where
+        F: Fn() -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                let mut stream =
+                    context.stream_from_shell(ScreenCaptureOperation::SubscribeScreenshots);
+
+                while let Some(event) = stream.next().await {
+                    let ScreenCaptureEvent::ScreenshotTaken = event else {
+                        panic!("attempt to convert ScreenCaptureEvent other than ScreenshotTaken to a screenshot");
+                    };
+
+                    context.update_app(callback());
+                }
+            }
+        });
+    }
+
+    /// Subscribe to screen recording. Every time recording starts or stops, whether the
+    /// screen is being recorded is passed to the app wrapped in the event produced by the
+    /// `callback`.
+    pub fn on_recording_change<F>(&self, callback: F)
+    where
+        F: Fn(bool) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                let mut stream =
+                    context.stream_from_shell(ScreenCaptureOperation::SubscribeRecording);
+
+                while let Some(event) = stream.next().await {
+                    let ScreenCaptureEvent::RecordingChanged { recording } = event else {
+                        panic!("attempt to convert ScreenCaptureEvent other than RecordingChanged to bool");
+                    };
+
+                    context.update_app(callback(recording));
+                }
+            }
+        });
+    }
+
+    /// Ask the Shell to prevent capturing the screen while it shows something sensitive,
+    /// or to allow it again when `secure` is `false`. Screenshots and recordings then
+    /// leave the app's content out, where the platform supports it.
+    pub fn set_secure(&self, secure: bool) {
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                context
+                    .notify_shell(ScreenCaptureOperation::SetSecure { secure })
+                    .await;
+            }
+        });
+    }
+}
@@ -0,0 +1,139 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_screen_capture::ScreenCapture;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        ShowTutorial,
+        ShowPaymentDetails,
+
+        #[serde(skip)]
+        ScreenshotTaken,
+        #[serde(skip)]
+        RecordingChanged(bool),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub screenshots: usize,
+        pub recording: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub screenshots: usize,
+        pub recording: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::ShowTutorial => {
+                    caps.screen_capture.on_screenshot(|| Event::ScreenshotTaken);
+                    caps.screen_capture
+                        .on_recording_change(Event::RecordingChanged);
+                }
+                Event::ShowPaymentDetails => caps.screen_capture.set_secure(true),
+                Event::ScreenshotTaken => {
+                    model.screenshots += 1;
+                    caps.render.render();
+                }
+                Event::RecordingChanged(recording) => {
+                    model.recording = recording;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                screenshots: model.screenshots,
+                recording: model.recording,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub screen_capture: ScreenCapture<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_screen_capture::{ScreenCaptureEvent, ScreenCaptureOperation};
+
+    #[test]
+    pub fn test_screenshot_notification() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut effects = app.update(Event::ShowTutorial, &mut model).into_effects();
+
+        let mut screenshots = effects.next().unwrap().expect_screen_capture();
+        assert_eq!(
+            screenshots.operation,
+            ScreenCaptureOperation::SubscribeScreenshots
+        );
+
+        let mut recording = effects.next().unwrap().expect_screen_capture();
+        assert_eq!(
+            recording.operation,
+            ScreenCaptureOperation::SubscribeRecording
+        );
+
+        let event = app
+            .resolve(&mut screenshots, ScreenCaptureEvent::ScreenshotTaken)
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::ScreenshotTaken);
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(app.view(&model).screenshots, 1);
+
+        let event = app
+            .resolve(
+                &mut recording,
+                ScreenCaptureEvent::RecordingChanged { recording: true },
+            )
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::RecordingChanged(true));
+
+        let _update = app.update(event, &mut model);
+        assert!(app.view(&model).recording);
+
+        assert!(screenshots.expects_response());
+        assert!(recording.expects_response());
+    }
+
+    #[test]
+    pub fn test_set_secure() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = app
+            .update(Event::ShowPaymentDetails, &mut model)
+            .expect_one_effect()
+            .expect_screen_capture();
+
+        assert_eq!(
+            request.operation,
+            ScreenCaptureOperation::SetSecure { secure: true }
+        );
+        assert!(!request.expects_response());
+    }
+}
@@ -0,0 +1,170 @@
+//! Motion sensor readings for Crux apps
+//!
+//! `crux_sensors` allows Crux apps to subscribe to the readings of the device's accelerometer,
+//! gyroscope or magnetometer. The Shell resolves a subscription with a reading every time the
+//! sensor produces one, until the app unsubscribes. If the sensor isn't available on the device,
+//! the Shell resolves the subscription with [`SensorsResponse::Unavailable`] instead, which the
+//! app receives as a [`SensorError`].
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crux_core::capability::{CapabilityContext, Operation};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The motion sensors an app can subscribe to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SensorKind {
+    Accelerometer,
+    Gyroscope,
+    Magnetometer,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubscriptionId(pub usize);
+
+fn get_subscription_id() -> SubscriptionId {
+    static COUNTER: AtomicUsize = AtomicUsize::new(1);
+    SubscriptionId(COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SensorsRequest {
+    /// Start sending readings of the `sensor`
+    Subscribe {
+        id: SubscriptionId,
+        sensor: SensorKind,
+    },
+    /// Stop sending readings for the subscription `id`
+    Unsubscribe { id: SubscriptionId },
+}
+
+/// A single reading of a sensor along its three axes. The units depend on the sensor,
+/// the `timestamp` is in milliseconds since the Unix epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SensorReading {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub timestamp: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SensorsResponse {
+    Reading(SensorReading),
+    /// The sensor isn't available on this device. No more readings will follow.
+    Unavailable,
+}
+
+impl Operation for SensorsRequest {
+    type Output = SensorsResponse;
+}
+
+pub type SensorResult = Result<SensorReading, SensorError>;
+
+/// Error type for sensor subscriptions
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Error)]
+#[serde(rename_all = "camelCase")]
+pub enum SensorError {
+    #[error("{sensor:?} is not available")]
+    Unavailable { sensor: SensorKind },
+}
+
+/// The Sensors capability API
+///
+/// This capability allows the app to subscribe to readings from the device's motion sensors.
+pub struct Sensors<Ev> {
+    context: CapabilityContext<SensorsRequest, Ev>,
+}
+
+impl<Ev> crux_core::Capability<Ev> for Sensors<Ev> {
+    type Operation = SensorsRequest;
+    type MappedSelf<MappedEv> = Sensors<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static + Send,
+    {
+        Sensors::new(self.context.map_event(f))
+    }
+
+    #[cfg(feature = "typegen")]
+    fn register_types(generator: &mut crux_core::typegen::TypeGen) -> crux_core::typegen::Result {
+        generator.register_type::<SensorKind>()?;
+        generator.register_type::<Self::Operation>()?;
+        generator.register_type::<<Self::Operation as Operation>::Output>()?;
+        Ok(())
+    }
+}
+
+impl<Ev> Clone for Sensors<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Sensors<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<SensorsRequest, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Subscribe to the readings of the `sensor`. Every reading is passed to the app
+    /// wrapped in the event produced by the `callback`, until the subscription is cancelled
+    /// with [`Sensors::unsubscribe`].
+    ///
+    /// If the sensor is not available, the `callback` is called once with a [`SensorError`].
+    pub fn subscribe<F>(&self, sensor: SensorKind, callback: F) -> SubscriptionId
+    where
+        F: Fn(SensorResult) -> Ev + Send + Sync + 'static,
+    {
+        let id = get_subscription_id();
+
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                let mut stream =
+                    context.stream_from_shell(SensorsRequest::Subscribe { id, sensor });
+
+                while let Some(response) = stream.next().await {
+                    match response {
+                        SensorsResponse::Reading(reading) => {
+                            context.update_app(callback(Ok(reading)));
+                        }
+                        SensorsResponse::Unavailable => {
+                            context.update_app(callback(Err(SensorError::Unavailable { sensor })));
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        id
+    }
+
+    /// Stop receiving readings for the subscription `id`.
+    pub fn unsubscribe(&self, id: SubscriptionId) {
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                context
+                    .notify_shell(SensorsRequest::Unsubscribe { id })
+                    .await;
+            }
+        });
+    }
+}
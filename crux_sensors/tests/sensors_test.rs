@@ -0,0 +1,147 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_sensors::{SensorKind, SensorReading, SensorResult, Sensors};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        StartWorkout,
+
+        #[serde(skip)]
+        Motion(SensorResult),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub readings: Vec<SensorReading>,
+        pub error: Option<String>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub readings: usize,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::StartWorkout => {
+                    caps.sensors
+                        .subscribe(SensorKind::Accelerometer, Event::Motion);
+                }
+                Event::Motion(Ok(reading)) => {
+                    model.readings.push(reading);
+                    caps.render.render();
+                }
+                Event::Motion(Err(error)) => {
+                    model.error = Some(error.to_string());
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                readings: model.readings.len(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub sensors: Sensors<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_sensors::{SensorError, SensorKind, SensorReading, SensorsRequest, SensorsResponse};
+
+    #[test]
+    pub fn test_accelerometer_readings() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::StartWorkout, &mut model)
+            .expect_one_effect()
+            .expect_sensors();
+
+        let SensorsRequest::Subscribe { sensor, .. } = request.operation else {
+            panic!("expected a subscription");
+        };
+        assert_eq!(sensor, SensorKind::Accelerometer);
+
+        let readings = [
+            SensorReading {
+                x: 0.1,
+                y: 0.2,
+                z: 9.8,
+                timestamp: 1000,
+            },
+            SensorReading {
+                x: 0.3,
+                y: 0.1,
+                z: 9.7,
+                timestamp: 1020,
+            },
+        ];
+
+        for reading in readings {
+            let update = app
+                .resolve(&mut request, SensorsResponse::Reading(reading))
+                .expect("to resolve");
+
+            let event = update.expect_one_event();
+            assert_eq!(event, Event::Motion(Ok(reading)));
+
+            let update = app.update(event, &mut model);
+            assert_effect!(update, Effect::Render(_));
+        }
+
+        assert_eq!(model.readings, readings);
+        assert!(model.error.is_none());
+    }
+
+    #[test]
+    pub fn test_unavailable_sensor() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::StartWorkout, &mut model)
+            .expect_one_effect()
+            .expect_sensors();
+
+        let update = app
+            .resolve(&mut request, SensorsResponse::Unavailable)
+            .expect("to resolve");
+
+        let event = update.expect_one_event();
+        assert_eq!(
+            event,
+            Event::Motion(Err(SensorError::Unavailable {
+                sensor: SensorKind::Accelerometer
+            }))
+        );
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+
+        assert_eq!(
+            model.error.as_deref(),
+            Some("Accelerometer is not available")
+        );
+    }
+}
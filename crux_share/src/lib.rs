@@ -0,0 +1,81 @@
+//! Native share sheet for Crux apps
+//!
+//! `crux_share` allows Crux apps to share content using the platform's share sheet. The Shell
+//! presents the sheet with the [`ShareContent`] and resolves the request once the user has either
+//! completed the share or dismissed the sheet.
+//!
+//! Files are referenced by the same app-scoped relative paths used with `crux_filesystem`, which
+//! the Shell resolves inside its storage location before handing them to the share sheet.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use serde::{Deserialize, Serialize};
+
+/// The content to share. Any of the parts can be left empty, but the Shell may refuse to present
+/// the share sheet with nothing to share.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareContent {
+    /// Text to share, e.g. a message or a title
+    pub text: Option<String>,
+    /// A link to share
+    pub url: Option<String>,
+    /// App-scoped paths of files to share
+    pub files: Vec<String>,
+}
+
+/// How the user left the share sheet
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShareResult {
+    /// The user picked a destination and the content was shared
+    Completed,
+    /// The user dismissed the share sheet without sharing
+    Dismissed,
+}
+
+impl Operation for ShareContent {
+    type Output = ShareResult;
+}
+
+#[derive(Capability)]
+pub struct Share<Ev> {
+    context: CapabilityContext<ShareContent, Ev>,
+}
+
+impl<Ev> Clone for Share<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Share<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<ShareContent, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Present the share sheet with the `content`. Whether the user completed the share
+    /// or dismissed the sheet is passed to the app wrapped in the event produced by the `callback`.
+    pub fn share<F>(&self, content: ShareContent, callback: F)
+    where
+        F: FnOnce(ShareResult) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.share_async(content).await));
+            }
+        });
+    }
+
+    /// Present the share sheet with the `content`, returning whether the user completed the share.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn share_async(&self, content: ShareContent) -> ShareResult {
+        self.context.request_from_shell(content).await
+    }
+}
@@ -0,0 +1,120 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_share::{Share, ShareContent, ShareResult};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        ShareArticle,
+
+        #[serde(skip)]
+        Shared(ShareResult),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub shared: Option<ShareResult>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub shared: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::ShareArticle => caps.share.share(
+                    ShareContent {
+                        text: Some("Have a look at this".to_string()),
+                        url: Some("https://example.com/article".to_string()),
+                        files: vec!["articles/cover.jpg".to_string()],
+                    },
+                    Event::Shared,
+                ),
+                Event::Shared(result) => {
+                    model.shared = Some(result);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                shared: model.shared == Some(ShareResult::Completed),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub share: Share<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_share::{ShareContent, ShareResult};
+
+    #[test]
+    pub fn test_share_carries_content() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::ShareArticle, &mut model)
+            .expect_one_effect()
+            .expect_share();
+
+        assert_eq!(
+            request.operation,
+            ShareContent {
+                text: Some("Have a look at this".to_string()),
+                url: Some("https://example.com/article".to_string()),
+                files: vec!["articles/cover.jpg".to_string()],
+            }
+        );
+
+        let event = app
+            .resolve(&mut request, ShareResult::Completed)
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Shared(ShareResult::Completed));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert!(app.view(&model).shared);
+    }
+
+    #[test]
+    pub fn test_share_dismissed() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::ShareArticle, &mut model)
+            .expect_one_effect()
+            .expect_share();
+
+        let event = app
+            .resolve(&mut request, ShareResult::Dismissed)
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::Shared(ShareResult::Dismissed));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert!(!app.view(&model).shared);
+    }
+}
@@ -0,0 +1,58 @@
+//! System sound cues for Crux apps
+//!
+//! `crux_sound` allows Crux apps to play standard system sounds, e.g. to confirm an action
+//! or signal an error for accessibility. The Shell maps each [`SoundKind`] to the matching
+//! sound of its platform. Playing a sound is fire-and-forget, the app doesn't hear back
+//! from the Shell.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use serde::{Deserialize, Serialize};
+
+/// The standard sound cues
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SoundKind {
+    /// A short click, e.g. on pressing a button
+    Click,
+    /// An action failed
+    Error,
+    /// An action completed successfully
+    Success,
+}
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SystemSoundOperation {
+    /// Play the sound cue of the given `kind`
+    Play { kind: SoundKind },
+}
+
+impl Operation for SystemSoundOperation {
+    type Output = ();
+}
+
+#[derive(Capability)]
+pub struct SystemSound<Ev> {
+    context: CapabilityContext<SystemSoundOperation, Ev>,
+}
+
+impl<Ev> SystemSound<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<SystemSoundOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Play the system sound cue of the given `kind`
+    pub fn play(&self, kind: SoundKind) {
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                context
+                    .notify_shell(SystemSoundOperation::Play { kind })
+                    .await;
+            }
+        });
+    }
+}
@@ -0,0 +1,110 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_sound::{SoundKind, SystemSound};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Event {
+        Submit { valid: bool },
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub submitted: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub submitted: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Submit { valid } => {
+                    model.submitted = valid;
+
+                    caps.sound.play(if valid {
+                        SoundKind::Success
+                    } else {
+                        SoundKind::Error
+                    });
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                submitted: model.submitted,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub sound: SystemSound<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::testing::AppTester;
+    use crux_sound::{SoundKind, SystemSoundOperation};
+
+    #[test]
+    pub fn test_play_carries_kind() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::Submit { valid: false }, &mut model);
+
+        let request = update
+            .take_effects(Effect::is_sound)
+            .pop_front()
+            .expect("a sound effect")
+            .expect_sound();
+
+        assert_eq!(
+            request.operation,
+            SystemSoundOperation::Play {
+                kind: SoundKind::Error
+            }
+        );
+
+        // playing a sound doesn't expect a response from the shell
+        assert!(!request.expects_response());
+    }
+
+    #[test]
+    pub fn test_play_success() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::Submit { valid: true }, &mut model);
+
+        let request = update
+            .take_effects(Effect::is_sound)
+            .pop_front()
+            .expect("a sound effect")
+            .expect_sound();
+
+        assert_eq!(
+            request.operation,
+            SystemSoundOperation::Play {
+                kind: SoundKind::Success
+            }
+        );
+        assert!(app.view(&model).submitted);
+    }
+}
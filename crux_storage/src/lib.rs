@@ -0,0 +1,86 @@
+//! Storage quota and usage for Crux apps
+//!
+//! `crux_storage` allows Crux apps to read how much storage they're using and how much is
+//! still available to them, e.g. to decide whether to evict cached data before caching
+//! more. Platforms don't always limit or report the space available to an app, so the
+//! quota can be unknown.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use serde::{Deserialize, Serialize};
+
+/// The storage used by the app and the space left, in bytes. The available and total
+/// space are `None` when the platform doesn't report them, or doesn't limit the app's
+/// storage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageUsage {
+    pub used_bytes: u64,
+    pub available_bytes: Option<u64>,
+    pub total_bytes: Option<u64>,
+}
+
+impl StorageUsage {
+    /// Whether `bytes` more can be stored. This is `true` when the available space is
+    /// unknown.
+    pub fn can_store(&self, bytes: u64) -> bool {
+        self.available_bytes
+            .map_or(true, |available_bytes| bytes <= available_bytes)
+    }
+}
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StorageOperation {
+    /// Read the storage usage and quota
+    Usage,
+}
+
+impl Operation for StorageOperation {
+    type Output = StorageUsage;
+}
+
+#[derive(Capability)]
+pub struct Storage<Ev> {
+    context: CapabilityContext<StorageOperation, Ev>,
+}
+
+impl<Ev> Clone for Storage<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Storage<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<StorageOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Read the storage usage and quota, which will be passed to the app wrapped in the
+    /// event produced by the `callback`.
+    pub fn usage<F>(&self, callback: F)
+    where
+        F: FnOnce(StorageUsage) -> Ev + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.usage_async().await));
+            }
+        });
+    }
+
+    /// Read the storage usage and quota.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn usage_async(&self) -> StorageUsage {
+        self.context
+            .request_from_shell(StorageOperation::Usage)
+            .await
+    }
+}
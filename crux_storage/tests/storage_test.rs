@@ -0,0 +1,111 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_storage::{Storage, StorageUsage};
+    use serde::{Deserialize, Serialize};
+
+    pub const DOWNLOAD_BYTES: u64 = 50_000_000;
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        Download,
+
+        #[serde(skip)]
+        Usage(StorageUsage),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub evict_cache: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub evict_cache: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Download => caps.storage.usage(Event::Usage),
+                Event::Usage(usage) => {
+                    model.evict_cache = !usage.can_store(DOWNLOAD_BYTES);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                evict_cache: model.evict_cache,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub storage: Storage<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_storage::{StorageOperation, StorageUsage};
+
+    #[test]
+    pub fn test_usage() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Download, &mut model)
+            .expect_one_effect()
+            .expect_storage();
+        assert_eq!(request.operation, StorageOperation::Usage);
+
+        let usage = StorageUsage {
+            used_bytes: 900_000_000,
+            available_bytes: Some(10_000_000),
+            total_bytes: Some(910_000_000),
+        };
+        let event = app.resolve(&mut request, usage).unwrap().expect_one_event();
+        assert_eq!(event, Event::Usage(usage));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert!(app.view(&model).evict_cache);
+    }
+
+    #[test]
+    pub fn test_unknown_quota() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Download, &mut model)
+            .expect_one_effect()
+            .expect_storage();
+
+        let usage = StorageUsage {
+            used_bytes: 900_000_000,
+            available_bytes: None,
+            total_bytes: None,
+        };
+        let event = app.resolve(&mut request, usage).unwrap().expect_one_event();
+        assert_eq!(event, Event::Usage(usage));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert!(!app.view(&model).evict_cache);
+    }
+}
@@ -21,6 +21,7 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 #[serde(rename_all = "camelCase")]
 pub enum TimeRequest {
     Now,
+    Monotonic,
     NotifyAt { id: TimerId, instant: Instant },
     NotifyAfter { id: TimerId, duration: Duration },
     Clear { id: TimerId },
@@ -38,6 +39,7 @@ fn get_timer_id() -> TimerId {
 #[serde(rename_all = "camelCase")]
 pub enum TimeResponse {
     Now(Instant),
+    Monotonic(u64),
     InstantArrived { id: TimerId },
     DurationElapsed { id: TimerId },
     Cleared { id: TimerId },
@@ -116,6 +118,37 @@ where
         self.context.request_from_shell(TimeRequest::Now).await
     }
 
+    /// Request a monotonic clock value, which will be passed to the app as a
+    /// [`TimeResponse::Monotonic`] wrapped in the event produced by the `callback`.
+    /// The value is in nanoseconds since an arbitrary point chosen by the shell,
+    /// which guarantees it never goes backwards.
+    ///
+    /// Unlike [`now`](Self::now), the value is unaffected by adjustments of the wall clock,
+    /// so it is the right choice for measuring elapsed time, e.g. for animations or timeouts.
+    /// Only differences between two values are meaningful.
+    pub fn monotonic<F>(&self, callback: F)
+    where
+        F: FnOnce(TimeResponse) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.monotonic_async().await));
+            }
+        });
+    }
+
+    /// Request a monotonic clock value, which will be passed to the app as a
+    /// [`TimeResponse::Monotonic`].
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn monotonic_async(&self) -> TimeResponse {
+        self.context
+            .request_from_shell(TimeRequest::Monotonic)
+            .await
+    }
+
     /// Ask to receive a notification when the specified [`Instant`] has arrived.
     pub fn notify_at<F>(&self, instant: Instant, callback: F) -> TimerId
     where
@@ -193,6 +226,14 @@ mod test {
         let deserialized: TimeRequest = serde_json::from_str(&serialized).unwrap();
         assert_eq!(now, deserialized);
 
+        let monotonic = TimeRequest::Monotonic;
+
+        let serialized = serde_json::to_string(&monotonic).unwrap();
+        assert_eq!(&serialized, "\"monotonic\"");
+
+        let deserialized: TimeRequest = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(monotonic, deserialized);
+
         let now = TimeRequest::NotifyAt {
             id: TimerId(1),
             instant: Instant::new(1, 2).expect("valid instant"),
@@ -232,6 +273,14 @@ mod test {
         let deserialized: TimeResponse = serde_json::from_str(&serialized).unwrap();
         assert_eq!(now, deserialized);
 
+        let monotonic = TimeResponse::Monotonic(123_456_789);
+
+        let serialized = serde_json::to_string(&monotonic).unwrap();
+        assert_eq!(&serialized, r#"{"monotonic":123456789}"#);
+
+        let deserialized: TimeResponse = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(monotonic, deserialized);
+
         let now = TimeResponse::DurationElapsed { id: TimerId(1) };
 
         let serialized = serde_json::to_string(&now).unwrap();
@@ -14,6 +14,8 @@ mod shared {
         Get,
         GetAsync,
         Set(TimeResponse),
+        Tick,
+        Ticked(TimeResponse),
 
         StartDebounce,
         DurationElapsed(usize, TimeResponse),
@@ -41,6 +43,7 @@ mod shared {
         debounce: Debounce,
         pub debounce_complete: bool,
         pub debounce_time_id: Option<TimerId>,
+        pub last_tick: Option<u64>,
     }
 
     #[derive(Serialize, Deserialize, Default)]
@@ -71,6 +74,13 @@ mod shared {
                         caps.render.render()
                     }
                 }
+                Event::Tick => caps.time.monotonic(Event::Ticked),
+                Event::Ticked(TimeResponse::Monotonic(nanos)) => {
+                    model.last_tick = Some(nanos);
+                }
+                Event::Ticked(_) => {
+                    panic!("Unexpected tick event")
+                }
                 Event::StartDebounce => {
                     let pending = model.debounce.start();
 
@@ -179,7 +189,7 @@ mod tests {
     };
     use chrono::{DateTime, Utc};
     use crux_core::{testing::AppTester, Core};
-    use crux_time::TimeResponse;
+    use crux_time::{TimeRequest, TimeResponse};
 
     #[test]
     pub fn test_time() {
@@ -207,6 +217,34 @@ mod tests {
         assert_eq!(app.view(&model).time, "2022-12-01T01:47:12.746202562+00:00");
     }
 
+    #[test]
+    pub fn test_monotonic() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::Tick, &mut model)
+            .expect_one_effect()
+            .expect_time();
+
+        assert_eq!(request.operation, TimeRequest::Monotonic);
+
+        let update = app
+            .resolve(request, TimeResponse::Monotonic(1_500_000_000))
+            .expect("to resolve");
+
+        assert_eq!(update.events.len(), 1);
+        assert!(matches!(
+            update.events[0],
+            Event::Ticked(TimeResponse::Monotonic(1_500_000_000))
+        ));
+
+        for event in update.events {
+            let _update = app.update(event, &mut model);
+        }
+        assert_eq!(model.last_tick, Some(1_500_000_000));
+    }
+
     #[test]
     pub fn test_debounce_timer() {
         let app = AppTester::<App, _>::default();
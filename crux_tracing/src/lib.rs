@@ -0,0 +1,132 @@
+//! Distributed tracing for Crux apps
+//!
+//! `crux_tracing` allows Crux apps to open and close spans from the core, which the Shell
+//! forwards to the distributed tracer of its platform, e.g. OpenTelemetry, so that traces
+//! continue across the FFI boundary. Both are fire-and-forget, the app doesn't hear back
+//! from the Shell. Spans are identified by a [`SpanId`] generated in the core, which the
+//! Shell uses to match the end of a span to its start.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use serde::{Deserialize, Serialize};
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TracingOperation {
+    /// Start a span called `name`, with additional attributes
+    StartSpan {
+        id: SpanId,
+        name: String,
+        attributes: BTreeMap<String, AttributeValue>,
+    },
+    /// End the span started with the same `id`
+    EndSpan { id: SpanId },
+}
+
+/// Identifies a span between its start and its end
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SpanId(pub u64);
+
+fn get_span_id() -> SpanId {
+    static COUNTER: AtomicU64 = AtomicU64::new(1);
+    SpanId(COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+/// The value of a span attribute
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AttributeValue {
+    String(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl From<String> for AttributeValue {
+    fn from(value: String) -> Self {
+        AttributeValue::String(value)
+    }
+}
+
+impl From<&str> for AttributeValue {
+    fn from(value: &str) -> Self {
+        AttributeValue::String(value.to_string())
+    }
+}
+
+impl From<i64> for AttributeValue {
+    fn from(value: i64) -> Self {
+        AttributeValue::Int(value)
+    }
+}
+
+impl From<f64> for AttributeValue {
+    fn from(value: f64) -> Self {
+        AttributeValue::Float(value)
+    }
+}
+
+impl From<bool> for AttributeValue {
+    fn from(value: bool) -> Self {
+        AttributeValue::Bool(value)
+    }
+}
+
+impl Operation for TracingOperation {
+    type Output = ();
+}
+
+#[derive(Capability)]
+pub struct Tracing<Ev> {
+    context: CapabilityContext<TracingOperation, Ev>,
+}
+
+impl<Ev> Tracing<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<TracingOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Start a span called `name`, with the provided `attributes`, returning its id,
+    /// which is needed to end it with [`Tracing::end_span`].
+    ///
+    /// The attributes are kept in a `BTreeMap`, so they are always sent to the Shell
+    /// in the same order.
+    pub fn start_span(
+        &self,
+        name: impl Into<String>,
+        attributes: BTreeMap<String, AttributeValue>,
+    ) -> SpanId {
+        let id = get_span_id();
+        let name = name.into();
+
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                context
+                    .notify_shell(TracingOperation::StartSpan {
+                        id,
+                        name,
+                        attributes,
+                    })
+                    .await;
+            }
+        });
+
+        id
+    }
+
+    /// End the span with the `id` returned by [`Tracing::start_span`].
+    pub fn end_span(&self, id: SpanId) {
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                context.notify_shell(TracingOperation::EndSpan { id }).await;
+            }
+        });
+    }
+}
@@ -0,0 +1,133 @@
+mod shared {
+    use std::collections::BTreeMap;
+
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_tracing::{SpanId, Tracing};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Event {
+        StartSync { items: i64 },
+        SyncDone,
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub sync_span: Option<SpanId>,
+        pub synced: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub synced: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::StartSync { items } => {
+                    let span = caps.tracing.start_span(
+                        "sync",
+                        BTreeMap::from([
+                            ("items".to_string(), items.into()),
+                            ("background".to_string(), false.into()),
+                        ]),
+                    );
+                    model.sync_span = Some(span);
+                }
+                Event::SyncDone => {
+                    if let Some(span) = model.sync_span.take() {
+                        caps.tracing.end_span(span);
+                    }
+
+                    model.synced = true;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                synced: model.synced,
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub tracing: Tracing<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_tracing::{AttributeValue, TracingOperation};
+
+    #[test]
+    pub fn test_span() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let start = app
+            .update(Event::StartSync { items: 3 }, &mut model)
+            .expect_one_effect()
+            .expect_tracing();
+
+        let TracingOperation::StartSpan {
+            id,
+            name,
+            attributes,
+        } = start.operation
+        else {
+            panic!("expected a span to start");
+        };
+        assert_eq!(name, "sync");
+        assert_eq!(
+            attributes,
+            BTreeMap::from([
+                ("background".to_string(), AttributeValue::Bool(false)),
+                ("items".to_string(), AttributeValue::Int(3)),
+            ])
+        );
+        assert_eq!(model.sync_span, Some(id));
+
+        let mut update = app.update(Event::SyncDone, &mut model);
+        assert_effect!(update, Effect::Render(_));
+
+        let end = update
+            .take_effects(Effect::is_tracing)
+            .pop_front()
+            .expect("a tracing effect")
+            .expect_tracing();
+        assert_eq!(end.operation, TracingOperation::EndSpan { id });
+
+        // spans don't expect a response from the shell
+        assert!(!end.expects_response());
+        assert!(app.view(&model).synced);
+    }
+
+    #[test]
+    pub fn test_span_ids_are_unique() {
+        let app = AppTester::<App, _>::default();
+        let mut first = Model::default();
+        let mut second = Model::default();
+
+        let _ = app.update(Event::StartSync { items: 1 }, &mut first);
+        let _ = app.update(Event::StartSync { items: 2 }, &mut second);
+
+        assert_ne!(first.sync_span, second.sync_span);
+    }
+}
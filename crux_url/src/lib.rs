@@ -0,0 +1,110 @@
+//! Opening URLs and handling deep links for Crux apps
+//!
+//! `crux_url` allows Crux apps to ask the Shell to open an external URL, e.g. in the browser,
+//! which resolves once with whether the URL could be opened. It also allows apps to receive
+//! the deep links they are opened with: subscribing resolves every time a deep link arrives.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+/// Supported operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UrlRequest {
+    /// Open the `url` outside of the app
+    Open { url: String },
+    /// Start delivering incoming deep links to the app
+    SubscribeDeepLinks,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UrlResponse {
+    /// Response to a `UrlRequest::Open`, `false` if no app could open the URL
+    Opened { opened: bool },
+    /// The app was opened with a deep link to the `url`
+    DeepLink { url: String },
+}
+
+impl Operation for UrlRequest {
+    type Output = UrlResponse;
+}
+
+#[derive(Capability)]
+pub struct UrlOpener<Ev> {
+    context: CapabilityContext<UrlRequest, Ev>,
+}
+
+impl<Ev> Clone for UrlOpener<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> UrlOpener<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<UrlRequest, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Open the `url` outside of the app. Whether it could be opened is passed to the app
+    /// wrapped in the event produced by the `callback`.
+    pub fn open<F>(&self, url: impl Into<String>, callback: F)
+    where
+        F: FnOnce(bool) -> Ev + Send + 'static,
+    {
+        let url = url.into();
+
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.open_async(url).await));
+            }
+        });
+    }
+
+    /// Open the `url` outside of the app, returning whether it could be opened.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn open_async(&self, url: impl Into<String>) -> bool {
+        let url = url.into();
+
+        match self
+            .context
+            .request_from_shell(UrlRequest::Open { url })
+            .await
+        {
+            UrlResponse::Opened { opened } => opened,
+            UrlResponse::DeepLink { .. } => false,
+        }
+    }
+
+    /// Receive the deep links the app is opened with. Every deep link URL is passed
+    /// to the app wrapped in the event produced by the `callback`.
+    pub fn on_deep_link<F>(&self, callback: F)
+    where
+        F: Fn(String) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+
+            async move {
+                let mut stream = context.stream_from_shell(UrlRequest::SubscribeDeepLinks);
+
+                while let Some(response) = stream.next().await {
+                    // only deep links are delivered to a subscription
+                    if let UrlResponse::DeepLink { url } = response {
+                        context.update_app(callback(url));
+                    }
+                }
+            }
+        });
+    }
+}
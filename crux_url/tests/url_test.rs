@@ -0,0 +1,136 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_url::UrlOpener;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub enum Event {
+        OpenHelp,
+        Listen,
+
+        #[serde(skip)]
+        HelpOpened(bool),
+        #[serde(skip)]
+        DeepLink(String),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub help_opened: Option<bool>,
+        pub route: Option<String>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub struct ViewModel {
+        pub route: String,
+    }
+
+    pub const HELP_URL: &str = "https://example.com/help";
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::OpenHelp => caps.url.open(HELP_URL, Event::HelpOpened),
+                Event::Listen => caps.url.on_deep_link(Event::DeepLink),
+                Event::HelpOpened(opened) => {
+                    model.help_opened = Some(opened);
+                    caps.render.render();
+                }
+                Event::DeepLink(url) => {
+                    model.route = Some(url);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                route: model.route.clone().unwrap_or_default(),
+            }
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub url: UrlOpener<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model, HELP_URL};
+    use crux_core::{assert_effect, testing::AppTester};
+    use crux_url::{UrlRequest, UrlResponse};
+
+    #[test]
+    pub fn test_open() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::OpenHelp, &mut model)
+            .expect_one_effect()
+            .expect_url();
+
+        assert_eq!(
+            request.operation,
+            UrlRequest::Open {
+                url: HELP_URL.to_string()
+            }
+        );
+
+        let event = app
+            .resolve(&mut request, UrlResponse::Opened { opened: true })
+            .unwrap()
+            .expect_one_event();
+        assert_eq!(event, Event::HelpOpened(true));
+
+        let update = app.update(event, &mut model);
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(model.help_opened, Some(true));
+
+        // opening is one-shot
+        app.assert_no_pending();
+    }
+
+    #[test]
+    pub fn test_delivered_deep_links() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Listen, &mut model)
+            .expect_one_effect()
+            .expect_url();
+
+        assert_eq!(request.operation, UrlRequest::SubscribeDeepLinks);
+
+        for url in ["myapp://orders/42", "myapp://settings"] {
+            let event = app
+                .resolve(
+                    &mut request,
+                    UrlResponse::DeepLink {
+                        url: url.to_string(),
+                    },
+                )
+                .unwrap()
+                .expect_one_event();
+            assert_eq!(event, Event::DeepLink(url.to_string()));
+
+            let update = app.update(event, &mut model);
+            assert_effect!(update, Effect::Render(_));
+        }
+
+        assert!(request.expects_response());
+        assert_eq!(app.view(&model).route, "myapp://settings");
+    }
+}
@@ -3,10 +3,12 @@ package com.example.bridge_echo
 import androidx.compose.runtime.getValue
 import androidx.compose.runtime.mutableStateOf
 import androidx.compose.runtime.setValue
+import com.example.bridge_echo.shared.handshake
 import com.example.bridge_echo.shared.processEvent
 import com.example.bridge_echo.shared.view
 import com.example.bridge_echo.shared_types.Effect
 import com.example.bridge_echo.shared_types.Event
+import com.example.bridge_echo.shared_types.ProtocolVersion
 import com.example.bridge_echo.shared_types.Request
 import com.example.bridge_echo.shared_types.Requests
 import com.example.bridge_echo.shared_types.ViewModel
@@ -16,6 +18,11 @@ open class Core : androidx.lifecycle.ViewModel() {
     var view: ViewModel? by mutableStateOf(null)
         private set
 
+    init {
+        // the generated types speak version 2.0 of the bridge protocol
+        handshake(ProtocolVersion(2.toShort(), 0.toShort()).bincodeSerialize())
+    }
+
     fun update(event: Event) {
         val effects = processEvent(event.bincodeSerialize())
 
@@ -7,6 +7,7 @@ import androidx.compose.runtime.getValue
 import androidx.compose.runtime.mutableStateOf
 import androidx.compose.runtime.setValue
 import com.redbadger.catfacts.shared.handleResponse
+import com.redbadger.catfacts.shared.handshake
 import com.redbadger.catfacts.shared.processEvent
 import com.redbadger.catfacts.shared.view
 import com.redbadger.catfacts.shared_types.Effect
@@ -14,6 +15,7 @@ import com.redbadger.catfacts.shared_types.Event
 import com.redbadger.catfacts.shared_types.HttpResult
 import com.redbadger.catfacts.shared_types.Instant
 import com.redbadger.catfacts.shared_types.PlatformResponse
+import com.redbadger.catfacts.shared_types.ProtocolVersion
 import com.redbadger.catfacts.shared_types.Request
 import com.redbadger.catfacts.shared_types.Requests
 import com.redbadger.catfacts.shared_types.TimeResponse
@@ -29,6 +31,11 @@ open class Core : androidx.lifecycle.ViewModel() {
 
     private val httpClient = HttpClient(CIO)
 
+    init {
+        // the generated types speak version 2.0 of the bridge protocol
+        handshake(ProtocolVersion(2.toShort(), 0.toShort()).bincodeSerialize())
+    }
+
     suspend fun update(event: Event) {
         val effects = processEvent(event.bincodeSerialize())
 
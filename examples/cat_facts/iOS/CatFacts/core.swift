@@ -7,6 +7,9 @@ class Core: ObservableObject {
     @Published var view: ViewModel
 
     init() {
+        // the generated types speak version 2.0 of the bridge protocol
+        _ = handshake(Data(try! ProtocolVersion(major: 2, minor: 0).bincodeSerialize()))
+
         view = try! .bincodeDeserialize(input: [UInt8](CatFacts.view()))
     }
 
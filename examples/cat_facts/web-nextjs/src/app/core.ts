@@ -1,7 +1,12 @@
 import type { Dispatch, SetStateAction } from "react";
 import UAParser from "ua-parser-js";
 
-import { handle_response, process_event, view } from "shared/shared";
+import {
+  handle_response,
+  handshake as coreHandshake,
+  process_event,
+  view,
+} from "shared/shared";
 import {
   BincodeDeserializer,
   BincodeSerializer,
@@ -18,6 +23,7 @@ import {
   Instant,
   KeyValueResponse,
   PlatformResponse,
+  ProtocolVersion,
   Request,
   TimeResponse,
   TimeResponseVariantnow,
@@ -32,6 +38,14 @@ type Response =
   | HttpResponse
   | KeyValueResponse;
 
+// the generated types speak version 2.0 of the bridge protocol
+export function handshake() {
+  const serializer = new BincodeSerializer();
+  new ProtocolVersion(2, 0).serialize(serializer);
+
+  coreHandshake(serializer.getBytes());
+}
+
 export function update(
   event: Event,
   callback: Dispatch<SetStateAction<ViewModel>>,
@@ -14,7 +14,7 @@ import {
   EventVariantFetch,
 } from "shared_types/types/shared_types";
 
-import { update } from "./core";
+import { handshake, update } from "./core";
 
 const Home: NextPage = () => {
   const [view, setView] = useState(new ViewModel("", new CatImage(""), ""));
@@ -26,6 +26,8 @@ const Home: NextPage = () => {
         initialized.current = true;
 
         init_core().then(() => {
+          handshake();
+
           // Initial events
           update(new EventVariantGetPlatform(), setView);
           update(new EventVariantGet(), setView);
@@ -7,11 +7,13 @@ import androidx.compose.runtime.mutableStateOf
 import androidx.compose.runtime.setValue
 import androidx.lifecycle.viewModelScope
 import com.example.counter.shared.handleResponse
+import com.example.counter.shared.handshake
 import com.example.counter.shared.processEvent
 import com.example.counter.shared.view
 import com.example.counter.shared_types.Effect
 import com.example.counter.shared_types.Event
 import com.example.counter.shared_types.HttpResult
+import com.example.counter.shared_types.ProtocolVersion
 import com.example.counter.shared_types.Request
 import com.example.counter.shared_types.Requests
 import com.example.counter.shared_types.ViewModel
@@ -37,6 +39,9 @@ class Core : androidx.lifecycle.ViewModel() {
     }
 
     init {
+        // the generated types speak version 2.0 of the bridge protocol
+        handshake(ProtocolVersion(2.toShort(), 0.toShort()).bincodeSerialize())
+
         viewModelScope.launch {
             update(Event.StartWatch())
         }
@@ -19,6 +19,12 @@ lazy_static! {
     static ref CORE: Bridge<Effect, App> = Bridge::new(Core::new());
 }
 
+#[wasm_bindgen]
+pub fn handshake(version: &[u8]) -> Vec<u8> {
+    CORE.handshake(version)
+        .expect("shell protocol version should be compatible with the core")
+}
+
 #[wasm_bindgen]
 pub fn process_event(data: &[u8]) -> Vec<u8> {
     CORE.process_event(data)
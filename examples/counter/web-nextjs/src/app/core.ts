@@ -1,6 +1,11 @@
 import type { Dispatch, SetStateAction } from "react";
 
-import { process_event, handle_response, view } from "shared/shared";
+import {
+  handshake as coreHandshake,
+  process_event,
+  handle_response,
+  view,
+} from "shared/shared";
 import type {
   Effect,
   Event,
@@ -12,6 +17,7 @@ import {
   ViewModel,
   EffectVariantHttp,
   EffectVariantServerSentEvents,
+  ProtocolVersion,
   Request,
 } from "shared_types/types/shared_types";
 import {
@@ -24,6 +30,14 @@ import { request as sse } from "./sse";
 
 type Response = HttpResponse | SseResponse;
 
+// the generated types speak version 2.0 of the bridge protocol
+export function handshake() {
+  const serializer = new BincodeSerializer();
+  new ProtocolVersion(2, 0).serialize(serializer);
+
+  coreHandshake(serializer.getBytes());
+}
+
 export function update(
   event: Event,
   callback: Dispatch<SetStateAction<ViewModel>>,
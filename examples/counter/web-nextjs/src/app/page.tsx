@@ -11,7 +11,7 @@ import {
   EventVariantIncrement,
 } from "shared_types/types/shared_types";
 
-import { update } from "./core";
+import { handshake, update } from "./core";
 
 const Home: NextPage = () => {
   const [view, setView] = useState(new ViewModel("", false));
@@ -23,6 +23,8 @@ const Home: NextPage = () => {
         initialized.current = true;
 
         init_core().then(() => {
+          handshake();
+
           update(new EventVariantStartWatch(), setView);
         });
       }
@@ -1,4 +1,5 @@
 import {
+  handshake as coreHandshake,
   process_event,
   handle_response,
   view as coreView,
@@ -13,6 +14,7 @@ import type {
 import {
   EffectVariantRender,
   ViewModel,
+  ProtocolVersion,
   Request,
   EffectVariantKeyValue,
   EffectVariantPubSub,
@@ -66,6 +68,14 @@ export class Core {
     this.subscriptionId = subscriptionId;
   }
 
+  // the generated types speak version 2.0 of the bridge protocol
+  handshake() {
+    const serializer = new BincodeSerializer();
+    new ProtocolVersion(2, 0).serialize(serializer);
+
+    coreHandshake(serializer.getBytes());
+  }
+
   view(): ViewModel {
     return deserializeView(coreView());
   }
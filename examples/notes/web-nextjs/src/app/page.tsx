@@ -96,6 +96,8 @@ const Home: NextPage = () => {
         initialized.current = true;
 
         init_core().then(() => {
+          core.current.handshake();
+
           // Subscribe to the BroadcastChannel
           channel.current.onmessage = onMessage;
 
@@ -3,10 +3,12 @@ package com.example.simple_counter
 import androidx.compose.runtime.getValue
 import androidx.compose.runtime.mutableStateOf
 import androidx.compose.runtime.setValue
+import com.example.simple_counter.shared.handshake
 import com.example.simple_counter.shared.processEvent
 import com.example.simple_counter.shared.view
 import com.example.simple_counter.shared_types.Effect
 import com.example.simple_counter.shared_types.Event
+import com.example.simple_counter.shared_types.ProtocolVersion
 import com.example.simple_counter.shared_types.Request
 import com.example.simple_counter.shared_types.Requests
 import com.example.simple_counter.shared_types.ViewModel
@@ -15,6 +17,11 @@ class Core : androidx.lifecycle.ViewModel() {
     var view: ViewModel? by mutableStateOf(null)
         private set
 
+    init {
+        // the generated types speak version 2.0 of the bridge protocol
+        handshake(ProtocolVersion(2.toShort(), 0.toShort()).bincodeSerialize())
+    }
+
     fun update(event: Event) {
         val effects = processEvent(event.bincodeSerialize())
 
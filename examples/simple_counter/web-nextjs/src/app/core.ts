@@ -1,10 +1,11 @@
 import type { Dispatch, SetStateAction } from "react";
 
-import { process_event, view } from "shared/shared";
+import { handshake as coreHandshake, process_event, view } from "shared/shared";
 import type { Effect, Event } from "shared_types/types/shared_types";
 import {
   EffectVariantRender,
   ViewModel,
+  ProtocolVersion,
   Request,
 } from "shared_types/types/shared_types";
 import {
@@ -12,6 +13,14 @@ import {
   BincodeDeserializer,
 } from "shared_types/bincode/mod";
 
+// the generated types speak version 2.0 of the bridge protocol
+export function handshake() {
+  const serializer = new BincodeSerializer();
+  new ProtocolVersion(2, 0).serialize(serializer);
+
+  coreHandshake(serializer.getBytes());
+}
+
 export function update(
   event: Event,
   callback: Dispatch<SetStateAction<ViewModel>>,
@@ -12,7 +12,7 @@ import {
   EventVariantDecrement,
 } from "shared_types/types/shared_types";
 
-import { update } from "./core";
+import { handshake, update } from "./core";
 
 const Home: NextPage = () => {
   const [view, setView] = useState(new ViewModel("0"));
@@ -24,6 +24,8 @@ const Home: NextPage = () => {
         initialized.current = true;
 
         init_core().then(() => {
+          handshake();
+
           // Initial event
           update(new EventVariantReset(), setView);
         });
@@ -10,7 +10,11 @@ import { hydrateRoot } from "react-dom/client";
 import init from "shared/shared";
 import wasm from "shared/shared_bg.wasm";
 
+import { handshake } from "./core";
+
 init(wasm).then(() => {
+  handshake();
+
   startTransition(() => {
     hydrateRoot(
       document,
@@ -1,9 +1,10 @@
-import { process_event, view } from "shared";
+import { handshake as coreHandshake, process_event, view } from "shared";
 import initCore from "shared";
 import { writable } from "svelte/store";
 import {
   EffectVariantRender,
   ViewModel,
+  ProtocolVersion,
   Request,
 } from "shared_types/types/shared_types";
 import type { Effect, Event } from "shared_types/types/shared_types";
@@ -14,6 +15,16 @@ import {
 
 const { subscribe, set } = writable(new ViewModel("0"));
 
+// the generated types speak version 2.0 of the bridge protocol
+export async function handshake() {
+  await initCore();
+
+  const serializer = new BincodeSerializer();
+  new ProtocolVersion(2, 0).serialize(serializer);
+
+  coreHandshake(serializer.getBytes());
+}
+
 export async function update(event: Event) {
   console.log("event", event);
   await initCore();
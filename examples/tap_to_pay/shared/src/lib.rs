@@ -13,6 +13,11 @@ lazy_static! {
     static ref CORE: Bridge<Effect, App> = Bridge::new(Core::new());
 }
 
+pub fn handshake(version: &[u8]) -> Vec<u8> {
+    CORE.handshake(version)
+        .expect("shell protocol version should be compatible with the core")
+}
+
 pub fn process_event(data: &[u8]) -> Vec<u8> {
     CORE.process_event(data)
 }
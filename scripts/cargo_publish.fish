@@ -1,6 +1,6 @@
 #!/usr/bin/env fish
 
-for dir in crux_macros crux_core crux_http crux_kv crux_platform crux_time
+for dir in crux_macros crux_core crux_accessibility crux_analytics crux_app_info crux_audio crux_background crux_badge crux_battery crux_biometrics crux_bluetooth crux_calendar crux_camera crux_config crux_connectivity crux_contacts crux_datetime_picker crux_debounce crux_dialog crux_display crux_filesystem crux_heartbeat crux_http crux_idle crux_keyboard crux_kv crux_localization crux_ml crux_nfc crux_orientation crux_permissions crux_platform crux_preferences crux_push crux_review crux_screen_capture crux_sensors crux_share crux_sound crux_storage crux_time crux_tracing crux_url
     echo $dir
     cargo publish --package $dir
 end